@@ -0,0 +1,5 @@
+-- Add down migration script here
+DROP INDEX IF EXISTS executions_user_id_index;
+DROP INDEX IF EXISTS executions_status_created_at_index;
+DROP TABLE IF EXISTS executions;
+DROP TYPE IF EXISTS execution_status;
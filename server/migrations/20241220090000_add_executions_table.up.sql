@@ -0,0 +1,23 @@
+-- Add up migration script here
+CREATE TYPE execution_status AS ENUM ('queued', 'running', 'completed', 'failed');
+
+CREATE TABLE IF NOT EXISTS executions (
+    id UUID PRIMARY KEY NOT NULL,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    language TEXT NOT NULL,
+    files JSONB NOT NULL,
+    stdin TEXT,
+    status execution_status NOT NULL,
+    stdout TEXT,
+    stderr TEXT,
+    error TEXT,
+    attempts INTEGER NOT NULL,
+    locked_until TIMESTAMPTZ,
+    started_at TIMESTAMPTZ,
+    finished_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS executions_status_created_at_index ON executions(status, created_at);
+CREATE INDEX IF NOT EXISTS executions_user_id_index ON executions(user_id);
@@ -0,0 +1,5 @@
+-- Add down migration script here
+DROP INDEX IF EXISTS executions_user_id_created_at_index;
+DROP INDEX IF EXISTS execution_usage_created_at_index;
+DROP INDEX IF EXISTS execution_usage_user_id_created_at_index;
+DROP TABLE IF EXISTS execution_usage;
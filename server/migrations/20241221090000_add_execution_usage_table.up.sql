@@ -0,0 +1,13 @@
+-- Add up migration script here
+CREATE TABLE IF NOT EXISTS execution_usage (
+    id UUID PRIMARY KEY NOT NULL,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    execution_id UUID REFERENCES executions(id) ON DELETE SET NULL,
+    language TEXT NOT NULL,
+    cpu_ms BIGINT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS execution_usage_user_id_created_at_index ON execution_usage(user_id, created_at);
+CREATE INDEX IF NOT EXISTS execution_usage_created_at_index ON execution_usage(created_at);
+CREATE INDEX IF NOT EXISTS executions_user_id_created_at_index ON executions(user_id, created_at);
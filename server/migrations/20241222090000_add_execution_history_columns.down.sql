@@ -0,0 +1,6 @@
+-- Add down migration script here
+DROP INDEX IF EXISTS executions_finished_at_index;
+ALTER TABLE executions
+    DROP COLUMN IF EXISTS duration_ms,
+    DROP COLUMN IF EXISTS exit_code,
+    DROP COLUMN IF EXISTS files_hash;
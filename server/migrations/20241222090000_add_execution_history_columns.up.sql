@@ -0,0 +1,7 @@
+-- Add up migration script here
+ALTER TABLE executions
+    ADD COLUMN IF NOT EXISTS files_hash TEXT NOT NULL DEFAULT '',
+    ADD COLUMN IF NOT EXISTS exit_code INTEGER,
+    ADD COLUMN IF NOT EXISTS duration_ms BIGINT;
+
+CREATE INDEX IF NOT EXISTS executions_finished_at_index ON executions(finished_at);
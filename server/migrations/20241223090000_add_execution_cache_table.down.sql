@@ -0,0 +1,4 @@
+-- Add down migration script here
+ALTER TABLE executions DROP COLUMN IF EXISTS cached;
+DROP INDEX IF EXISTS execution_cache_expires_at_index;
+DROP TABLE IF EXISTS execution_cache;
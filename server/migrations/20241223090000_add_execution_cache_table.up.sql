@@ -0,0 +1,17 @@
+-- Add up migration script here
+CREATE TABLE IF NOT EXISTS execution_cache (
+    key TEXT PRIMARY KEY NOT NULL,
+    language TEXT NOT NULL,
+    stdout TEXT NOT NULL,
+    stderr TEXT NOT NULL,
+    error TEXT,
+    exit_code INTEGER,
+    duration_ms BIGINT NOT NULL,
+    hits BIGINT NOT NULL,
+    expires_at TIMESTAMPTZ NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS execution_cache_expires_at_index ON execution_cache(expires_at);
+
+ALTER TABLE executions ADD COLUMN IF NOT EXISTS cached BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,4 @@
+-- Add down migration script here
+ALTER TABLE executions
+    DROP COLUMN IF EXISTS case_results,
+    DROP COLUMN IF EXISTS judge;
@@ -0,0 +1,4 @@
+-- Add up migration script here
+ALTER TABLE executions
+    ADD COLUMN IF NOT EXISTS judge JSONB,
+    ADD COLUMN IF NOT EXISTS case_results JSONB;
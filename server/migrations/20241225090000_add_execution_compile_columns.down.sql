@@ -0,0 +1,3 @@
+-- Add down migration script here
+ALTER TABLE execution_cache DROP COLUMN IF EXISTS compile;
+ALTER TABLE executions DROP COLUMN IF EXISTS compile;
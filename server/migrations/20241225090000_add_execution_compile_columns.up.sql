@@ -0,0 +1,3 @@
+-- Add up migration script here
+ALTER TABLE executions ADD COLUMN IF NOT EXISTS compile JSONB;
+ALTER TABLE execution_cache ADD COLUMN IF NOT EXISTS compile JSONB;
@@ -0,0 +1,4 @@
+-- Add down migration script here
+DROP TABLE IF EXISTS workspace_files;
+DROP INDEX IF EXISTS workspaces_owner_id_index;
+DROP TABLE IF EXISTS workspaces;
@@ -0,0 +1,4 @@
+-- Add down migration script here
+DROP INDEX IF EXISTS file_revisions_blob_hash_index;
+DROP TABLE IF EXISTS file_revisions;
+DROP TABLE IF EXISTS blobs;
@@ -0,0 +1,20 @@
+-- Add up migration script here
+CREATE TABLE IF NOT EXISTS blobs (
+    hash TEXT PRIMARY KEY NOT NULL,
+    content TEXT NOT NULL,
+    size BIGINT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS file_revisions (
+    id UUID PRIMARY KEY NOT NULL,
+    file_id UUID NOT NULL REFERENCES workspace_files(id) ON DELETE CASCADE,
+    number INTEGER NOT NULL,
+    path TEXT NOT NULL,
+    blob_hash TEXT NOT NULL REFERENCES blobs(hash),
+    author_id UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL,
+    UNIQUE (file_id, number)
+);
+
+CREATE INDEX IF NOT EXISTS file_revisions_blob_hash_index ON file_revisions(blob_hash);
@@ -0,0 +1,5 @@
+-- Add down migration script here
+DROP INDEX IF EXISTS snippets_public_index;
+DROP INDEX IF EXISTS snippets_owner_id_index;
+DROP TABLE IF EXISTS snippets;
+DROP TYPE IF EXISTS snippet_visibility;
@@ -0,0 +1,20 @@
+-- Add up migration script here
+CREATE TYPE snippet_visibility AS ENUM ('private', 'unlisted', 'public');
+
+CREATE TABLE IF NOT EXISTS snippets (
+    id UUID PRIMARY KEY NOT NULL,
+    owner_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    slug TEXT NOT NULL UNIQUE,
+    title TEXT NOT NULL,
+    language TEXT,
+    files JSONB NOT NULL,
+    visibility snippet_visibility NOT NULL,
+    password_hash TEXT,
+    expires_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS snippets_owner_id_index ON snippets(owner_id, created_at DESC);
+CREATE INDEX IF NOT EXISTS snippets_public_index ON snippets(created_at DESC)
+    WHERE visibility = 'public' AND password_hash IS NULL;
@@ -0,0 +1,4 @@
+-- Add down migration script here
+DROP INDEX IF EXISTS collab_updates_file_id_index;
+DROP TABLE IF EXISTS collab_updates;
+DROP TABLE IF EXISTS collab_snapshots;
@@ -0,0 +1,17 @@
+-- Add up migration script here
+CREATE TABLE IF NOT EXISTS collab_snapshots (
+    file_id UUID PRIMARY KEY NOT NULL REFERENCES workspace_files(id) ON DELETE CASCADE,
+    seq BIGINT NOT NULL,
+    data BYTEA NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS collab_updates (
+    seq BIGSERIAL PRIMARY KEY NOT NULL,
+    file_id UUID NOT NULL REFERENCES workspace_files(id) ON DELETE CASCADE,
+    author_id UUID REFERENCES users(id) ON DELETE SET NULL,
+    data BYTEA NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS collab_updates_file_id_index ON collab_updates(file_id, seq);
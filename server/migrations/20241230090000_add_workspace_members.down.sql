@@ -0,0 +1,5 @@
+-- Add down migration script here
+DROP TABLE IF EXISTS workspace_invitations;
+DROP TABLE IF EXISTS workspace_members;
+DROP TYPE IF EXISTS invitation_status;
+DROP TYPE IF EXISTS workspace_role;
@@ -0,0 +1,36 @@
+-- Add up migration script here
+CREATE TYPE workspace_role AS ENUM ('owner', 'editor', 'commenter', 'viewer');
+CREATE TYPE invitation_status AS ENUM ('pending', 'accepted', 'declined', 'revoked');
+
+-- The owner is recorded on the workspace itself; this table holds everybody else
+CREATE TABLE IF NOT EXISTS workspace_members (
+    workspace_id UUID NOT NULL REFERENCES workspaces(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    role workspace_role NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL,
+    PRIMARY KEY (workspace_id, user_id)
+);
+
+CREATE INDEX IF NOT EXISTS workspace_members_user_id_index ON workspace_members(user_id);
+
+CREATE TABLE IF NOT EXISTS workspace_invitations (
+    id UUID PRIMARY KEY NOT NULL,
+    workspace_id UUID NOT NULL REFERENCES workspaces(id) ON DELETE CASCADE,
+    inviter_id UUID REFERENCES users(id) ON DELETE SET NULL,
+    invitee_id UUID REFERENCES users(id) ON DELETE CASCADE,
+    token TEXT UNIQUE,
+    role workspace_role NOT NULL,
+    status invitation_status NOT NULL,
+    expires_at TIMESTAMPTZ,
+    max_uses INTEGER,
+    uses INTEGER NOT NULL DEFAULT 0,
+    created_at TIMESTAMPTZ NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL,
+    CHECK ((invitee_id IS NULL) <> (token IS NULL))
+);
+
+CREATE INDEX IF NOT EXISTS workspace_invitations_workspace_id_index
+    ON workspace_invitations(workspace_id, created_at DESC);
+CREATE INDEX IF NOT EXISTS workspace_invitations_invitee_id_index
+    ON workspace_invitations(invitee_id) WHERE status = 'pending';
@@ -0,0 +1,5 @@
+-- Add down migration script here
+DROP TABLE IF EXISTS mentions;
+DROP TABLE IF EXISTS chat_messages;
+DROP TABLE IF EXISTS comments;
+DROP TABLE IF EXISTS comment_threads;
@@ -0,0 +1,53 @@
+-- Add up migration script here
+CREATE TABLE IF NOT EXISTS comment_threads (
+    id UUID PRIMARY KEY NOT NULL,
+    workspace_id UUID NOT NULL REFERENCES workspaces(id) ON DELETE CASCADE,
+    file_id UUID NOT NULL REFERENCES workspace_files(id) ON DELETE CASCADE,
+    author_id UUID REFERENCES users(id) ON DELETE SET NULL,
+    start_line INTEGER NOT NULL,
+    end_line INTEGER NOT NULL,
+    outdated BOOLEAN NOT NULL DEFAULT FALSE,
+    resolved_at TIMESTAMPTZ,
+    resolved_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL,
+    CHECK (start_line >= 1 AND end_line >= start_line)
+);
+
+CREATE INDEX IF NOT EXISTS comment_threads_file_id_index ON comment_threads(file_id, start_line);
+
+CREATE TABLE IF NOT EXISTS comments (
+    id UUID PRIMARY KEY NOT NULL,
+    thread_id UUID NOT NULL REFERENCES comment_threads(id) ON DELETE CASCADE,
+    author_id UUID REFERENCES users(id) ON DELETE SET NULL,
+    body TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS comments_thread_id_index ON comments(thread_id, created_at);
+
+CREATE TABLE IF NOT EXISTS chat_messages (
+    id UUID PRIMARY KEY NOT NULL,
+    workspace_id UUID NOT NULL REFERENCES workspaces(id) ON DELETE CASCADE,
+    author_id UUID REFERENCES users(id) ON DELETE SET NULL,
+    body TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS chat_messages_workspace_id_index
+    ON chat_messages(workspace_id, created_at DESC);
+
+CREATE TABLE IF NOT EXISTS mentions (
+    id UUID PRIMARY KEY NOT NULL,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    workspace_id UUID NOT NULL REFERENCES workspaces(id) ON DELETE CASCADE,
+    author_id UUID REFERENCES users(id) ON DELETE SET NULL,
+    comment_id UUID REFERENCES comments(id) ON DELETE CASCADE,
+    chat_message_id UUID REFERENCES chat_messages(id) ON DELETE CASCADE,
+    read_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL,
+    CHECK ((comment_id IS NULL) <> (chat_message_id IS NULL))
+);
+
+CREATE INDEX IF NOT EXISTS mentions_user_id_index ON mentions(user_id, created_at DESC);
@@ -0,0 +1,6 @@
+-- Add down migration script here
+DROP INDEX IF EXISTS session_events_file_id_index;
+DROP INDEX IF EXISTS session_events_workspace_id_index;
+DROP TABLE IF EXISTS session_events;
+ALTER TABLE executions DROP COLUMN IF EXISTS workspace_id;
+DROP TYPE IF EXISTS session_event_kind;
@@ -0,0 +1,21 @@
+-- Add up migration script here
+CREATE TYPE session_event_kind AS ENUM (
+    'edit', 'snapshot', 'save', 'run_queued', 'run_completed', 'run_failed'
+);
+
+ALTER TABLE executions ADD COLUMN workspace_id UUID REFERENCES workspaces(id) ON DELETE SET NULL;
+
+CREATE TABLE IF NOT EXISTS session_events (
+    id BIGSERIAL PRIMARY KEY NOT NULL,
+    workspace_id UUID NOT NULL REFERENCES workspaces(id) ON DELETE CASCADE,
+    file_id UUID REFERENCES workspace_files(id) ON DELETE CASCADE,
+    user_id UUID REFERENCES users(id) ON DELETE SET NULL,
+    kind session_event_kind NOT NULL,
+    seq BIGINT,
+    data BYTEA,
+    execution_id UUID REFERENCES executions(id) ON DELETE SET NULL,
+    recorded_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS session_events_workspace_id_index ON session_events(workspace_id, recorded_at);
+CREATE INDEX IF NOT EXISTS session_events_file_id_index ON session_events(file_id, kind, recorded_at);
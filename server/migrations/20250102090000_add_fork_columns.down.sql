@@ -0,0 +1,7 @@
+-- Add down migration script here
+ALTER TABLE snippets DROP COLUMN IF EXISTS fork_count;
+
+ALTER TABLE workspaces
+    DROP COLUMN IF EXISTS fork_count,
+    DROP COLUMN IF EXISTS forked_from_snippet_id,
+    DROP COLUMN IF EXISTS forked_from_workspace_id;
@@ -0,0 +1,7 @@
+-- Add up migration script here
+ALTER TABLE workspaces
+    ADD COLUMN forked_from_workspace_id UUID REFERENCES workspaces(id) ON DELETE SET NULL,
+    ADD COLUMN forked_from_snippet_id UUID REFERENCES snippets(id) ON DELETE SET NULL,
+    ADD COLUMN fork_count INTEGER NOT NULL DEFAULT 0;
+
+ALTER TABLE snippets ADD COLUMN fork_count INTEGER NOT NULL DEFAULT 0;
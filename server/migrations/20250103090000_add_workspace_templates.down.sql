@@ -0,0 +1,6 @@
+-- Add down migration script here
+ALTER TABLE workspaces DROP COLUMN IF EXISTS template_id;
+
+DROP INDEX IF EXISTS workspace_templates_tags_index;
+DROP INDEX IF EXISTS workspace_templates_workspace_id_index;
+DROP TABLE IF EXISTS workspace_templates;
@@ -0,0 +1,48 @@
+-- Add up migration script here
+CREATE TABLE IF NOT EXISTS workspace_templates (
+    id UUID PRIMARY KEY NOT NULL,
+    slug TEXT NOT NULL UNIQUE,
+    name TEXT NOT NULL,
+    description TEXT NOT NULL DEFAULT '',
+    language TEXT,
+    files JSONB NOT NULL,
+    tags TEXT[] NOT NULL DEFAULT '{}',
+    workspace_id UUID REFERENCES workspaces(id) ON DELETE CASCADE,
+    author_id UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS workspace_templates_workspace_id_index ON workspace_templates(workspace_id);
+CREATE INDEX IF NOT EXISTS workspace_templates_tags_index ON workspace_templates USING GIN (tags);
+
+ALTER TABLE workspaces
+    ADD COLUMN template_id UUID REFERENCES workspace_templates(id) ON DELETE SET NULL;
+
+INSERT INTO workspace_templates (id, slug, name, description, language, files, tags, created_at, updated_at)
+VALUES
+    (
+        gen_random_uuid(), 'python', 'Python', 'A single Python script.', 'python',
+        '[{"path": "main.py", "content": "print(\"Hello, World!\")\n"}]',
+        '{starter}', NOW(), NOW()
+    ),
+    (
+        gen_random_uuid(), 'python-pytest', 'Python with pytest', 'A module with a pytest suite.', 'python',
+        '[{"path": "main.py", "content": "def greet(name):\n    return f\"Hello, {name}!\"\n\n\nif __name__ == \"__main__\":\n    print(greet(\"World\"))\n"}, {"path": "test_main.py", "content": "from main import greet\n\n\ndef test_greet():\n    assert greet(\"World\") == \"Hello, World!\"\n"}]',
+        '{testing}', NOW(), NOW()
+    ),
+    (
+        gen_random_uuid(), 'rust-cargo', 'Rust with Cargo', 'A Cargo binary crate.', 'rust',
+        '[{"path": "Cargo.toml", "content": "[package]\nname = \"hello\"\nversion = \"0.1.0\"\nedition = \"2021\"\n"}, {"path": "src/main.rs", "content": "fn main() {\n    println!(\"Hello, World!\");\n}\n"}]',
+        '{starter,cargo}', NOW(), NOW()
+    ),
+    (
+        gen_random_uuid(), 'go-module', 'Go module', 'A Go module with a main package.', 'go',
+        '[{"path": "go.mod", "content": "module hello\n\ngo 1.21\n"}, {"path": "main.go", "content": "package main\n\nimport \"fmt\"\n\nfunc main() {\n\tfmt.Println(\"Hello, World!\")\n}\n"}]',
+        '{starter}', NOW(), NOW()
+    ),
+    (
+        gen_random_uuid(), 'javascript', 'JavaScript', 'A single Node.js script.', 'javascript',
+        '[{"path": "main.js", "content": "console.log(\"Hello, World!\");\n"}]',
+        '{starter}', NOW(), NOW()
+    );
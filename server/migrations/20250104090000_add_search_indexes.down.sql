@@ -0,0 +1,6 @@
+-- Add down migration script here
+DROP INDEX IF EXISTS snippets_files_trgm_index;
+DROP INDEX IF EXISTS snippets_files_fts_index;
+DROP INDEX IF EXISTS workspace_files_path_trgm_index;
+DROP INDEX IF EXISTS workspace_files_content_trgm_index;
+DROP INDEX IF EXISTS workspace_files_content_fts_index;
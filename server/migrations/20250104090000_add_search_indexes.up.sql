@@ -0,0 +1,14 @@
+-- Add up migration script here
+CREATE EXTENSION IF NOT EXISTS pg_trgm;
+
+CREATE INDEX workspace_files_content_fts_index
+    ON workspace_files USING GIN (to_tsvector('simple', content));
+CREATE INDEX workspace_files_content_trgm_index
+    ON workspace_files USING GIN (content gin_trgm_ops);
+CREATE INDEX workspace_files_path_trgm_index
+    ON workspace_files USING GIN (path gin_trgm_ops);
+
+CREATE INDEX snippets_files_fts_index
+    ON snippets USING GIN (to_tsvector('simple', files));
+CREATE INDEX snippets_files_trgm_index
+    ON snippets USING GIN ((files::TEXT) gin_trgm_ops);
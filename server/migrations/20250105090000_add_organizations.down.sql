@@ -0,0 +1,18 @@
+-- Add down migration script here
+DROP INDEX IF EXISTS execution_usage_organization_id_created_at_index;
+DROP INDEX IF EXISTS executions_organization_id_created_at_index;
+ALTER TABLE execution_usage DROP COLUMN IF EXISTS organization_id;
+ALTER TABLE executions DROP COLUMN IF EXISTS organization_id;
+
+DROP INDEX IF EXISTS workspace_templates_organization_id_index;
+ALTER TABLE workspace_templates
+    DROP CONSTRAINT IF EXISTS workspace_templates_scope_check,
+    DROP COLUMN IF EXISTS organization_id;
+
+DROP INDEX IF EXISTS workspaces_organization_id_index;
+ALTER TABLE workspaces DROP COLUMN IF EXISTS organization_id;
+
+DROP INDEX IF EXISTS organization_members_user_id_index;
+DROP TABLE IF EXISTS organization_members;
+DROP TABLE IF EXISTS organizations;
+DROP TYPE IF EXISTS organization_role;
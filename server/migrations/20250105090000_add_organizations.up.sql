@@ -0,0 +1,46 @@
+-- Add up migration script here
+CREATE TYPE organization_role AS ENUM ('member', 'admin', 'owner');
+
+-- Execution quotas are set per organization, since they are what the organization pays for
+CREATE TABLE IF NOT EXISTS organizations (
+    id UUID PRIMARY KEY NOT NULL,
+    slug TEXT NOT NULL UNIQUE,
+    name TEXT NOT NULL,
+    executions_per_hour BIGINT NOT NULL,
+    cpu_seconds_per_day BIGINT NOT NULL,
+    concurrent_runs BIGINT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS organization_members (
+    organization_id UUID NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    role organization_role NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL,
+    PRIMARY KEY (organization_id, user_id)
+);
+
+CREATE INDEX IF NOT EXISTS organization_members_user_id_index ON organization_members(user_id);
+
+ALTER TABLE workspaces
+    ADD COLUMN organization_id UUID REFERENCES organizations(id) ON DELETE CASCADE;
+CREATE INDEX IF NOT EXISTS workspaces_organization_id_index ON workspaces(organization_id);
+
+ALTER TABLE workspace_templates
+    ADD COLUMN organization_id UUID REFERENCES organizations(id) ON DELETE CASCADE,
+    ADD CONSTRAINT workspace_templates_scope_check
+        CHECK (workspace_id IS NULL OR organization_id IS NULL);
+CREATE INDEX IF NOT EXISTS workspace_templates_organization_id_index
+    ON workspace_templates(organization_id);
+
+-- Runs started from an organization's workspaces are billed to the organization
+ALTER TABLE executions
+    ADD COLUMN organization_id UUID REFERENCES organizations(id) ON DELETE SET NULL;
+ALTER TABLE execution_usage
+    ADD COLUMN organization_id UUID REFERENCES organizations(id) ON DELETE SET NULL;
+CREATE INDEX IF NOT EXISTS executions_organization_id_created_at_index
+    ON executions(organization_id, created_at) WHERE organization_id IS NOT NULL;
+CREATE INDEX IF NOT EXISTS execution_usage_organization_id_created_at_index
+    ON execution_usage(organization_id, created_at) WHERE organization_id IS NOT NULL;
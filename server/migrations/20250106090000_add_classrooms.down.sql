@@ -0,0 +1,10 @@
+-- Add down migration script here
+DROP INDEX IF EXISTS submissions_assignment_id_user_id_index;
+DROP TABLE IF EXISTS submissions;
+DROP INDEX IF EXISTS assignments_classroom_id_index;
+DROP TABLE IF EXISTS assignments;
+DROP INDEX IF EXISTS classroom_members_user_id_index;
+DROP TABLE IF EXISTS classroom_members;
+DROP TABLE IF EXISTS classrooms;
+DROP TYPE IF EXISTS submission_status;
+DROP TYPE IF EXISTS classroom_role;
@@ -0,0 +1,59 @@
+-- Add up migration script here
+CREATE TYPE classroom_role AS ENUM ('student', 'instructor');
+CREATE TYPE submission_status AS ENUM ('pending', 'graded', 'failed');
+
+CREATE TABLE IF NOT EXISTS classrooms (
+    id UUID PRIMARY KEY NOT NULL,
+    slug TEXT NOT NULL UNIQUE,
+    name TEXT NOT NULL,
+    join_code TEXT NOT NULL UNIQUE,
+    created_at TIMESTAMPTZ NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS classroom_members (
+    classroom_id UUID NOT NULL REFERENCES classrooms(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    role classroom_role NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL,
+    PRIMARY KEY (classroom_id, user_id)
+);
+
+CREATE INDEX IF NOT EXISTS classroom_members_user_id_index ON classroom_members(user_id);
+
+-- Starter files are copied from a template so that later edits to it leave the assignment be
+CREATE TABLE IF NOT EXISTS assignments (
+    id UUID PRIMARY KEY NOT NULL,
+    classroom_id UUID NOT NULL REFERENCES classrooms(id) ON DELETE CASCADE,
+    title TEXT NOT NULL,
+    description TEXT NOT NULL,
+    language TEXT NOT NULL,
+    entrypoint TEXT,
+    starter_files JSONB NOT NULL,
+    judge JSONB NOT NULL,
+    deadline TIMESTAMPTZ NOT NULL,
+    author_id UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS assignments_classroom_id_index ON assignments(classroom_id);
+
+-- Submissions keep their own snapshot and score, since executions are pruned after a while
+CREATE TABLE IF NOT EXISTS submissions (
+    id UUID PRIMARY KEY NOT NULL,
+    assignment_id UUID NOT NULL REFERENCES assignments(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    workspace_id UUID REFERENCES workspaces(id) ON DELETE SET NULL,
+    execution_id UUID UNIQUE REFERENCES executions(id) ON DELETE SET NULL,
+    files JSONB NOT NULL,
+    status submission_status NOT NULL,
+    passed_cases INTEGER NOT NULL,
+    total_cases INTEGER NOT NULL,
+    score DOUBLE PRECISION,
+    created_at TIMESTAMPTZ NOT NULL,
+    graded_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS submissions_assignment_id_user_id_index
+    ON submissions(assignment_id, user_id);
@@ -0,0 +1,6 @@
+-- Add down migration script here
+DROP TABLE IF EXISTS notification_mutes;
+DROP INDEX IF EXISTS notifications_user_id_unread_index;
+DROP INDEX IF EXISTS notifications_user_id_created_at_index;
+DROP TABLE IF EXISTS notifications;
+DROP TYPE IF EXISTS notification_category;
@@ -0,0 +1,26 @@
+-- Add up migration script here
+CREATE TYPE notification_category AS ENUM ('invitation', 'mention', 'security', 'run');
+
+CREATE TABLE IF NOT EXISTS notifications (
+    id UUID PRIMARY KEY NOT NULL,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    category notification_category NOT NULL,
+    title TEXT NOT NULL,
+    body TEXT NOT NULL,
+    -- IDs of what the notification is about, for clients to link to
+    data JSONB NOT NULL,
+    read_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS notifications_user_id_created_at_index
+    ON notifications(user_id, created_at DESC);
+CREATE INDEX IF NOT EXISTS notifications_user_id_unread_index
+    ON notifications(user_id) WHERE read_at IS NULL;
+
+-- Categories a user does not want to be notified about
+CREATE TABLE IF NOT EXISTS notification_mutes (
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    category notification_category NOT NULL,
+    PRIMARY KEY (user_id, category)
+);
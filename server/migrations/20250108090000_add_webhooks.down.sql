@@ -0,0 +1,9 @@
+-- Add down migration script here
+DROP INDEX IF EXISTS webhook_deliveries_pending_index;
+DROP INDEX IF EXISTS webhook_deliveries_webhook_id_created_at_index;
+DROP TABLE IF EXISTS webhook_deliveries;
+DROP INDEX IF EXISTS webhooks_organization_id_index;
+DROP INDEX IF EXISTS webhooks_user_id_index;
+DROP TABLE IF EXISTS webhooks;
+DROP TYPE IF EXISTS webhook_delivery_status;
+DROP TYPE IF EXISTS webhook_event;
@@ -0,0 +1,58 @@
+-- Add up migration script here
+CREATE TYPE webhook_event AS ENUM (
+    'workspace.created',
+    'workspace.updated',
+    'workspace.deleted',
+    'file.created',
+    'file.updated',
+    'file.deleted',
+    'submission.created',
+    'submission.graded'
+);
+CREATE TYPE webhook_delivery_status AS ENUM ('pending', 'delivered', 'failed');
+
+-- Endpoints registered by a user or an organization, but not both
+CREATE TABLE IF NOT EXISTS webhooks (
+    id UUID PRIMARY KEY NOT NULL,
+    user_id UUID REFERENCES users(id) ON DELETE CASCADE,
+    organization_id UUID REFERENCES organizations(id) ON DELETE CASCADE,
+    url TEXT NOT NULL,
+    -- Key of the HMAC-SHA256 signature sent with every delivery
+    secret TEXT NOT NULL,
+    -- The names of the events to deliver, as a JSON array
+    events JSONB NOT NULL,
+    active BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at TIMESTAMPTZ NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL,
+    CHECK (num_nonnulls(user_id, organization_id) = 1)
+);
+
+CREATE INDEX IF NOT EXISTS webhooks_user_id_index ON webhooks(user_id);
+CREATE INDEX IF NOT EXISTS webhooks_organization_id_index ON webhooks(organization_id);
+
+-- The delivery queue, kept afterwards as the delivery log
+CREATE TABLE IF NOT EXISTS webhook_deliveries (
+    id UUID PRIMARY KEY NOT NULL,
+    webhook_id UUID NOT NULL REFERENCES webhooks(id) ON DELETE CASCADE,
+    event webhook_event NOT NULL,
+    payload JSONB NOT NULL,
+    status webhook_delivery_status NOT NULL DEFAULT 'pending',
+    attempts INT NOT NULL DEFAULT 0,
+    next_attempt_at TIMESTAMPTZ NOT NULL,
+    locked_until TIMESTAMPTZ,
+    -- The outcome of the latest attempt
+    response_status INT,
+    response_body TEXT,
+    error TEXT,
+    duration_ms BIGINT,
+    -- The delivery this one manually repeats
+    redelivery_of UUID REFERENCES webhook_deliveries(id) ON DELETE SET NULL,
+    finished_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS webhook_deliveries_webhook_id_created_at_index
+    ON webhook_deliveries(webhook_id, created_at DESC);
+CREATE INDEX IF NOT EXISTS webhook_deliveries_pending_index
+    ON webhook_deliveries(next_attempt_at) WHERE status = 'pending';
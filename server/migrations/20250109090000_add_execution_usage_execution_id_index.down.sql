@@ -0,0 +1,2 @@
+-- Add down migration script here
+DROP INDEX IF EXISTS execution_usage_execution_id_index;
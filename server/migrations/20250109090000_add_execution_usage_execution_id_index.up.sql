@@ -0,0 +1,2 @@
+-- Add up migration script here
+CREATE UNIQUE INDEX IF NOT EXISTS execution_usage_execution_id_index ON execution_usage(execution_id);
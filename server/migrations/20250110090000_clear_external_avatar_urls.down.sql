@@ -0,0 +1,2 @@
+-- Add down migration script here
+-- Cleared avatar URLs cannot be restored
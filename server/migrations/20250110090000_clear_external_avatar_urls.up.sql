@@ -0,0 +1,6 @@
+-- Add up migration script here
+-- Avatars used to be arbitrary URLs chosen by users, which can no longer be set. Only
+-- uploaded avatars, addressed by the hash of their content, are kept.
+UPDATE users
+SET avatar_url = NULL, updated_at = NOW()
+WHERE avatar_url IS NOT NULL AND avatar_url !~ '/[0-9a-f]{64}/[0-9]+\.png$';
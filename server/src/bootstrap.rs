@@ -1,11 +1,11 @@
-use std::{net::SocketAddr, time::Duration};
+use std::{net::SocketAddr, sync::Arc, time::Duration};
 
 use anyhow::Context;
 use axum::{
     error_handling::HandleErrorLayer,
-    extract::FromRef,
+    extract::{DefaultBodyLimit, FromRef},
     http::{HeaderValue, Method, StatusCode},
-    routing::{delete, get, patch, post},
+    routing::{delete, get, patch, post, put},
     serve, BoxError, Router,
 };
 use axum_extra::extract::cookie::Key;
@@ -17,12 +17,45 @@ use tower_http::{cors::CorsLayer, timeout::TimeoutLayer, trace::TraceLayer};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
 use crate::{
+    collab::CollabHub,
     controllers::{
-        delete_me, delete_user, get_all_users, get_me, get_user, health_check, login, logout,
-        refresh_session_by_body, refresh_session_by_cookie, register, revoke_all_sessions,
-        revoke_my_session, revoke_user_session, update_me, update_user,
+        accept_invitation, add_classroom_member, add_organization_member, collaborate,
+        create_assignment, create_classroom, create_comment_thread, create_invite_link,
+        create_organization, create_organization_webhook, create_snippet, create_template,
+        create_webhook, create_workspace, create_workspace_file, create_workspace_invitation,
+        decline_invitation, delete_assignment, delete_classroom, delete_comment_thread, delete_me,
+        delete_my_avatar, delete_organization, delete_snippet, delete_template, delete_user,
+        delete_webhook, delete_workspace, delete_workspace_file, diff_file_revisions,
+        export_gradebook, export_workspace, fork_snippet, fork_workspace, format_code,
+        get_all_users, get_assignment, get_assignments, get_avatar, get_chat_messages,
+        get_classroom, get_classroom_members, get_comment_threads, get_file_revision,
+        get_file_revisions, get_file_state_at, get_gradebook, get_me, get_my_classrooms,
+        get_my_invitations, get_my_mentions, get_my_notifications, get_my_organizations,
+        get_my_run, get_my_runs, get_my_snippets, get_my_usage, get_my_webhooks, get_my_workspaces,
+        get_notification_preferences, get_organization, get_organization_members,
+        get_organization_usage, get_organization_webhooks, get_organization_workspaces,
+        get_public_snippets, get_run, get_snippet, get_submission, get_submissions, get_template,
+        get_templates, get_usage_report, get_user, get_webhook, get_webhook_deliveries,
+        get_webhook_delivery, get_workspace, get_workspace_file, get_workspace_files,
+        get_workspace_invitations, get_workspace_members, get_workspace_presence,
+        get_workspace_recording, health_check, import_workspace, join_classroom, language_server,
+        leave_workspace, login, logout, mark_all_notifications_read, mark_mention_read,
+        mark_notification_read, post_chat_message, redeem_invite_link, redeliver_webhook_delivery,
+        refresh_session_by_body, refresh_session_by_cookie, register, remove_classroom_member,
+        remove_organization_member, remove_workspace_member, reply_to_comment_thread, rerun,
+        resolve_comment_thread, restore_file_revision, revoke_all_sessions, revoke_my_session,
+        revoke_user_session, revoke_workspace_invitation, search, start_assignment,
+        stream_notifications, submit_assignment, submit_run, transfer_workspace,
+        unresolve_comment_thread, update_me, update_my_avatar, update_notification_preferences,
+        update_organization, update_organization_member, update_snippet, update_template,
+        update_user, update_webhook, update_workspace, update_workspace_file,
+        update_workspace_member,
     },
-    utils::{AppConfig, CaraiResult, DatabaseConfig},
+    executor::RceClient,
+    notifications::NotificationHub,
+    storage::{create_blob_store, BlobStore},
+    utils::{AppConfig, CaraiResult, DatabaseConfig, PermitRegistry},
+    workers::{spawn_cleanup_worker, spawn_execution_workers, spawn_webhook_workers},
 };
 
 pub async fn run_application(config: AppConfig) -> CaraiResult<()> {
@@ -30,6 +63,10 @@ pub async fn run_application(config: AppConfig) -> CaraiResult<()> {
 
     let db_pool = create_connection_pool(config.database()).await?;
 
+    spawn_execution_workers(db_pool.clone(), config.clone())?;
+    spawn_webhook_workers(db_pool.clone(), config.clone())?;
+    spawn_cleanup_worker(db_pool.clone(), config.clone());
+
     let app = create_router(db_pool, config.clone());
 
     let address = SocketAddr::new(config.server().host().parse()?, *config.server().port());
@@ -81,6 +118,18 @@ pub struct AppState {
     config: AppConfig,
     #[getset(get = "pub")]
     key: Key,
+    #[getset(get = "pub")]
+    collab: CollabHub,
+    #[getset(get = "pub")]
+    rce: RceClient,
+    #[getset(get = "pub")]
+    lsp: PermitRegistry,
+    #[getset(get = "pub")]
+    formats: PermitRegistry,
+    #[getset(get = "pub")]
+    notifications: NotificationHub,
+    #[getset(get = "pub")]
+    blobs: Arc<dyn BlobStore>,
 }
 
 impl FromRef<AppState> for Key {
@@ -91,10 +140,18 @@ impl FromRef<AppState> for Key {
 
 pub fn create_router(db_pool: PgPool, config: AppConfig) -> Router {
     let key = Key::from(config.server().cookie_secret().as_bytes());
+    let rce = RceClient::new(config.rce()).expect("Failed to build RCE client");
+    let blobs = create_blob_store(config.storage()).expect("Failed to build blob store");
     let state = AppState {
         db_pool,
         config,
         key,
+        collab: CollabHub::default(),
+        rce,
+        lsp: PermitRegistry::default(),
+        formats: PermitRegistry::default(),
+        notifications: NotificationHub::default(),
+        blobs,
     };
     let timeout = Duration::from_secs(*state.config.server().timeout_in_secs());
     let origins: Vec<HeaderValue> = state
@@ -139,11 +196,22 @@ pub fn create_router(db_pool: PgPool, config: AppConfig) -> Router {
         .route("/register", post(register))
         .route("/", get(get_all_users))
         .route("/me", get(get_me))
+        .route("/me/usage", get(get_my_usage))
+        .route("/me/runs", get(get_my_runs))
+        .route("/me/runs/:id", get(get_my_run))
+        .route("/usage", get(get_usage_report))
         .route("/:id", get(get_user))
         .route("/:id", patch(update_user))
         .route("/me", patch(update_me))
         .route("/:id", delete(delete_user))
-        .route("/me", delete(delete_me));
+        .route("/me", delete(delete_me))
+        .route(
+            "/me/avatar",
+            put(update_my_avatar).layer(DefaultBodyLimit::max(
+                *state.config.avatar().max_upload_bytes(),
+            )),
+        )
+        .route("/me/avatar", delete(delete_my_avatar));
 
     let auth_router = Router::new()
         .route("/login", post(login))
@@ -156,11 +224,184 @@ pub fn create_router(db_pool: PgPool, config: AppConfig) -> Router {
         .route("/:id", patch(revoke_user_session))
         .route("/", patch(revoke_all_sessions));
 
+    let runs_router = Router::new()
+        .route("/", post(submit_run))
+        .route("/:id", get(get_run))
+        .route("/:id/rerun", post(rerun));
+
+    let workspaces_router = Router::new()
+        .route("/", post(create_workspace))
+        .route("/", get(get_my_workspaces))
+        .route("/:id", get(get_workspace))
+        .route("/:id", patch(update_workspace))
+        .route("/:id", delete(delete_workspace))
+        .route(
+            "/import",
+            post(import_workspace).layer(DefaultBodyLimit::max(
+                *state.config.archive().max_upload_bytes(),
+            )),
+        )
+        .route("/:id/fork", post(fork_workspace))
+        .route("/:id/export", get(export_workspace))
+        .route("/:id/files", post(create_workspace_file))
+        .route("/:id/files", get(get_workspace_files))
+        .route("/:id/files/:file_id", get(get_workspace_file))
+        .route("/:id/files/:file_id", patch(update_workspace_file))
+        .route("/:id/files/:file_id", delete(delete_workspace_file))
+        .route("/:id/files/:file_id/revisions", get(get_file_revisions))
+        .route(
+            "/:id/files/:file_id/revisions/:number",
+            get(get_file_revision),
+        )
+        .route(
+            "/:id/files/:file_id/revisions/:number/restore",
+            post(restore_file_revision),
+        )
+        .route("/:id/files/:file_id/diff", get(diff_file_revisions))
+        .route("/:id/files/:file_id/state", get(get_file_state_at))
+        .route("/:id/files/:file_id/collab", get(collaborate))
+        .route("/:id/files/:file_id/comments", get(get_comment_threads))
+        .route("/:id/files/:file_id/comments", post(create_comment_thread))
+        .route(
+            "/:id/files/:file_id/comments/:thread_id",
+            delete(delete_comment_thread),
+        )
+        .route(
+            "/:id/files/:file_id/comments/:thread_id/replies",
+            post(reply_to_comment_thread),
+        )
+        .route(
+            "/:id/files/:file_id/comments/:thread_id/resolve",
+            post(resolve_comment_thread),
+        )
+        .route(
+            "/:id/files/:file_id/comments/:thread_id/unresolve",
+            post(unresolve_comment_thread),
+        )
+        .route("/:id/chat", get(get_chat_messages))
+        .route("/:id/chat", post(post_chat_message))
+        .route("/:id/presence", get(get_workspace_presence))
+        .route("/:id/recording", get(get_workspace_recording))
+        .route("/:id/lsp", get(language_server))
+        .route("/:id/members", get(get_workspace_members))
+        .route("/:id/members/:user_id", patch(update_workspace_member))
+        .route("/:id/members/:user_id", delete(remove_workspace_member))
+        .route("/:id/leave", post(leave_workspace))
+        .route("/:id/transfer", post(transfer_workspace))
+        .route("/:id/invitations", post(create_workspace_invitation))
+        .route("/:id/invitations", get(get_workspace_invitations))
+        .route(
+            "/:id/invitations/:invitation_id",
+            delete(revoke_workspace_invitation),
+        )
+        .route("/:id/invite-links", post(create_invite_link));
+
+    let mentions_router = Router::new()
+        .route("/", get(get_my_mentions))
+        .route("/:id/read", post(mark_mention_read));
+
+    let notifications_router = Router::new()
+        .route("/", get(get_my_notifications))
+        .route("/read", post(mark_all_notifications_read))
+        .route("/:id/read", post(mark_notification_read))
+        .route("/preferences", get(get_notification_preferences))
+        .route("/preferences", put(update_notification_preferences))
+        .route("/stream", get(stream_notifications));
+
+    let webhooks_router = Router::new()
+        .route("/", post(create_webhook))
+        .route("/", get(get_my_webhooks))
+        .route("/:id", get(get_webhook))
+        .route("/:id", patch(update_webhook))
+        .route("/:id", delete(delete_webhook))
+        .route("/:id/deliveries", get(get_webhook_deliveries))
+        .route("/:id/deliveries/:delivery_id", get(get_webhook_delivery))
+        .route(
+            "/:id/deliveries/:delivery_id/redeliver",
+            post(redeliver_webhook_delivery),
+        );
+
+    let invitations_router = Router::new()
+        .route("/", get(get_my_invitations))
+        .route("/:id/accept", post(accept_invitation))
+        .route("/:id/decline", post(decline_invitation))
+        .route("/links/:token/accept", post(redeem_invite_link));
+
+    let snippets_router = Router::new()
+        .route("/", post(create_snippet))
+        .route("/", get(get_public_snippets))
+        .route("/mine", get(get_my_snippets))
+        .route("/:slug", get(get_snippet))
+        .route("/:slug", patch(update_snippet))
+        .route("/:slug", delete(delete_snippet))
+        .route("/:slug/fork", post(fork_snippet));
+
+    let templates_router = Router::new()
+        .route("/", post(create_template))
+        .route("/", get(get_templates))
+        .route("/:slug", get(get_template))
+        .route("/:slug", patch(update_template))
+        .route("/:slug", delete(delete_template));
+
+    let classrooms_router = Router::new()
+        .route("/", post(create_classroom))
+        .route("/", get(get_my_classrooms))
+        .route("/join", post(join_classroom))
+        .route("/:slug", get(get_classroom))
+        .route("/:slug", delete(delete_classroom))
+        .route("/:slug/members", get(get_classroom_members))
+        .route("/:slug/members", post(add_classroom_member))
+        .route("/:slug/members/:user_id", delete(remove_classroom_member))
+        .route("/:slug/assignments", post(create_assignment))
+        .route("/:slug/assignments", get(get_assignments))
+        .route("/:slug/assignments/:id", get(get_assignment))
+        .route("/:slug/assignments/:id", delete(delete_assignment))
+        .route("/:slug/assignments/:id/workspace", post(start_assignment))
+        .route(
+            "/:slug/assignments/:id/submissions",
+            post(submit_assignment),
+        )
+        .route("/:slug/assignments/:id/submissions", get(get_submissions))
+        .route("/:slug/submissions/:id", get(get_submission))
+        .route("/:slug/gradebook", get(get_gradebook))
+        .route("/:slug/gradebook.csv", get(export_gradebook));
+
+    let organizations_router = Router::new()
+        .route("/", post(create_organization))
+        .route("/", get(get_my_organizations))
+        .route("/:slug", get(get_organization))
+        .route("/:slug", patch(update_organization))
+        .route("/:slug", delete(delete_organization))
+        .route("/:slug/members", get(get_organization_members))
+        .route("/:slug/members", post(add_organization_member))
+        .route("/:slug/members/:user_id", patch(update_organization_member))
+        .route(
+            "/:slug/members/:user_id",
+            delete(remove_organization_member),
+        )
+        .route("/:slug/workspaces", get(get_organization_workspaces))
+        .route("/:slug/usage", get(get_organization_usage))
+        .route("/:slug/webhooks", post(create_organization_webhook))
+        .route("/:slug/webhooks", get(get_organization_webhooks));
+
     Router::new()
         .route("/", get(health_check))
+        .route("/search", get(search))
+        .route("/format", post(format_code))
+        .route("/avatars/:hash/:file", get(get_avatar))
         .nest("/users", users_router)
         .nest("/auth", auth_router)
         .nest("/sessions", session_router)
+        .nest("/runs", runs_router)
+        .nest("/workspaces", workspaces_router)
+        .nest("/invitations", invitations_router)
+        .nest("/mentions", mentions_router)
+        .nest("/notifications", notifications_router)
+        .nest("/webhooks", webhooks_router)
+        .nest("/snippets", snippets_router)
+        .nest("/templates", templates_router)
+        .nest("/organizations", organizations_router)
+        .nest("/classes", classrooms_router)
         .layer(trace_layer)
         .layer(cors_layer)
         .layer(timeout_layer)
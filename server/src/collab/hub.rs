@@ -0,0 +1,248 @@
+#![deny(missing_docs)]
+//! In-memory registry of the collaboration rooms open on this server.
+//!
+//! A room exists while at least one client is connected to a file. Updates are
+//! persisted and fanned out while holding the room's write lock, so every client
+//! receives them in sequence order; this assumes a single server instance.
+//! The hub also keeps the presence of every participant, so it can be listed per
+//! workspace without asking the clients.
+
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicI64, Ordering},
+        Arc, Mutex,
+    },
+};
+
+use chrono::Utc;
+use tokio::sync::{
+    broadcast::{self, error::RecvError},
+    Mutex as AsyncMutex, MutexGuard,
+};
+use uuid::Uuid;
+
+use super::{Frame, Participant};
+
+/// Capacity of each room's fan-out channel; slower clients are disconnected.
+const ROOM_CAPACITY: usize = 256;
+
+/// A frame published to a room, tagged with the connection that sent it.
+#[derive(Debug, Clone)]
+pub struct Broadcast {
+    /// The connection that published the frame.
+    pub from: Uuid,
+    /// The frame, already encoded.
+    pub frame: Arc<Vec<u8>>,
+}
+
+#[derive(Debug)]
+struct Room {
+    sender: broadcast::Sender<Broadcast>,
+    write_lock: AsyncMutex<()>,
+    saved_seq: AtomicI64,
+}
+
+#[derive(Debug)]
+struct RoomEntry {
+    room: Arc<Room>,
+    workspace_id: Uuid,
+    connections: usize,
+    participants: HashMap<Uuid, Participant>,
+}
+
+/// Tracks open rooms, keyed by workspace file ID.
+#[derive(Debug, Clone, Default)]
+pub struct CollabHub {
+    rooms: Arc<Mutex<HashMap<Uuid, RoomEntry>>>,
+}
+
+impl CollabHub {
+    /// Joins the room of a workspace file, opening it if this is the first connection.
+    pub fn join(&self, workspace_id: Uuid, file_id: Uuid) -> RoomHandle {
+        let mut rooms = self.rooms.lock().unwrap_or_else(|e| e.into_inner());
+        let entry = rooms.entry(file_id).or_insert_with(|| RoomEntry {
+            room: Arc::new(Room {
+                sender: broadcast::channel(ROOM_CAPACITY).0,
+                write_lock: AsyncMutex::new(()),
+                saved_seq: AtomicI64::new(0),
+            }),
+            workspace_id,
+            connections: 0,
+            participants: HashMap::new(),
+        });
+        entry.connections += 1;
+
+        RoomHandle {
+            hub: self.clone(),
+            workspace_id,
+            file_id,
+            connection_id: Uuid::new_v4(),
+            receiver: entry.room.sender.subscribe(),
+            room: entry.room.clone(),
+        }
+    }
+
+    /// Number of connections currently in a file's room.
+    pub fn connections(&self, file_id: Uuid) -> usize {
+        let rooms = self.rooms.lock().unwrap_or_else(|e| e.into_inner());
+        rooms.get(&file_id).map_or(0, |entry| entry.connections)
+    }
+
+    /// Participants present in any file of a workspace, in the order they joined.
+    pub fn presence(&self, workspace_id: Uuid) -> Vec<Participant> {
+        let rooms = self.rooms.lock().unwrap_or_else(|e| e.into_inner());
+        let mut participants: Vec<Participant> = rooms
+            .values()
+            .filter(|entry| entry.workspace_id == workspace_id)
+            .flat_map(|entry| entry.participants.values().cloned())
+            .collect();
+        participants.sort_by_key(|participant| participant.joined_at);
+        participants
+    }
+
+    /// Publishes an encoded frame to every room of a workspace.
+    ///
+    /// `from` identifies the sending connection, or is nil for frames that did not
+    /// come from a collaborator's socket.
+    pub fn publish_to_workspace(&self, workspace_id: Uuid, from: Uuid, frame: Vec<u8>) {
+        let rooms = self.rooms.lock().unwrap_or_else(|e| e.into_inner());
+        let frame = Arc::new(frame);
+        for entry in rooms
+            .values()
+            .filter(|entry| entry.workspace_id == workspace_id)
+        {
+            let _ = entry.room.sender.send(Broadcast {
+                from,
+                frame: frame.clone(),
+            });
+        }
+    }
+
+    fn with_entry<R>(&self, file_id: Uuid, f: impl FnOnce(&mut RoomEntry) -> R) -> Option<R> {
+        let mut rooms = self.rooms.lock().unwrap_or_else(|e| e.into_inner());
+        rooms.get_mut(&file_id).map(f)
+    }
+
+    /// Leaves a room, returning whether the connection was still present in it.
+    fn leave(&self, file_id: Uuid, connection_id: Uuid) -> bool {
+        let mut rooms = self.rooms.lock().unwrap_or_else(|e| e.into_inner());
+        let Some(entry) = rooms.get_mut(&file_id) else {
+            return false;
+        };
+        let was_present = entry.participants.remove(&connection_id).is_some();
+        entry.connections -= 1;
+        if entry.connections == 0 {
+            rooms.remove(&file_id);
+        }
+        was_present
+    }
+}
+
+/// A connection's membership in a room; leaves the room when dropped.
+#[derive(Debug)]
+pub struct RoomHandle {
+    hub: CollabHub,
+    workspace_id: Uuid,
+    file_id: Uuid,
+    connection_id: Uuid,
+    receiver: broadcast::Receiver<Broadcast>,
+    room: Arc<Room>,
+}
+
+impl RoomHandle {
+    /// The workspace the room's file belongs to.
+    pub fn workspace_id(&self) -> Uuid {
+        self.workspace_id
+    }
+
+    /// The unique ID of this connection within the room.
+    pub fn connection_id(&self) -> Uuid {
+        self.connection_id
+    }
+
+    /// Serializes writers so that stored sequence numbers match broadcast order.
+    pub async fn lock_writes(&self) -> MutexGuard<'_, ()> {
+        self.room.write_lock.lock().await
+    }
+
+    /// Claims the saving of the text reported as of update `seq`, returning `false` when
+    /// text reflecting that update or a later one was already saved during this room.
+    pub fn claim_save(&self, seq: i64) -> bool {
+        self.room.saved_seq.fetch_max(seq, Ordering::SeqCst) < seq
+    }
+
+    /// Publishes an encoded frame to every room of this room's workspace, including this one.
+    pub fn publish_to_workspace(&self, frame: Vec<u8>) {
+        self.hub
+            .publish_to_workspace(self.workspace_id, self.connection_id, frame);
+    }
+
+    /// Publishes an encoded frame to every connection in the room, including this one.
+    pub fn publish(&self, frame: Vec<u8>) {
+        // Sending only fails when nobody listens, which cannot outlive this handle
+        let _ = self.room.sender.send(Broadcast {
+            from: self.connection_id,
+            frame: Arc::new(frame),
+        });
+    }
+
+    /// Records or replaces this connection's presence in the room.
+    pub fn set_presence(&self, participant: Participant) {
+        self.hub.with_entry(self.file_id, |entry| {
+            entry.participants.insert(self.connection_id, participant);
+        });
+    }
+
+    /// Marks this connection as active, returning whether its presence is still listed.
+    pub fn touch_presence(&self) -> bool {
+        self.hub
+            .with_entry(self.file_id, |entry| {
+                entry
+                    .participants
+                    .get_mut(&self.connection_id)
+                    .map(|participant| participant.last_active_at = Utc::now())
+                    .is_some()
+            })
+            .unwrap_or(false)
+    }
+
+    /// Removes this connection's presence, returning whether it was listed.
+    pub fn clear_presence(&self) -> bool {
+        self.hub
+            .with_entry(self.file_id, |entry| {
+                entry.participants.remove(&self.connection_id).is_some()
+            })
+            .unwrap_or(false)
+    }
+
+    /// The other participants present in this room.
+    pub fn participants(&self) -> Vec<Participant> {
+        self.hub
+            .with_entry(self.file_id, |entry| {
+                entry
+                    .participants
+                    .iter()
+                    .filter(|(id, _)| **id != self.connection_id)
+                    .map(|(_, participant)| participant.clone())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Waits for the next frame published to the room.
+    pub async fn recv(&mut self) -> Result<Broadcast, RecvError> {
+        self.receiver.recv().await
+    }
+}
+
+impl Drop for RoomHandle {
+    fn drop(&mut self) {
+        if self.hub.leave(self.file_id, self.connection_id) {
+            let departed = Frame::Departed {
+                connection_id: self.connection_id,
+            };
+            self.publish(departed.encode());
+        }
+    }
+}
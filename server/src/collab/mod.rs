@@ -0,0 +1,9 @@
+mod hub;
+mod presence;
+mod protocol;
+mod session;
+
+pub use hub::*;
+pub use presence::*;
+pub use protocol::*;
+pub use session::*;
@@ -0,0 +1,92 @@
+#![deny(missing_docs)]
+//! Awareness state that collaborators share about themselves while connected.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use validator::{Validate, ValidationError};
+
+use crate::models::WorkspaceRole;
+
+/// Colours handed out to collaborators that have not picked one yet.
+const PALETTE: [&str; 8] = [
+    "#e6194b", "#3cb44b", "#4363d8", "#f58231", "#911eb4", "#42d4f4", "#f032e6", "#469990",
+];
+
+/// A zero-based line and column in a file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Position {
+    /// Zero-based line number.
+    pub line: u32,
+    /// Zero-based column, in UTF-16 code units as reported by browser editors.
+    pub column: u32,
+}
+
+/// A selected range; `head` is where the cursor sits and may precede `anchor`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SelectionRange {
+    /// Where the selection started.
+    pub anchor: Position,
+    /// Where the selection ends.
+    pub head: Position,
+}
+
+/// What a collaborator broadcasts about themselves in a [`super::Frame::Awareness`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Validate)]
+pub struct PresenceState {
+    /// Display colour as `#rrggbb`.
+    #[validate(custom(function = "validate_color"))]
+    pub color: String,
+    /// Cursor position, absent while the editor is not focused.
+    #[serde(default)]
+    pub cursor: Option<Position>,
+    /// Selected ranges, for editors with multiple cursors.
+    #[validate(length(max = 100))]
+    #[serde(default)]
+    pub selections: Vec<SelectionRange>,
+}
+
+impl PresenceState {
+    /// The state of a collaborator who has not reported anything yet.
+    pub fn initial(user_id: Uuid) -> Self {
+        Self {
+            color: PALETTE[user_id.as_u128() as usize % PALETTE.len()].to_string(),
+            cursor: None,
+            selections: Vec::new(),
+        }
+    }
+}
+
+fn validate_color(color: &str) -> Result<(), ValidationError> {
+    let is_hex = color.len() == 7
+        && color.starts_with('#')
+        && color[1..].chars().all(|c| c.is_ascii_hexdigit());
+    if !is_hex {
+        return Err(ValidationError::new("color")
+            .with_message("Colours must be formatted as #rrggbb".into()));
+    }
+    Ok(())
+}
+
+/// A collaborator currently present in a file's room.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Participant {
+    /// Identifies the connection, since a user may join from several tabs.
+    pub connection_id: Uuid,
+    /// The collaborating user.
+    pub user_id: Uuid,
+    /// The collaborating user's name.
+    pub username: String,
+    /// The user's role in the workspace, which decides whether they may edit.
+    pub role: WorkspaceRole,
+    /// The file the connection is editing.
+    pub file_id: Uuid,
+    /// The latest awareness state the collaborator reported.
+    #[serde(flatten)]
+    pub state: PresenceState,
+    /// When the collaborator joined the room.
+    pub joined_at: DateTime<Utc>,
+    /// When the server last heard from the collaborator.
+    pub last_active_at: DateTime<Utc>,
+}
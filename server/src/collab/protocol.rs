@@ -0,0 +1,201 @@
+#![deny(missing_docs)]
+//! Binary wire format of collaborative editing sessions.
+//!
+//! Every frame is a one-byte tag, a big-endian `i64` sequence number and a payload.
+//! Payloads of snapshots and updates are opaque Yjs updates (as produced by
+//! `Y.encodeStateAsUpdate` and the document's `update` event); the server stores and
+//! relays them without interpreting them, relying on Yjs updates being commutative
+//! and idempotent. Awareness and chat payloads are JSON, since the server reads them.
+//!
+//! The file's text is the root `Y.Text` named [`TEXT_NAME`]. As the server cannot
+//! merge Yjs updates itself, clients report the merged text with [`Frame::Content`],
+//! and a document that has nothing stored yet is seeded from the file with
+//! [`seed_update`].
+
+use anyhow::{anyhow, bail};
+use uuid::Uuid;
+
+use crate::utils::CaraiResult;
+
+const HEADER_LEN: usize = 9;
+
+/// Name of the root `Y.Text` holding the file's text.
+pub const TEXT_NAME: &str = "content";
+/// Yjs client ID the server seeds documents under; clients pick random 32-bit IDs.
+const SEED_CLIENT_ID: u64 = 0;
+/// Yjs reference number of string content.
+const CONTENT_STRING_REF: u8 = 4;
+
+/// A message exchanged over a collaboration WebSocket.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Frame {
+    /// A merged document covering every update up to and including `seq`.
+    ///
+    /// Sent by the server to late joiners, and by clients when asked to compact.
+    Snapshot {
+        /// The last update sequence number merged into `data`.
+        seq: i64,
+        /// The encoded Yjs document state.
+        data: Vec<u8>,
+    },
+    /// An incremental Yjs update; clients send `seq` as zero and the server stamps it.
+    Update {
+        /// The sequence number the server stored the update under.
+        seq: i64,
+        /// The encoded Yjs update.
+        data: Vec<u8>,
+    },
+    /// Confirms that the receiving client's own update was stored under `seq`.
+    Ack {
+        /// The sequence number of the stored update.
+        seq: i64,
+    },
+    /// Marks the end of the catch-up sent after joining.
+    Synced {
+        /// The last sequence number included in the catch-up.
+        seq: i64,
+    },
+    /// Asks the client to reply with a [`Frame::Snapshot`] of its whole document,
+    /// followed by its [`Frame::Content`].
+    RequestSnapshot,
+    /// A collaborator's presence; `seq` is always zero.
+    ///
+    /// Clients send a JSON [`super::PresenceState`], and the server relays it to the
+    /// room as a JSON [`super::Participant`].
+    Awareness {
+        /// The JSON encoded presence.
+        data: Vec<u8>,
+    },
+    /// A collaborator disconnected or went idle, so their presence expired.
+    Departed {
+        /// The connection of the [`super::Participant`] that left.
+        connection_id: Uuid,
+    },
+    /// A message in the workspace chat; `seq` is always zero.
+    ///
+    /// Clients send a JSON [`crate::dto::ChatMessageReqDto`], and the server relays the
+    /// stored message to every room of the workspace, including the sender, as a JSON
+    /// [`crate::dto::ChatMessageResDto`].
+    Chat {
+        /// The JSON encoded message.
+        data: Vec<u8>,
+    },
+    /// The document's merged text, saved as the file's content.
+    ///
+    /// Sent by clients after a [`Frame::Snapshot`], and whenever their edits settle so
+    /// the file is up to date once the last collaborator leaves.
+    Content {
+        /// The last update sequence number reflected in `data`.
+        seq: i64,
+        /// The UTF-8 encoded text.
+        data: Vec<u8>,
+    },
+}
+
+impl Frame {
+    fn tag(&self) -> u8 {
+        match self {
+            Frame::Snapshot { .. } => 0,
+            Frame::Update { .. } => 1,
+            Frame::Ack { .. } => 2,
+            Frame::Synced { .. } => 3,
+            Frame::RequestSnapshot => 4,
+            Frame::Awareness { .. } => 5,
+            Frame::Departed { .. } => 6,
+            Frame::Chat { .. } => 7,
+            Frame::Content { .. } => 8,
+        }
+    }
+
+    /// Whether the frame writes to the workspace, as edits and chat messages do.
+    pub fn is_write(&self) -> bool {
+        matches!(
+            self,
+            Frame::Snapshot { .. }
+                | Frame::Update { .. }
+                | Frame::Chat { .. }
+                | Frame::Content { .. }
+        )
+    }
+
+    /// Serializes the frame for a binary WebSocket message.
+    pub fn encode(&self) -> Vec<u8> {
+        let (seq, data): (i64, &[u8]) = match self {
+            Frame::Snapshot { seq, data }
+            | Frame::Update { seq, data }
+            | Frame::Content { seq, data } => (*seq, data),
+            Frame::Ack { seq } | Frame::Synced { seq } => (*seq, &[]),
+            Frame::RequestSnapshot => (0, &[]),
+            Frame::Awareness { data } | Frame::Chat { data } => (0, data),
+            Frame::Departed { connection_id } => (0, connection_id.as_bytes()),
+        };
+        let mut bytes = Vec::with_capacity(HEADER_LEN + data.len());
+        bytes.push(self.tag());
+        bytes.extend_from_slice(&seq.to_be_bytes());
+        bytes.extend_from_slice(data);
+        bytes
+    }
+
+    /// Parses a binary WebSocket message.
+    pub fn decode(bytes: &[u8]) -> CaraiResult<Self> {
+        if bytes.len() < HEADER_LEN {
+            bail!("Collab frame is shorter than its header");
+        }
+        let (header, data) = bytes.split_at(HEADER_LEN);
+        let seq = i64::from_be_bytes(
+            header[1..]
+                .try_into()
+                .map_err(|_| anyhow!("Malformed collab frame header"))?,
+        );
+        let data = data.to_vec();
+        Ok(match header[0] {
+            0 => Frame::Snapshot { seq, data },
+            1 => Frame::Update { seq, data },
+            2 => Frame::Ack { seq },
+            3 => Frame::Synced { seq },
+            4 => Frame::RequestSnapshot,
+            5 => Frame::Awareness { data },
+            6 => Frame::Departed {
+                connection_id: Uuid::from_slice(&data)
+                    .map_err(|_| anyhow!("Malformed collab departure frame"))?,
+            },
+            7 => Frame::Chat { data },
+            8 => Frame::Content { seq, data },
+            tag => bail!("Unknown collab frame tag {}", tag),
+        })
+    }
+}
+
+/// Encodes a Yjs update that inserts `text` into an empty document.
+///
+/// The update holds a single string item in the root `Y.Text` named [`TEXT_NAME`],
+/// in the v1 encoding of `Y.encodeStateAsUpdate`, and deletes nothing.
+pub fn seed_update(text: &str) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(text.len() + TEXT_NAME.len() + 16);
+    // One client with one struct, starting at clock zero
+    write_var_uint(&mut bytes, 1);
+    write_var_uint(&mut bytes, 1);
+    write_var_uint(&mut bytes, SEED_CLIENT_ID);
+    write_var_uint(&mut bytes, 0);
+    // Without origins, the item names its parent by root type key
+    bytes.push(CONTENT_STRING_REF);
+    write_var_uint(&mut bytes, 1);
+    write_var_string(&mut bytes, TEXT_NAME);
+    write_var_string(&mut bytes, text);
+    // An empty delete set
+    write_var_uint(&mut bytes, 0);
+    bytes
+}
+
+fn write_var_uint(bytes: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        bytes.push((value as u8 & 0x7f) | 0x80);
+        value >>= 7;
+    }
+    bytes.push(value as u8);
+}
+
+fn write_var_string(bytes: &mut Vec<u8>, value: &str) {
+    write_var_uint(bytes, value.len() as u64);
+    bytes.extend_from_slice(value.as_bytes());
+}
@@ -0,0 +1,355 @@
+#![deny(missing_docs)]
+//! Drives a single collaborator's WebSocket connection to a file's room.
+
+use std::time::Duration;
+
+use axum::extract::ws::{close_code, CloseFrame, Message, WebSocket};
+use chrono::Utc;
+use futures_util::{stream::SplitSink, SinkExt, StreamExt};
+use sqlx::PgPool;
+use tokio::{
+    sync::broadcast::error::RecvError,
+    time::{interval, Instant},
+};
+use uuid::Uuid;
+use validator::Validate;
+
+use super::{Frame, Participant, PresenceState, RoomHandle};
+use crate::{
+    bootstrap::AppState,
+    dto::{ChatMessageReqDto, ChatMessageResDto},
+    models::{ChatMessage, CollabSnapshot, SessionEvent, User, WorkspaceRole},
+    services::{
+        compact_collab_document, count_collab_updates_after, create_chat_message,
+        create_collab_update, get_collab_document, get_latest_collab_seq, get_workspace_file_by_id,
+        record_session_event, refresh_workspace_role, save_collab_content, seed_collab_document,
+    },
+    utils::CaraiResult,
+};
+
+/// How often a collaborator's role is looked up again while they only read.
+const ACCESS_CHECK_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Serves a collaborator until they disconnect.
+///
+/// On joining, the client receives the stored snapshot (if any) followed by every
+/// update stored after it, then a [`Frame::Synced`] marker. A document with nothing
+/// stored yet is first seeded with the file's content. Afterwards, updates from
+/// any participant are persisted and relayed to the others; the sender gets an
+/// [`Frame::Ack`] instead. Once enough updates pile up, the server asks a client
+/// for a merged [`Frame::Snapshot`], which replaces the updates it covers. Snapshots
+/// nobody asked for, or covering updates that were never stored, are dropped. The
+/// merged text clients report with [`Frame::Content`] is saved as the file's content.
+///
+/// The collaborator is listed as present from the moment they join, and after the
+/// catch-up receives a [`Frame::Awareness`] for every other participant. Their own
+/// awareness reports are relayed to the room. Presence expires with a
+/// [`Frame::Departed`] once they disconnect or stay silent for the idle timeout,
+/// and is restored by their next message.
+///
+/// Every stored update and snapshot is also recorded in the workspace's session
+/// recording, which outlives compaction so the session can be played back.
+///
+/// Collaborators below the editor role follow along read-only: their updates,
+/// snapshots and content are dropped, while their awareness is still shared. Commenters and
+/// above may also post to the workspace chat with [`Frame::Chat`]. The role is looked
+/// up again before every such write and every few seconds, so collaborators who lose
+/// access to the workspace are disconnected and demoted ones become read-only.
+pub async fn serve_collab_connection(
+    socket: WebSocket,
+    state: AppState,
+    workspace_id: Uuid,
+    file_id: Uuid,
+    user: User,
+    role: WorkspaceRole,
+) {
+    let mut room = state.collab().join(workspace_id, file_id);
+    tracing::debug!("User {} joined collab room {}", user.id, file_id);
+    if let Err(e) = run_session(socket, &state, &mut room, file_id, &user, role).await {
+        tracing::warn!("Collab session on {} ended with an error: {}", file_id, e);
+    }
+    tracing::debug!("User {} left collab room {}", user.id, file_id);
+}
+
+async fn run_session(
+    socket: WebSocket,
+    state: &AppState,
+    room: &mut RoomHandle,
+    file_id: Uuid,
+    user: &User,
+    mut role: WorkspaceRole,
+) -> CaraiResult<()> {
+    let (mut sink, mut stream) = socket.split();
+    let db_pool = state.db_pool();
+    let user_id = user.id;
+
+    let now = Utc::now();
+    let mut presence = Participant {
+        connection_id: room.connection_id(),
+        user_id,
+        username: user.username.clone(),
+        role,
+        file_id,
+        state: PresenceState::initial(user_id),
+        joined_at: now,
+        last_active_at: now,
+    };
+    announce(room, &presence)?;
+
+    let guard = room.lock_writes().await;
+    if let Some(file) = get_workspace_file_by_id(db_pool, room.workspace_id(), file_id).await? {
+        if let Some(update) = seed_collab_document(db_pool, &file, user_id).await? {
+            let event = SessionEvent::edit(
+                room.workspace_id(),
+                file_id,
+                user_id,
+                update.seq,
+                update.data,
+            );
+            record_session_event(db_pool, &event).await?;
+        }
+    }
+    drop(guard);
+
+    // Subscribing before loading means nothing stored meanwhile is missed; anything
+    // received twice is harmless since applying a Yjs update again is a no-op
+    let (snapshot, updates) = get_collab_document(db_pool, file_id).await?;
+    let mut snapshot_seq = snapshot.as_ref().map_or(0, |snapshot| snapshot.seq);
+    let mut synced_seq = snapshot_seq;
+    if let Some(snapshot) = snapshot {
+        send(
+            &mut sink,
+            Frame::Snapshot {
+                seq: snapshot.seq,
+                data: snapshot.data,
+            },
+        )
+        .await?;
+    }
+    for update in updates {
+        synced_seq = update.seq;
+        send(
+            &mut sink,
+            Frame::Update {
+                seq: update.seq,
+                data: update.data,
+            },
+        )
+        .await?;
+    }
+    send(&mut sink, Frame::Synced { seq: synced_seq }).await?;
+    for participant in room.participants() {
+        send(&mut sink, awareness_frame(&participant)?).await?;
+    }
+
+    let snapshot_interval = *state.config().collab().snapshot_interval_updates();
+    let mut snapshot_requested = false;
+    // A zero timeout would make the idle check's period zero, which panics
+    let idle_timeout = Duration::from_secs(*state.config().collab().presence_idle_timeout_secs())
+        .max(Duration::from_secs(1));
+    let mut last_active = Instant::now();
+    let mut idle_check = interval(idle_timeout / 2);
+    let mut access_check = interval(ACCESS_CHECK_INTERVAL);
+    loop {
+        tokio::select! {
+            message = stream.next() => {
+                let bytes = match message {
+                    Some(Ok(Message::Binary(bytes))) => bytes,
+                    Some(Ok(Message::Close(_))) | None => return Ok(()),
+                    Some(Ok(_)) => continue,
+                    Some(Err(e)) => return Err(e.into()),
+                };
+                let frame = Frame::decode(&bytes)?;
+                if frame.is_write() {
+                    let Some(current) = refresh_role(db_pool, room, user, &mut presence).await?
+                    else {
+                        return close(&mut sink, "Access to the workspace was revoked").await;
+                    };
+                    role = current;
+                }
+                last_active = Instant::now();
+                if !room.touch_presence() && !matches!(frame, Frame::Awareness { .. }) {
+                    // The collaborator went idle earlier and is back
+                    presence.last_active_at = Utc::now();
+                    announce(room, &presence)?;
+                }
+                match frame {
+                    Frame::Update { .. } | Frame::Snapshot { .. } | Frame::Content { .. }
+                        if role < WorkspaceRole::Editor =>
+                    {
+                        tracing::debug!("Dropping edit from read-only collaborator {}", user_id);
+                    }
+                    Frame::Update { data, .. } => {
+                        let guard = room.lock_writes().await;
+                        let update = create_collab_update(db_pool, file_id, user_id, &data).await?;
+                        room.publish(Frame::Update { seq: update.seq, data: data.clone() }.encode());
+                        drop(guard);
+                        let event = SessionEvent::edit(room.workspace_id(), file_id, user_id, update.seq, data);
+                        record_session_event(db_pool, &event).await?;
+
+                        if !snapshot_requested
+                            && count_collab_updates_after(db_pool, file_id, snapshot_seq).await?
+                                >= snapshot_interval
+                        {
+                            snapshot_requested = true;
+                            send(&mut sink, Frame::RequestSnapshot).await?;
+                        }
+                    }
+                    Frame::Snapshot { .. } if !snapshot_requested => {
+                        tracing::debug!("Dropping unrequested snapshot from {}", user_id);
+                    }
+                    Frame::Snapshot { seq, data } => {
+                        let snapshot = CollabSnapshot::new(file_id, seq, data);
+                        if compact_collab_document(db_pool, &snapshot).await? {
+                            tracing::debug!("Compacted {}", snapshot);
+                            // Kept as a checkpoint so playback need not replay every edit
+                            let event = SessionEvent::snapshot(
+                                room.workspace_id(), file_id, user_id, seq, snapshot.data,
+                            );
+                            record_session_event(db_pool, &event).await?;
+                            snapshot_seq = snapshot_seq.max(seq);
+                        }
+                        snapshot_requested = false;
+                    }
+                    Frame::Content { seq, data } => {
+                        save_content(db_pool, room, file_id, user_id, seq, data).await?;
+                    }
+                    Frame::Awareness { data } => {
+                        let reported = serde_json::from_slice::<PresenceState>(&data)
+                            .map_err(anyhow::Error::from)
+                            .and_then(|reported| {
+                                reported.validate()?;
+                                Ok(reported)
+                            });
+                        match reported {
+                            Ok(reported) => {
+                                presence.state = reported;
+                                presence.last_active_at = Utc::now();
+                                announce(room, &presence)?;
+                            }
+                            Err(e) => tracing::debug!("Ignoring invalid awareness: {}", e),
+                        }
+                    }
+                    Frame::Chat { data } if role >= WorkspaceRole::Commenter => {
+                        let request = serde_json::from_slice::<ChatMessageReqDto>(&data)
+                            .map_err(anyhow::Error::from)
+                            .and_then(|request| {
+                                request.validate()?;
+                                Ok(request)
+                            });
+                        match request {
+                            Ok(request) => {
+                                let message = ChatMessage::new(room.workspace_id(), user_id, request.body);
+                                let message = create_chat_message(db_pool, &message).await?;
+                                let data = serde_json::to_vec(&ChatMessageResDto::from(message))?;
+                                room.publish_to_workspace(Frame::Chat { data }.encode());
+                            }
+                            Err(e) => tracing::debug!("Ignoring invalid chat message: {}", e),
+                        }
+                    }
+                    frame => tracing::debug!("Ignoring unexpected collab frame {:?}", frame),
+                }
+            }
+            _ = access_check.tick() => {
+                let Some(current) = refresh_role(db_pool, room, user, &mut presence).await? else {
+                    return close(&mut sink, "Access to the workspace was revoked").await;
+                };
+                role = current;
+            }
+            _ = idle_check.tick() => {
+                if last_active.elapsed() >= idle_timeout && room.clear_presence() {
+                    let connection_id = room.connection_id();
+                    room.publish(Frame::Departed { connection_id }.encode());
+                }
+            }
+            broadcast = room.recv() => match broadcast {
+                Ok(broadcast) if broadcast.from == room.connection_id() => {
+                    // Echo only the sequence number back to the author, who knows its own
+                    // presence; chat messages come back whole as they gained an ID
+                    match Frame::decode(&broadcast.frame)? {
+                        Frame::Update { seq, .. } => send(&mut sink, Frame::Ack { seq }).await?,
+                        Frame::Chat { .. } => {
+                            sink.send(Message::Binary(broadcast.frame.to_vec())).await?
+                        }
+                        _ => {}
+                    }
+                }
+                Ok(broadcast) => sink.send(Message::Binary(broadcast.frame.to_vec())).await?,
+                Err(RecvError::Lagged(skipped)) => {
+                    // The client missed updates; closing makes it reconnect and resync
+                    tracing::warn!("Collab client on {} lagged by {} frames", file_id, skipped);
+                    return Ok(());
+                }
+                Err(RecvError::Closed) => return Ok(()),
+            }
+        }
+    }
+}
+
+/// Looks the collaborator's role up again, returning `None` once they lost access.
+async fn refresh_role(
+    db_pool: &PgPool,
+    room: &RoomHandle,
+    user: &User,
+    presence: &mut Participant,
+) -> CaraiResult<Option<WorkspaceRole>> {
+    let role = refresh_workspace_role(db_pool, room.workspace_id(), user.id, user.is_admin).await?;
+    if let Some(role) = role {
+        presence.role = role;
+    }
+    Ok(role)
+}
+
+/// Saves the text a collaborator reported as the file's content, unless it claims
+/// updates that were never stored or is older than text already saved.
+async fn save_content(
+    db_pool: &PgPool,
+    room: &RoomHandle,
+    file_id: Uuid,
+    user_id: Uuid,
+    seq: i64,
+    data: Vec<u8>,
+) -> CaraiResult<()> {
+    let Ok(content) = String::from_utf8(data) else {
+        tracing::debug!("Ignoring content that is not UTF-8 from {}", user_id);
+        return Ok(());
+    };
+    if seq > get_latest_collab_seq(db_pool, file_id).await? || !room.claim_save(seq) {
+        tracing::debug!("Ignoring content as of {} from {}", seq, user_id);
+        return Ok(());
+    }
+    let Some(file) = get_workspace_file_by_id(db_pool, room.workspace_id(), file_id).await? else {
+        return Ok(());
+    };
+    if let Some(file) = save_collab_content(db_pool, file, content, user_id).await? {
+        tracing::debug!("Saved {} as of update {}", file, seq);
+    }
+    Ok(())
+}
+
+/// Lists the participant in the room and tells everybody else about them.
+fn announce(room: &RoomHandle, participant: &Participant) -> CaraiResult<()> {
+    room.set_presence(participant.clone());
+    room.publish(awareness_frame(participant)?.encode());
+    Ok(())
+}
+
+fn awareness_frame(participant: &Participant) -> CaraiResult<Frame> {
+    Ok(Frame::Awareness {
+        data: serde_json::to_vec(participant)?,
+    })
+}
+
+async fn send(sink: &mut SplitSink<WebSocket, Message>, frame: Frame) -> CaraiResult<()> {
+    sink.send(Message::Binary(frame.encode())).await?;
+    Ok(())
+}
+
+async fn close(sink: &mut SplitSink<WebSocket, Message>, reason: &'static str) -> CaraiResult<()> {
+    let frame = CloseFrame {
+        code: close_code::POLICY,
+        reason: reason.into(),
+    };
+    sink.send(Message::Close(Some(frame))).await?;
+    Ok(())
+}
@@ -0,0 +1,129 @@
+use anyhow::Context;
+use axum::{
+    body::{Body, Bytes},
+    extract::{Path, Query, State},
+    http::{header, StatusCode},
+    response::IntoResponse,
+};
+use uuid::Uuid;
+use validator::Validate;
+
+use super::{authorize_workspace, ensure_unique_name, normalize_language, MAX_WORKSPACE_FILES};
+use crate::{
+    bootstrap::AppState,
+    dto::{ExportQueryDto, ImportQueryDto, ImportResDto, WorkspaceResDto},
+    models::{Workspace, WorkspaceRole, ARCHIVE_MANIFEST_PATH},
+    services::{self, ArchiveError},
+    token::Claims,
+    utils::{AppError, SuccessResponse},
+};
+
+/// Name given to imported workspaces when neither the request nor the manifest has one.
+const DEFAULT_IMPORT_NAME: &str = "Imported workspace";
+
+/// Downloads the files of a workspace as a zip or tar.gz archive with a `carai.json` manifest.
+///
+/// The archive is streamed while it is packed. Workspaces holding a file of their own at
+/// the manifest's path, from before that path was reserved, must rename it first.
+pub async fn export_workspace(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    claims: Claims,
+    Query(query): Query<ExportQueryDto>,
+) -> Result<impl IntoResponse, AppError> {
+    let (workspace, _) = authorize_workspace(&state, id, &claims, WorkspaceRole::Viewer).await?;
+    if services::get_workspace_file_by_path(state.db_pool(), workspace.id, ARCHIVE_MANIFEST_PATH)
+        .await?
+        .is_some()
+    {
+        return Err(AppError::new(
+            StatusCode::CONFLICT,
+            format!(
+                "Rename {} before exporting, as archives keep their manifest there",
+                ARCHIVE_MANIFEST_PATH
+            ),
+        ));
+    }
+    let archive = services::export_workspace(state.db_pool(), &workspace, query.format).await?;
+    let disposition = format!(
+        "attachment; filename=\"{}.{}\"",
+        archive_file_stem(&workspace.name),
+        query.format
+    );
+    Ok((
+        [
+            (header::CONTENT_TYPE, query.format.content_type().to_owned()),
+            (header::CONTENT_DISPOSITION, disposition),
+        ],
+        Body::from_stream(archive),
+    ))
+}
+
+/// Creates a workspace from an uploaded zip or tar.gz archive sent as the request body.
+///
+/// A `carai.json` manifest at the root of the archive supplies the name and language;
+/// workspace files cannot take that path.
+/// Binary files are refused or left out depending on the configured policy.
+pub async fn import_workspace(
+    State(state): State<AppState>,
+    claims: Claims,
+    Query(query): Query<ImportQueryDto>,
+    body: Bytes,
+) -> Result<SuccessResponse<ImportResDto>, AppError> {
+    query
+        .validate()
+        .map_err(|e| AppError::new(StatusCode::UNPROCESSABLE_ENTITY, format!("{}", e)))?;
+
+    // Decompressing is CPU bound, so it stays off the async workers
+    let config = state.config().archive().clone();
+    let archive = tokio::task::spawn_blocking(move || {
+        services::unpack_archive(&body, &config, MAX_WORKSPACE_FILES as usize)
+    })
+    .await
+    .context("Archive unpacking panicked")?
+    .map_err(archive_error)?;
+    let manifest = archive.manifest.as_ref();
+    let name = query
+        .name
+        .or_else(|| manifest.map(|manifest| manifest.name.clone()))
+        .filter(|name| (1..=100).contains(&name.chars().count()))
+        .unwrap_or_else(|| DEFAULT_IMPORT_NAME.to_owned());
+    let language = manifest
+        .and_then(|manifest| manifest.language.as_deref())
+        .map(normalize_language)
+        .transpose()?;
+    ensure_unique_name(&state, *claims.jti(), &name).await?;
+
+    let workspace = Workspace::new(*claims.jti(), name, language);
+    let file_count = archive.files.len();
+    let workspace = services::import_workspace(state.db_pool(), &workspace, archive.files).await?;
+    tracing::info!("Imported {} with {} files", workspace, file_count);
+    Ok(SuccessResponse::created(ImportResDto {
+        workspace: WorkspaceResDto::from(workspace),
+        file_count,
+        skipped: archive.skipped,
+    }))
+}
+
+fn archive_error(error: ArchiveError) -> AppError {
+    let status = match error {
+        ArchiveError::TooLarge(_) => StatusCode::PAYLOAD_TOO_LARGE,
+        _ => StatusCode::UNPROCESSABLE_ENTITY,
+    };
+    match error {
+        ArchiveError::BinaryFiles(ref paths) => {
+            AppError::with_data(status, error.to_string(), paths)
+        }
+        _ => AppError::new(status, error.to_string()),
+    }
+}
+
+/// Keeps workspace names usable as download file names.
+fn archive_file_stem(name: &str) -> String {
+    name.chars()
+        .map(|c| match c {
+            'a'..='z' | 'A'..='Z' | '0'..='9' | '-' | '_' | '.' => c,
+            _ => '_',
+        })
+        .collect()
+}
@@ -0,0 +1,97 @@
+use anyhow::Context;
+use axum::{
+    body::Bytes,
+    extract::{Path, State},
+    http::{header, StatusCode},
+    response::IntoResponse,
+};
+
+use crate::{
+    bootstrap::AppState,
+    dto::UserResDto,
+    services::{self, get_user_by_id, AvatarError, AVATAR_SIZES},
+    token::Claims,
+    utils::{AppError, SuccessResponse},
+};
+
+/// Replaces the avatar of the current user with an image sent as the request body.
+///
+/// PNG, JPEG and WebP images are accepted. The image is cropped to a square, stored
+/// at every avatar size and served from a URL derived from its content.
+pub async fn update_my_avatar(
+    State(state): State<AppState>,
+    claims: Claims,
+    body: Bytes,
+) -> Result<SuccessResponse<UserResDto>, AppError> {
+    let mut user = get_user_by_id(state.db_pool(), *claims.jti())
+        .await?
+        .ok_or_else(|| AppError::new(StatusCode::NOT_FOUND, "User not found"))?;
+
+    let config = state.config().avatar().clone();
+    let avatar = tokio::task::spawn_blocking(move || services::process_avatar(&body, &config))
+        .await
+        .context("Avatar processing panicked")?
+        .map_err(avatar_error)?;
+    services::store_avatar(state.blobs().as_ref(), &avatar).await?;
+
+    user.avatar_url = Some(services::avatar_url(state.config().avatar(), &avatar.hash));
+    let user = services::update_user(state.db_pool(), &user).await?;
+    tracing::info!("Updated avatar of {}", user);
+    Ok(SuccessResponse::ok(UserResDto::from(user)))
+}
+
+/// Removes the avatar of the current user; the stored images are kept, as other
+/// users may have uploaded the same picture.
+pub async fn delete_my_avatar(
+    State(state): State<AppState>,
+    claims: Claims,
+) -> Result<SuccessResponse<UserResDto>, AppError> {
+    let mut user = get_user_by_id(state.db_pool(), *claims.jti())
+        .await?
+        .ok_or_else(|| AppError::new(StatusCode::NOT_FOUND, "User not found"))?;
+    user.avatar_url = None;
+    let user = services::update_user(state.db_pool(), &user).await?;
+    Ok(SuccessResponse::ok(UserResDto::from(user)))
+}
+
+/// Serves one size of an avatar, e.g. `/avatars/{hash}/128.png`.
+///
+/// The URL changes whenever the image does, so responses may be cached forever.
+pub async fn get_avatar(
+    State(state): State<AppState>,
+    Path((hash, file)): Path<(String, String)>,
+) -> Result<impl IntoResponse, AppError> {
+    let not_found = || AppError::new(StatusCode::NOT_FOUND, "Avatar not found");
+    let is_hash = hash.len() == 64
+        && hash
+            .chars()
+            .all(|c| c.is_ascii_digit() || ('a'..='f').contains(&c));
+    let size = file
+        .strip_suffix(".png")
+        .and_then(|size| size.parse::<u32>().ok())
+        .filter(|size| AVATAR_SIZES.contains(size) && file == format!("{}.png", size));
+    let Some(size) = size.filter(|_| is_hash) else {
+        return Err(not_found());
+    };
+
+    let image = state
+        .blobs()
+        .get(&services::avatar_key(&hash, size))
+        .await?
+        .ok_or_else(not_found)?;
+    Ok((
+        [
+            (header::CONTENT_TYPE, "image/png"),
+            (header::CACHE_CONTROL, "public, max-age=31536000, immutable"),
+        ],
+        image,
+    ))
+}
+
+fn avatar_error(error: AvatarError) -> AppError {
+    let status = match error {
+        AvatarError::UnsupportedFormat => StatusCode::UNSUPPORTED_MEDIA_TYPE,
+        _ => StatusCode::UNPROCESSABLE_ENTITY,
+    };
+    AppError::new(status, error.to_string())
+}
@@ -0,0 +1,542 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::{header, StatusCode},
+    response::IntoResponse,
+    Json,
+};
+use uuid::Uuid;
+use validator::Validate;
+
+use super::{
+    authorize_workspace, check_quotas, ensure_unique_name, move_entrypoint_first,
+    normalize_language, open_template, DEFAULT_TOLERANCE,
+};
+use crate::{
+    bootstrap::AppState,
+    dto::{
+        AssignmentReqDto, AssignmentResDto, AssignmentsResDto, ClassroomMemberReqDto,
+        ClassroomMemberResDto, ClassroomMembersResDto, ClassroomReqDto, ClassroomResDto,
+        ClassroomsResDto, GradebookResDto, JoinClassroomReqDto, SubmissionDetailResDto,
+        SubmissionReqDto, SubmissionResDto, SubmissionsQueryDto, SubmissionsResDto,
+        WorkspaceResDto,
+    },
+    executor::find_language,
+    models::{
+        Assignment, CaseResult, Classroom, ClassroomMember, ClassroomRole, Execution,
+        ExecutionFile, JudgeSpec, Submission, Workspace, WorkspaceRole,
+    },
+    services,
+    token::Claims,
+    utils::{AppError, CaraiResult, SuccessResponse},
+};
+
+/// Creates a classroom taught by the caller.
+pub async fn create_classroom(
+    State(state): State<AppState>,
+    claims: Claims,
+    Json(dto): Json<ClassroomReqDto>,
+) -> Result<SuccessResponse<ClassroomResDto>, AppError> {
+    dto.validate()
+        .map_err(|e| AppError::new(StatusCode::UNPROCESSABLE_ENTITY, format!("{}", e)))?;
+
+    if services::get_classroom_by_slug(state.db_pool(), &dto.slug)
+        .await?
+        .is_some()
+    {
+        return Err(AppError::new(
+            StatusCode::CONFLICT,
+            "Classroom with this slug already exists",
+        ));
+    }
+
+    let classroom = Classroom::new(dto.slug, dto.name);
+    let classroom = services::create_classroom(state.db_pool(), &classroom, *claims.jti()).await?;
+    tracing::info!("Created {}", classroom);
+    Ok(SuccessResponse::created(ClassroomResDto::new(
+        classroom,
+        ClassroomRole::Instructor,
+    )))
+}
+
+pub async fn get_my_classrooms(
+    State(state): State<AppState>,
+    claims: Claims,
+) -> Result<SuccessResponse<ClassroomsResDto>, AppError> {
+    let memberships = services::get_classrooms_by_member_id(state.db_pool(), *claims.jti()).await?;
+    Ok(SuccessResponse::ok(ClassroomsResDto::from(memberships)))
+}
+
+pub async fn get_classroom(
+    State(state): State<AppState>,
+    Path(slug): Path<String>,
+    claims: Claims,
+) -> Result<SuccessResponse<ClassroomResDto>, AppError> {
+    let (classroom, role) =
+        authorize_classroom(&state, &slug, &claims, ClassroomRole::Student).await?;
+    Ok(SuccessResponse::ok(ClassroomResDto::new(classroom, role)))
+}
+
+pub async fn delete_classroom(
+    State(state): State<AppState>,
+    Path(slug): Path<String>,
+    claims: Claims,
+) -> Result<impl IntoResponse, AppError> {
+    let (classroom, _) =
+        authorize_classroom(&state, &slug, &claims, ClassroomRole::Instructor).await?;
+    services::delete_classroom(state.db_pool(), classroom.id).await?;
+    tracing::info!("Deleted {}", classroom);
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Enrolls the caller as a student of the classroom the join code belongs to.
+pub async fn join_classroom(
+    State(state): State<AppState>,
+    claims: Claims,
+    Json(dto): Json<JoinClassroomReqDto>,
+) -> Result<SuccessResponse<ClassroomResDto>, AppError> {
+    dto.validate()
+        .map_err(|e| AppError::new(StatusCode::UNPROCESSABLE_ENTITY, format!("{}", e)))?;
+
+    let classroom = services::get_classroom_by_join_code(state.db_pool(), &dto.code)
+        .await?
+        .ok_or_else(|| AppError::new(StatusCode::NOT_FOUND, "Classroom not found"))?;
+    let member = ClassroomMember::new(classroom.id, *claims.jti(), ClassroomRole::Student);
+    let member = enroll(&state, &member).await?;
+    tracing::info!("Enrolled {}", member);
+    Ok(SuccessResponse::ok(ClassroomResDto::new(
+        classroom,
+        member.role,
+    )))
+}
+
+pub async fn get_classroom_members(
+    State(state): State<AppState>,
+    Path(slug): Path<String>,
+    claims: Claims,
+) -> Result<SuccessResponse<ClassroomMembersResDto>, AppError> {
+    let (classroom, _) =
+        authorize_classroom(&state, &slug, &claims, ClassroomRole::Instructor).await?;
+    let members = services::get_classroom_member_profiles(state.db_pool(), classroom.id).await?;
+    Ok(SuccessResponse::ok(ClassroomMembersResDto::from(members)))
+}
+
+/// Enrolls a user right away, as a student unless the instructor says otherwise.
+pub async fn add_classroom_member(
+    State(state): State<AppState>,
+    Path(slug): Path<String>,
+    claims: Claims,
+    Json(dto): Json<ClassroomMemberReqDto>,
+) -> Result<SuccessResponse<ClassroomMemberResDto>, AppError> {
+    dto.validate()
+        .map_err(|e| AppError::new(StatusCode::UNPROCESSABLE_ENTITY, format!("{}", e)))?;
+
+    let (classroom, _) =
+        authorize_classroom(&state, &slug, &claims, ClassroomRole::Instructor).await?;
+    let user = services::get_user_by_username(state.db_pool(), &dto.username.to_lowercase())
+        .await?
+        .ok_or_else(|| AppError::new(StatusCode::NOT_FOUND, "User not found"))?;
+    let role = dto.role.unwrap_or(ClassroomRole::Student);
+    let member = enroll(&state, &ClassroomMember::new(classroom.id, user.id, role)).await?;
+    tracing::info!("Added {}", member);
+    let profile = services::get_classroom_member_profiles(state.db_pool(), classroom.id)
+        .await?
+        .into_iter()
+        .find(|profile| profile.user_id == member.user_id)
+        .ok_or_else(|| AppError::new(StatusCode::NOT_FOUND, "Member not found"))?;
+    Ok(SuccessResponse::created(ClassroomMemberResDto::from(
+        profile,
+    )))
+}
+
+/// Removes a member from a classroom; students may also remove themselves to drop out.
+pub async fn remove_classroom_member(
+    State(state): State<AppState>,
+    Path((slug, user_id)): Path<(String, Uuid)>,
+    claims: Claims,
+) -> Result<impl IntoResponse, AppError> {
+    let required = if user_id == *claims.jti() {
+        ClassroomRole::Student
+    } else {
+        ClassroomRole::Instructor
+    };
+    let (classroom, _) = authorize_classroom(&state, &slug, &claims, required).await?;
+    let members = services::get_classroom_member_profiles(state.db_pool(), classroom.id).await?;
+    let member = members
+        .iter()
+        .find(|member| member.user_id == user_id)
+        .ok_or_else(|| AppError::new(StatusCode::NOT_FOUND, "Member not found"))?;
+    let instructors = members
+        .iter()
+        .filter(|member| member.role == ClassroomRole::Instructor)
+        .count();
+    if member.role == ClassroomRole::Instructor && instructors <= 1 {
+        return Err(AppError::new(
+            StatusCode::UNPROCESSABLE_ENTITY,
+            "Classrooms must keep at least one instructor",
+        ));
+    }
+
+    services::delete_classroom_member(state.db_pool(), classroom.id, user_id).await?;
+    tracing::info!("Removed user {} from {}", user_id, classroom);
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Publishes an assignment whose starter files are copied from a template.
+pub async fn create_assignment(
+    State(state): State<AppState>,
+    Path(slug): Path<String>,
+    claims: Claims,
+    Json(dto): Json<AssignmentReqDto>,
+) -> Result<SuccessResponse<AssignmentResDto>, AppError> {
+    dto.validate()
+        .map_err(|e| AppError::new(StatusCode::UNPROCESSABLE_ENTITY, format!("{}", e)))?;
+
+    let (classroom, role) =
+        authorize_classroom(&state, &slug, &claims, ClassroomRole::Instructor).await?;
+    let language = normalize_language(&dto.language)?;
+    let starter_files = match dto.template {
+        Some(slug) => open_template(&state, &slug, Some(&claims)).await?.files.0,
+        None => Vec::new(),
+    };
+    let judge = JudgeSpec {
+        comparison: dto.comparison.unwrap_or_default(),
+        tolerance: dto.tolerance.unwrap_or(DEFAULT_TOLERANCE),
+        cases: dto.tests.into_iter().map(Into::into).collect(),
+        hidden: true,
+    };
+
+    let mut assignment = Assignment::new(
+        classroom.id,
+        dto.title,
+        language,
+        judge,
+        dto.deadline,
+        *claims.jti(),
+    );
+    assignment.description = dto.description.unwrap_or_default();
+    assignment.entrypoint = dto.entrypoint;
+    assignment.starter_files = sqlx::types::Json(starter_files);
+    let assignment = services::create_assignment(state.db_pool(), &assignment).await?;
+    tracing::info!("Created {}", assignment);
+    Ok(SuccessResponse::created(AssignmentResDto::new(
+        assignment, role,
+    )))
+}
+
+pub async fn get_assignments(
+    State(state): State<AppState>,
+    Path(slug): Path<String>,
+    claims: Claims,
+) -> Result<SuccessResponse<AssignmentsResDto>, AppError> {
+    let (classroom, role) =
+        authorize_classroom(&state, &slug, &claims, ClassroomRole::Student).await?;
+    let assignments =
+        services::get_assignments_by_classroom_id(state.db_pool(), classroom.id).await?;
+    Ok(SuccessResponse::ok(AssignmentsResDto::new(
+        assignments,
+        role,
+    )))
+}
+
+/// Shows an assignment; only instructors get to see its test cases.
+pub async fn get_assignment(
+    State(state): State<AppState>,
+    Path((slug, id)): Path<(String, Uuid)>,
+    claims: Claims,
+) -> Result<SuccessResponse<AssignmentResDto>, AppError> {
+    let (classroom, role) =
+        authorize_classroom(&state, &slug, &claims, ClassroomRole::Student).await?;
+    let assignment = find_assignment(&state, &classroom, id).await?;
+    Ok(SuccessResponse::ok(AssignmentResDto::new(assignment, role)))
+}
+
+pub async fn delete_assignment(
+    State(state): State<AppState>,
+    Path((slug, id)): Path<(String, Uuid)>,
+    claims: Claims,
+) -> Result<impl IntoResponse, AppError> {
+    let (classroom, _) =
+        authorize_classroom(&state, &slug, &claims, ClassroomRole::Instructor).await?;
+    let assignment = find_assignment(&state, &classroom, id).await?;
+    services::delete_assignment(state.db_pool(), assignment.id).await?;
+    tracing::info!("Deleted {}", assignment);
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Creates a workspace for the caller holding the assignment's starter files.
+pub async fn start_assignment(
+    State(state): State<AppState>,
+    Path((slug, id)): Path<(String, Uuid)>,
+    claims: Claims,
+) -> Result<SuccessResponse<WorkspaceResDto>, AppError> {
+    let (classroom, _) =
+        authorize_classroom(&state, &slug, &claims, ClassroomRole::Student).await?;
+    let assignment = find_assignment(&state, &classroom, id).await?;
+    ensure_unique_name(&state, *claims.jti(), &assignment.title).await?;
+
+    let workspace = Workspace::new(
+        *claims.jti(),
+        assignment.title.clone(),
+        Some(assignment.language.clone()),
+    );
+    let workspace =
+        services::create_workspace_from_assignment(state.db_pool(), &workspace, &assignment)
+            .await?;
+    tracing::info!("Started {} in {}", assignment, workspace);
+    Ok(SuccessResponse::created(WorkspaceResDto::from(workspace)))
+}
+
+/// Hands in a snapshot of one of the caller's workspaces and queues it for grading
+/// against the assignment's hidden test cases.
+pub async fn submit_assignment(
+    State(state): State<AppState>,
+    Path((slug, id)): Path<(String, Uuid)>,
+    claims: Claims,
+    Json(dto): Json<SubmissionReqDto>,
+) -> Result<SuccessResponse<SubmissionResDto>, AppError> {
+    let (classroom, _) =
+        authorize_classroom(&state, &slug, &claims, ClassroomRole::Student).await?;
+    let assignment = find_assignment(&state, &classroom, id).await?;
+    if assignment.is_closed() {
+        return Err(AppError::new(
+            StatusCode::UNPROCESSABLE_ENTITY,
+            "The deadline for this assignment has passed",
+        ));
+    }
+    let (workspace, _) =
+        authorize_workspace(&state, dto.workspace_id, &claims, WorkspaceRole::Owner).await?;
+
+    let mut files: Vec<ExecutionFile> =
+        services::get_workspace_files(state.db_pool(), workspace.id)
+            .await?
+            .into_iter()
+            .map(|file| ExecutionFile {
+                name: file.path,
+                content: file.content,
+            })
+            .collect();
+    if files.is_empty() {
+        return Err(AppError::new(
+            StatusCode::UNPROCESSABLE_ENTITY,
+            "Workspace has no files to submit",
+        ));
+    }
+    if let Some(entrypoint) = &assignment.entrypoint {
+        move_entrypoint_first(&mut files, entrypoint)?;
+    }
+    let language = find_language(&assignment.language)
+        .ok_or_else(|| AppError::new(StatusCode::UNPROCESSABLE_ENTITY, "Unsupported language"))?;
+
+    let mut execution = Execution::new(*claims.jti(), language.name, files.clone(), None);
+    execution.workspace_id = Some(workspace.id);
+    execution.organization_id = workspace.organization_id;
+    execution.judge = Some(sqlx::types::Json(assignment.judge.0.clone()));
+    let limit = check_quotas(&state, &claims, execution.organization_id).await?;
+
+    let submission = Submission::new(
+        &assignment,
+        *claims.jti(),
+        workspace.id,
+        execution.id,
+        files,
+    );
+    let submission = services::create_submission(
+        state.db_pool(),
+        &submission,
+        &execution,
+        limit.concurrent_runs,
+    )
+    .await?
+    .ok_or_else(|| limit.exceeded())?;
+    tracing::info!("Queued {}", submission);
+    Ok(SuccessResponse::accepted(SubmissionResDto::from(
+        submission,
+    )))
+}
+
+/// Lists the submission history for an assignment: a student's own, or anybody's for
+/// instructors.
+pub async fn get_submissions(
+    State(state): State<AppState>,
+    Path((slug, id)): Path<(String, Uuid)>,
+    claims: Claims,
+    Query(query): Query<SubmissionsQueryDto>,
+) -> Result<SuccessResponse<SubmissionsResDto>, AppError> {
+    let limit = query.limit.unwrap_or(50).min(100);
+    let offset = query.offset.unwrap_or(0);
+
+    let (classroom, role) =
+        authorize_classroom(&state, &slug, &claims, ClassroomRole::Student).await?;
+    let assignment = find_assignment(&state, &classroom, id).await?;
+    let user_id = match role {
+        ClassroomRole::Instructor => query.user_id,
+        ClassroomRole::Student => Some(*claims.jti()),
+    };
+    let submissions = services::get_submissions_by_assignment_id(
+        state.db_pool(),
+        assignment.id,
+        user_id,
+        limit,
+        offset,
+    )
+    .await?;
+    Ok(SuccessResponse::ok(SubmissionsResDto::from(submissions)))
+}
+
+/// Shows a submission with its per-case results; students only learn the verdicts.
+pub async fn get_submission(
+    State(state): State<AppState>,
+    Path((slug, id)): Path<(String, Uuid)>,
+    claims: Claims,
+) -> Result<SuccessResponse<SubmissionDetailResDto>, AppError> {
+    let not_found = || AppError::new(StatusCode::NOT_FOUND, "Submission not found");
+    let (classroom, role) =
+        authorize_classroom(&state, &slug, &claims, ClassroomRole::Student).await?;
+    let submission = services::get_submission_by_id(state.db_pool(), id)
+        .await?
+        .filter(|submission| {
+            role == ClassroomRole::Instructor || submission.user_id == *claims.jti()
+        })
+        .ok_or_else(not_found)?;
+    find_assignment(&state, &classroom, submission.assignment_id)
+        .await
+        .map_err(|_| not_found())?;
+
+    let execution = match submission.execution_id {
+        Some(execution_id) => services::get_execution_by_id(state.db_pool(), execution_id).await?,
+        None => None,
+    };
+    let cases = execution
+        .and_then(|execution| execution.case_results)
+        .map(|results| match role {
+            ClassroomRole::Instructor => results.0,
+            ClassroomRole::Student => results.0.into_iter().map(CaseResult::redacted).collect(),
+        });
+    Ok(SuccessResponse::ok(SubmissionDetailResDto::new(
+        submission, cases,
+    )))
+}
+
+/// Shows every student's best score on every assignment.
+pub async fn get_gradebook(
+    State(state): State<AppState>,
+    Path(slug): Path<String>,
+    claims: Claims,
+) -> Result<SuccessResponse<GradebookResDto>, AppError> {
+    let (classroom, _) =
+        authorize_classroom(&state, &slug, &claims, ClassroomRole::Instructor).await?;
+    let gradebook = build_gradebook(&state, &classroom).await?;
+    Ok(SuccessResponse::ok(gradebook))
+}
+
+/// Downloads the gradebook as CSV: one row per student and one column per assignment.
+pub async fn export_gradebook(
+    State(state): State<AppState>,
+    Path(slug): Path<String>,
+    claims: Claims,
+) -> Result<impl IntoResponse, AppError> {
+    let (classroom, _) =
+        authorize_classroom(&state, &slug, &claims, ClassroomRole::Instructor).await?;
+    let gradebook = build_gradebook(&state, &classroom).await?;
+    let csv = gradebook_csv(&gradebook)?;
+    let disposition = format!("attachment; filename=\"{}-gradebook.csv\"", classroom.slug);
+    Ok((
+        [
+            (header::CONTENT_TYPE, "text/csv; charset=utf-8".to_owned()),
+            (header::CONTENT_DISPOSITION, disposition),
+        ],
+        csv,
+    ))
+}
+
+/// Loads a classroom in which the caller holds at least the `required` role.
+///
+/// Classrooms the caller does not belong to are reported as missing, while students
+/// are refused what takes an instructor. Administrators act as instructors.
+async fn authorize_classroom(
+    state: &AppState,
+    slug: &str,
+    claims: &Claims,
+    required: ClassroomRole,
+) -> Result<(Classroom, ClassroomRole), AppError> {
+    let not_found = || AppError::new(StatusCode::NOT_FOUND, "Classroom not found");
+    let classroom = services::get_classroom_by_slug(state.db_pool(), slug)
+        .await?
+        .ok_or_else(not_found)?;
+    let role = if *claims.is_admin() {
+        ClassroomRole::Instructor
+    } else {
+        services::get_classroom_member(state.db_pool(), classroom.id, *claims.jti())
+            .await?
+            .ok_or_else(not_found)?
+            .role
+    };
+    if role < required {
+        return Err(AppError::new(
+            StatusCode::FORBIDDEN,
+            format!("This requires the {} role", required),
+        ));
+    }
+    Ok((classroom, role))
+}
+
+async fn find_assignment(
+    state: &AppState,
+    classroom: &Classroom,
+    id: Uuid,
+) -> Result<Assignment, AppError> {
+    services::get_assignment_by_id(state.db_pool(), id)
+        .await?
+        .filter(|assignment| assignment.classroom_id == classroom.id)
+        .ok_or_else(|| AppError::new(StatusCode::NOT_FOUND, "Assignment not found"))
+}
+
+async fn enroll(state: &AppState, member: &ClassroomMember) -> Result<ClassroomMember, AppError> {
+    if services::get_classroom_member(state.db_pool(), member.classroom_id, member.user_id)
+        .await?
+        .is_some()
+    {
+        return Err(AppError::new(
+            StatusCode::CONFLICT,
+            "User is already a member of this classroom",
+        ));
+    }
+    Ok(services::create_classroom_member(state.db_pool(), member).await?)
+}
+
+async fn build_gradebook(
+    state: &AppState,
+    classroom: &Classroom,
+) -> Result<GradebookResDto, AppError> {
+    let assignments =
+        services::get_assignments_by_classroom_id(state.db_pool(), classroom.id).await?;
+    let members = services::get_classroom_member_profiles(state.db_pool(), classroom.id).await?;
+    let entries = services::get_gradebook_entries(state.db_pool(), classroom.id).await?;
+    Ok(GradebookResDto::new(assignments, members, entries))
+}
+
+/// Missing scores are left blank, so a spreadsheet can tell them apart from zeros.
+fn gradebook_csv(gradebook: &GradebookResDto) -> CaraiResult<Vec<u8>> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    let mut header = vec!["username".to_owned()];
+    header.extend(
+        gradebook
+            .assignments
+            .iter()
+            .map(|assignment| assignment.title.clone()),
+    );
+    header.push("average".to_owned());
+    writer.write_record(&header)?;
+
+    for student in &gradebook.students {
+        let mut record = vec![student.username.clone()];
+        record.extend(student.grades.iter().map(|grade| {
+            grade
+                .best_score
+                .map_or_else(String::new, |score| format!("{:.2}", score))
+        }));
+        record.push(format!("{:.2}", student.average_score));
+        writer.write_record(&record)?;
+    }
+    Ok(writer.into_inner()?)
+}
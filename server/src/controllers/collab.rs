@@ -0,0 +1,53 @@
+use axum::{
+    extract::{ws::WebSocketUpgrade, Path, Query, State},
+    http::StatusCode,
+    response::Response,
+};
+use uuid::Uuid;
+
+use super::{authorize_workspace, find_workspace_file};
+use crate::{
+    bootstrap::AppState,
+    collab::serve_collab_connection,
+    dto::{CollabQueryDto, PresenceResDto},
+    middlewares::auth::validate_access_token,
+    models::WorkspaceRole,
+    services,
+    token::Claims,
+    utils::{AppError, SuccessResponse},
+};
+
+/// Upgrades to a WebSocket that joins the collaborative editing room of a file.
+///
+/// Members below the editor role join read-only.
+pub async fn collaborate(
+    State(state): State<AppState>,
+    Path((id, file_id)): Path<(Uuid, Uuid)>,
+    Query(query): Query<CollabQueryDto>,
+    ws: WebSocketUpgrade,
+) -> Result<Response, AppError> {
+    let claims = validate_access_token(&state, &query.token)?;
+    let (workspace, role) = authorize_workspace(&state, id, &claims, WorkspaceRole::Viewer).await?;
+    let file = find_workspace_file(&state, workspace.id, file_id).await?;
+    let user = services::get_user_by_id(state.db_pool(), *claims.jti())
+        .await?
+        .ok_or_else(|| AppError::new(StatusCode::UNAUTHORIZED, "User not found"))?;
+
+    let max_frame_bytes = *state.config().collab().max_frame_bytes();
+    Ok(ws
+        .max_message_size(max_frame_bytes)
+        .on_upgrade(move |socket| {
+            serve_collab_connection(socket, state, workspace.id, file.id, user, role)
+        }))
+}
+
+/// Lists the collaborators currently connected to any file of a workspace.
+pub async fn get_workspace_presence(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    claims: Claims,
+) -> Result<SuccessResponse<PresenceResDto>, AppError> {
+    let (workspace, _) = authorize_workspace(&state, id, &claims, WorkspaceRole::Viewer).await?;
+    let participants = state.collab().presence(workspace.id);
+    Ok(SuccessResponse::ok(PresenceResDto { participants }))
+}
@@ -0,0 +1,237 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use uuid::Uuid;
+use validator::Validate;
+
+use super::{authorize_workspace, find_workspace_file};
+use crate::{
+    bootstrap::AppState,
+    collab::Frame,
+    dto::{
+        ChatMessageReqDto, ChatMessageResDto, ChatMessagesQueryDto, ChatMessagesResDto,
+        CommentReqDto, CommentResDto, CommentThreadReqDto, CommentThreadResDto,
+        CommentThreadsQueryDto, CommentThreadsResDto, MentionsQueryDto, MentionsResDto,
+    },
+    models::{ChatMessage, CommentThread, WorkspaceFile, WorkspaceRole},
+    services,
+    token::Claims,
+    utils::{AppError, SuccessResponse},
+};
+
+pub async fn get_comment_threads(
+    State(state): State<AppState>,
+    Path((id, file_id)): Path<(Uuid, Uuid)>,
+    claims: Claims,
+    Query(query): Query<CommentThreadsQueryDto>,
+) -> Result<SuccessResponse<CommentThreadsResDto>, AppError> {
+    let (file, _) = get_file(&state, id, file_id, &claims, WorkspaceRole::Viewer).await?;
+    let threads = services::get_comment_threads(state.db_pool(), file.id, query.resolved).await?;
+    let thread_ids: Vec<Uuid> = threads.iter().map(|thread| thread.id).collect();
+    let comments = services::get_comments_by_thread_ids(state.db_pool(), &thread_ids).await?;
+    Ok(SuccessResponse::ok(CommentThreadsResDto::new(
+        threads, comments,
+    )))
+}
+
+/// Opens a comment thread on a range of lines of a file.
+pub async fn create_comment_thread(
+    State(state): State<AppState>,
+    Path((id, file_id)): Path<(Uuid, Uuid)>,
+    claims: Claims,
+    Json(dto): Json<CommentThreadReqDto>,
+) -> Result<SuccessResponse<CommentThreadResDto>, AppError> {
+    dto.validate()
+        .map_err(|e| AppError::new(StatusCode::UNPROCESSABLE_ENTITY, format!("{}", e)))?;
+
+    let (file, _) = get_file(&state, id, file_id, &claims, WorkspaceRole::Commenter).await?;
+    let line_count = file.content.lines().count().max(1) as i32;
+    if dto.end_line < dto.start_line || dto.end_line > line_count {
+        return Err(AppError::new(
+            StatusCode::UNPROCESSABLE_ENTITY,
+            format!(
+                "Lines must form a range within the file's {} lines",
+                line_count
+            ),
+        ));
+    }
+
+    let thread = CommentThread::new(
+        file.workspace_id,
+        file.id,
+        *claims.jti(),
+        dto.start_line,
+        dto.end_line,
+    );
+    let (thread, comment) =
+        services::create_comment_thread(state.db_pool(), &thread, &dto.body, *claims.jti()).await?;
+    tracing::info!("Created {}", thread);
+    Ok(SuccessResponse::created(CommentThreadResDto::new(
+        thread,
+        vec![comment],
+    )))
+}
+
+pub async fn reply_to_comment_thread(
+    State(state): State<AppState>,
+    Path((id, file_id, thread_id)): Path<(Uuid, Uuid, Uuid)>,
+    claims: Claims,
+    Json(dto): Json<CommentReqDto>,
+) -> Result<SuccessResponse<CommentResDto>, AppError> {
+    dto.validate()
+        .map_err(|e| AppError::new(StatusCode::UNPROCESSABLE_ENTITY, format!("{}", e)))?;
+
+    let (file, _) = get_file(&state, id, file_id, &claims, WorkspaceRole::Commenter).await?;
+    let thread = find_thread(&state, file.id, thread_id).await?;
+    let comment =
+        services::create_comment(state.db_pool(), &thread, &dto.body, *claims.jti()).await?;
+    tracing::info!("Created {}", comment);
+    Ok(SuccessResponse::created(CommentResDto::from(comment)))
+}
+
+pub async fn resolve_comment_thread(
+    State(state): State<AppState>,
+    Path((id, file_id, thread_id)): Path<(Uuid, Uuid, Uuid)>,
+    claims: Claims,
+) -> Result<SuccessResponse<CommentThreadResDto>, AppError> {
+    set_resolution(&state, (id, file_id, thread_id), &claims, true).await
+}
+
+pub async fn unresolve_comment_thread(
+    State(state): State<AppState>,
+    Path((id, file_id, thread_id)): Path<(Uuid, Uuid, Uuid)>,
+    claims: Claims,
+) -> Result<SuccessResponse<CommentThreadResDto>, AppError> {
+    set_resolution(&state, (id, file_id, thread_id), &claims, false).await
+}
+
+/// Deletes a thread; allowed for its author and for editors and above.
+pub async fn delete_comment_thread(
+    State(state): State<AppState>,
+    Path((id, file_id, thread_id)): Path<(Uuid, Uuid, Uuid)>,
+    claims: Claims,
+) -> Result<impl IntoResponse, AppError> {
+    let (file, role) = get_file(&state, id, file_id, &claims, WorkspaceRole::Commenter).await?;
+    let thread = find_thread(&state, file.id, thread_id).await?;
+    if role < WorkspaceRole::Editor && thread.author_id != Some(*claims.jti()) {
+        return Err(AppError::new(
+            StatusCode::FORBIDDEN,
+            "Only the author or an editor can delete this thread",
+        ));
+    }
+    services::delete_comment_thread(state.db_pool(), thread.id).await?;
+    tracing::info!("Deleted {}", thread);
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub async fn get_chat_messages(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    claims: Claims,
+    Query(query): Query<ChatMessagesQueryDto>,
+) -> Result<SuccessResponse<ChatMessagesResDto>, AppError> {
+    let (workspace, _) = authorize_workspace(&state, id, &claims, WorkspaceRole::Viewer).await?;
+    let limit = query.limit.unwrap_or(50).min(100);
+    let messages =
+        services::get_chat_messages(state.db_pool(), workspace.id, query.before, limit).await?;
+    Ok(SuccessResponse::ok(ChatMessagesResDto::from(messages)))
+}
+
+/// Posts to the workspace chat and relays the message to everybody collaborating.
+pub async fn post_chat_message(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    claims: Claims,
+    Json(dto): Json<ChatMessageReqDto>,
+) -> Result<SuccessResponse<ChatMessageResDto>, AppError> {
+    dto.validate()
+        .map_err(|e| AppError::new(StatusCode::UNPROCESSABLE_ENTITY, format!("{}", e)))?;
+
+    let (workspace, _) = authorize_workspace(&state, id, &claims, WorkspaceRole::Commenter).await?;
+    let message = ChatMessage::new(workspace.id, *claims.jti(), dto.body);
+    let message = services::create_chat_message(state.db_pool(), &message).await?;
+
+    let message = ChatMessageResDto::from(message);
+    let frame = Frame::Chat {
+        data: serde_json::to_vec(&message).map_err(AppError::internal)?,
+    };
+    state
+        .collab()
+        .publish_to_workspace(workspace.id, Uuid::nil(), frame.encode());
+    Ok(SuccessResponse::created(message))
+}
+
+pub async fn get_my_mentions(
+    State(state): State<AppState>,
+    claims: Claims,
+    Query(query): Query<MentionsQueryDto>,
+) -> Result<SuccessResponse<MentionsResDto>, AppError> {
+    let limit = query.limit.unwrap_or(50).min(100);
+    let offset = query.offset.unwrap_or(0);
+    let unread_only = query.unread.unwrap_or(false);
+
+    let mentions = services::get_mentions_by_user_id(
+        state.db_pool(),
+        *claims.jti(),
+        unread_only,
+        limit,
+        offset,
+    )
+    .await?;
+    Ok(SuccessResponse::ok(MentionsResDto::from(mentions)))
+}
+
+pub async fn mark_mention_read(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    claims: Claims,
+) -> Result<impl IntoResponse, AppError> {
+    if !services::mark_mention_read(state.db_pool(), *claims.jti(), id).await? {
+        return Err(AppError::new(StatusCode::NOT_FOUND, "Mention not found"));
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn set_resolution(
+    state: &AppState,
+    (id, file_id, thread_id): (Uuid, Uuid, Uuid),
+    claims: &Claims,
+    resolved: bool,
+) -> Result<SuccessResponse<CommentThreadResDto>, AppError> {
+    let (file, _) = get_file(state, id, file_id, claims, WorkspaceRole::Commenter).await?;
+    let thread = find_thread(state, file.id, thread_id).await?;
+    let resolved_by = resolved.then(|| *claims.jti());
+
+    let thread =
+        services::update_comment_thread_resolution(state.db_pool(), thread.id, resolved_by).await?;
+    tracing::info!("Updated {}", thread);
+    let comments = services::get_comments_by_thread_ids(state.db_pool(), &[thread.id]).await?;
+    Ok(SuccessResponse::ok(CommentThreadResDto::new(
+        thread, comments,
+    )))
+}
+
+async fn get_file(
+    state: &AppState,
+    id: Uuid,
+    file_id: Uuid,
+    claims: &Claims,
+    required: WorkspaceRole,
+) -> Result<(WorkspaceFile, WorkspaceRole), AppError> {
+    let (workspace, role) = authorize_workspace(state, id, claims, required).await?;
+    let file = find_workspace_file(state, workspace.id, file_id).await?;
+    Ok((file, role))
+}
+
+async fn find_thread(
+    state: &AppState,
+    file_id: Uuid,
+    thread_id: Uuid,
+) -> Result<CommentThread, AppError> {
+    services::get_comment_thread_by_id(state.db_pool(), file_id, thread_id)
+        .await?
+        .ok_or_else(|| AppError::new(StatusCode::NOT_FOUND, "Comment thread not found"))
+}
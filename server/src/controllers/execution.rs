@@ -0,0 +1,257 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use uuid::Uuid;
+use validator::Validate;
+
+use super::authorize_workspace;
+use crate::{
+    bootstrap::AppState,
+    dto::{RunDetailResDto, RunReqDto, RunResDto, RunsQueryDto, RunsResDto},
+    executor::{find_language, Language},
+    models::{Execution, ExecutionFile, JudgeSpec, QuotaKind, QuotaUsage, WorkspaceRole},
+    services,
+    token::Claims,
+    utils::{AppError, SuccessResponse},
+};
+
+/// Tolerance used by floating-point comparison when the request does not set one.
+pub(super) const DEFAULT_TOLERANCE: f64 = 1e-6;
+
+pub async fn submit_run(
+    State(state): State<AppState>,
+    claims: Claims,
+    Json(dto): Json<RunReqDto>,
+) -> Result<SuccessResponse<RunResDto>, AppError> {
+    dto.validate()
+        .map_err(|e| AppError::new(StatusCode::UNPROCESSABLE_ENTITY, format!("{}", e)))?;
+
+    let language = find_language(&dto.language)
+        .ok_or_else(|| AppError::new(StatusCode::UNPROCESSABLE_ENTITY, "Unsupported language"))?;
+
+    let mut files: Vec<ExecutionFile> = dto.files.into_iter().map(Into::into).collect();
+    if files
+        .iter()
+        .enumerate()
+        .any(|(i, file)| files[..i].iter().any(|other| other.name == file.name))
+    {
+        return Err(AppError::new(
+            StatusCode::UNPROCESSABLE_ENTITY,
+            "File names must be unique",
+        ));
+    }
+    if let Some(entrypoint) = dto.entrypoint {
+        move_entrypoint_first(&mut files, &entrypoint)?;
+    }
+
+    let mut execution = Execution::new(*claims.jti(), language.name, files, dto.stdin);
+    authorize_recording(&state, &mut execution, dto.workspace_id, &claims).await?;
+    execution.judge = dto.tests.map(|tests| {
+        sqlx::types::Json(JudgeSpec {
+            comparison: dto.comparison.unwrap_or_default(),
+            tolerance: dto.tolerance.unwrap_or(DEFAULT_TOLERANCE),
+            cases: tests.into_iter().map(Into::into).collect(),
+            hidden: false,
+        })
+    });
+    queue_execution(&state, &claims, language, execution).await
+}
+
+pub async fn rerun(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    claims: Claims,
+) -> Result<SuccessResponse<RunResDto>, AppError> {
+    let previous = get_owned_execution(&state, id, &claims).await?;
+    let language = find_language(&previous.language)
+        .ok_or_else(|| AppError::new(StatusCode::UNPROCESSABLE_ENTITY, "Unsupported language"))?;
+
+    let mut execution = Execution::new(
+        *claims.jti(),
+        previous.language,
+        previous.files.0,
+        previous.stdin,
+    );
+    execution.judge = previous.judge;
+    authorize_recording(&state, &mut execution, previous.workspace_id, &claims).await?;
+    queue_execution(&state, &claims, language, execution).await
+}
+
+pub async fn get_run(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    claims: Claims,
+) -> Result<SuccessResponse<RunResDto>, AppError> {
+    let execution = get_owned_execution(&state, id, &claims).await?;
+    Ok(SuccessResponse::ok(RunResDto::from(execution)))
+}
+
+pub async fn get_my_runs(
+    State(state): State<AppState>,
+    claims: Claims,
+    Query(query): Query<RunsQueryDto>,
+) -> Result<SuccessResponse<RunsResDto>, AppError> {
+    let limit = query.limit.unwrap_or(20).min(100);
+    let offset = query.offset.unwrap_or(0);
+
+    let executions = services::get_executions_by_user_id(
+        state.db_pool(),
+        *claims.jti(),
+        query.language.as_deref(),
+        query.status,
+        limit,
+        offset,
+    )
+    .await?;
+    Ok(SuccessResponse::ok(RunsResDto::from(executions)))
+}
+
+pub async fn get_my_run(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    claims: Claims,
+) -> Result<SuccessResponse<RunDetailResDto>, AppError> {
+    let execution = get_owned_execution(&state, id, &claims).await?;
+    Ok(SuccessResponse::ok(RunDetailResDto::from(execution)))
+}
+
+async fn get_owned_execution(
+    state: &AppState,
+    id: Uuid,
+    claims: &Claims,
+) -> Result<Execution, AppError> {
+    services::get_execution_by_id(state.db_pool(), id)
+        .await?
+        .filter(|execution| execution.user_id == *claims.jti() || *claims.is_admin())
+        .ok_or_else(|| AppError::new(StatusCode::NOT_FOUND, "Run not found"))
+}
+
+/// Checks that a run may be recorded in the workspace it was started from, and bills
+/// it to the workspace's organization if there is one.
+async fn authorize_recording(
+    state: &AppState,
+    execution: &mut Execution,
+    workspace_id: Option<Uuid>,
+    claims: &Claims,
+) -> Result<(), AppError> {
+    let Some(workspace_id) = workspace_id else {
+        return Ok(());
+    };
+    let (workspace, _) =
+        authorize_workspace(state, workspace_id, claims, WorkspaceRole::Editor).await?;
+    execution.workspace_id = Some(workspace.id);
+    execution.organization_id = workspace.organization_id;
+    Ok(())
+}
+
+/// The sandbox runs the first file, so the entrypoint is stored at the front.
+pub(super) fn move_entrypoint_first(
+    files: &mut [ExecutionFile],
+    entrypoint: &str,
+) -> Result<(), AppError> {
+    let position = files
+        .iter()
+        .position(|file| file.name == entrypoint)
+        .ok_or_else(|| {
+            AppError::new(
+                StatusCode::UNPROCESSABLE_ENTITY,
+                "Entrypoint must be one of the submitted files",
+            )
+        })?;
+    files[..=position].rotate_right(1);
+    Ok(())
+}
+
+/// The concurrent-run quota that is enforced when a run is queued, after [`check_quotas`]
+/// has vetted the others; only the insert itself can count concurrent runs reliably.
+pub(super) struct RunLimit {
+    pub(super) concurrent_runs: i64,
+    scope: &'static str,
+}
+
+impl RunLimit {
+    /// The error for a run refused because parallel requests used up the free slots.
+    pub(super) fn exceeded(&self) -> AppError {
+        quota_exceeded(
+            self.scope,
+            QuotaUsage {
+                quota: QuotaKind::ConcurrentRuns,
+                used: self.concurrent_runs,
+                limit: self.concurrent_runs,
+                resets_at: None,
+            },
+        )
+    }
+}
+
+/// Refuses a run once the quotas it is billed against are used up: those of the
+/// organization if there is one, and the caller's own otherwise.
+pub(super) async fn check_quotas(
+    state: &AppState,
+    claims: &Claims,
+    organization_id: Option<Uuid>,
+) -> Result<RunLimit, AppError> {
+    let (quotas, limit) = match organization_id {
+        Some(organization_id) => {
+            let organization = services::get_organization_by_id(state.db_pool(), organization_id)
+                .await?
+                .ok_or_else(|| AppError::new(StatusCode::NOT_FOUND, "Organization not found"))?;
+            let quotas =
+                services::get_organization_quota_usage(state.db_pool(), &organization).await?;
+            let limit = RunLimit {
+                concurrent_runs: organization.concurrent_runs,
+                scope: "Organization execution quota",
+            };
+            (quotas, limit)
+        }
+        None => {
+            let limits = state.config().quota().limits_for(*claims.is_admin());
+            let quotas = services::get_quota_usage(state.db_pool(), *claims.jti(), limits).await?;
+            let limit = RunLimit {
+                concurrent_runs: *limits.concurrent_runs(),
+                scope: "Execution quota",
+            };
+            (quotas, limit)
+        }
+    };
+    if let Some(exhausted) = quotas.into_iter().find(QuotaUsage::is_exhausted) {
+        return Err(quota_exceeded(limit.scope, exhausted));
+    }
+    Ok(limit)
+}
+
+fn quota_exceeded(scope: &str, exhausted: QuotaUsage) -> AppError {
+    AppError::with_data(
+        StatusCode::TOO_MANY_REQUESTS,
+        format!("{} exceeded: {}", scope, exhausted.quota),
+        exhausted,
+    )
+}
+
+async fn queue_execution(
+    state: &AppState,
+    claims: &Claims,
+    language: &Language,
+    mut execution: Execution,
+) -> Result<SuccessResponse<RunResDto>, AppError> {
+    let limit = check_quotas(state, claims, execution.organization_id).await?;
+
+    if execution.judge.is_none() && state.config().execution().is_cacheable(language.name) {
+        let key = services::cache_key(language, &execution);
+        if let Some(cached) = services::hit_cached_execution(state.db_pool(), &key).await? {
+            execution.complete_from_cache(cached);
+            let execution = services::create_execution(state.db_pool(), &execution).await?;
+            tracing::info!("Served {} from cache", execution);
+            return Ok(SuccessResponse::ok(RunResDto::from(execution)));
+        }
+    }
+
+    let execution =
+        services::create_queued_execution(state.db_pool(), &execution, limit.concurrent_runs)
+            .await?
+            .ok_or_else(|| limit.exceeded())?;
+    tracing::info!("Queued {}", execution);
+    Ok(SuccessResponse::accepted(RunResDto::from(execution)))
+}
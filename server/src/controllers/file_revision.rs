@@ -0,0 +1,116 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+};
+use uuid::Uuid;
+
+use super::{authorize_workspace, find_workspace_file, save_workspace_file};
+use crate::{
+    bootstrap::AppState,
+    dto::{
+        FileDiffQueryDto, FileDiffResDto, FileRevisionContentResDto, FileRevisionResDto,
+        FileRevisionsQueryDto, FileRevisionsResDto, WorkspaceFileResDto,
+    },
+    models::{FileRevision, WorkspaceFile, WorkspaceRole},
+    services,
+    token::Claims,
+    utils::{AppError, SuccessResponse},
+};
+
+pub async fn get_file_revisions(
+    State(state): State<AppState>,
+    Path((id, file_id)): Path<(Uuid, Uuid)>,
+    claims: Claims,
+    Query(query): Query<FileRevisionsQueryDto>,
+) -> Result<SuccessResponse<FileRevisionsResDto>, AppError> {
+    let file = get_file(&state, id, file_id, &claims, WorkspaceRole::Viewer).await?;
+    let limit = query.limit.unwrap_or(50).min(100);
+    let offset = query.offset.unwrap_or(0);
+
+    let revisions = services::get_file_revisions(state.db_pool(), file.id, limit, offset).await?;
+    Ok(SuccessResponse::ok(FileRevisionsResDto::from(revisions)))
+}
+
+pub async fn get_file_revision(
+    State(state): State<AppState>,
+    Path((id, file_id, number)): Path<(Uuid, Uuid, i32)>,
+    claims: Claims,
+) -> Result<SuccessResponse<FileRevisionContentResDto>, AppError> {
+    let file = get_file(&state, id, file_id, &claims, WorkspaceRole::Viewer).await?;
+    let revision = get_revision(&state, file.id, number).await?;
+    let content = get_content(&state, &revision).await?;
+    Ok(SuccessResponse::ok(FileRevisionContentResDto {
+        revision: FileRevisionResDto::from(revision),
+        content,
+    }))
+}
+
+pub async fn diff_file_revisions(
+    State(state): State<AppState>,
+    Path((id, file_id)): Path<(Uuid, Uuid)>,
+    claims: Claims,
+    Query(query): Query<FileDiffQueryDto>,
+) -> Result<SuccessResponse<FileDiffResDto>, AppError> {
+    let file = get_file(&state, id, file_id, &claims, WorkspaceRole::Viewer).await?;
+    let from = get_revision(&state, file.id, query.from).await?;
+    let to = match query.to {
+        Some(number) => get_revision(&state, file.id, number).await?,
+        None => services::get_latest_file_revision(state.db_pool(), file.id)
+            .await?
+            .ok_or_else(|| AppError::new(StatusCode::NOT_FOUND, "Revision not found"))?,
+    };
+
+    let from_content = get_content(&state, &from).await?;
+    let to_content = get_content(&state, &to).await?;
+    let diff = services::diff_revisions((&from, &from_content), (&to, &to_content));
+    Ok(SuccessResponse::ok(FileDiffResDto {
+        from: from.number,
+        to: to.number,
+        diff,
+    }))
+}
+
+/// Restores a file to an earlier revision, recorded as a new revision on top.
+pub async fn restore_file_revision(
+    State(state): State<AppState>,
+    Path((id, file_id, number)): Path<(Uuid, Uuid, i32)>,
+    claims: Claims,
+) -> Result<SuccessResponse<WorkspaceFileResDto>, AppError> {
+    let mut file = get_file(&state, id, file_id, &claims, WorkspaceRole::Editor).await?;
+    let revision = get_revision(&state, file.id, number).await?;
+    let content = get_content(&state, &revision).await?;
+    let content_changed = content != file.content;
+    file.content = content;
+
+    let file = save_workspace_file(&state, &file, content_changed, *claims.jti()).await?;
+    tracing::info!("Restored {} to revision {}", file, number);
+    Ok(SuccessResponse::ok(WorkspaceFileResDto::from(file)))
+}
+
+async fn get_file(
+    state: &AppState,
+    id: Uuid,
+    file_id: Uuid,
+    claims: &Claims,
+    required: WorkspaceRole,
+) -> Result<WorkspaceFile, AppError> {
+    let (workspace, _) = authorize_workspace(state, id, claims, required).await?;
+    find_workspace_file(state, workspace.id, file_id).await
+}
+
+async fn get_revision(
+    state: &AppState,
+    file_id: Uuid,
+    number: i32,
+) -> Result<FileRevision, AppError> {
+    services::get_file_revision_by_number(state.db_pool(), file_id, number)
+        .await?
+        .ok_or_else(|| AppError::new(StatusCode::NOT_FOUND, "Revision not found"))
+}
+
+async fn get_content(state: &AppState, revision: &FileRevision) -> Result<String, AppError> {
+    services::get_blob_by_hash(state.db_pool(), &revision.blob_hash)
+        .await?
+        .map(|blob| blob.content)
+        .ok_or_else(|| AppError::internal(anyhow::anyhow!("Missing blob {}", revision.blob_hash)))
+}
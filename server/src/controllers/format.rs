@@ -0,0 +1,77 @@
+use std::time::Instant;
+
+use axum::{extract::State, http::StatusCode, Json};
+use validator::Validate;
+
+use super::check_quotas;
+use crate::{
+    bootstrap::AppState,
+    dto::{FormatErrorsResDto, FormatReqDto, FormatResDto},
+    executor::{self, find_language, FormatOutput},
+    models::ExecutionUsage,
+    services,
+    token::Claims,
+    utils::{AppError, SuccessResponse},
+};
+
+/// Formats code with the formatter its language is configured with, run in the sandbox.
+///
+/// Formatting is held to the caller's execution quotas: it is refused once they are used
+/// up, its sandbox time is charged to them, and each user may only format as many files
+/// at once as they may run programs. Code the formatter refuses is reported as 422, with
+/// the formatter's diagnostics.
+pub async fn format_code(
+    State(state): State<AppState>,
+    claims: Claims,
+    Json(dto): Json<FormatReqDto>,
+) -> Result<SuccessResponse<FormatResDto>, AppError> {
+    dto.validate()
+        .map_err(|e| AppError::new(StatusCode::UNPROCESSABLE_ENTITY, format!("{}", e)))?;
+
+    let language = find_language(&dto.language)
+        .ok_or_else(|| AppError::new(StatusCode::UNPROCESSABLE_ENTITY, "Unsupported language"))?;
+    if language.formatter.is_none() {
+        return Err(AppError::new(
+            StatusCode::UNPROCESSABLE_ENTITY,
+            format!("No formatter is available for {}", language.name),
+        ));
+    }
+    let path = dto.path.as_deref().unwrap_or(language.filename);
+
+    let limit = check_quotas(&state, &claims, None).await?;
+    let concurrent = usize::try_from(limit.concurrent_runs).unwrap_or_default();
+    let _permit = state
+        .formats()
+        .acquire(*claims.jti(), concurrent)
+        .ok_or_else(|| limit.exceeded())?;
+
+    let started = Instant::now();
+    let output = executor::format_code(state.rce(), language, path, &dto.code).await;
+    // Like runs, formatting is charged the wall-clock time it held the sandbox for
+    let usage = ExecutionUsage::new(
+        *claims.jti(),
+        None,
+        language.name,
+        started.elapsed().as_millis() as i64,
+    );
+    services::record_execution_usage(state.db_pool(), &usage).await?;
+
+    let output = output.map_err(|e| {
+        tracing::error!("Unable to format {} code: {}", language.name, e);
+        AppError::new(
+            StatusCode::SERVICE_UNAVAILABLE,
+            "Formatting service unavailable, please try again",
+        )
+    })?;
+    match output {
+        FormatOutput::Formatted(formatted) => Ok(SuccessResponse::ok(FormatResDto {
+            changed: formatted != dto.code,
+            formatted,
+        })),
+        FormatOutput::Rejected(diagnostics) => Err(AppError::with_data(
+            StatusCode::UNPROCESSABLE_ENTITY,
+            "The formatter rejected the code",
+            FormatErrorsResDto { diagnostics },
+        )),
+    }
+}
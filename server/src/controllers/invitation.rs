@@ -0,0 +1,225 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use chrono::Utc;
+use uuid::Uuid;
+use validator::Validate;
+
+use super::{authorize_workspace, ensure_assignable};
+use crate::{
+    bootstrap::AppState,
+    dto::{
+        process_optional_fields, InvitationReqDto, InvitationResDto, InvitationsResDto,
+        InviteLinkReqDto, WorkspaceResDto,
+    },
+    models::{InvitationStatus, Workspace, WorkspaceInvitation, WorkspaceRole},
+    services,
+    token::Claims,
+    utils::{AppError, SuccessResponse},
+};
+
+/// Invites an existing user by username or email.
+pub async fn create_workspace_invitation(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    claims: Claims,
+    Json(dto): Json<InvitationReqDto>,
+) -> Result<SuccessResponse<InvitationResDto>, AppError> {
+    dto.validate()
+        .map_err(|e| AppError::new(StatusCode::UNPROCESSABLE_ENTITY, format!("{}", e)))?;
+
+    let (workspace, _) = authorize_workspace(&state, id, &claims, WorkspaceRole::Owner).await?;
+    ensure_assignable(dto.role)?;
+    let (username, email) = process_optional_fields(dto.username, dto.email)?;
+    let invitee = services::get_user_by_username_or_email(state.db_pool(), &username, &email)
+        .await?
+        .ok_or_else(|| AppError::new(StatusCode::NOT_FOUND, "User not found"))?;
+    ensure_not_member(&state, &workspace, invitee.id).await?;
+    if services::get_pending_invitation_for_user(state.db_pool(), workspace.id, invitee.id)
+        .await?
+        .is_some()
+    {
+        return Err(AppError::new(
+            StatusCode::CONFLICT,
+            "User has already been invited",
+        ));
+    }
+
+    let invitation = WorkspaceInvitation::direct(workspace.id, *claims.jti(), invitee.id, dto.role);
+    let invitation = services::create_workspace_invitation(state.db_pool(), &invitation).await?;
+    tracing::info!("Created {}", invitation);
+    Ok(SuccessResponse::created(InvitationResDto::from(invitation)))
+}
+
+/// Creates an invite link that anybody signed in can redeem.
+pub async fn create_invite_link(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    claims: Claims,
+    Json(dto): Json<InviteLinkReqDto>,
+) -> Result<SuccessResponse<InvitationResDto>, AppError> {
+    dto.validate()
+        .map_err(|e| AppError::new(StatusCode::UNPROCESSABLE_ENTITY, format!("{}", e)))?;
+
+    let (workspace, _) = authorize_workspace(&state, id, &claims, WorkspaceRole::Owner).await?;
+    ensure_assignable(dto.role)?;
+    let expires_at = dto
+        .expires_in_secs
+        .map(|secs| Utc::now() + chrono::Duration::seconds(secs));
+
+    let invitation = WorkspaceInvitation::link(
+        workspace.id,
+        *claims.jti(),
+        dto.role,
+        expires_at,
+        dto.max_uses,
+    );
+    let invitation = services::create_workspace_invitation(state.db_pool(), &invitation).await?;
+    tracing::info!("Created {}", invitation);
+    Ok(SuccessResponse::created(InvitationResDto::from(invitation)))
+}
+
+pub async fn get_workspace_invitations(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    claims: Claims,
+) -> Result<SuccessResponse<InvitationsResDto>, AppError> {
+    let (workspace, _) = authorize_workspace(&state, id, &claims, WorkspaceRole::Owner).await?;
+    let invitations = services::get_workspace_invitations(state.db_pool(), workspace.id).await?;
+    Ok(SuccessResponse::ok(InvitationsResDto::from(invitations)))
+}
+
+pub async fn revoke_workspace_invitation(
+    State(state): State<AppState>,
+    Path((id, invitation_id)): Path<(Uuid, Uuid)>,
+    claims: Claims,
+) -> Result<impl IntoResponse, AppError> {
+    let (workspace, _) = authorize_workspace(&state, id, &claims, WorkspaceRole::Owner).await?;
+    let invitation = services::get_workspace_invitation_by_id(state.db_pool(), invitation_id)
+        .await?
+        .filter(|invitation| {
+            invitation.workspace_id == workspace.id
+                && invitation.status == InvitationStatus::Pending
+        })
+        .ok_or_else(|| AppError::new(StatusCode::NOT_FOUND, "Invitation not found"))?;
+
+    services::update_workspace_invitation_status(
+        state.db_pool(),
+        invitation.id,
+        InvitationStatus::Revoked,
+    )
+    .await?;
+    tracing::info!("Revoked {}", invitation);
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Lists the pending invitations addressed to the caller.
+pub async fn get_my_invitations(
+    State(state): State<AppState>,
+    claims: Claims,
+) -> Result<SuccessResponse<InvitationsResDto>, AppError> {
+    let invitations =
+        services::get_invitations_by_invitee_id(state.db_pool(), *claims.jti()).await?;
+    Ok(SuccessResponse::ok(InvitationsResDto::from(invitations)))
+}
+
+pub async fn accept_invitation(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    claims: Claims,
+) -> Result<SuccessResponse<WorkspaceResDto>, AppError> {
+    let invitation = get_my_pending_invitation(&state, id, &claims).await?;
+    let workspace = get_invited_workspace(&state, &invitation).await?;
+    ensure_not_member(&state, &workspace, *claims.jti()).await?;
+
+    let member =
+        services::accept_workspace_invitation(state.db_pool(), &invitation, *claims.jti()).await?;
+    tracing::info!("Accepted invitation: {}", member);
+    Ok(SuccessResponse::ok(WorkspaceResDto::from(workspace)))
+}
+
+pub async fn decline_invitation(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    claims: Claims,
+) -> Result<impl IntoResponse, AppError> {
+    let invitation = get_my_pending_invitation(&state, id, &claims).await?;
+    services::update_workspace_invitation_status(
+        state.db_pool(),
+        invitation.id,
+        InvitationStatus::Declined,
+    )
+    .await?;
+    tracing::info!("Declined {}", invitation);
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Joins a workspace through an invite link.
+pub async fn redeem_invite_link(
+    State(state): State<AppState>,
+    Path(token): Path<String>,
+    claims: Claims,
+) -> Result<SuccessResponse<WorkspaceResDto>, AppError> {
+    let invitation = services::get_workspace_invitation_by_token(state.db_pool(), &token)
+        .await?
+        .filter(|invitation| invitation.status == InvitationStatus::Pending)
+        .ok_or_else(|| AppError::new(StatusCode::NOT_FOUND, "Invitation not found"))?;
+    let workspace = get_invited_workspace(&state, &invitation).await?;
+    ensure_not_member(&state, &workspace, *claims.jti()).await?;
+
+    let member = services::redeem_workspace_invitation(state.db_pool(), &invitation, *claims.jti())
+        .await?
+        .ok_or_else(|| {
+            AppError::new(
+                StatusCode::GONE,
+                "Invitation link has expired or been used up",
+            )
+        })?;
+    tracing::info!("Redeemed invite link: {}", member);
+    Ok(SuccessResponse::ok(WorkspaceResDto::from(workspace)))
+}
+
+async fn get_my_pending_invitation(
+    state: &AppState,
+    id: Uuid,
+    claims: &Claims,
+) -> Result<WorkspaceInvitation, AppError> {
+    services::get_workspace_invitation_by_id(state.db_pool(), id)
+        .await?
+        .filter(|invitation| {
+            invitation.invitee_id == Some(*claims.jti())
+                && invitation.status == InvitationStatus::Pending
+                && !invitation.is_expired()
+        })
+        .ok_or_else(|| AppError::new(StatusCode::NOT_FOUND, "Invitation not found"))
+}
+
+async fn get_invited_workspace(
+    state: &AppState,
+    invitation: &WorkspaceInvitation,
+) -> Result<Workspace, AppError> {
+    services::get_workspace_by_id(state.db_pool(), invitation.workspace_id)
+        .await?
+        .ok_or_else(|| AppError::new(StatusCode::NOT_FOUND, "Workspace not found"))
+}
+
+async fn ensure_not_member(
+    state: &AppState,
+    workspace: &Workspace,
+    user_id: Uuid,
+) -> Result<(), AppError> {
+    let is_member = workspace.owner_id == user_id
+        || services::get_workspace_member(state.db_pool(), workspace.id, user_id)
+            .await?
+            .is_some();
+    if is_member {
+        return Err(AppError::new(
+            StatusCode::CONFLICT,
+            "User is already a member of the workspace",
+        ));
+    }
+    Ok(())
+}
@@ -0,0 +1,55 @@
+use axum::{
+    extract::{ws::WebSocketUpgrade, Path, Query, State},
+    http::StatusCode,
+    response::Response,
+};
+use uuid::Uuid;
+
+use super::authorize_workspace;
+use crate::{
+    bootstrap::AppState,
+    dto::LspQueryDto,
+    executor::find_language,
+    lsp::{serve_lsp_connection, LANGUAGE_SERVER_ROLE},
+    middlewares::auth::validate_access_token,
+    utils::AppError,
+};
+
+/// Upgrades to a WebSocket speaking LSP with a language server over the workspace files.
+///
+/// Each connection starts its own server, and users may only run a few at a time. Language
+/// servers may run code from the workspace, so viewers cannot start them.
+pub async fn language_server(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Query(query): Query<LspQueryDto>,
+    ws: WebSocketUpgrade,
+) -> Result<Response, AppError> {
+    let claims = validate_access_token(&state, &query.token)?;
+    let (workspace, _) = authorize_workspace(&state, id, &claims, LANGUAGE_SERVER_ROLE).await?;
+
+    let name = query.language.or(workspace.language).unwrap_or_default();
+    let language = find_language(&name)
+        .ok_or_else(|| AppError::new(StatusCode::UNPROCESSABLE_ENTITY, "Unsupported language"))?;
+    if language.language_server.is_none() {
+        return Err(AppError::new(
+            StatusCode::UNPROCESSABLE_ENTITY,
+            format!("No language server is available for {}", language.name),
+        ));
+    }
+
+    let limit = *state.config().lsp().max_servers_per_user();
+    let permit = state.lsp().acquire(*claims.jti(), limit).ok_or_else(|| {
+        AppError::new(
+            StatusCode::TOO_MANY_REQUESTS,
+            format!("At most {} language servers may run at once", limit),
+        )
+    })?;
+
+    let max_message_bytes = *state.config().lsp().max_message_bytes();
+    Ok(ws
+        .max_message_size(max_message_bytes)
+        .on_upgrade(move |socket| {
+            serve_lsp_connection(socket, state, workspace.id, claims, language, permit)
+        }))
+}
@@ -1,13 +1,53 @@
+mod archive;
 mod auth;
+mod avatar;
+mod classroom;
+mod collab;
+mod comment;
+mod execution;
+mod file_revision;
+mod format;
 mod health_check;
+mod invitation;
+mod lsp;
+mod notification;
+mod organization;
+mod recording;
+mod search;
 mod session;
+mod snippet;
+mod template;
+mod usage;
 mod user;
+mod webhook;
+mod workspace;
+mod workspace_member;
 
+pub use archive::*;
 pub use auth::*;
+pub use avatar::*;
 use axum_extra::extract::cookie::{Cookie, SameSite};
+pub use classroom::*;
+pub use collab::*;
+pub use comment::*;
+pub use execution::*;
+pub use file_revision::*;
+pub use format::*;
 pub use health_check::*;
+pub use invitation::*;
+pub use lsp::*;
+pub use notification::*;
+pub use organization::*;
+pub use recording::*;
+pub use search::*;
 pub use session::*;
+pub use snippet::*;
+pub use template::*;
+pub use usage::*;
 pub use user::*;
+pub use webhook::*;
+pub use workspace::*;
+pub use workspace_member::*;
 
 pub(super) fn create_cookie_session(refresh_token: impl Into<String>, ttl: i64) -> Cookie<'static> {
     let max_age = time::Duration::seconds(ttl);
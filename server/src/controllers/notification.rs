@@ -0,0 +1,96 @@
+use axum::{
+    extract::{ws::WebSocketUpgrade, Path, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use uuid::Uuid;
+
+use crate::{
+    bootstrap::AppState,
+    dto::{
+        NotificationPreferencesDto, NotificationStreamQueryDto, NotificationsQueryDto,
+        NotificationsResDto,
+    },
+    middlewares::auth::validate_access_token,
+    notifications::serve_notification_stream,
+    services,
+    token::Claims,
+    utils::{AppError, SuccessResponse},
+};
+
+pub async fn get_my_notifications(
+    State(state): State<AppState>,
+    claims: Claims,
+    Query(query): Query<NotificationsQueryDto>,
+) -> Result<SuccessResponse<NotificationsResDto>, AppError> {
+    let limit = query.limit.unwrap_or(50).min(100);
+    let offset = query.offset.unwrap_or(0);
+    let unread_only = query.unread.unwrap_or(false);
+
+    let notifications = services::get_notifications_by_user_id(
+        state.db_pool(),
+        *claims.jti(),
+        unread_only,
+        query.category,
+        limit,
+        offset,
+    )
+    .await?;
+    let unread_count = services::count_unread_notifications(state.db_pool(), *claims.jti()).await?;
+    Ok(SuccessResponse::ok(NotificationsResDto::new(
+        notifications,
+        unread_count,
+    )))
+}
+
+pub async fn mark_notification_read(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    claims: Claims,
+) -> Result<impl IntoResponse, AppError> {
+    if !services::mark_notification_read(state.db_pool(), *claims.jti(), id).await? {
+        return Err(AppError::new(
+            StatusCode::NOT_FOUND,
+            "Notification not found",
+        ));
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub async fn mark_all_notifications_read(
+    State(state): State<AppState>,
+    claims: Claims,
+) -> Result<impl IntoResponse, AppError> {
+    services::mark_all_notifications_read(state.db_pool(), *claims.jti()).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub async fn get_notification_preferences(
+    State(state): State<AppState>,
+    claims: Claims,
+) -> Result<SuccessResponse<NotificationPreferencesDto>, AppError> {
+    let muted = services::get_muted_categories(state.db_pool(), *claims.jti()).await?;
+    Ok(SuccessResponse::ok(NotificationPreferencesDto { muted }))
+}
+
+pub async fn update_notification_preferences(
+    State(state): State<AppState>,
+    claims: Claims,
+    Json(dto): Json<NotificationPreferencesDto>,
+) -> Result<SuccessResponse<NotificationPreferencesDto>, AppError> {
+    services::set_muted_categories(state.db_pool(), *claims.jti(), &dto.muted).await?;
+    let muted = services::get_muted_categories(state.db_pool(), *claims.jti()).await?;
+    Ok(SuccessResponse::ok(NotificationPreferencesDto { muted }))
+}
+
+/// Upgrades to a WebSocket that pushes the user's notifications as they are created.
+pub async fn stream_notifications(
+    State(state): State<AppState>,
+    Query(query): Query<NotificationStreamQueryDto>,
+    ws: WebSocketUpgrade,
+) -> Result<Response, AppError> {
+    let claims = validate_access_token(&state, &query.token)?;
+    let user_id = *claims.jti();
+    Ok(ws.on_upgrade(move |socket| serve_notification_stream(socket, state, user_id)))
+}
@@ -0,0 +1,372 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use chrono::{Duration, Utc};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    bootstrap::AppState,
+    dto::{
+        OrganizationMemberReqDto, OrganizationMemberResDto, OrganizationMembersResDto,
+        OrganizationReqDto, OrganizationResDto, OrganizationUsageResDto, OrganizationsResDto,
+        PatchOrganizationMemberReqDto, PatchOrganizationReqDto, UsageReportQueryDto,
+        WorkspacesQueryDto, WorkspacesResDto,
+    },
+    middlewares::auth::check_admin,
+    models::{Organization, OrganizationMember, OrganizationMemberProfile, OrganizationRole},
+    services,
+    token::Claims,
+    utils::{AppError, SuccessResponse},
+};
+
+/// Creates an organization owned by the caller.
+pub async fn create_organization(
+    State(state): State<AppState>,
+    claims: Claims,
+    Json(dto): Json<OrganizationReqDto>,
+) -> Result<SuccessResponse<OrganizationResDto>, AppError> {
+    dto.validate()
+        .map_err(|e| AppError::new(StatusCode::UNPROCESSABLE_ENTITY, format!("{}", e)))?;
+
+    if services::get_organization_by_slug(state.db_pool(), &dto.slug)
+        .await?
+        .is_some()
+    {
+        return Err(AppError::new(
+            StatusCode::CONFLICT,
+            "Organization with this slug already exists",
+        ));
+    }
+
+    let limits = state.config().quota().organization();
+    let organization = Organization::new(dto.slug, dto.name, limits);
+    let organization =
+        services::create_organization(state.db_pool(), &organization, *claims.jti()).await?;
+    tracing::info!("Created {}", organization);
+    Ok(SuccessResponse::created(OrganizationResDto::from(
+        organization,
+    )))
+}
+
+pub async fn get_my_organizations(
+    State(state): State<AppState>,
+    claims: Claims,
+) -> Result<SuccessResponse<OrganizationsResDto>, AppError> {
+    let memberships =
+        services::get_organizations_by_member_id(state.db_pool(), *claims.jti()).await?;
+    Ok(SuccessResponse::ok(OrganizationsResDto::from(memberships)))
+}
+
+pub async fn get_organization(
+    State(state): State<AppState>,
+    Path(slug): Path<String>,
+    claims: Claims,
+) -> Result<SuccessResponse<OrganizationResDto>, AppError> {
+    let (organization, _) =
+        authorize_organization(&state, &slug, &claims, OrganizationRole::Member).await?;
+    Ok(SuccessResponse::ok(OrganizationResDto::from(organization)))
+}
+
+/// Renames an organization, which takes one of its admins; changing its quotas
+/// takes an administrator of the platform, since quotas are what the organization pays for.
+pub async fn update_organization(
+    State(state): State<AppState>,
+    Path(slug): Path<String>,
+    claims: Claims,
+    Json(dto): Json<PatchOrganizationReqDto>,
+) -> Result<SuccessResponse<OrganizationResDto>, AppError> {
+    dto.validate()
+        .map_err(|e| AppError::new(StatusCode::UNPROCESSABLE_ENTITY, format!("{}", e)))?;
+
+    let (mut organization, _) =
+        authorize_organization(&state, &slug, &claims, OrganizationRole::Admin).await?;
+    let changes_quotas = dto.executions_per_hour.is_some()
+        || dto.cpu_seconds_per_day.is_some()
+        || dto.concurrent_runs.is_some();
+    if changes_quotas {
+        check_admin(&claims)?;
+    }
+    if let Some(name) = dto.name {
+        organization.name = name;
+    }
+    if let Some(executions_per_hour) = dto.executions_per_hour {
+        organization.executions_per_hour = executions_per_hour;
+    }
+    if let Some(cpu_seconds_per_day) = dto.cpu_seconds_per_day {
+        organization.cpu_seconds_per_day = cpu_seconds_per_day;
+    }
+    if let Some(concurrent_runs) = dto.concurrent_runs {
+        organization.concurrent_runs = concurrent_runs;
+    }
+
+    let organization = services::update_organization(state.db_pool(), &organization).await?;
+    tracing::info!("Updated {}", organization);
+    Ok(SuccessResponse::ok(OrganizationResDto::from(organization)))
+}
+
+/// Deletes an organization along with its workspaces and templates.
+pub async fn delete_organization(
+    State(state): State<AppState>,
+    Path(slug): Path<String>,
+    claims: Claims,
+) -> Result<impl IntoResponse, AppError> {
+    let (organization, _) =
+        authorize_organization(&state, &slug, &claims, OrganizationRole::Owner).await?;
+    services::delete_organization(state.db_pool(), organization.id).await?;
+    tracing::info!("Deleted {}", organization);
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub async fn get_organization_members(
+    State(state): State<AppState>,
+    Path(slug): Path<String>,
+    claims: Claims,
+) -> Result<SuccessResponse<OrganizationMembersResDto>, AppError> {
+    let (organization, _) =
+        authorize_organization(&state, &slug, &claims, OrganizationRole::Member).await?;
+    let members =
+        services::get_organization_member_profiles(state.db_pool(), organization.id).await?;
+    Ok(SuccessResponse::ok(OrganizationMembersResDto::from(
+        members,
+    )))
+}
+
+/// Adds a user to an organization right away; organizations are managed centrally, so
+/// there is no invitation to accept.
+pub async fn add_organization_member(
+    State(state): State<AppState>,
+    Path(slug): Path<String>,
+    claims: Claims,
+    Json(dto): Json<OrganizationMemberReqDto>,
+) -> Result<SuccessResponse<OrganizationMemberResDto>, AppError> {
+    dto.validate()
+        .map_err(|e| AppError::new(StatusCode::UNPROCESSABLE_ENTITY, format!("{}", e)))?;
+
+    let (organization, caller_role) =
+        authorize_organization(&state, &slug, &claims, OrganizationRole::Admin).await?;
+    let role = dto.role.unwrap_or(OrganizationRole::Member);
+    ensure_can_manage(caller_role, role)?;
+    let user = services::get_user_by_username(state.db_pool(), &dto.username.to_lowercase())
+        .await?
+        .ok_or_else(|| AppError::new(StatusCode::NOT_FOUND, "User not found"))?;
+    if services::get_organization_member(state.db_pool(), organization.id, user.id)
+        .await?
+        .is_some()
+    {
+        return Err(AppError::new(
+            StatusCode::CONFLICT,
+            "User is already a member of this organization",
+        ));
+    }
+
+    let member = OrganizationMember::new(organization.id, user.id, role);
+    let member = services::create_organization_member(state.db_pool(), &member).await?;
+    tracing::info!("Added {}", member);
+    let profile = find_member_profile(&state, organization.id, member.user_id).await?;
+    Ok(SuccessResponse::created(OrganizationMemberResDto::from(
+        profile,
+    )))
+}
+
+pub async fn update_organization_member(
+    State(state): State<AppState>,
+    Path((slug, user_id)): Path<(String, Uuid)>,
+    claims: Claims,
+    Json(dto): Json<PatchOrganizationMemberReqDto>,
+) -> Result<SuccessResponse<OrganizationMemberResDto>, AppError> {
+    let (organization, caller_role) =
+        authorize_organization(&state, &slug, &claims, OrganizationRole::Admin).await?;
+    let member = find_member(&state, organization.id, user_id).await?;
+    ensure_can_manage(caller_role, member.role.max(dto.role))?;
+    if member.role == OrganizationRole::Owner && dto.role != OrganizationRole::Owner {
+        ensure_other_owner(&state, organization.id).await?;
+    }
+
+    let member = services::update_organization_member_role(
+        state.db_pool(),
+        organization.id,
+        member.user_id,
+        dto.role,
+    )
+    .await?;
+    tracing::info!("Updated {}", member);
+    let profile = find_member_profile(&state, organization.id, member.user_id).await?;
+    Ok(SuccessResponse::ok(OrganizationMemberResDto::from(profile)))
+}
+
+/// Removes a member from an organization; members may also remove themselves to leave it.
+pub async fn remove_organization_member(
+    State(state): State<AppState>,
+    Path((slug, user_id)): Path<(String, Uuid)>,
+    claims: Claims,
+) -> Result<impl IntoResponse, AppError> {
+    let required = if user_id == *claims.jti() {
+        OrganizationRole::Member
+    } else {
+        OrganizationRole::Admin
+    };
+    let (organization, caller_role) =
+        authorize_organization(&state, &slug, &claims, required).await?;
+    let member = find_member(&state, organization.id, user_id).await?;
+    if user_id != *claims.jti() {
+        ensure_can_manage(caller_role, member.role)?;
+    }
+    if member.role == OrganizationRole::Owner {
+        ensure_other_owner(&state, organization.id).await?;
+    }
+
+    services::delete_organization_member(state.db_pool(), organization.id, member.user_id).await?;
+    tracing::info!("Removed {}", member);
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub async fn get_organization_workspaces(
+    State(state): State<AppState>,
+    Path(slug): Path<String>,
+    claims: Claims,
+    Query(query): Query<WorkspacesQueryDto>,
+) -> Result<SuccessResponse<WorkspacesResDto>, AppError> {
+    let (organization, _) =
+        authorize_organization(&state, &slug, &claims, OrganizationRole::Member).await?;
+    let limit = query.limit.unwrap_or(50).min(100);
+    let offset = query.offset.unwrap_or(0);
+
+    let workspaces = services::get_workspaces_by_organization_id(
+        state.db_pool(),
+        organization.id,
+        limit,
+        offset,
+    )
+    .await?;
+    Ok(SuccessResponse::ok(WorkspacesResDto::from(workspaces)))
+}
+
+/// Reports the organization's quotas and how much of them each member used.
+pub async fn get_organization_usage(
+    State(state): State<AppState>,
+    Path(slug): Path<String>,
+    claims: Claims,
+    Query(query): Query<UsageReportQueryDto>,
+) -> Result<SuccessResponse<OrganizationUsageResDto>, AppError> {
+    let (organization, _) =
+        authorize_organization(&state, &slug, &claims, OrganizationRole::Admin).await?;
+    let to = query.to.unwrap_or_else(Utc::now);
+    let from = query.from.unwrap_or(to - Duration::days(1));
+    let limit = query.limit.unwrap_or(50).min(100);
+    let offset = query.offset.unwrap_or(0);
+
+    let quotas = services::get_organization_quota_usage(state.db_pool(), &organization).await?;
+    let users = services::get_organization_usage_report(
+        state.db_pool(),
+        organization.id,
+        from,
+        to,
+        limit,
+        offset,
+    )
+    .await?;
+    Ok(SuccessResponse::ok(OrganizationUsageResDto {
+        quotas,
+        from,
+        to,
+        users,
+    }))
+}
+
+/// Loads an organization in which the caller holds at least the `required` role.
+///
+/// Organizations the caller does not belong to are reported as missing, while members
+/// lacking the role are refused. Administrators act as owners.
+pub(super) async fn authorize_organization(
+    state: &AppState,
+    slug: &str,
+    claims: &Claims,
+    required: OrganizationRole,
+) -> Result<(Organization, OrganizationRole), AppError> {
+    let organization = services::get_organization_by_slug(state.db_pool(), slug).await?;
+    authorize_member(state, organization, claims, required).await
+}
+
+/// Like [`authorize_organization`], for organizations referenced by ID in request bodies.
+pub(super) async fn authorize_organization_by_id(
+    state: &AppState,
+    id: Uuid,
+    claims: &Claims,
+    required: OrganizationRole,
+) -> Result<(Organization, OrganizationRole), AppError> {
+    let organization = services::get_organization_by_id(state.db_pool(), id).await?;
+    authorize_member(state, organization, claims, required).await
+}
+
+async fn authorize_member(
+    state: &AppState,
+    organization: Option<Organization>,
+    claims: &Claims,
+    required: OrganizationRole,
+) -> Result<(Organization, OrganizationRole), AppError> {
+    let not_found = || AppError::new(StatusCode::NOT_FOUND, "Organization not found");
+    let organization = organization.ok_or_else(not_found)?;
+    let role = if *claims.is_admin() {
+        OrganizationRole::Owner
+    } else {
+        services::get_organization_member(state.db_pool(), organization.id, *claims.jti())
+            .await?
+            .ok_or_else(not_found)?
+            .role
+    };
+    if role < required {
+        return Err(AppError::new(
+            StatusCode::FORBIDDEN,
+            format!("This requires the {} role", required),
+        ));
+    }
+    Ok((organization, role))
+}
+
+/// Admins manage members and admins, while only owners may appoint or demote owners.
+fn ensure_can_manage(caller: OrganizationRole, role: OrganizationRole) -> Result<(), AppError> {
+    if role == OrganizationRole::Owner && caller != OrganizationRole::Owner {
+        return Err(AppError::new(
+            StatusCode::FORBIDDEN,
+            "Only owners can manage owners",
+        ));
+    }
+    Ok(())
+}
+
+/// Keeps organizations from ending up without an owner.
+async fn ensure_other_owner(state: &AppState, organization_id: Uuid) -> Result<(), AppError> {
+    if services::count_organization_owners(state.db_pool(), organization_id).await? <= 1 {
+        return Err(AppError::new(
+            StatusCode::UNPROCESSABLE_ENTITY,
+            "Organizations must keep at least one owner",
+        ));
+    }
+    Ok(())
+}
+
+async fn find_member(
+    state: &AppState,
+    organization_id: Uuid,
+    user_id: Uuid,
+) -> Result<OrganizationMember, AppError> {
+    services::get_organization_member(state.db_pool(), organization_id, user_id)
+        .await?
+        .ok_or_else(|| AppError::new(StatusCode::NOT_FOUND, "Member not found"))
+}
+
+async fn find_member_profile(
+    state: &AppState,
+    organization_id: Uuid,
+    user_id: Uuid,
+) -> Result<OrganizationMemberProfile, AppError> {
+    services::get_organization_member_profiles(state.db_pool(), organization_id)
+        .await?
+        .into_iter()
+        .find(|profile| profile.user_id == user_id)
+        .ok_or_else(|| AppError::new(StatusCode::NOT_FOUND, "Member not found"))
+}
@@ -0,0 +1,74 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::{header, StatusCode},
+    response::IntoResponse,
+};
+use chrono::Utc;
+use uuid::Uuid;
+
+use super::{authorize_workspace, find_workspace_file};
+use crate::{
+    bootstrap::AppState,
+    dto::{DocumentStateQueryDto, DocumentStateResDto, RecordedEventResDto, RecordingQueryDto},
+    models::WorkspaceRole,
+    services,
+    token::Claims,
+    utils::{AppError, SuccessResponse},
+};
+
+/// Streams the recorded events of a workspace within a time window.
+///
+/// The log is gzipped newline-delimited JSON, oldest event first. A page holds at most
+/// `limit` events; when it is full, ask again with `after` set to the last event's ID.
+pub async fn get_workspace_recording(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    claims: Claims,
+    Query(query): Query<RecordingQueryDto>,
+) -> Result<impl IntoResponse, AppError> {
+    let (workspace, _) = authorize_workspace(&state, id, &claims, WorkspaceRole::Viewer).await?;
+    let to = query.to.unwrap_or_else(Utc::now);
+    if query.from > to {
+        return Err(AppError::new(
+            StatusCode::UNPROCESSABLE_ENTITY,
+            "The window must not end before it starts",
+        ));
+    }
+    let limit = query.limit.unwrap_or(1000).clamp(1, 5000);
+
+    let events = services::get_session_events(
+        state.db_pool(),
+        workspace.id,
+        query.file_id,
+        query.from,
+        to,
+        query.after.unwrap_or(0),
+        limit,
+    )
+    .await?;
+    let events: Vec<RecordedEventResDto> = events.into_iter().map(Into::into).collect();
+    let log = services::compress_session_log(&events)?;
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/x-ndjson"),
+            (header::CONTENT_ENCODING, "gzip"),
+        ],
+        log,
+    ))
+}
+
+/// Reconstructs a file as it was at the requested moment, for scrubbing through history.
+pub async fn get_file_state_at(
+    State(state): State<AppState>,
+    Path((id, file_id)): Path<(Uuid, Uuid)>,
+    claims: Claims,
+    Query(query): Query<DocumentStateQueryDto>,
+) -> Result<SuccessResponse<DocumentStateResDto>, AppError> {
+    let (workspace, _) = authorize_workspace(&state, id, &claims, WorkspaceRole::Viewer).await?;
+    let file = find_workspace_file(&state, workspace.id, file_id).await?;
+
+    let document = services::get_document_state_at(state.db_pool(), file.id, query.at).await?;
+    Ok(SuccessResponse::ok(DocumentStateResDto::new(
+        query.at, document,
+    )))
+}
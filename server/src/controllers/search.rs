@@ -0,0 +1,57 @@
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+};
+use validator::Validate;
+
+use super::normalize_language;
+use crate::{
+    bootstrap::AppState,
+    dto::{SearchQueryDto, SearchResDto},
+    models::SearchFilter,
+    services,
+    token::Claims,
+    utils::{AppError, SuccessResponse},
+};
+
+/// Searches the code of the caller's workspaces and of public snippets, best match first.
+///
+/// Anonymous callers only search public snippets.
+pub async fn search(
+    State(state): State<AppState>,
+    claims: Option<Claims>,
+    Query(query): Query<SearchQueryDto>,
+) -> Result<SuccessResponse<SearchResDto>, AppError> {
+    query
+        .validate()
+        .map_err(|e| AppError::new(StatusCode::UNPROCESSABLE_ENTITY, format!("{}", e)))?;
+    let limit = query.limit.unwrap_or(50).min(100);
+    let offset = query.offset.unwrap_or(0);
+
+    let mut filter = SearchFilter {
+        language: query
+            .language
+            .as_deref()
+            .map(normalize_language)
+            .transpose()?,
+        workspace_id: query.workspace,
+        owner_id: None,
+    };
+    if let Some(owner) = query.owner {
+        match services::get_user_by_username(state.db_pool(), &owner).await? {
+            Some(user) => filter.owner_id = Some(user.id),
+            None => return Ok(SuccessResponse::ok(SearchResDto { hits: Vec::new() })),
+        }
+    }
+
+    let hits = services::search_code(
+        state.db_pool(),
+        &query.q,
+        claims.map(|claims| *claims.jti()),
+        &filter,
+        limit,
+        offset,
+    )
+    .await?;
+    Ok(SuccessResponse::ok(SearchResDto::from(hits)))
+}
@@ -12,7 +12,10 @@ use crate::{
     bootstrap::AppState,
     dto::{AccessTokenReqDto, AccessTokenResDto},
     middlewares::auth::{check_admin, RefreshClaims},
-    services::{delete_session_by_user_id, get_session_by_user_id, revoke_session},
+    models::Notification,
+    services::{
+        delete_session_by_user_id, get_session_by_user_id, get_user_by_id, notify, revoke_session,
+    },
     token::{Claims, TokenManager},
     utils::{AppError, SuccessResponse},
 };
@@ -74,6 +77,9 @@ pub async fn revoke_user_session(
     check_admin(&claims)?;
 
     revoke_session(state.db_pool(), user_id).await?;
+    if get_user_by_id(state.db_pool(), user_id).await?.is_some() {
+        notify(state.db_pool(), &Notification::sessions_revoked(user_id)).await?;
+    }
     Ok(StatusCode::NO_CONTENT)
 }
 
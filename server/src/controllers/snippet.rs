@@ -0,0 +1,206 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode},
+    response::IntoResponse,
+    Json,
+};
+use chrono::{Duration, Utc};
+use validator::Validate;
+
+use super::{ensure_unique_name, normalize_language};
+use crate::{
+    bootstrap::AppState,
+    dto::{
+        ForkReqDto, PatchSnippetReqDto, SnippetReqDto, SnippetResDto, SnippetsQueryDto,
+        SnippetsResDto, WorkspaceResDto,
+    },
+    models::{Snippet, SnippetVisibility, Workspace},
+    services,
+    token::Claims,
+    utils::{check_password, hash_password, AppError, SuccessResponse},
+};
+
+/// Header carrying the password of a password-protected snippet.
+const SNIPPET_PASSWORD_HEADER: &str = "X-Snippet-Password";
+
+pub async fn create_snippet(
+    State(state): State<AppState>,
+    claims: Claims,
+    Json(dto): Json<SnippetReqDto>,
+) -> Result<SuccessResponse<SnippetResDto>, AppError> {
+    dto.validate()
+        .map_err(|e| AppError::new(StatusCode::UNPROCESSABLE_ENTITY, format!("{}", e)))?;
+
+    let language = dto
+        .language
+        .as_deref()
+        .map(normalize_language)
+        .transpose()?;
+    let mut snippet = Snippet::new(
+        *claims.jti(),
+        dto.title,
+        language,
+        dto.files.into_iter().map(Into::into).collect(),
+        dto.visibility.unwrap_or(SnippetVisibility::Unlisted),
+    );
+    snippet.password_hash = dto.password.as_deref().map(hash_password).transpose()?;
+    snippet.expires_at = dto
+        .expires_in_secs
+        .map(|secs| Utc::now() + Duration::seconds(secs));
+
+    let snippet = services::create_snippet(state.db_pool(), &snippet).await?;
+    tracing::info!("Created {}", snippet);
+    Ok(SuccessResponse::created(SnippetResDto::from(snippet)))
+}
+
+pub async fn get_my_snippets(
+    State(state): State<AppState>,
+    claims: Claims,
+    Query(query): Query<SnippetsQueryDto>,
+) -> Result<SuccessResponse<SnippetsResDto>, AppError> {
+    let limit = query.limit.unwrap_or(50).min(100);
+    let offset = query.offset.unwrap_or(0);
+
+    let snippets =
+        services::get_snippets_by_owner_id(state.db_pool(), *claims.jti(), limit, offset).await?;
+    Ok(SuccessResponse::ok(SnippetsResDto::from(snippets)))
+}
+
+pub async fn get_public_snippets(
+    State(state): State<AppState>,
+    Query(query): Query<SnippetsQueryDto>,
+) -> Result<SuccessResponse<SnippetsResDto>, AppError> {
+    let limit = query.limit.unwrap_or(50).min(100);
+    let offset = query.offset.unwrap_or(0);
+
+    let snippets = services::get_public_snippets(state.db_pool(), limit, offset).await?;
+    Ok(SuccessResponse::ok(SnippetsResDto::from(snippets)))
+}
+
+/// Opens a snippet by slug, anonymously unless it is private.
+///
+/// Owners always see their own snippets. Everyone else gets a 404 for private or
+/// expired snippets and must send the password header for protected ones.
+pub async fn get_snippet(
+    State(state): State<AppState>,
+    Path(slug): Path<String>,
+    claims: Option<Claims>,
+    headers: HeaderMap,
+) -> Result<SuccessResponse<SnippetResDto>, AppError> {
+    let snippet = open_snippet(&state, &slug, claims.as_ref(), &headers).await?;
+    Ok(SuccessResponse::ok(SnippetResDto::from(snippet)))
+}
+
+/// Copies a snippet the caller can open into a new workspace of theirs.
+pub async fn fork_snippet(
+    State(state): State<AppState>,
+    Path(slug): Path<String>,
+    claims: Claims,
+    headers: HeaderMap,
+    Json(dto): Json<ForkReqDto>,
+) -> Result<SuccessResponse<WorkspaceResDto>, AppError> {
+    dto.validate()
+        .map_err(|e| AppError::new(StatusCode::UNPROCESSABLE_ENTITY, format!("{}", e)))?;
+
+    let snippet = open_snippet(&state, &slug, Some(&claims), &headers).await?;
+    let name = dto.name.unwrap_or_else(|| snippet.title.clone());
+    ensure_unique_name(&state, *claims.jti(), &name).await?;
+
+    let mut fork = Workspace::new(*claims.jti(), name, snippet.language.clone());
+    fork.forked_from_snippet_id = Some(snippet.id);
+    let fork = services::fork_snippet(state.db_pool(), &snippet, &fork).await?;
+    tracing::info!("Forked {} into {}", snippet, fork);
+    Ok(SuccessResponse::created(WorkspaceResDto::from(fork)))
+}
+
+pub async fn update_snippet(
+    State(state): State<AppState>,
+    Path(slug): Path<String>,
+    claims: Claims,
+    Json(dto): Json<PatchSnippetReqDto>,
+) -> Result<SuccessResponse<SnippetResDto>, AppError> {
+    dto.validate()
+        .map_err(|e| AppError::new(StatusCode::UNPROCESSABLE_ENTITY, format!("{}", e)))?;
+
+    let mut snippet = get_owned_snippet(&state, &slug, &claims).await?;
+    if let Some(title) = dto.title {
+        snippet.title = title;
+    }
+    if let Some(language) = dto.language {
+        snippet.language = Some(normalize_language(&language)?);
+    }
+    if let Some(files) = dto.files {
+        snippet.files.0 = files.into_iter().map(Into::into).collect();
+    }
+    if let Some(visibility) = dto.visibility {
+        snippet.visibility = visibility;
+    }
+    if let Some(password) = dto.password {
+        snippet.password_hash = password.as_deref().map(hash_password).transpose()?;
+    }
+    if let Some(expires_in_secs) = dto.expires_in_secs {
+        snippet.expires_at = expires_in_secs.map(|secs| Utc::now() + Duration::seconds(secs));
+    }
+
+    let snippet = services::update_snippet(state.db_pool(), &snippet).await?;
+    tracing::info!("Updated {}", snippet);
+    Ok(SuccessResponse::ok(SnippetResDto::from(snippet)))
+}
+
+pub async fn delete_snippet(
+    State(state): State<AppState>,
+    Path(slug): Path<String>,
+    claims: Claims,
+) -> Result<impl IntoResponse, AppError> {
+    let snippet = get_owned_snippet(&state, &slug, &claims).await?;
+    services::delete_snippet(state.db_pool(), snippet.id).await?;
+    tracing::info!("Deleted {}", snippet);
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn open_snippet(
+    state: &AppState,
+    slug: &str,
+    claims: Option<&Claims>,
+    headers: &HeaderMap,
+) -> Result<Snippet, AppError> {
+    let snippet = find_snippet(state, slug).await?;
+    let is_owner =
+        claims.is_some_and(|claims| snippet.owner_id == *claims.jti() || *claims.is_admin());
+    if is_owner {
+        return Ok(snippet);
+    }
+
+    if snippet.visibility == SnippetVisibility::Private || snippet.is_expired() {
+        return Err(AppError::new(StatusCode::NOT_FOUND, "Snippet not found"));
+    }
+    if let Some(password_hash) = &snippet.password_hash {
+        let password = headers
+            .get(SNIPPET_PASSWORD_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .ok_or_else(|| AppError::new(StatusCode::UNAUTHORIZED, "Snippet password required"))?;
+        if !check_password(password, password_hash)? {
+            return Err(AppError::new(
+                StatusCode::UNAUTHORIZED,
+                "Invalid snippet password",
+            ));
+        }
+    }
+    Ok(snippet)
+}
+
+async fn find_snippet(state: &AppState, slug: &str) -> Result<Snippet, AppError> {
+    services::get_snippet_by_slug(state.db_pool(), slug)
+        .await?
+        .ok_or_else(|| AppError::new(StatusCode::NOT_FOUND, "Snippet not found"))
+}
+
+async fn get_owned_snippet(
+    state: &AppState,
+    slug: &str,
+    claims: &Claims,
+) -> Result<Snippet, AppError> {
+    Some(find_snippet(state, slug).await?)
+        .filter(|snippet| snippet.owner_id == *claims.jti() || *claims.is_admin())
+        .ok_or_else(|| AppError::new(StatusCode::NOT_FOUND, "Snippet not found"))
+}
@@ -0,0 +1,217 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use validator::Validate;
+
+use super::{authorize_organization_by_id, authorize_workspace, normalize_language};
+use crate::{
+    bootstrap::AppState,
+    dto::{
+        PatchTemplateReqDto, TemplateReqDto, TemplateResDto, TemplatesQueryDto, TemplatesResDto,
+    },
+    middlewares::auth::check_admin,
+    models::{OrganizationRole, WorkspaceRole, WorkspaceTemplate},
+    services,
+    token::Claims,
+    utils::{AppError, SuccessResponse},
+};
+
+/// Lists the public catalogue, plus the private templates of the caller's workspaces and
+/// organizations.
+pub async fn get_templates(
+    State(state): State<AppState>,
+    claims: Option<Claims>,
+    Query(query): Query<TemplatesQueryDto>,
+) -> Result<SuccessResponse<TemplatesResDto>, AppError> {
+    let limit = query.limit.unwrap_or(50).min(100);
+    let offset = query.offset.unwrap_or(0);
+    let language = query
+        .language
+        .as_deref()
+        .map(normalize_language)
+        .transpose()?;
+
+    let templates = services::get_workspace_templates(
+        state.db_pool(),
+        claims.map(|claims| *claims.jti()),
+        language.as_deref(),
+        query.tag.as_deref(),
+        limit,
+        offset,
+    )
+    .await?;
+    Ok(SuccessResponse::ok(TemplatesResDto::from(templates)))
+}
+
+pub async fn get_template(
+    State(state): State<AppState>,
+    Path(slug): Path<String>,
+    claims: Option<Claims>,
+) -> Result<SuccessResponse<TemplateResDto>, AppError> {
+    let template = open_template(&state, &slug, claims.as_ref()).await?;
+    Ok(SuccessResponse::ok(TemplateResDto::from(template)))
+}
+
+/// Adds a template to the public catalogue, which takes an admin, or publishes one
+/// privately to a workspace the caller can edit or an organization the caller administers.
+pub async fn create_template(
+    State(state): State<AppState>,
+    claims: Claims,
+    Json(dto): Json<TemplateReqDto>,
+) -> Result<SuccessResponse<TemplateResDto>, AppError> {
+    dto.validate()
+        .map_err(|e| AppError::new(StatusCode::UNPROCESSABLE_ENTITY, format!("{}", e)))?;
+
+    let (workspace_id, organization_id) = match (dto.workspace_id, dto.organization_id) {
+        (Some(workspace_id), None) => {
+            let (workspace, _) =
+                authorize_workspace(&state, workspace_id, &claims, WorkspaceRole::Editor).await?;
+            (Some(workspace.id), None)
+        }
+        (None, Some(organization_id)) => {
+            let (organization, _) = authorize_organization_by_id(
+                &state,
+                organization_id,
+                &claims,
+                OrganizationRole::Admin,
+            )
+            .await?;
+            (None, Some(organization.id))
+        }
+        (None, None) => {
+            check_admin(&claims)?;
+            (None, None)
+        }
+        (Some(_), Some(_)) => {
+            return Err(AppError::new(
+                StatusCode::UNPROCESSABLE_ENTITY,
+                "Templates belong to either a workspace or an organization",
+            ));
+        }
+    };
+    if services::get_workspace_template_by_slug(state.db_pool(), &dto.slug)
+        .await?
+        .is_some()
+    {
+        return Err(AppError::new(
+            StatusCode::CONFLICT,
+            "Template with this slug already exists",
+        ));
+    }
+    let language = dto
+        .language
+        .as_deref()
+        .map(normalize_language)
+        .transpose()?;
+
+    let mut template = WorkspaceTemplate::new(
+        dto.slug,
+        dto.name,
+        language,
+        dto.files.into_iter().map(Into::into).collect(),
+        *claims.jti(),
+    );
+    template.description = dto.description.unwrap_or_default();
+    template.tags = dto.tags;
+    template.workspace_id = workspace_id;
+    template.organization_id = organization_id;
+
+    let template = services::create_workspace_template(state.db_pool(), &template).await?;
+    tracing::info!("Created {}", template);
+    Ok(SuccessResponse::created(TemplateResDto::from(template)))
+}
+
+pub async fn update_template(
+    State(state): State<AppState>,
+    Path(slug): Path<String>,
+    claims: Claims,
+    Json(dto): Json<PatchTemplateReqDto>,
+) -> Result<SuccessResponse<TemplateResDto>, AppError> {
+    dto.validate()
+        .map_err(|e| AppError::new(StatusCode::UNPROCESSABLE_ENTITY, format!("{}", e)))?;
+
+    let mut template = get_managed_template(&state, &slug, &claims).await?;
+    if let Some(name) = dto.name {
+        template.name = name;
+    }
+    if let Some(description) = dto.description {
+        template.description = description;
+    }
+    if let Some(language) = dto.language {
+        template.language = Some(normalize_language(&language)?);
+    }
+    if let Some(files) = dto.files {
+        template.files.0 = files.into_iter().map(Into::into).collect();
+    }
+    if let Some(tags) = dto.tags {
+        template.tags = tags;
+    }
+
+    let template = services::update_workspace_template(state.db_pool(), &template).await?;
+    tracing::info!("Updated {}", template);
+    Ok(SuccessResponse::ok(TemplateResDto::from(template)))
+}
+
+pub async fn delete_template(
+    State(state): State<AppState>,
+    Path(slug): Path<String>,
+    claims: Claims,
+) -> Result<impl IntoResponse, AppError> {
+    let template = get_managed_template(&state, &slug, &claims).await?;
+    services::delete_workspace_template(state.db_pool(), template.id).await?;
+    tracing::info!("Deleted {}", template);
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Finds a template the caller may use; private ones are hidden from non-members.
+pub(super) async fn open_template(
+    state: &AppState,
+    slug: &str,
+    claims: Option<&Claims>,
+) -> Result<WorkspaceTemplate, AppError> {
+    let not_found = || AppError::new(StatusCode::NOT_FOUND, "Template not found");
+    let template = find_template(state, slug).await?;
+    if let Some(workspace_id) = template.workspace_id {
+        let claims = claims.ok_or_else(not_found)?;
+        authorize_workspace(state, workspace_id, claims, WorkspaceRole::Viewer)
+            .await
+            .map_err(|_| not_found())?;
+    }
+    if let Some(organization_id) = template.organization_id {
+        let claims = claims.ok_or_else(not_found)?;
+        authorize_organization_by_id(state, organization_id, claims, OrganizationRole::Member)
+            .await
+            .map_err(|_| not_found())?;
+    }
+    Ok(template)
+}
+
+/// Finds a template the caller may change: public ones are managed by admins, and
+/// private ones by editors of their workspace or admins of their organization.
+async fn get_managed_template(
+    state: &AppState,
+    slug: &str,
+    claims: &Claims,
+) -> Result<WorkspaceTemplate, AppError> {
+    let template = open_template(state, slug, Some(claims)).await?;
+    match (template.workspace_id, template.organization_id) {
+        (Some(workspace_id), _) => {
+            authorize_workspace(state, workspace_id, claims, WorkspaceRole::Editor).await?;
+        }
+        (None, Some(organization_id)) => {
+            authorize_organization_by_id(state, organization_id, claims, OrganizationRole::Admin)
+                .await?;
+        }
+        (None, None) => check_admin(claims)?,
+    }
+    Ok(template)
+}
+
+async fn find_template(state: &AppState, slug: &str) -> Result<WorkspaceTemplate, AppError> {
+    services::get_workspace_template_by_slug(state.db_pool(), slug)
+        .await?
+        .ok_or_else(|| AppError::new(StatusCode::NOT_FOUND, "Template not found"))
+}
@@ -0,0 +1,36 @@
+use axum::extract::{Query, State};
+use chrono::{Duration, Utc};
+
+use crate::{
+    bootstrap::AppState,
+    dto::{UsageReportQueryDto, UsageReportResDto, UsageResDto},
+    middlewares::auth::check_admin,
+    services,
+    token::Claims,
+    utils::{AppError, SuccessResponse},
+};
+
+pub async fn get_my_usage(
+    State(state): State<AppState>,
+    claims: Claims,
+) -> Result<SuccessResponse<UsageResDto>, AppError> {
+    let limits = state.config().quota().limits_for(*claims.is_admin());
+    let quotas = services::get_quota_usage(state.db_pool(), *claims.jti(), limits).await?;
+    Ok(SuccessResponse::ok(UsageResDto { quotas }))
+}
+
+pub async fn get_usage_report(
+    State(state): State<AppState>,
+    claims: Claims,
+    Query(query): Query<UsageReportQueryDto>,
+) -> Result<SuccessResponse<UsageReportResDto>, AppError> {
+    check_admin(&claims)?;
+
+    let to = query.to.unwrap_or_else(Utc::now);
+    let from = query.from.unwrap_or(to - Duration::days(1));
+    let limit = query.limit.unwrap_or(50).min(100);
+    let offset = query.offset.unwrap_or(0);
+
+    let users = services::get_usage_report(state.db_pool(), from, to, limit, offset).await?;
+    Ok(SuccessResponse::ok(UsageReportResDto { from, to, users }))
+}
@@ -41,13 +41,7 @@ pub async fn register(
 
     let password_hash = hash_password(&dto.password)?;
 
-    let new_user = User::new(
-        dto.github_id,
-        email,
-        password_hash,
-        username,
-        dto.avatar_url,
-    );
+    let new_user = User::new(dto.github_id, email, password_hash, username, None);
     tracing::info!("Creating new user: {}", new_user);
     let user = services::create_user(state.db_pool(), &new_user).await?;
     Ok(SuccessResponse::created(UserResDto::from(user)))
@@ -166,8 +160,8 @@ async fn handle_patch_updates(
         user.email = email;
     }
 
-    if dto.github_id.is_some() {
-        if get_user_by_github_id(state.db_pool(), dto.github_id.unwrap())
+    if let Some(github_id) = dto.github_id {
+        if get_user_by_github_id(state.db_pool(), github_id)
             .await?
             .is_some()
         {
@@ -184,10 +178,6 @@ async fn handle_patch_updates(
         user.password_hash = hash_password(&password)?;
     }
 
-    if dto.avatar_url.is_some() {
-        user.avatar_url = dto.avatar_url;
-    }
-
     let user = services::update_user(state.db_pool(), &user).await?;
     Ok(SuccessResponse::ok(UserResDto::from(user)))
 }
@@ -0,0 +1,229 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use uuid::Uuid;
+use validator::Validate;
+
+use super::{authorize_organization, authorize_organization_by_id};
+use crate::{
+    bootstrap::AppState,
+    dto::{
+        WebhookDeliveriesQueryDto, WebhookDeliveriesResDto, WebhookDeliveryDetailResDto,
+        WebhookDeliveryResDto, WebhookReqDto, WebhookResDto, WebhookUpdateReqDto, WebhooksResDto,
+    },
+    models::{OrganizationRole, Webhook, WebhookDelivery},
+    services,
+    token::Claims,
+    utils::{AppError, SuccessResponse},
+    webhooks,
+};
+
+/// Registers a webhook for events about the caller's own workspaces and submissions.
+pub async fn create_webhook(
+    State(state): State<AppState>,
+    claims: Claims,
+    Json(dto): Json<WebhookReqDto>,
+) -> Result<SuccessResponse<WebhookResDto>, AppError> {
+    dto.validate()
+        .map_err(|e| AppError::new(StatusCode::UNPROCESSABLE_ENTITY, format!("{}", e)))?;
+    check_webhook_url(&state, &dto.url).await?;
+
+    let webhook = Webhook::new(Some(*claims.jti()), None, dto.url, dto.secret, dto.events);
+    let webhook = services::create_webhook(state.db_pool(), &webhook).await?;
+    tracing::info!("Created {}", webhook);
+    Ok(SuccessResponse::created(WebhookResDto::with_secret(
+        webhook,
+    )))
+}
+
+pub async fn get_my_webhooks(
+    State(state): State<AppState>,
+    claims: Claims,
+) -> Result<SuccessResponse<WebhooksResDto>, AppError> {
+    let webhooks =
+        services::get_webhooks_by_owner(state.db_pool(), Some(*claims.jti()), None).await?;
+    Ok(SuccessResponse::ok(WebhooksResDto::from(webhooks)))
+}
+
+/// Registers a webhook for events about the workspaces of an organization; admins only.
+pub async fn create_organization_webhook(
+    State(state): State<AppState>,
+    Path(slug): Path<String>,
+    claims: Claims,
+    Json(dto): Json<WebhookReqDto>,
+) -> Result<SuccessResponse<WebhookResDto>, AppError> {
+    let (organization, _) =
+        authorize_organization(&state, &slug, &claims, OrganizationRole::Admin).await?;
+    dto.validate()
+        .map_err(|e| AppError::new(StatusCode::UNPROCESSABLE_ENTITY, format!("{}", e)))?;
+    check_webhook_url(&state, &dto.url).await?;
+
+    let webhook = Webhook::new(None, Some(organization.id), dto.url, dto.secret, dto.events);
+    let webhook = services::create_webhook(state.db_pool(), &webhook).await?;
+    tracing::info!("Created {}", webhook);
+    Ok(SuccessResponse::created(WebhookResDto::with_secret(
+        webhook,
+    )))
+}
+
+pub async fn get_organization_webhooks(
+    State(state): State<AppState>,
+    Path(slug): Path<String>,
+    claims: Claims,
+) -> Result<SuccessResponse<WebhooksResDto>, AppError> {
+    let (organization, _) =
+        authorize_organization(&state, &slug, &claims, OrganizationRole::Admin).await?;
+    let webhooks =
+        services::get_webhooks_by_owner(state.db_pool(), None, Some(organization.id)).await?;
+    Ok(SuccessResponse::ok(WebhooksResDto::from(webhooks)))
+}
+
+pub async fn get_webhook(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    claims: Claims,
+) -> Result<SuccessResponse<WebhookResDto>, AppError> {
+    let webhook = authorize_webhook(&state, id, &claims).await?;
+    Ok(SuccessResponse::ok(WebhookResDto::from(webhook)))
+}
+
+/// Changes the URL, events or secret of a webhook, or pauses it with `active: false`.
+pub async fn update_webhook(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    claims: Claims,
+    Json(dto): Json<WebhookUpdateReqDto>,
+) -> Result<SuccessResponse<WebhookResDto>, AppError> {
+    let mut webhook = authorize_webhook(&state, id, &claims).await?;
+    dto.validate()
+        .map_err(|e| AppError::new(StatusCode::UNPROCESSABLE_ENTITY, format!("{}", e)))?;
+
+    if let Some(url) = dto.url {
+        check_webhook_url(&state, &url).await?;
+        webhook.url = url;
+    }
+    if let Some(events) = dto.events {
+        webhook.events.0 = events;
+    }
+    if let Some(secret) = dto.secret {
+        webhook.secret = secret;
+    }
+    if let Some(active) = dto.active {
+        webhook.active = active;
+    }
+
+    let webhook = services::update_webhook(state.db_pool(), &webhook).await?;
+    tracing::info!("Updated {}", webhook);
+    Ok(SuccessResponse::ok(WebhookResDto::from(webhook)))
+}
+
+pub async fn delete_webhook(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    claims: Claims,
+) -> Result<impl IntoResponse, AppError> {
+    let webhook = authorize_webhook(&state, id, &claims).await?;
+    services::delete_webhook(state.db_pool(), webhook.id).await?;
+    tracing::info!("Deleted webhook with ID: {}", webhook.id);
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Lists the delivery log of a webhook, newest first.
+pub async fn get_webhook_deliveries(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    claims: Claims,
+    Query(query): Query<WebhookDeliveriesQueryDto>,
+) -> Result<SuccessResponse<WebhookDeliveriesResDto>, AppError> {
+    let webhook = authorize_webhook(&state, id, &claims).await?;
+    let limit = query.limit.unwrap_or(50).min(100);
+    let offset = query.offset.unwrap_or(0);
+
+    let deliveries =
+        services::get_webhook_deliveries(state.db_pool(), webhook.id, query.status, limit, offset)
+            .await?;
+    Ok(SuccessResponse::ok(WebhookDeliveriesResDto::from(
+        deliveries,
+    )))
+}
+
+pub async fn get_webhook_delivery(
+    State(state): State<AppState>,
+    Path((id, delivery_id)): Path<(Uuid, Uuid)>,
+    claims: Claims,
+) -> Result<SuccessResponse<WebhookDeliveryDetailResDto>, AppError> {
+    let webhook = authorize_webhook(&state, id, &claims).await?;
+    let delivery = find_delivery(&state, webhook.id, delivery_id).await?;
+    Ok(SuccessResponse::ok(WebhookDeliveryDetailResDto::from(
+        delivery,
+    )))
+}
+
+/// Queues the payload of an earlier delivery again, as a new delivery.
+pub async fn redeliver_webhook_delivery(
+    State(state): State<AppState>,
+    Path((id, delivery_id)): Path<(Uuid, Uuid)>,
+    claims: Claims,
+) -> Result<SuccessResponse<WebhookDeliveryResDto>, AppError> {
+    let webhook = authorize_webhook(&state, id, &claims).await?;
+    if !webhook.active {
+        return Err(AppError::new(
+            StatusCode::UNPROCESSABLE_ENTITY,
+            "Activate the webhook before redelivering",
+        ));
+    }
+    let delivery = find_delivery(&state, webhook.id, delivery_id).await?;
+
+    let redelivery = services::redeliver_webhook_delivery(state.db_pool(), &delivery).await?;
+    tracing::info!("Queued {}", redelivery);
+    Ok(SuccessResponse::accepted(WebhookDeliveryResDto::from(
+        redelivery,
+    )))
+}
+
+/// Finds a webhook the caller manages: their own, or one of an organization they administer.
+///
+/// Webhooks of others are reported as missing rather than forbidden.
+async fn authorize_webhook(
+    state: &AppState,
+    id: Uuid,
+    claims: &Claims,
+) -> Result<Webhook, AppError> {
+    let not_found = || AppError::new(StatusCode::NOT_FOUND, "Webhook not found");
+    let webhook = services::get_webhook_by_id(state.db_pool(), id)
+        .await?
+        .ok_or_else(not_found)?;
+    if webhook.user_id == Some(*claims.jti()) {
+        return Ok(webhook);
+    }
+    let Some(organization_id) = webhook.organization_id else {
+        return Err(not_found());
+    };
+    match authorize_organization_by_id(state, organization_id, claims, OrganizationRole::Admin)
+        .await
+    {
+        Ok(_) => Ok(webhook),
+        Err(e) if e.details().status == StatusCode::FORBIDDEN => Err(not_found()),
+        Err(e) => Err(e),
+    }
+}
+
+async fn find_delivery(
+    state: &AppState,
+    webhook_id: Uuid,
+    id: Uuid,
+) -> Result<WebhookDelivery, AppError> {
+    services::get_webhook_delivery_by_id(state.db_pool(), webhook_id, id)
+        .await?
+        .ok_or_else(|| AppError::new(StatusCode::NOT_FOUND, "Delivery not found"))
+}
+
+async fn check_webhook_url(state: &AppState, url: &str) -> Result<(), AppError> {
+    let allow_private = *state.config().webhook().allow_private_addresses();
+    webhooks::check_webhook_url(url, allow_private)
+        .await
+        .map_err(|e| AppError::new(StatusCode::UNPROCESSABLE_ENTITY, e))
+}
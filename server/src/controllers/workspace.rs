@@ -0,0 +1,354 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use uuid::Uuid;
+use validator::Validate;
+
+use super::{authorize_organization_by_id, open_template};
+use crate::{
+    bootstrap::AppState,
+    dto::{
+        CreateWorkspaceQueryDto, ForkReqDto, PatchWorkspaceFileReqDto, PatchWorkspaceReqDto,
+        WorkspaceFileReqDto, WorkspaceFileResDto, WorkspaceFilesResDto, WorkspaceReqDto,
+        WorkspaceResDto, WorkspacesQueryDto, WorkspacesResDto,
+    },
+    executor::find_language,
+    models::{OrganizationRole, Workspace, WorkspaceFile, WorkspaceRole},
+    services,
+    token::Claims,
+    utils::{AppError, SuccessResponse},
+};
+
+/// Upper bound on the number of files a single workspace may hold.
+pub(super) const MAX_WORKSPACE_FILES: i64 = 200;
+
+/// Creates an empty workspace, or one holding a copy of the requested template's files.
+pub async fn create_workspace(
+    State(state): State<AppState>,
+    claims: Claims,
+    Query(query): Query<CreateWorkspaceQueryDto>,
+    Json(dto): Json<WorkspaceReqDto>,
+) -> Result<SuccessResponse<WorkspaceResDto>, AppError> {
+    dto.validate()
+        .map_err(|e| AppError::new(StatusCode::UNPROCESSABLE_ENTITY, format!("{}", e)))?;
+
+    let template = match query.template {
+        Some(slug) => Some(open_template(&state, &slug, Some(&claims)).await?),
+        None => None,
+    };
+    let language = dto
+        .language
+        .as_deref()
+        .map(normalize_language)
+        .transpose()?
+        .or_else(|| {
+            template
+                .as_ref()
+                .and_then(|template| template.language.clone())
+        });
+    let organization_id = match dto.organization_id {
+        Some(organization_id) => Some(
+            authorize_organization_by_id(
+                &state,
+                organization_id,
+                &claims,
+                OrganizationRole::Member,
+            )
+            .await?
+            .0
+            .id,
+        ),
+        None => None,
+    };
+    ensure_unique_name(&state, *claims.jti(), &dto.name).await?;
+
+    let mut workspace = Workspace::new(*claims.jti(), dto.name, language);
+    workspace.organization_id = organization_id;
+    tracing::info!("Creating new workspace: {}", workspace);
+    let workspace = match template {
+        Some(template) => {
+            workspace.template_id = Some(template.id);
+            services::create_workspace_from_template(state.db_pool(), &workspace, &template).await?
+        }
+        None => services::create_workspace(state.db_pool(), &workspace).await?,
+    };
+    Ok(SuccessResponse::created(WorkspaceResDto::from(workspace)))
+}
+
+pub async fn get_my_workspaces(
+    State(state): State<AppState>,
+    claims: Claims,
+    Query(query): Query<WorkspacesQueryDto>,
+) -> Result<SuccessResponse<WorkspacesResDto>, AppError> {
+    let limit = query.limit.unwrap_or(50).min(100);
+    let offset = query.offset.unwrap_or(0);
+
+    let workspaces =
+        services::get_workspaces_by_member_id(state.db_pool(), *claims.jti(), limit, offset)
+            .await?;
+    Ok(SuccessResponse::ok(WorkspacesResDto::from(workspaces)))
+}
+
+pub async fn get_workspace(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    claims: Claims,
+) -> Result<SuccessResponse<WorkspaceResDto>, AppError> {
+    let (workspace, _) = authorize_workspace(&state, id, &claims, WorkspaceRole::Viewer).await?;
+    Ok(SuccessResponse::ok(WorkspaceResDto::from(workspace)))
+}
+
+pub async fn update_workspace(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    claims: Claims,
+    Json(dto): Json<PatchWorkspaceReqDto>,
+) -> Result<SuccessResponse<WorkspaceResDto>, AppError> {
+    dto.validate()
+        .map_err(|e| AppError::new(StatusCode::UNPROCESSABLE_ENTITY, format!("{}", e)))?;
+
+    let (mut workspace, _) =
+        authorize_workspace(&state, id, &claims, WorkspaceRole::Editor).await?;
+    if let Some(name) = dto.name {
+        if name != workspace.name {
+            ensure_unique_name(&state, workspace.owner_id, &name).await?;
+            workspace.name = name;
+        }
+    }
+    if let Some(language) = dto.language {
+        workspace.language = Some(normalize_language(&language)?);
+    }
+
+    let workspace = services::update_workspace(state.db_pool(), &workspace).await?;
+    tracing::info!("Updated workspace: {}", workspace);
+    Ok(SuccessResponse::ok(WorkspaceResDto::from(workspace)))
+}
+
+pub async fn delete_workspace(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    claims: Claims,
+) -> Result<impl IntoResponse, AppError> {
+    let (workspace, _) = authorize_workspace(&state, id, &claims, WorkspaceRole::Owner).await?;
+    services::delete_workspace(state.db_pool(), &workspace).await?;
+    tracing::info!("Deleted workspace with ID: {}", workspace.id);
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Copies the files of a workspace the caller can view into a new workspace of theirs.
+pub async fn fork_workspace(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    claims: Claims,
+    Json(dto): Json<ForkReqDto>,
+) -> Result<SuccessResponse<WorkspaceResDto>, AppError> {
+    dto.validate()
+        .map_err(|e| AppError::new(StatusCode::UNPROCESSABLE_ENTITY, format!("{}", e)))?;
+
+    let (source, _) = authorize_workspace(&state, id, &claims, WorkspaceRole::Viewer).await?;
+    let name = dto.name.unwrap_or_else(|| source.name.clone());
+    ensure_unique_name(&state, *claims.jti(), &name).await?;
+
+    let mut fork = Workspace::new(*claims.jti(), name, source.language.clone());
+    fork.forked_from_workspace_id = Some(source.id);
+    let fork = services::fork_workspace(state.db_pool(), &source, &fork).await?;
+    tracing::info!("Forked {} into {}", source, fork);
+    Ok(SuccessResponse::created(WorkspaceResDto::from(fork)))
+}
+
+pub async fn create_workspace_file(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    claims: Claims,
+    Json(dto): Json<WorkspaceFileReqDto>,
+) -> Result<SuccessResponse<WorkspaceFileResDto>, AppError> {
+    dto.validate()
+        .map_err(|e| AppError::new(StatusCode::UNPROCESSABLE_ENTITY, format!("{}", e)))?;
+
+    let (workspace, _) = authorize_workspace(&state, id, &claims, WorkspaceRole::Editor).await?;
+    if services::count_workspace_files(state.db_pool(), workspace.id).await? >= MAX_WORKSPACE_FILES
+    {
+        return Err(AppError::new(
+            StatusCode::UNPROCESSABLE_ENTITY,
+            format!("Workspaces are limited to {} files", MAX_WORKSPACE_FILES),
+        ));
+    }
+    ensure_unique_path(&state, workspace.id, &dto.path).await?;
+
+    let file = WorkspaceFile::new(workspace.id, dto.path, dto.content);
+    let file = services::create_workspace_file(state.db_pool(), &file, *claims.jti()).await?;
+    tracing::info!("Created {}", file);
+    Ok(SuccessResponse::created(WorkspaceFileResDto::from(file)))
+}
+
+pub async fn get_workspace_files(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    claims: Claims,
+) -> Result<SuccessResponse<WorkspaceFilesResDto>, AppError> {
+    let (workspace, _) = authorize_workspace(&state, id, &claims, WorkspaceRole::Viewer).await?;
+    let files = services::get_workspace_files(state.db_pool(), workspace.id).await?;
+    Ok(SuccessResponse::ok(WorkspaceFilesResDto::from(files)))
+}
+
+pub async fn get_workspace_file(
+    State(state): State<AppState>,
+    Path((id, file_id)): Path<(Uuid, Uuid)>,
+    claims: Claims,
+) -> Result<SuccessResponse<WorkspaceFileResDto>, AppError> {
+    let (workspace, _) = authorize_workspace(&state, id, &claims, WorkspaceRole::Viewer).await?;
+    let file = find_workspace_file(&state, workspace.id, file_id).await?;
+    Ok(SuccessResponse::ok(WorkspaceFileResDto::from(file)))
+}
+
+pub async fn update_workspace_file(
+    State(state): State<AppState>,
+    Path((id, file_id)): Path<(Uuid, Uuid)>,
+    claims: Claims,
+    Json(dto): Json<PatchWorkspaceFileReqDto>,
+) -> Result<SuccessResponse<WorkspaceFileResDto>, AppError> {
+    dto.validate()
+        .map_err(|e| AppError::new(StatusCode::UNPROCESSABLE_ENTITY, format!("{}", e)))?;
+
+    let (workspace, _) = authorize_workspace(&state, id, &claims, WorkspaceRole::Editor).await?;
+    let mut file = find_workspace_file(&state, workspace.id, file_id).await?;
+    if let Some(path) = dto.path {
+        if path != file.path {
+            ensure_unique_path(&state, workspace.id, &path).await?;
+            file.path = path;
+        }
+    }
+    let content_changed = dto.content.as_ref().is_some_and(|c| *c != file.content);
+    if let Some(content) = dto.content {
+        file.content = content;
+    }
+
+    let file = save_workspace_file(&state, &file, content_changed, *claims.jti()).await?;
+    tracing::info!("Updated {}", file);
+    Ok(SuccessResponse::ok(WorkspaceFileResDto::from(file)))
+}
+
+pub async fn delete_workspace_file(
+    State(state): State<AppState>,
+    Path((id, file_id)): Path<(Uuid, Uuid)>,
+    claims: Claims,
+) -> Result<impl IntoResponse, AppError> {
+    let (workspace, _) = authorize_workspace(&state, id, &claims, WorkspaceRole::Editor).await?;
+    let file = find_workspace_file(&state, workspace.id, file_id).await?;
+    services::delete_workspace_file(state.db_pool(), &file).await?;
+    tracing::info!("Deleted {}", file);
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Loads a workspace on which the caller holds at least the `required` role.
+///
+/// Workspaces the caller is not a member of are reported as missing, while members
+/// lacking the role are refused. Administrators act as owners, and members of the
+/// workspace's organization get the role their organization role grants, unless they
+/// were given a higher one.
+pub(super) async fn authorize_workspace(
+    state: &AppState,
+    id: Uuid,
+    claims: &Claims,
+    required: WorkspaceRole,
+) -> Result<(Workspace, WorkspaceRole), AppError> {
+    let not_found = || AppError::new(StatusCode::NOT_FOUND, "Workspace not found");
+    let workspace = services::get_workspace_by_id(state.db_pool(), id)
+        .await?
+        .ok_or_else(not_found)?;
+    let role = if *claims.is_admin() {
+        WorkspaceRole::Owner
+    } else {
+        services::get_workspace_role(state.db_pool(), &workspace, *claims.jti())
+            .await?
+            .ok_or_else(not_found)?
+    };
+    if role < required {
+        return Err(AppError::new(
+            StatusCode::FORBIDDEN,
+            format!("This requires the {} role", required),
+        ));
+    }
+    Ok((workspace, role))
+}
+
+pub(super) async fn find_workspace_file(
+    state: &AppState,
+    workspace_id: Uuid,
+    file_id: Uuid,
+) -> Result<WorkspaceFile, AppError> {
+    services::get_workspace_file_by_id(state.db_pool(), workspace_id, file_id)
+        .await?
+        .ok_or_else(|| AppError::new(StatusCode::NOT_FOUND, "File not found"))
+}
+
+/// Saves a file written from outside its collaboration room.
+///
+/// Once a client saved it, a room's document would overwrite whatever the file was
+/// changed to meanwhile. So new content is refused while the file is being edited live,
+/// and otherwise replaces the stored document, which later rooms then start from.
+pub(super) async fn save_workspace_file(
+    state: &AppState,
+    file: &WorkspaceFile,
+    content_changed: bool,
+    author_id: Uuid,
+) -> Result<WorkspaceFile, AppError> {
+    if !content_changed {
+        return Ok(services::update_workspace_file(state.db_pool(), file, author_id).await?);
+    }
+    // Holding the room keeps anyone from joining until the document was replaced
+    let room = state.collab().join(file.workspace_id, file.id);
+    let _guard = room.lock_writes().await;
+    if state.collab().connections(file.id) > 1 {
+        return Err(AppError::new(
+            StatusCode::CONFLICT,
+            "The file is being edited live; change it from the editing session",
+        ));
+    }
+    let file = services::update_workspace_file(state.db_pool(), file, author_id).await?;
+    services::reset_collab_document(state.db_pool(), &file, author_id).await?;
+    Ok(file)
+}
+
+pub(super) async fn ensure_unique_name(
+    state: &AppState,
+    owner_id: Uuid,
+    name: &str,
+) -> Result<(), AppError> {
+    if services::get_workspace_by_name(state.db_pool(), owner_id, name)
+        .await?
+        .is_some()
+    {
+        return Err(AppError::new(
+            StatusCode::CONFLICT,
+            "Workspace with this name already exists",
+        ));
+    }
+    Ok(())
+}
+
+async fn ensure_unique_path(
+    state: &AppState,
+    workspace_id: Uuid,
+    path: &str,
+) -> Result<(), AppError> {
+    if services::get_workspace_file_by_path(state.db_pool(), workspace_id, path)
+        .await?
+        .is_some()
+    {
+        return Err(AppError::new(
+            StatusCode::CONFLICT,
+            "File with this path already exists",
+        ));
+    }
+    Ok(())
+}
+
+pub(super) fn normalize_language(name: &str) -> Result<String, AppError> {
+    find_language(name)
+        .map(|language| language.name.to_owned())
+        .ok_or_else(|| AppError::new(StatusCode::UNPROCESSABLE_ENTITY, "Unsupported language"))
+}
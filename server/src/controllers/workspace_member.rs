@@ -0,0 +1,130 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use uuid::Uuid;
+
+use super::{authorize_workspace, ensure_unique_name};
+use crate::{
+    bootstrap::AppState,
+    dto::{
+        MemberResDto, MembersResDto, PatchMemberReqDto, TransferWorkspaceReqDto, WorkspaceResDto,
+    },
+    models::{WorkspaceMember, WorkspaceRole},
+    services,
+    token::Claims,
+    utils::{AppError, SuccessResponse},
+};
+
+pub async fn get_workspace_members(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    claims: Claims,
+) -> Result<SuccessResponse<MembersResDto>, AppError> {
+    let (workspace, _) = authorize_workspace(&state, id, &claims, WorkspaceRole::Viewer).await?;
+    let members = services::get_workspace_member_profiles(state.db_pool(), workspace.id).await?;
+    Ok(SuccessResponse::ok(MembersResDto::from(members)))
+}
+
+pub async fn update_workspace_member(
+    State(state): State<AppState>,
+    Path((id, user_id)): Path<(Uuid, Uuid)>,
+    claims: Claims,
+    Json(dto): Json<PatchMemberReqDto>,
+) -> Result<SuccessResponse<MemberResDto>, AppError> {
+    let (workspace, _) = authorize_workspace(&state, id, &claims, WorkspaceRole::Owner).await?;
+    ensure_assignable(dto.role)?;
+    let member = find_member(&state, workspace.id, user_id).await?;
+
+    let member = services::update_workspace_member_role(
+        state.db_pool(),
+        workspace.id,
+        member.user_id,
+        dto.role,
+    )
+    .await?;
+    tracing::info!("Updated {}", member);
+    let profile = services::get_workspace_member_profiles(state.db_pool(), workspace.id)
+        .await?
+        .into_iter()
+        .find(|profile| profile.user_id == member.user_id)
+        .ok_or_else(|| AppError::new(StatusCode::NOT_FOUND, "Member not found"))?;
+    Ok(SuccessResponse::ok(MemberResDto::from(profile)))
+}
+
+pub async fn remove_workspace_member(
+    State(state): State<AppState>,
+    Path((id, user_id)): Path<(Uuid, Uuid)>,
+    claims: Claims,
+) -> Result<impl IntoResponse, AppError> {
+    let (workspace, _) = authorize_workspace(&state, id, &claims, WorkspaceRole::Owner).await?;
+    let member = find_member(&state, workspace.id, user_id).await?;
+    services::delete_workspace_member(state.db_pool(), workspace.id, member.user_id).await?;
+    tracing::info!("Removed {}", member);
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub async fn leave_workspace(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    claims: Claims,
+) -> Result<impl IntoResponse, AppError> {
+    let (workspace, _) = authorize_workspace(&state, id, &claims, WorkspaceRole::Viewer).await?;
+    if workspace.owner_id == *claims.jti() {
+        return Err(AppError::new(
+            StatusCode::UNPROCESSABLE_ENTITY,
+            "Owners must transfer the workspace before leaving it",
+        ));
+    }
+    let member = find_member(&state, workspace.id, *claims.jti()).await?;
+    services::delete_workspace_member(state.db_pool(), workspace.id, member.user_id).await?;
+    tracing::info!("Left workspace: {}", member);
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Makes another member the owner; the previous owner stays on as an editor.
+pub async fn transfer_workspace(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    claims: Claims,
+    Json(dto): Json<TransferWorkspaceReqDto>,
+) -> Result<SuccessResponse<WorkspaceResDto>, AppError> {
+    let (workspace, _) = authorize_workspace(&state, id, &claims, WorkspaceRole::Owner).await?;
+    let member = services::get_workspace_member(state.db_pool(), workspace.id, dto.user_id)
+        .await?
+        .ok_or_else(|| {
+            AppError::new(
+                StatusCode::UNPROCESSABLE_ENTITY,
+                "The new owner must be a member of the workspace",
+            )
+        })?;
+    ensure_unique_name(&state, member.user_id, &workspace.name).await?;
+
+    let workspace =
+        services::transfer_workspace_ownership(state.db_pool(), &workspace, member.user_id).await?;
+    tracing::info!("Transferred {}", workspace);
+    Ok(SuccessResponse::ok(WorkspaceResDto::from(workspace)))
+}
+
+/// Rejects granting the owner role, which only changes hands through a transfer.
+pub(super) fn ensure_assignable(role: WorkspaceRole) -> Result<(), AppError> {
+    if role == WorkspaceRole::Owner {
+        return Err(AppError::new(
+            StatusCode::UNPROCESSABLE_ENTITY,
+            "Ownership can only be transferred",
+        ));
+    }
+    Ok(())
+}
+
+async fn find_member(
+    state: &AppState,
+    workspace_id: Uuid,
+    user_id: Uuid,
+) -> Result<WorkspaceMember, AppError> {
+    services::get_workspace_member(state.db_pool(), workspace_id, user_id)
+        .await?
+        .ok_or_else(|| AppError::new(StatusCode::NOT_FOUND, "Member not found"))
+}
@@ -0,0 +1,405 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use validator::Validate;
+
+use super::TestCaseReqDto;
+use crate::models::{
+    Assignment, CaseResult, Classroom, ClassroomMemberProfile, ClassroomMembership, ClassroomRole,
+    ComparisonMode, ExecutionFile, GradebookEntry, Submission, SubmissionStatus, TemplateFile,
+    TestCase,
+};
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct ClassroomReqDto {
+    #[validate(length(min = 1, max = 64), custom(function = "super::validate_slug"))]
+    pub slug: String,
+    #[validate(length(min = 1, max = 100))]
+    pub name: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClassroomResDto {
+    pub id: Uuid,
+    pub slug: String,
+    pub name: String,
+    pub role: ClassroomRole,
+    /// Only shown to instructors, who hand it out to their students.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub join_code: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl ClassroomResDto {
+    pub fn new(classroom: Classroom, role: ClassroomRole) -> Self {
+        ClassroomResDto {
+            id: classroom.id,
+            slug: classroom.slug,
+            name: classroom.name,
+            role,
+            join_code: Some(classroom.join_code).filter(|_| role == ClassroomRole::Instructor),
+            created_at: classroom.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClassroomMembershipResDto {
+    pub id: Uuid,
+    pub slug: String,
+    pub name: String,
+    pub role: ClassroomRole,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<ClassroomMembership> for ClassroomMembershipResDto {
+    fn from(membership: ClassroomMembership) -> Self {
+        ClassroomMembershipResDto {
+            id: membership.id,
+            slug: membership.slug,
+            name: membership.name,
+            role: membership.role,
+            created_at: membership.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ClassroomsResDto {
+    pub classrooms: Vec<ClassroomMembershipResDto>,
+}
+
+impl From<Vec<ClassroomMembership>> for ClassroomsResDto {
+    fn from(memberships: Vec<ClassroomMembership>) -> Self {
+        Self {
+            classrooms: memberships
+                .into_iter()
+                .map(ClassroomMembershipResDto::from)
+                .collect(),
+        }
+    }
+}
+
+/// Enrolls the caller as a student with the code handed out by an instructor.
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct JoinClassroomReqDto {
+    #[validate(length(min = 1, max = 64))]
+    pub code: String,
+}
+
+/// Adds an existing user to a classroom, as a student unless stated otherwise.
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct ClassroomMemberReqDto {
+    #[validate(length(min = 3, max = 30))]
+    pub username: String,
+    #[serde(default)]
+    pub role: Option<ClassroomRole>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClassroomMemberResDto {
+    pub user_id: Uuid,
+    pub username: String,
+    pub avatar_url: Option<String>,
+    pub role: ClassroomRole,
+    pub joined_at: DateTime<Utc>,
+}
+
+impl From<ClassroomMemberProfile> for ClassroomMemberResDto {
+    fn from(member: ClassroomMemberProfile) -> Self {
+        ClassroomMemberResDto {
+            user_id: member.user_id,
+            username: member.username,
+            avatar_url: member.avatar_url,
+            role: member.role,
+            joined_at: member.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ClassroomMembersResDto {
+    pub members: Vec<ClassroomMemberResDto>,
+}
+
+impl From<Vec<ClassroomMemberProfile>> for ClassroomMembersResDto {
+    fn from(members: Vec<ClassroomMemberProfile>) -> Self {
+        Self {
+            members: members
+                .into_iter()
+                .map(ClassroomMemberResDto::from)
+                .collect(),
+        }
+    }
+}
+
+/// Publishes an assignment; the test cases are hidden from students.
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct AssignmentReqDto {
+    #[validate(length(min = 1, max = 100))]
+    pub title: String,
+    #[validate(length(max = 10000))]
+    #[serde(default)]
+    pub description: Option<String>,
+    #[validate(length(min = 1, max = 30))]
+    pub language: String,
+    /// Slug of the template whose files students start from.
+    #[validate(length(min = 1, max = 64))]
+    #[serde(default)]
+    pub template: Option<String>,
+    /// The file submissions are run from; defaults to the first file.
+    #[validate(
+        length(min = 1, max = 255),
+        custom(function = "super::validate_file_path")
+    )]
+    #[serde(default)]
+    pub entrypoint: Option<String>,
+    #[validate(length(min = 1, max = 50), nested)]
+    pub tests: Vec<TestCaseReqDto>,
+    #[serde(default)]
+    pub comparison: Option<ComparisonMode>,
+    #[validate(range(min = 0.0, max = 1.0))]
+    #[serde(default)]
+    pub tolerance: Option<f64>,
+    pub deadline: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AssignmentResDto {
+    pub id: Uuid,
+    pub classroom_id: Uuid,
+    pub title: String,
+    pub description: String,
+    pub language: String,
+    pub entrypoint: Option<String>,
+    pub starter_files: Vec<TemplateFile>,
+    pub test_count: usize,
+    /// Only shown to instructors.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tests: Option<Vec<TestCase>>,
+    pub deadline: DateTime<Utc>,
+    pub closed: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+impl AssignmentResDto {
+    pub fn new(assignment: Assignment, role: ClassroomRole) -> Self {
+        let closed = assignment.is_closed();
+        let judge = assignment.judge.0;
+        AssignmentResDto {
+            id: assignment.id,
+            classroom_id: assignment.classroom_id,
+            title: assignment.title,
+            description: assignment.description,
+            language: assignment.language,
+            entrypoint: assignment.entrypoint,
+            starter_files: assignment.starter_files.0,
+            test_count: judge.cases.len(),
+            tests: Some(judge.cases).filter(|_| role == ClassroomRole::Instructor),
+            deadline: assignment.deadline,
+            closed,
+            created_at: assignment.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AssignmentsResDto {
+    pub assignments: Vec<AssignmentResDto>,
+}
+
+impl AssignmentsResDto {
+    pub fn new(assignments: Vec<Assignment>, role: ClassroomRole) -> Self {
+        Self {
+            assignments: assignments
+                .into_iter()
+                .map(|assignment| AssignmentResDto::new(assignment, role))
+                .collect(),
+        }
+    }
+}
+
+/// Hands in the current files of one of the caller's workspaces.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SubmissionReqDto {
+    pub workspace_id: Uuid,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SubmissionsQueryDto {
+    /// Instructors may narrow the list to one student; students only see their own.
+    #[serde(default)]
+    pub user_id: Option<Uuid>,
+    #[serde(default)]
+    pub limit: Option<i64>,
+    #[serde(default)]
+    pub offset: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubmissionResDto {
+    pub id: Uuid,
+    pub assignment_id: Uuid,
+    pub user_id: Uuid,
+    pub workspace_id: Option<Uuid>,
+    pub execution_id: Option<Uuid>,
+    pub status: SubmissionStatus,
+    pub passed_cases: i32,
+    pub total_cases: i32,
+    pub score: Option<f64>,
+    pub created_at: DateTime<Utc>,
+    pub graded_at: Option<DateTime<Utc>>,
+}
+
+impl From<Submission> for SubmissionResDto {
+    fn from(submission: Submission) -> Self {
+        SubmissionResDto {
+            id: submission.id,
+            assignment_id: submission.assignment_id,
+            user_id: submission.user_id,
+            workspace_id: submission.workspace_id,
+            execution_id: submission.execution_id,
+            status: submission.status,
+            passed_cases: submission.passed_cases,
+            total_cases: submission.total_cases,
+            score: submission.score,
+            created_at: submission.created_at,
+            graded_at: submission.graded_at,
+        }
+    }
+}
+
+/// A submission with its snapshot and, while its run is kept, the per-case results.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubmissionDetailResDto {
+    #[serde(flatten)]
+    pub submission: SubmissionResDto,
+    pub files: Vec<ExecutionFile>,
+    pub cases: Option<Vec<CaseResult>>,
+}
+
+impl SubmissionDetailResDto {
+    pub fn new(mut submission: Submission, cases: Option<Vec<CaseResult>>) -> Self {
+        let files = std::mem::take(&mut submission.files.0);
+        SubmissionDetailResDto {
+            submission: SubmissionResDto::from(submission),
+            files,
+            cases,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SubmissionsResDto {
+    pub submissions: Vec<SubmissionResDto>,
+}
+
+impl From<Vec<Submission>> for SubmissionsResDto {
+    fn from(submissions: Vec<Submission>) -> Self {
+        Self {
+            submissions: submissions
+                .into_iter()
+                .map(SubmissionResDto::from)
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GradebookAssignmentResDto {
+    pub id: Uuid,
+    pub title: String,
+    pub deadline: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GradeResDto {
+    pub assignment_id: Uuid,
+    pub best_score: Option<f64>,
+    pub submissions: i64,
+    pub last_submitted_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GradebookStudentResDto {
+    pub user_id: Uuid,
+    pub username: String,
+    /// One grade per assignment, in the order the assignments are listed.
+    pub grades: Vec<GradeResDto>,
+    /// The mean best score over all assignments, counting missing work as zero.
+    pub average_score: f64,
+}
+
+/// Every student's best score on every assignment of a classroom.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GradebookResDto {
+    pub assignments: Vec<GradebookAssignmentResDto>,
+    pub students: Vec<GradebookStudentResDto>,
+}
+
+impl GradebookResDto {
+    pub fn new(
+        assignments: Vec<Assignment>,
+        members: Vec<ClassroomMemberProfile>,
+        entries: Vec<GradebookEntry>,
+    ) -> Self {
+        let entries: HashMap<(Uuid, Uuid), GradebookEntry> = entries
+            .into_iter()
+            .map(|entry| ((entry.user_id, entry.assignment_id), entry))
+            .collect();
+        let students = members
+            .into_iter()
+            .filter(|member| member.role == ClassroomRole::Student)
+            .map(|member| {
+                let grades: Vec<GradeResDto> = assignments
+                    .iter()
+                    .map(
+                        |assignment| match entries.get(&(member.user_id, assignment.id)) {
+                            Some(entry) => GradeResDto {
+                                assignment_id: assignment.id,
+                                best_score: entry.best_score,
+                                submissions: entry.submissions,
+                                last_submitted_at: Some(entry.last_submitted_at),
+                            },
+                            None => GradeResDto {
+                                assignment_id: assignment.id,
+                                best_score: None,
+                                submissions: 0,
+                                last_submitted_at: None,
+                            },
+                        },
+                    )
+                    .collect();
+                let total: f64 = grades.iter().filter_map(|grade| grade.best_score).sum();
+                GradebookStudentResDto {
+                    user_id: member.user_id,
+                    username: member.username,
+                    average_score: total / grades.len().max(1) as f64,
+                    grades,
+                }
+            })
+            .collect();
+        Self {
+            assignments: assignments
+                .into_iter()
+                .map(|assignment| GradebookAssignmentResDto {
+                    id: assignment.id,
+                    title: assignment.title,
+                    deadline: assignment.deadline,
+                })
+                .collect(),
+            students,
+        }
+    }
+}
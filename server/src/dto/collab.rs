@@ -0,0 +1,14 @@
+use serde::{Deserialize, Serialize};
+
+use crate::collab::Participant;
+
+#[derive(Debug, Deserialize)]
+pub struct CollabQueryDto {
+    /// Access token; WebSocket handshakes from browsers cannot carry an `Authorization` header.
+    pub token: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PresenceResDto {
+    pub participants: Vec<Participant>,
+}
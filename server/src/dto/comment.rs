@@ -0,0 +1,200 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::models::{ChatMessage, Comment, CommentThread, Mention};
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct CommentThreadReqDto {
+    #[validate(range(min = 1))]
+    pub start_line: i32,
+    #[validate(range(min = 1))]
+    pub end_line: i32,
+    #[validate(length(min = 1, max = 10000))]
+    pub body: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct CommentReqDto {
+    #[validate(length(min = 1, max = 10000))]
+    pub body: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CommentThreadsQueryDto {
+    #[serde(default)]
+    pub resolved: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommentResDto {
+    pub id: Uuid,
+    pub author_id: Option<Uuid>,
+    pub body: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<Comment> for CommentResDto {
+    fn from(comment: Comment) -> Self {
+        CommentResDto {
+            id: comment.id,
+            author_id: comment.author_id,
+            body: comment.body,
+            created_at: comment.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommentThreadResDto {
+    pub id: Uuid,
+    pub file_id: Uuid,
+    pub author_id: Option<Uuid>,
+    pub start_line: i32,
+    pub end_line: i32,
+    pub outdated: bool,
+    pub resolved_at: Option<DateTime<Utc>>,
+    pub resolved_by: Option<Uuid>,
+    pub comments: Vec<CommentResDto>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl CommentThreadResDto {
+    pub fn new(thread: CommentThread, comments: Vec<Comment>) -> Self {
+        CommentThreadResDto {
+            id: thread.id,
+            file_id: thread.file_id,
+            author_id: thread.author_id,
+            start_line: thread.start_line,
+            end_line: thread.end_line,
+            outdated: thread.outdated,
+            resolved_at: thread.resolved_at,
+            resolved_by: thread.resolved_by,
+            comments: comments.into_iter().map(CommentResDto::from).collect(),
+            created_at: thread.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CommentThreadsResDto {
+    pub threads: Vec<CommentThreadResDto>,
+}
+
+impl CommentThreadsResDto {
+    /// Groups comments, listed in any thread order, under their threads.
+    pub fn new(threads: Vec<CommentThread>, comments: Vec<Comment>) -> Self {
+        let mut threads: Vec<CommentThreadResDto> = threads
+            .into_iter()
+            .map(|thread| CommentThreadResDto::new(thread, Vec::new()))
+            .collect();
+        for comment in comments {
+            if let Some(thread) = threads.iter_mut().find(|t| t.id == comment.thread_id) {
+                thread.comments.push(CommentResDto::from(comment));
+            }
+        }
+        Self { threads }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct ChatMessageReqDto {
+    #[validate(length(min = 1, max = 4000))]
+    pub body: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ChatMessagesQueryDto {
+    #[serde(default)]
+    pub before: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChatMessageResDto {
+    pub id: Uuid,
+    pub workspace_id: Uuid,
+    pub author_id: Option<Uuid>,
+    pub body: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<ChatMessage> for ChatMessageResDto {
+    fn from(message: ChatMessage) -> Self {
+        ChatMessageResDto {
+            id: message.id,
+            workspace_id: message.workspace_id,
+            author_id: message.author_id,
+            body: message.body,
+            created_at: message.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ChatMessagesResDto {
+    pub messages: Vec<ChatMessageResDto>,
+}
+
+impl From<Vec<ChatMessage>> for ChatMessagesResDto {
+    fn from(messages: Vec<ChatMessage>) -> Self {
+        Self {
+            messages: messages.into_iter().map(ChatMessageResDto::from).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MentionsQueryDto {
+    #[serde(default)]
+    pub unread: Option<bool>,
+    #[serde(default)]
+    pub limit: Option<i64>,
+    #[serde(default)]
+    pub offset: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MentionResDto {
+    pub id: Uuid,
+    pub workspace_id: Uuid,
+    pub author_id: Option<Uuid>,
+    pub comment_id: Option<Uuid>,
+    pub chat_message_id: Option<Uuid>,
+    pub read_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<Mention> for MentionResDto {
+    fn from(mention: Mention) -> Self {
+        MentionResDto {
+            id: mention.id,
+            workspace_id: mention.workspace_id,
+            author_id: mention.author_id,
+            comment_id: mention.comment_id,
+            chat_message_id: mention.chat_message_id,
+            read_at: mention.read_at,
+            created_at: mention.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MentionsResDto {
+    pub mentions: Vec<MentionResDto>,
+}
+
+impl From<Vec<Mention>> for MentionsResDto {
+    fn from(mentions: Vec<Mention>) -> Self {
+        Self {
+            mentions: mentions.into_iter().map(MentionResDto::from).collect(),
+        }
+    }
+}
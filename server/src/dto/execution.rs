@@ -0,0 +1,193 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::models::{
+    overall_verdict, CaseResult, ComparisonMode, CompileOutput, Execution, ExecutionFile,
+    ExecutionStatus, TestCase, Verdict,
+};
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct RunFileReqDto {
+    #[validate(
+        length(min = 1, max = 255),
+        custom(function = "super::validate_file_path")
+    )]
+    pub name: String,
+    #[validate(length(max = 1048576))]
+    pub content: String,
+}
+
+impl From<RunFileReqDto> for ExecutionFile {
+    fn from(dto: RunFileReqDto) -> Self {
+        ExecutionFile {
+            name: dto.name,
+            content: dto.content,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct TestCaseReqDto {
+    #[validate(length(max = 65536))]
+    #[serde(default)]
+    pub stdin: String,
+    #[validate(length(max = 65536))]
+    pub expected_stdout: String,
+    #[validate(range(min = 1, max = 60000))]
+    #[serde(default)]
+    pub time_limit_ms: Option<i64>,
+}
+
+impl From<TestCaseReqDto> for TestCase {
+    fn from(dto: TestCaseReqDto) -> Self {
+        TestCase {
+            stdin: dto.stdin,
+            expected_stdout: dto.expected_stdout,
+            time_limit_ms: dto.time_limit_ms,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct RunReqDto {
+    #[validate(length(min = 1, max = 30))]
+    pub language: String,
+    #[validate(length(min = 1, max = 100), nested)]
+    pub files: Vec<RunFileReqDto>,
+    /// The file to run (or build from); defaults to the first file.
+    #[validate(length(min = 1, max = 255))]
+    #[serde(default)]
+    pub entrypoint: Option<String>,
+    #[validate(length(max = 65536))]
+    #[serde(default)]
+    pub stdin: Option<String>,
+    /// Test cases to judge the program against; omit for a plain run.
+    #[validate(length(min = 1, max = 50), nested)]
+    #[serde(default)]
+    pub tests: Option<Vec<TestCaseReqDto>>,
+    #[serde(default)]
+    pub comparison: Option<ComparisonMode>,
+    #[validate(range(min = 0.0, max = 1.0))]
+    #[serde(default)]
+    pub tolerance: Option<f64>,
+    /// Records the run in the recording of a workspace the caller may edit.
+    #[serde(default)]
+    pub workspace_id: Option<Uuid>,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RunResDto {
+    pub id: Uuid,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub workspace_id: Option<Uuid>,
+    pub language: String,
+    pub status: ExecutionStatus,
+    pub stdout: Option<String>,
+    pub stderr: Option<String>,
+    pub error: Option<String>,
+    pub exit_code: Option<i32>,
+    pub duration_ms: Option<i64>,
+    pub files_hash: String,
+    pub cached: bool,
+    pub entrypoint: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub compile: Option<CompileOutput>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub verdict: Option<Verdict>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cases: Option<Vec<CaseResult>>,
+    pub created_at: DateTime<Utc>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+impl From<Execution> for RunResDto {
+    fn from(execution: Execution) -> Self {
+        let verdict = if execution.failed_to_compile() {
+            execution.judge.as_ref().map(|_| Verdict::CompileError)
+        } else {
+            execution
+                .case_results
+                .as_deref()
+                .map(|results| overall_verdict(results))
+        };
+        let entrypoint = execution.entrypoint().to_owned();
+        let hidden = execution.judge.as_ref().is_some_and(|spec| spec.hidden);
+        let cases = execution.case_results.map(|results| {
+            if hidden {
+                results.0.into_iter().map(CaseResult::redacted).collect()
+            } else {
+                results.0
+            }
+        });
+        RunResDto {
+            id: execution.id,
+            workspace_id: execution.workspace_id,
+            language: execution.language,
+            status: execution.status,
+            stdout: execution.stdout,
+            stderr: execution.stderr,
+            error: execution.error,
+            exit_code: execution.exit_code,
+            duration_ms: execution.duration_ms,
+            files_hash: execution.files_hash,
+            cached: execution.cached,
+            entrypoint,
+            compile: execution.compile.map(|compile| compile.0),
+            verdict,
+            cases,
+            created_at: execution.created_at,
+            started_at: execution.started_at,
+            finished_at: execution.finished_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunDetailResDto {
+    #[serde(flatten)]
+    pub run: RunResDto,
+    pub files: Vec<ExecutionFile>,
+    pub stdin: Option<String>,
+}
+
+impl From<Execution> for RunDetailResDto {
+    fn from(mut execution: Execution) -> Self {
+        let files = std::mem::take(&mut execution.files.0);
+        let stdin = execution.stdin.take();
+        RunDetailResDto {
+            run: RunResDto::from(execution),
+            files,
+            stdin,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RunsQueryDto {
+    #[serde(default)]
+    pub language: Option<String>,
+    #[serde(default)]
+    pub status: Option<ExecutionStatus>,
+    #[serde(default)]
+    pub limit: Option<i64>,
+    #[serde(default)]
+    pub offset: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RunsResDto {
+    pub runs: Vec<RunResDto>,
+}
+
+impl From<Vec<Execution>> for RunsResDto {
+    fn from(executions: Vec<Execution>) -> Self {
+        Self {
+            runs: executions.into_iter().map(RunResDto::from).collect(),
+        }
+    }
+}
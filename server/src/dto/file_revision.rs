@@ -0,0 +1,77 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::models::FileRevision;
+
+#[derive(Debug, Deserialize)]
+pub struct FileRevisionsQueryDto {
+    #[serde(default)]
+    pub limit: Option<i64>,
+    #[serde(default)]
+    pub offset: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FileDiffQueryDto {
+    pub from: i32,
+    /// Defaults to the latest revision.
+    #[serde(default)]
+    pub to: Option<i32>,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FileRevisionResDto {
+    pub id: Uuid,
+    pub number: i32,
+    pub path: String,
+    pub hash: String,
+    pub author_id: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<FileRevision> for FileRevisionResDto {
+    fn from(revision: FileRevision) -> Self {
+        FileRevisionResDto {
+            id: revision.id,
+            number: revision.number,
+            path: revision.path,
+            hash: revision.blob_hash,
+            author_id: revision.author_id,
+            created_at: revision.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FileRevisionsResDto {
+    pub revisions: Vec<FileRevisionResDto>,
+}
+
+impl From<Vec<FileRevision>> for FileRevisionsResDto {
+    fn from(revisions: Vec<FileRevision>) -> Self {
+        Self {
+            revisions: revisions
+                .into_iter()
+                .map(FileRevisionResDto::from)
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileRevisionContentResDto {
+    #[serde(flatten)]
+    pub revision: FileRevisionResDto,
+    pub content: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileDiffResDto {
+    pub from: i32,
+    pub to: i32,
+    pub diff: String,
+}
@@ -0,0 +1,34 @@
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+use crate::executor::FormatDiagnostic;
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct FormatReqDto {
+    #[validate(length(min = 1, max = 30))]
+    pub language: String,
+    #[validate(length(max = 1048576))]
+    pub code: String,
+    /// Where the code lives, which some formatters read their settings from; defaults to
+    /// the language's entrypoint.
+    #[validate(
+        length(min = 1, max = 255),
+        custom(function = "super::validate_file_path")
+    )]
+    #[serde(default)]
+    pub path: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FormatResDto {
+    pub formatted: String,
+    /// Whether formatting changed the submitted code.
+    pub changed: bool,
+}
+
+/// Sent as the data of the error returned when the formatter refuses the code.
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct FormatErrorsResDto {
+    pub diagnostics: Vec<FormatDiagnostic>,
+}
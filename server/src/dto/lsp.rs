@@ -0,0 +1,9 @@
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+pub struct LspQueryDto {
+    /// Access token; WebSocket handshakes from browsers cannot carry an `Authorization` header.
+    pub token: String,
+    /// The language whose server to start; defaults to the workspace's language.
+    pub language: Option<String>,
+}
@@ -1,15 +1,50 @@
 mod auth;
+mod classroom;
+mod collab;
+mod comment;
+mod execution;
+mod file_revision;
+mod format;
+mod lsp;
+mod notification;
+mod organization;
+mod recording;
+mod search;
 mod session;
+mod snippet;
+mod usage;
 mod user;
+mod webhook;
+mod workspace;
+mod workspace_member;
+mod workspace_template;
 
 pub use auth::*;
 use axum::http::StatusCode;
+pub use classroom::*;
+pub use collab::*;
+pub use comment::*;
+pub use execution::*;
+pub use file_revision::*;
+pub use format::*;
+pub use lsp::*;
+pub use notification::*;
+pub use organization::*;
+pub use recording::*;
+pub use search::*;
 pub use session::*;
+pub use snippet::*;
+pub use usage::*;
 pub use user::*;
+pub use webhook::*;
+pub use workspace::*;
+pub use workspace_member::*;
+pub use workspace_template::*;
 
 use serde::{Deserialize, Deserializer};
+use validator::ValidationError;
 
-use crate::utils::AppError;
+use crate::{models::ARCHIVE_MANIFEST_PATH, utils::AppError};
 
 pub(super) fn to_lowercase<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
 where
@@ -19,6 +54,61 @@ where
     Ok(Some(value.to_lowercase()))
 }
 
+/// Distinguishes a field sent as `null` (`Some(None)`) from one left out (`None`).
+pub(super) fn double_option<'de, T, D>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    T: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
+
+/// Accepts relative, `/`-separated paths that stay inside the project root.
+pub(crate) fn validate_file_path(path: &str) -> Result<(), ValidationError> {
+    let escapes_root = path.starts_with('/')
+        || path.contains('\\')
+        || path
+            .split('/')
+            .any(|component| matches!(component, "" | "." | ".."));
+    if escapes_root {
+        return Err(ValidationError::new("file_path")
+            .with_message("File names must be relative paths inside the project".into()));
+    }
+    Ok(())
+}
+
+/// Accepts paths for files that end up in a workspace, which may not take the place of
+/// the manifest carried by exported archives.
+pub(super) fn validate_workspace_file_path(path: &str) -> Result<(), ValidationError> {
+    validate_file_path(path)?;
+    if path == ARCHIVE_MANIFEST_PATH {
+        return Err(ValidationError::new("file_path").with_message(
+            format!(
+                "{} is reserved for archive manifests",
+                ARCHIVE_MANIFEST_PATH
+            )
+            .into(),
+        ));
+    }
+    Ok(())
+}
+
+/// Slugs appear in URLs, so they are limited to lowercase letters, digits and dashes.
+pub(super) fn validate_slug(slug: &str) -> Result<(), ValidationError> {
+    if !is_slug(slug) {
+        return Err(ValidationError::new("slug")
+            .with_message("Slugs may only contain lowercase letters, digits and dashes".into()));
+    }
+    Ok(())
+}
+
+pub(super) fn is_slug(value: &str) -> bool {
+    !value.is_empty()
+        && value
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+}
+
 pub fn process_optional_fields(
     username: Option<String>,
     email: Option<String>,
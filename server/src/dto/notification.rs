@@ -0,0 +1,75 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::models::{Notification, NotificationCategory};
+
+#[derive(Debug, Deserialize)]
+pub struct NotificationsQueryDto {
+    #[serde(default)]
+    pub unread: Option<bool>,
+    #[serde(default)]
+    pub category: Option<NotificationCategory>,
+    #[serde(default)]
+    pub limit: Option<i64>,
+    #[serde(default)]
+    pub offset: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NotificationResDto {
+    pub id: Uuid,
+    pub category: NotificationCategory,
+    pub title: String,
+    pub body: String,
+    pub data: Value,
+    pub read_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<Notification> for NotificationResDto {
+    fn from(notification: Notification) -> Self {
+        NotificationResDto {
+            id: notification.id,
+            category: notification.category,
+            title: notification.title,
+            body: notification.body,
+            data: notification.data.0,
+            read_at: notification.read_at,
+            created_at: notification.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NotificationsResDto {
+    pub notifications: Vec<NotificationResDto>,
+    pub unread_count: i64,
+}
+
+impl NotificationsResDto {
+    pub fn new(notifications: Vec<Notification>, unread_count: i64) -> Self {
+        Self {
+            notifications: notifications
+                .into_iter()
+                .map(NotificationResDto::from)
+                .collect(),
+            unread_count,
+        }
+    }
+}
+
+/// Replaces the muted categories; notifications in them are neither stored nor pushed.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct NotificationPreferencesDto {
+    pub muted: Vec<NotificationCategory>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct NotificationStreamQueryDto {
+    /// Access token; WebSocket handshakes from browsers cannot carry an `Authorization` header.
+    pub token: String,
+}
@@ -0,0 +1,161 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::models::{
+    Organization, OrganizationMemberProfile, OrganizationMembership, OrganizationRole, QuotaUsage,
+    UserUsageReport,
+};
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct OrganizationReqDto {
+    #[validate(length(min = 1, max = 64), custom(function = "super::validate_slug"))]
+    pub slug: String,
+    #[validate(length(min = 1, max = 100))]
+    pub name: String,
+}
+
+/// Renames an organization; its quotas can only be changed by administrators.
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct PatchOrganizationReqDto {
+    #[validate(length(min = 1, max = 100))]
+    #[serde(default)]
+    pub name: Option<String>,
+    #[validate(range(min = 0))]
+    #[serde(default)]
+    pub executions_per_hour: Option<i64>,
+    #[validate(range(min = 0))]
+    #[serde(default)]
+    pub cpu_seconds_per_day: Option<i64>,
+    #[validate(range(min = 0))]
+    #[serde(default)]
+    pub concurrent_runs: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OrganizationResDto {
+    pub id: Uuid,
+    pub slug: String,
+    pub name: String,
+    pub executions_per_hour: i64,
+    pub cpu_seconds_per_day: i64,
+    pub concurrent_runs: i64,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<Organization> for OrganizationResDto {
+    fn from(organization: Organization) -> Self {
+        OrganizationResDto {
+            id: organization.id,
+            slug: organization.slug,
+            name: organization.name,
+            executions_per_hour: organization.executions_per_hour,
+            cpu_seconds_per_day: organization.cpu_seconds_per_day,
+            concurrent_runs: organization.concurrent_runs,
+            created_at: organization.created_at,
+            updated_at: organization.updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MembershipResDto {
+    pub id: Uuid,
+    pub slug: String,
+    pub name: String,
+    pub role: OrganizationRole,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<OrganizationMembership> for MembershipResDto {
+    fn from(membership: OrganizationMembership) -> Self {
+        MembershipResDto {
+            id: membership.id,
+            slug: membership.slug,
+            name: membership.name,
+            role: membership.role,
+            created_at: membership.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OrganizationsResDto {
+    pub organizations: Vec<MembershipResDto>,
+}
+
+impl From<Vec<OrganizationMembership>> for OrganizationsResDto {
+    fn from(memberships: Vec<OrganizationMembership>) -> Self {
+        Self {
+            organizations: memberships
+                .into_iter()
+                .map(MembershipResDto::from)
+                .collect(),
+        }
+    }
+}
+
+/// Adds an existing user to an organization, as a plain member unless stated otherwise.
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct OrganizationMemberReqDto {
+    #[validate(length(min = 3, max = 30))]
+    pub username: String,
+    #[serde(default)]
+    pub role: Option<OrganizationRole>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PatchOrganizationMemberReqDto {
+    pub role: OrganizationRole,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OrganizationMemberResDto {
+    pub user_id: Uuid,
+    pub username: String,
+    pub avatar_url: Option<String>,
+    pub role: OrganizationRole,
+    pub joined_at: DateTime<Utc>,
+}
+
+impl From<OrganizationMemberProfile> for OrganizationMemberResDto {
+    fn from(member: OrganizationMemberProfile) -> Self {
+        OrganizationMemberResDto {
+            user_id: member.user_id,
+            username: member.username,
+            avatar_url: member.avatar_url,
+            role: member.role,
+            joined_at: member.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OrganizationMembersResDto {
+    pub members: Vec<OrganizationMemberResDto>,
+}
+
+impl From<Vec<OrganizationMemberProfile>> for OrganizationMembersResDto {
+    fn from(members: Vec<OrganizationMemberProfile>) -> Self {
+        Self {
+            members: members
+                .into_iter()
+                .map(OrganizationMemberResDto::from)
+                .collect(),
+        }
+    }
+}
+
+/// The organization's quotas, and how its members used them over the requested period.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OrganizationUsageResDto {
+    pub quotas: Vec<QuotaUsage>,
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    pub users: Vec<UserUsageReport>,
+}
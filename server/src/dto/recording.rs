@@ -0,0 +1,102 @@
+use base64::{engine::general_purpose::STANDARD, Engine};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::models::{DocumentState, SessionEvent, SessionEventKind};
+
+#[derive(Debug, Deserialize)]
+pub struct RecordingQueryDto {
+    pub from: DateTime<Utc>,
+    #[serde(default)]
+    pub to: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub file_id: Option<Uuid>,
+    /// The ID of the last event received, to fetch the next page.
+    #[serde(default)]
+    pub after: Option<i64>,
+    #[serde(default)]
+    pub limit: Option<i64>,
+}
+
+/// One line of the event log; Yjs payloads are base64 encoded.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordedEventResDto {
+    pub id: i64,
+    pub kind: SessionEventKind,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file_id: Option<Uuid>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_id: Option<Uuid>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seq: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub execution_id: Option<Uuid>,
+    pub recorded_at: DateTime<Utc>,
+}
+
+impl From<SessionEvent> for RecordedEventResDto {
+    fn from(event: SessionEvent) -> Self {
+        Self {
+            id: event.id,
+            kind: event.kind,
+            file_id: event.file_id,
+            user_id: event.user_id,
+            seq: event.seq,
+            data: event.data.map(|data| STANDARD.encode(data)),
+            execution_id: event.execution_id,
+            recorded_at: event.recorded_at,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DocumentStateQueryDto {
+    pub at: DateTime<Utc>,
+}
+
+/// A file at some moment: its saved text, and its collaborative document as a base64
+/// Yjs snapshot followed by the updates to apply on top.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DocumentStateResDto {
+    pub at: DateTime<Utc>,
+    pub revision: Option<i32>,
+    pub content: Option<String>,
+    pub snapshot: Option<String>,
+    pub updates: Vec<String>,
+    /// The sequence number of the last edit included.
+    pub seq: i64,
+}
+
+impl DocumentStateResDto {
+    pub fn new(at: DateTime<Utc>, state: DocumentState) -> Self {
+        let (revision, content) = state.revision.map_or((None, None), |(revision, content)| {
+            (Some(revision.number), Some(content))
+        });
+        let mut seq = state.snapshot.as_ref().and_then(|snapshot| snapshot.seq);
+        let snapshot = state
+            .snapshot
+            .and_then(|snapshot| snapshot.data)
+            .map(|data| STANDARD.encode(data));
+        let updates = state
+            .edits
+            .into_iter()
+            .filter_map(|edit| {
+                seq = edit.seq.or(seq);
+                edit.data.map(|data| STANDARD.encode(data))
+            })
+            .collect();
+        Self {
+            at,
+            revision,
+            content,
+            snapshot,
+            updates,
+            seq: seq.unwrap_or(0),
+        }
+    }
+}
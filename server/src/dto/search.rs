@@ -0,0 +1,86 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::models::{MatchedLine, SearchHit};
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct SearchQueryDto {
+    #[validate(length(min = 2, max = 200))]
+    pub q: String,
+    #[serde(default)]
+    pub language: Option<String>,
+    /// Restricts the search to one workspace, which leaves snippets out.
+    #[serde(default)]
+    pub workspace: Option<Uuid>,
+    /// The username of the owner of the workspaces and snippets to search.
+    #[serde(default)]
+    pub owner: Option<String>,
+    #[serde(default)]
+    pub limit: Option<i64>,
+    #[serde(default)]
+    pub offset: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct MatchedLineResDto {
+    pub number: usize,
+    pub text: String,
+    pub highlights: Vec<(usize, usize)>,
+}
+
+impl From<MatchedLine> for MatchedLineResDto {
+    fn from(line: MatchedLine) -> Self {
+        MatchedLineResDto {
+            number: line.number,
+            text: line.text,
+            highlights: line.highlights,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchHitResDto {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub workspace_id: Option<Uuid>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub snippet_slug: Option<String>,
+    pub title: String,
+    pub owner_id: Uuid,
+    pub owner_username: String,
+    pub language: Option<String>,
+    pub path: String,
+    pub score: f32,
+    pub lines: Vec<MatchedLineResDto>,
+}
+
+impl From<(SearchHit, Vec<MatchedLine>)> for SearchHitResDto {
+    fn from((hit, lines): (SearchHit, Vec<MatchedLine>)) -> Self {
+        SearchHitResDto {
+            workspace_id: hit.workspace_id,
+            snippet_slug: hit.snippet_slug,
+            title: hit.title,
+            owner_id: hit.owner_id,
+            owner_username: hit.owner_username,
+            language: hit.language,
+            path: hit.path,
+            score: hit.rank,
+            lines: lines.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SearchResDto {
+    pub hits: Vec<SearchHitResDto>,
+}
+
+impl From<Vec<(SearchHit, Vec<MatchedLine>)>> for SearchResDto {
+    fn from(hits: Vec<(SearchHit, Vec<MatchedLine>)>) -> Self {
+        SearchResDto {
+            hits: hits.into_iter().map(Into::into).collect(),
+        }
+    }
+}
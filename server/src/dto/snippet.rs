@@ -0,0 +1,125 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::models::{Snippet, SnippetFile, SnippetVisibility};
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct SnippetFileReqDto {
+    #[validate(
+        length(min = 1, max = 255),
+        custom(function = "super::validate_workspace_file_path")
+    )]
+    pub name: String,
+    #[validate(length(max = 262144))]
+    pub content: String,
+}
+
+impl From<SnippetFileReqDto> for SnippetFile {
+    fn from(dto: SnippetFileReqDto) -> Self {
+        SnippetFile {
+            name: dto.name,
+            content: dto.content,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct SnippetReqDto {
+    #[validate(length(min = 1, max = 200))]
+    pub title: String,
+    #[validate(length(min = 1, max = 30))]
+    #[serde(default)]
+    pub language: Option<String>,
+    #[validate(length(min = 1, max = 20), nested)]
+    pub files: Vec<SnippetFileReqDto>,
+    /// Defaults to `unlisted`.
+    #[serde(default)]
+    pub visibility: Option<SnippetVisibility>,
+    #[validate(length(min = 4, max = 128))]
+    #[serde(default)]
+    pub password: Option<String>,
+    #[validate(range(min = 60, max = 31536000))]
+    #[serde(default)]
+    pub expires_in_secs: Option<i64>,
+}
+
+/// Partial update; `password` and `expires_in_secs` are removed when sent as `null`.
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct PatchSnippetReqDto {
+    #[validate(length(min = 1, max = 200))]
+    #[serde(default)]
+    pub title: Option<String>,
+    #[validate(length(min = 1, max = 30))]
+    #[serde(default)]
+    pub language: Option<String>,
+    #[validate(length(min = 1, max = 20), nested)]
+    #[serde(default)]
+    pub files: Option<Vec<SnippetFileReqDto>>,
+    #[serde(default)]
+    pub visibility: Option<SnippetVisibility>,
+    #[validate(length(min = 4, max = 128))]
+    #[serde(default, deserialize_with = "super::double_option")]
+    pub password: Option<Option<String>>,
+    #[validate(range(min = 60, max = 31536000))]
+    #[serde(default, deserialize_with = "super::double_option")]
+    pub expires_in_secs: Option<Option<i64>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SnippetsQueryDto {
+    #[serde(default)]
+    pub limit: Option<i64>,
+    #[serde(default)]
+    pub offset: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SnippetResDto {
+    pub id: Uuid,
+    pub owner_id: Uuid,
+    pub slug: String,
+    pub title: String,
+    pub language: Option<String>,
+    pub files: Vec<SnippetFile>,
+    pub visibility: SnippetVisibility,
+    pub has_password: bool,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub fork_count: i32,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<Snippet> for SnippetResDto {
+    fn from(snippet: Snippet) -> Self {
+        SnippetResDto {
+            id: snippet.id,
+            owner_id: snippet.owner_id,
+            slug: snippet.slug,
+            title: snippet.title,
+            language: snippet.language,
+            files: snippet.files.0,
+            visibility: snippet.visibility,
+            has_password: snippet.password_hash.is_some(),
+            expires_at: snippet.expires_at,
+            fork_count: snippet.fork_count,
+            created_at: snippet.created_at,
+            updated_at: snippet.updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SnippetsResDto {
+    pub snippets: Vec<SnippetResDto>,
+}
+
+impl From<Vec<Snippet>> for SnippetsResDto {
+    fn from(snippets: Vec<Snippet>) -> Self {
+        Self {
+            snippets: snippets.into_iter().map(SnippetResDto::from).collect(),
+        }
+    }
+}
@@ -0,0 +1,28 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::models::{QuotaUsage, UserUsageReport};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UsageResDto {
+    pub quotas: Vec<QuotaUsage>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UsageReportQueryDto {
+    #[serde(default)]
+    pub from: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub to: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub limit: Option<i64>,
+    #[serde(default)]
+    pub offset: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct UsageReportResDto {
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    pub users: Vec<UserUsageReport>,
+}
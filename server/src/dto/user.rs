@@ -14,9 +14,6 @@ pub struct UserReqDto {
     #[validate(length(min = 8, max = 128))]
     #[serde(default)]
     pub password: String,
-    #[validate(url)]
-    #[serde(default)]
-    pub avatar_url: Option<String>,
     #[serde(default)]
     pub github_id: Option<i64>,
 }
@@ -53,9 +50,6 @@ pub struct PatchReqDto {
     #[validate(length(min = 8, max = 128))]
     #[serde(default)]
     pub password: Option<String>,
-    #[validate(url)]
-    #[serde(default)]
-    pub avatar_url: Option<String>,
     #[serde(default)]
     pub github_id: Option<i64>,
 }
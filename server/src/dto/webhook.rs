@@ -0,0 +1,181 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use uuid::Uuid;
+use validator::{Validate, ValidationError};
+
+use crate::models::{Webhook, WebhookDelivery, WebhookDeliveryStatus, WebhookEvent};
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct WebhookReqDto {
+    #[validate(length(max = 2048), url, custom(function = "validate_webhook_url"))]
+    pub url: String,
+    #[validate(length(min = 1, message = "Subscribe to at least one event"))]
+    pub events: Vec<WebhookEvent>,
+    /// Signs the deliveries; a random one is generated when left out.
+    #[validate(length(min = 16, max = 256))]
+    pub secret: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct WebhookUpdateReqDto {
+    #[validate(length(max = 2048), url, custom(function = "validate_webhook_url"))]
+    pub url: Option<String>,
+    #[validate(length(min = 1, message = "Subscribe to at least one event"))]
+    pub events: Option<Vec<WebhookEvent>>,
+    #[validate(length(min = 16, max = 256))]
+    pub secret: Option<String>,
+    pub active: Option<bool>,
+}
+
+/// Deliveries are plain HTTP requests, so other schemes are refused.
+fn validate_webhook_url(url: &str) -> Result<(), ValidationError> {
+    if !(url.starts_with("http://") || url.starts_with("https://")) {
+        return Err(
+            ValidationError::new("url").with_message("Webhook URLs must use http or https".into())
+        );
+    }
+    Ok(())
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookResDto {
+    pub id: Uuid,
+    pub user_id: Option<Uuid>,
+    pub organization_id: Option<Uuid>,
+    pub url: String,
+    pub events: Vec<WebhookEvent>,
+    pub active: bool,
+    /// The signing secret, only returned when the webhook is created.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub secret: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl WebhookResDto {
+    /// Describes a webhook that was just created, including its secret.
+    pub fn with_secret(webhook: Webhook) -> Self {
+        let secret = webhook.secret.clone();
+        Self {
+            secret: Some(secret),
+            ..Self::from(webhook)
+        }
+    }
+}
+
+impl From<Webhook> for WebhookResDto {
+    fn from(webhook: Webhook) -> Self {
+        WebhookResDto {
+            id: webhook.id,
+            user_id: webhook.user_id,
+            organization_id: webhook.organization_id,
+            url: webhook.url,
+            events: webhook.events.0,
+            active: webhook.active,
+            secret: None,
+            created_at: webhook.created_at,
+            updated_at: webhook.updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WebhooksResDto {
+    pub webhooks: Vec<WebhookResDto>,
+}
+
+impl From<Vec<Webhook>> for WebhooksResDto {
+    fn from(webhooks: Vec<Webhook>) -> Self {
+        Self {
+            webhooks: webhooks.into_iter().map(WebhookResDto::from).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct WebhookDeliveriesQueryDto {
+    #[serde(default)]
+    pub status: Option<WebhookDeliveryStatus>,
+    #[serde(default)]
+    pub limit: Option<i64>,
+    #[serde(default)]
+    pub offset: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookDeliveryResDto {
+    pub id: Uuid,
+    pub webhook_id: Uuid,
+    pub event: WebhookEvent,
+    pub status: WebhookDeliveryStatus,
+    pub attempts: i32,
+    /// When the next attempt is due, while the delivery is pending.
+    pub next_attempt_at: Option<DateTime<Utc>>,
+    pub response_status: Option<i32>,
+    pub error: Option<String>,
+    pub duration_ms: Option<i64>,
+    pub redelivery_of: Option<Uuid>,
+    pub finished_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<WebhookDelivery> for WebhookDeliveryResDto {
+    fn from(delivery: WebhookDelivery) -> Self {
+        WebhookDeliveryResDto {
+            id: delivery.id,
+            webhook_id: delivery.webhook_id,
+            event: delivery.event,
+            status: delivery.status,
+            attempts: delivery.attempts,
+            next_attempt_at: (delivery.status == WebhookDeliveryStatus::Pending)
+                .then_some(delivery.next_attempt_at),
+            response_status: delivery.response_status,
+            error: delivery.error,
+            duration_ms: delivery.duration_ms,
+            redelivery_of: delivery.redelivery_of,
+            finished_at: delivery.finished_at,
+            created_at: delivery.created_at,
+        }
+    }
+}
+
+/// A delivery with the payload it sends and the latest response it got.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookDeliveryDetailResDto {
+    #[serde(flatten)]
+    pub delivery: WebhookDeliveryResDto,
+    pub payload: Value,
+    pub response_body: Option<String>,
+}
+
+impl From<WebhookDelivery> for WebhookDeliveryDetailResDto {
+    fn from(mut delivery: WebhookDelivery) -> Self {
+        let payload = std::mem::take(&mut delivery.payload.0);
+        let response_body = delivery.response_body.take();
+        WebhookDeliveryDetailResDto {
+            delivery: WebhookDeliveryResDto::from(delivery),
+            payload,
+            response_body,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WebhookDeliveriesResDto {
+    pub deliveries: Vec<WebhookDeliveryResDto>,
+}
+
+impl From<Vec<WebhookDelivery>> for WebhookDeliveriesResDto {
+    fn from(deliveries: Vec<WebhookDelivery>) -> Self {
+        Self {
+            deliveries: deliveries
+                .into_iter()
+                .map(WebhookDeliveryResDto::from)
+                .collect(),
+        }
+    }
+}
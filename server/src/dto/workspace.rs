@@ -0,0 +1,181 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::models::{ArchiveFormat, Workspace, WorkspaceFile};
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct WorkspaceReqDto {
+    #[validate(length(min = 1, max = 100))]
+    pub name: String,
+    #[validate(length(min = 1, max = 30))]
+    #[serde(default)]
+    pub language: Option<String>,
+    /// Creates the workspace in this organization, shared with its members.
+    #[serde(default)]
+    pub organization_id: Option<Uuid>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct PatchWorkspaceReqDto {
+    #[validate(length(min = 1, max = 100))]
+    #[serde(default)]
+    pub name: Option<String>,
+    #[validate(length(min = 1, max = 30))]
+    #[serde(default)]
+    pub language: Option<String>,
+}
+
+/// Copies a workspace or snippet; the fork keeps the source's name unless renamed.
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct ForkReqDto {
+    #[validate(length(min = 1, max = 100))]
+    #[serde(default)]
+    pub name: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateWorkspaceQueryDto {
+    /// The slug of a template to copy the starter files from.
+    #[serde(default)]
+    pub template: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ExportQueryDto {
+    #[serde(default)]
+    pub format: ArchiveFormat,
+}
+
+/// Names the imported workspace; defaults to the name in the archive's manifest.
+#[derive(Debug, Deserialize, Validate)]
+pub struct ImportQueryDto {
+    #[validate(length(min = 1, max = 100))]
+    #[serde(default)]
+    pub name: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct WorkspacesQueryDto {
+    #[serde(default)]
+    pub limit: Option<i64>,
+    #[serde(default)]
+    pub offset: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceResDto {
+    pub id: Uuid,
+    pub owner_id: Uuid,
+    pub name: String,
+    pub language: Option<String>,
+    pub organization_id: Option<Uuid>,
+    pub forked_from_workspace_id: Option<Uuid>,
+    pub forked_from_snippet_id: Option<Uuid>,
+    pub template_id: Option<Uuid>,
+    pub fork_count: i32,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<Workspace> for WorkspaceResDto {
+    fn from(workspace: Workspace) -> Self {
+        WorkspaceResDto {
+            id: workspace.id,
+            owner_id: workspace.owner_id,
+            name: workspace.name,
+            language: workspace.language,
+            organization_id: workspace.organization_id,
+            forked_from_workspace_id: workspace.forked_from_workspace_id,
+            forked_from_snippet_id: workspace.forked_from_snippet_id,
+            template_id: workspace.template_id,
+            fork_count: workspace.fork_count,
+            created_at: workspace.created_at,
+            updated_at: workspace.updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WorkspacesResDto {
+    pub workspaces: Vec<WorkspaceResDto>,
+}
+
+impl From<Vec<Workspace>> for WorkspacesResDto {
+    fn from(workspaces: Vec<Workspace>) -> Self {
+        Self {
+            workspaces: workspaces.into_iter().map(WorkspaceResDto::from).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct WorkspaceFileReqDto {
+    #[validate(
+        length(min = 1, max = 255),
+        custom(function = "super::validate_workspace_file_path")
+    )]
+    pub path: String,
+    #[validate(length(max = 1048576))]
+    #[serde(default)]
+    pub content: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct PatchWorkspaceFileReqDto {
+    #[validate(
+        length(min = 1, max = 255),
+        custom(function = "super::validate_workspace_file_path")
+    )]
+    #[serde(default)]
+    pub path: Option<String>,
+    #[validate(length(max = 1048576))]
+    #[serde(default)]
+    pub content: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceFileResDto {
+    pub id: Uuid,
+    pub path: String,
+    pub content: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<WorkspaceFile> for WorkspaceFileResDto {
+    fn from(file: WorkspaceFile) -> Self {
+        WorkspaceFileResDto {
+            id: file.id,
+            path: file.path,
+            content: file.content,
+            created_at: file.created_at,
+            updated_at: file.updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WorkspaceFilesResDto {
+    pub files: Vec<WorkspaceFileResDto>,
+}
+
+impl From<Vec<WorkspaceFile>> for WorkspaceFilesResDto {
+    fn from(files: Vec<WorkspaceFile>) -> Self {
+        Self {
+            files: files.into_iter().map(WorkspaceFileResDto::from).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportResDto {
+    pub workspace: WorkspaceResDto,
+    pub file_count: usize,
+    /// Binary files that were left out of the workspace.
+    pub skipped: Vec<String>,
+}
@@ -0,0 +1,126 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::models::{InvitationStatus, WorkspaceInvitation, WorkspaceMemberProfile, WorkspaceRole};
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MemberResDto {
+    pub user_id: Uuid,
+    pub username: String,
+    pub avatar_url: Option<String>,
+    pub role: WorkspaceRole,
+    pub joined_at: DateTime<Utc>,
+}
+
+impl From<WorkspaceMemberProfile> for MemberResDto {
+    fn from(member: WorkspaceMemberProfile) -> Self {
+        MemberResDto {
+            user_id: member.user_id,
+            username: member.username,
+            avatar_url: member.avatar_url,
+            role: member.role,
+            joined_at: member.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MembersResDto {
+    pub members: Vec<MemberResDto>,
+}
+
+impl From<Vec<WorkspaceMemberProfile>> for MembersResDto {
+    fn from(members: Vec<WorkspaceMemberProfile>) -> Self {
+        Self {
+            members: members.into_iter().map(MemberResDto::from).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PatchMemberReqDto {
+    pub role: WorkspaceRole,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransferWorkspaceReqDto {
+    pub user_id: Uuid,
+}
+
+/// Invites an existing user, identified by either username or email.
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct InvitationReqDto {
+    #[validate(length(min = 3, max = 30))]
+    #[serde(default, deserialize_with = "super::to_lowercase")]
+    pub username: Option<String>,
+    #[validate(email, length(max = 320))]
+    #[serde(default, deserialize_with = "super::to_lowercase")]
+    pub email: Option<String>,
+    pub role: WorkspaceRole,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct InviteLinkReqDto {
+    pub role: WorkspaceRole,
+    #[validate(range(min = 60, max = 2592000))]
+    #[serde(default)]
+    pub expires_in_secs: Option<i64>,
+    #[validate(range(min = 1, max = 1000))]
+    #[serde(default)]
+    pub max_uses: Option<i32>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InvitationResDto {
+    pub id: Uuid,
+    pub workspace_id: Uuid,
+    pub inviter_id: Option<Uuid>,
+    pub invitee_id: Option<Uuid>,
+    pub token: Option<String>,
+    pub role: WorkspaceRole,
+    pub status: InvitationStatus,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub max_uses: Option<i32>,
+    pub uses: i32,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<WorkspaceInvitation> for InvitationResDto {
+    fn from(invitation: WorkspaceInvitation) -> Self {
+        InvitationResDto {
+            id: invitation.id,
+            workspace_id: invitation.workspace_id,
+            inviter_id: invitation.inviter_id,
+            invitee_id: invitation.invitee_id,
+            token: invitation.token,
+            role: invitation.role,
+            status: invitation.status,
+            expires_at: invitation.expires_at,
+            max_uses: invitation.max_uses,
+            uses: invitation.uses,
+            created_at: invitation.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct InvitationsResDto {
+    pub invitations: Vec<InvitationResDto>,
+}
+
+impl From<Vec<WorkspaceInvitation>> for InvitationsResDto {
+    fn from(invitations: Vec<WorkspaceInvitation>) -> Self {
+        Self {
+            invitations: invitations
+                .into_iter()
+                .map(InvitationResDto::from)
+                .collect(),
+        }
+    }
+}
@@ -0,0 +1,145 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use validator::{Validate, ValidationError};
+
+use crate::models::{TemplateFile, WorkspaceTemplate};
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct TemplateFileReqDto {
+    #[validate(
+        length(min = 1, max = 255),
+        custom(function = "super::validate_workspace_file_path")
+    )]
+    pub path: String,
+    #[validate(length(max = 1048576))]
+    #[serde(default)]
+    pub content: String,
+}
+
+impl From<TemplateFileReqDto> for TemplateFile {
+    fn from(dto: TemplateFileReqDto) -> Self {
+        TemplateFile {
+            path: dto.path,
+            content: dto.content,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct TemplateReqDto {
+    #[validate(length(min = 1, max = 64), custom(function = "super::validate_slug"))]
+    pub slug: String,
+    #[validate(length(min = 1, max = 100))]
+    pub name: String,
+    #[validate(length(max = 1000))]
+    #[serde(default)]
+    pub description: Option<String>,
+    #[validate(length(min = 1, max = 30))]
+    #[serde(default)]
+    pub language: Option<String>,
+    #[validate(length(min = 1, max = 200), nested)]
+    pub files: Vec<TemplateFileReqDto>,
+    #[validate(length(max = 10), custom(function = "validate_tags"))]
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Publishes the template privately to the members of this workspace.
+    #[serde(default)]
+    pub workspace_id: Option<Uuid>,
+    /// Publishes the template privately to the members of this organization.
+    #[serde(default)]
+    pub organization_id: Option<Uuid>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct PatchTemplateReqDto {
+    #[validate(length(min = 1, max = 100))]
+    #[serde(default)]
+    pub name: Option<String>,
+    #[validate(length(max = 1000))]
+    #[serde(default)]
+    pub description: Option<String>,
+    #[validate(length(min = 1, max = 30))]
+    #[serde(default)]
+    pub language: Option<String>,
+    #[validate(length(min = 1, max = 200), nested)]
+    #[serde(default)]
+    pub files: Option<Vec<TemplateFileReqDto>>,
+    #[validate(length(max = 10), custom(function = "validate_tags"))]
+    #[serde(default)]
+    pub tags: Option<Vec<String>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TemplatesQueryDto {
+    #[serde(default)]
+    pub language: Option<String>,
+    #[serde(default)]
+    pub tag: Option<String>,
+    #[serde(default)]
+    pub limit: Option<i64>,
+    #[serde(default)]
+    pub offset: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TemplateResDto {
+    pub id: Uuid,
+    pub slug: String,
+    pub name: String,
+    pub description: String,
+    pub language: Option<String>,
+    pub files: Vec<TemplateFile>,
+    pub tags: Vec<String>,
+    pub workspace_id: Option<Uuid>,
+    pub organization_id: Option<Uuid>,
+    pub author_id: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<WorkspaceTemplate> for TemplateResDto {
+    fn from(template: WorkspaceTemplate) -> Self {
+        TemplateResDto {
+            id: template.id,
+            slug: template.slug,
+            name: template.name,
+            description: template.description,
+            language: template.language,
+            files: template.files.0,
+            tags: template.tags,
+            workspace_id: template.workspace_id,
+            organization_id: template.organization_id,
+            author_id: template.author_id,
+            created_at: template.created_at,
+            updated_at: template.updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TemplatesResDto {
+    pub templates: Vec<TemplateResDto>,
+}
+
+impl From<Vec<WorkspaceTemplate>> for TemplatesResDto {
+    fn from(templates: Vec<WorkspaceTemplate>) -> Self {
+        Self {
+            templates: templates.into_iter().map(TemplateResDto::from).collect(),
+        }
+    }
+}
+
+fn validate_tags(tags: &[String]) -> Result<(), ValidationError> {
+    if !tags
+        .iter()
+        .all(|tag| tag.len() <= 30 && super::is_slug(tag))
+    {
+        return Err(ValidationError::new("tags").with_message(
+            "Tags may only contain lowercase letters, digits and dashes, up to 30 characters"
+                .into(),
+        ));
+    }
+    Ok(())
+}
@@ -0,0 +1,158 @@
+#![deny(missing_docs)]
+//! HTTP client for the remote code execution (RCE) sandbox.
+
+use std::time::Duration;
+
+use anyhow::{anyhow, bail, Context};
+use serde::{Deserialize, Serialize};
+
+use super::Language;
+use crate::{
+    models::ExecutionFile,
+    utils::{CaraiResult, RceConfig},
+};
+
+/// A program to run in the sandbox.
+#[derive(Debug)]
+pub struct ExecutionRequest<'a> {
+    /// The language (and therefore image) to run the program with.
+    pub language: &'static Language,
+    /// The source files making up the program.
+    pub files: &'a [ExecutionFile],
+    /// Optional data piped to the program's standard input.
+    pub stdin: Option<&'a str>,
+    /// A shell command to run instead of the image's default runner.
+    pub command: Option<&'a str>,
+    /// Overrides the client-wide timeout for this request.
+    pub timeout: Option<Duration>,
+}
+
+/// What the sandbox reported after running a program.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ExecutionOutput {
+    /// Captured standard output.
+    #[serde(default)]
+    pub stdout: String,
+    /// Captured standard error.
+    #[serde(default)]
+    pub stderr: String,
+    /// A sandbox-level error, such as a non-zero exit or a killed process.
+    #[serde(default)]
+    pub error: String,
+}
+
+impl ExecutionOutput {
+    /// Derives the program's exit code from the sandbox report.
+    ///
+    /// The sandbox only reports failures as text such as `exit status 2`, so a clean
+    /// run maps to `0` and an error without a recognisable status maps to `None`.
+    pub fn exit_code(&self) -> Option<i32> {
+        if self.error.is_empty() {
+            return Some(0);
+        }
+        self.error
+            .rsplit_once("exit status ")
+            .and_then(|(_, code)| code.trim().parse().ok())
+    }
+}
+
+#[derive(Serialize)]
+struct RunPayload<'a> {
+    language: &'a str,
+    files: &'a [ExecutionFile],
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stdin: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    command: Option<&'a str>,
+}
+
+#[derive(Serialize)]
+struct RunRequest<'a> {
+    image: &'a str,
+    payload: RunPayload<'a>,
+}
+
+#[derive(Deserialize)]
+struct RunError {
+    message: String,
+}
+
+/// Sends programs to the RCE sandbox and collects their output.
+#[derive(Debug, Clone)]
+pub struct RceClient {
+    http: reqwest::Client,
+    base_url: String,
+    access_token: String,
+    timeout: Duration,
+}
+
+impl RceClient {
+    /// Creates a client from the `rce` section of the application configuration.
+    pub fn new(config: &RceConfig) -> CaraiResult<Self> {
+        let timeout = Duration::from_secs(*config.timeout_in_secs());
+        let http = reqwest::Client::builder()
+            .timeout(timeout)
+            .build()
+            .context("Failed to build RCE client")?;
+
+        Ok(Self {
+            http,
+            base_url: config.base_url().to_owned(),
+            access_token: config.access_token().to_owned(),
+            timeout,
+        })
+    }
+
+    /// Runs a program and waits for the sandbox to report its output.
+    ///
+    /// Returns an error when the sandbox cannot be reached or rejects the request;
+    /// failures of the program itself are reported through [`ExecutionOutput::error`].
+    /// A request that outlives its timeout counts as a program failure, since the
+    /// sandbox is still busy running it.
+    pub async fn execute(&self, request: &ExecutionRequest<'_>) -> CaraiResult<ExecutionOutput> {
+        let body = RunRequest {
+            image: request.language.image,
+            payload: RunPayload {
+                language: request.language.name,
+                files: request.files,
+                stdin: request.stdin,
+                command: request.command,
+            },
+        };
+        let timeout = request.timeout.unwrap_or(self.timeout);
+
+        let response = match self
+            .http
+            .post(&self.base_url)
+            .header("X-Access-Token", &self.access_token)
+            .timeout(timeout)
+            .json(&body)
+            .send()
+            .await
+        {
+            Ok(response) => response,
+            Err(e) if e.is_timeout() => {
+                return Ok(ExecutionOutput {
+                    error: format!("Execution timed out after {:?}", timeout),
+                    ..Default::default()
+                })
+            }
+            Err(e) => bail!("Unable to reach RCE service ({})", e),
+        };
+
+        let status = response.status();
+        if !status.is_success() {
+            let message = response
+                .json::<RunError>()
+                .await
+                .map(|e| e.message)
+                .unwrap_or_else(|_| status.to_string());
+            bail!("RCE service rejected the request ({})", message);
+        }
+
+        response
+            .json::<ExecutionOutput>()
+            .await
+            .map_err(|e| anyhow!("Unable to decode RCE response ({})", e))
+    }
+}
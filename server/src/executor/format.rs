@@ -0,0 +1,142 @@
+#![deny(missing_docs)]
+//! Code formatting through the formatters installed in the sandbox images.
+
+use anyhow::anyhow;
+use serde::{Deserialize, Serialize};
+
+use super::{ExecutionRequest, Language, RceClient};
+use crate::{models::ExecutionFile, utils::CaraiResult};
+
+/// Upper bound on the diagnostics reported for a single file.
+const MAX_DIAGNOSTICS: usize = 20;
+
+/// A problem reported by a formatter, located when its message names a position.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FormatDiagnostic {
+    /// One-based line of the problem.
+    pub line: Option<u32>,
+    /// One-based column of the problem.
+    pub column: Option<u32>,
+    /// The formatter's message, as printed.
+    pub message: String,
+}
+
+/// What formatting a file produced.
+#[derive(Debug)]
+pub enum FormatOutput {
+    /// The formatter succeeded and printed the formatted file.
+    Formatted(String),
+    /// The formatter refused the file, typically because it does not parse.
+    Rejected(Vec<FormatDiagnostic>),
+}
+
+/// Formats `code` as the file at `path` with the language's formatter.
+///
+/// Returns an error when the language has no formatter or the sandbox is unreachable;
+/// complaints of the formatter itself are reported as [`FormatOutput::Rejected`].
+pub async fn format_code(
+    client: &RceClient,
+    language: &'static Language,
+    path: &str,
+    code: &str,
+) -> CaraiResult<FormatOutput> {
+    let command = language
+        .format_command(path)
+        .ok_or_else(|| anyhow!("No formatter is configured for {}", language.name))?;
+    let files = [ExecutionFile {
+        name: path.to_owned(),
+        content: code.to_owned(),
+    }];
+    let request = ExecutionRequest {
+        language,
+        files: &files,
+        stdin: None,
+        command: Some(&command),
+        timeout: None,
+    };
+    let output = client.execute(&request).await?;
+    if output.error.is_empty() {
+        return Ok(FormatOutput::Formatted(output.stdout));
+    }
+
+    let mut diagnostics = parse_diagnostics(&output.stderr);
+    if diagnostics.is_empty() {
+        let message = Some(output.stderr.trim())
+            .filter(|stderr| !stderr.is_empty())
+            .unwrap_or(&output.error);
+        diagnostics.push(FormatDiagnostic {
+            line: None,
+            column: None,
+            message: message.to_owned(),
+        });
+    }
+    Ok(FormatOutput::Rejected(diagnostics))
+}
+
+/// Picks the error lines out of a formatter's stderr.
+///
+/// Formatters disagree on their output, so this only relies on what they share: errors
+/// start with `error` or carry a `line:column` position. A line holding nothing but a
+/// position (rustfmt's `--> <stdin>:1:5`) locates the error before it.
+fn parse_diagnostics(stderr: &str) -> Vec<FormatDiagnostic> {
+    let mut diagnostics: Vec<FormatDiagnostic> = Vec::new();
+    for text in stderr
+        .lines()
+        .map(str::trim)
+        .filter(|text| !text.is_empty())
+    {
+        let position = find_position(text);
+        if text.starts_with("-->") {
+            if let (Some((line, column)), Some(last)) = (position, diagnostics.last_mut()) {
+                if last.line.is_none() {
+                    last.line = Some(line);
+                    last.column = Some(column);
+                }
+            }
+            continue;
+        }
+        let is_error = text
+            .get(..5)
+            .is_some_and(|prefix| prefix.eq_ignore_ascii_case("error"));
+        if is_error || position.is_some() {
+            diagnostics.push(FormatDiagnostic {
+                line: position.map(|(line, _)| line),
+                column: position.map(|(_, column)| column),
+                message: text.to_owned(),
+            });
+        }
+    }
+    diagnostics.truncate(MAX_DIAGNOSTICS);
+    diagnostics
+}
+
+/// Finds the first `line:column` pair that starts a word or follows a colon.
+fn find_position(text: &str) -> Option<(u32, u32)> {
+    let bytes = text.as_bytes();
+    let digits = |from: usize| {
+        bytes[from..]
+            .iter()
+            .take_while(|byte| byte.is_ascii_digit())
+            .count()
+    };
+    let mut start = 0;
+    while start < bytes.len() {
+        let at_boundary = start == 0 || matches!(bytes[start - 1], b':' | b' ');
+        let line_len = digits(start);
+        if line_len == 0 {
+            start += 1;
+            continue;
+        }
+        let line_end = start + line_len;
+        if at_boundary && bytes.get(line_end) == Some(&b':') {
+            let column_len = digits(line_end + 1);
+            if column_len > 0 {
+                let line = text[start..line_end].parse().ok()?;
+                let column = text[line_end + 1..line_end + 1 + column_len].parse().ok()?;
+                return Some((line, column));
+            }
+        }
+        start = line_end;
+    }
+    None
+}
@@ -0,0 +1,98 @@
+#![deny(missing_docs)]
+//! Grading of program output against expected test case output.
+
+use similar::TextDiff;
+
+use super::ExecutionOutput;
+use crate::models::{CaseResult, ComparisonMode, JudgeSpec, TestCase, Verdict};
+
+/// Time a test case gets beyond its limit, covering what the measured duration includes
+/// besides the program itself: the request, starting the container and unpacking builds.
+pub const CASE_TIMEOUT_MARGIN_MS: i64 = 500;
+
+/// Compares actual output with the expected output using the given mode.
+///
+/// * `Exact` requires byte-for-byte equality.
+/// * `Trimmed` ignores trailing whitespace on each line and trailing blank lines.
+/// * `Float` compares whitespace-separated tokens, treating numeric tokens as equal
+///   when they differ by at most `tolerance` (absolute or relative, whichever is looser).
+pub fn outputs_match(mode: ComparisonMode, tolerance: f64, expected: &str, actual: &str) -> bool {
+    match mode {
+        ComparisonMode::Exact => expected == actual,
+        ComparisonMode::Trimmed => trimmed_lines(expected) == trimmed_lines(actual),
+        ComparisonMode::Float => {
+            let expected: Vec<&str> = expected.split_whitespace().collect();
+            let actual: Vec<&str> = actual.split_whitespace().collect();
+            expected.len() == actual.len()
+                && expected
+                    .iter()
+                    .zip(&actual)
+                    .all(|(e, a)| tokens_match(e, a, tolerance))
+        }
+    }
+}
+
+fn trimmed_lines(text: &str) -> Vec<&str> {
+    let mut lines: Vec<&str> = text.lines().map(str::trim_end).collect();
+    while lines.last().is_some_and(|line| line.is_empty()) {
+        lines.pop();
+    }
+    lines
+}
+
+fn tokens_match(expected: &str, actual: &str, tolerance: f64) -> bool {
+    match (expected.parse::<f64>(), actual.parse::<f64>()) {
+        (Ok(e), Ok(a)) => (e - a).abs() <= tolerance * e.abs().max(1.0),
+        _ => expected == actual,
+    }
+}
+
+/// Grades the output of a single test case run.
+///
+/// The sandbox reports resource exhaustion only as free-form error text, so time
+/// and memory limits are recognised from that text and the measured duration. Runs
+/// are cut off [`CASE_TIMEOUT_MARGIN_MS`] past their limit, so only durations beyond
+/// that margin count as exceeding it.
+pub fn grade_case(
+    spec: &JudgeSpec,
+    case: &TestCase,
+    output: ExecutionOutput,
+    duration_ms: i64,
+) -> CaseResult {
+    let error = output.error.to_lowercase();
+    let timed_out = case
+        .time_limit_ms
+        .is_some_and(|limit| duration_ms > limit.saturating_add(CASE_TIMEOUT_MARGIN_MS))
+        || error.contains("timeout")
+        || error.contains("timed out");
+
+    let (verdict, diff) = if timed_out {
+        (Verdict::TimeLimitExceeded, None)
+    } else if error.contains("out of memory") || error.contains("memory limit") {
+        (Verdict::MemoryLimitExceeded, None)
+    } else if !error.is_empty() {
+        (Verdict::RuntimeError, None)
+    } else if outputs_match(
+        spec.comparison,
+        spec.tolerance,
+        &case.expected_stdout,
+        &output.stdout,
+    ) {
+        (Verdict::Accepted, None)
+    } else {
+        let diff = TextDiff::from_lines(&case.expected_stdout, &output.stdout)
+            .unified_diff()
+            .header("expected", "actual")
+            .to_string();
+        (Verdict::WrongAnswer, Some(diff))
+    };
+
+    CaseResult {
+        verdict,
+        stdout: output.stdout,
+        stderr: output.stderr,
+        error: Some(output.error).filter(|e| !e.is_empty()),
+        duration_ms,
+        diff,
+    }
+}
@@ -0,0 +1,180 @@
+#![deny(missing_docs)]
+//! Server-side registry of the languages the remote code execution service can run.
+
+/// A language supported by the execution sandbox.
+#[derive(Debug)]
+pub struct Language {
+    /// The language name sent by clients and forwarded to the sandbox.
+    pub name: &'static str,
+    /// The sandbox image that runs this language.
+    pub image: &'static str,
+    /// The conventional entrypoint filename for single-file programs.
+    pub filename: &'static str,
+    /// How to build and run multi-file projects, for languages with a compile step.
+    pub build: Option<Build>,
+    /// A command printing the formatted `{entrypoint}` on stdout, for languages with a
+    /// formatter installed in their image.
+    pub formatter: Option<&'static str>,
+    /// A command starting a language server that speaks LSP over stdio.
+    pub language_server: Option<&'static str>,
+}
+
+impl Language {
+    /// Returns the command formatting the file at `path`, if the language has a formatter.
+    pub fn format_command(&self, path: &str) -> Option<String> {
+        self.formatter.map(|formatter| expand(formatter, path))
+    }
+}
+
+/// File the build artifacts are shipped to the run phase in.
+///
+/// The sandbox starts every request in a fresh container, so nothing the compile phase
+/// writes survives it; instead it prints its artifacts as a base64 tarball, which the
+/// run phase receives as this file and unpacks before running.
+pub const BUILD_ARTIFACT_FILE: &str = ".carai-build";
+
+/// Shell commands for a language's separate compile and run phases.
+///
+/// Commands run from the project root and may reference the entrypoint through the
+/// `{entrypoint}` placeholder, or through `{class}` for its dotted, extension-less
+/// form (as JVM languages expect). Either is substituted as a single, quoted word.
+#[derive(Debug)]
+pub struct Build {
+    /// Compiles the whole project; a non-zero exit is reported as a compile error.
+    pub compile: &'static str,
+    /// Runs the program produced by `compile`.
+    pub run: &'static str,
+    /// The files or directories `compile` produces and `run` needs.
+    pub artifacts: &'static str,
+}
+
+impl Build {
+    /// Returns the compile command for a project with the given entrypoint.
+    ///
+    /// What the compiler prints goes to stderr, as stdout carries the packed artifacts.
+    pub fn compile_command(&self, entrypoint: &str) -> String {
+        format!(
+            "{{ {}; }} 1>&2 && tar czf - {} | base64",
+            expand(self.compile, entrypoint),
+            self.artifacts
+        )
+    }
+
+    /// Returns the run command for a project with the given entrypoint, which expects the
+    /// artifacts printed by the compile command in [`BUILD_ARTIFACT_FILE`].
+    pub fn run_command(&self, entrypoint: &str) -> String {
+        format!(
+            "base64 -d {} | tar xzf - && {}",
+            BUILD_ARTIFACT_FILE,
+            expand(self.run, entrypoint)
+        )
+    }
+}
+
+/// Substitutes the entrypoint into a shell command, quoted since it comes from clients.
+fn expand(command: &str, entrypoint: &str) -> String {
+    let class = entrypoint
+        .rsplit_once('.')
+        .map_or(entrypoint, |(stem, _)| stem)
+        .replace('/', ".");
+    command
+        .replace("{entrypoint}", &shell_quote(entrypoint))
+        .replace("{class}", &shell_quote(&class))
+}
+
+/// Quotes a word for `sh`, leaving plain paths as they are.
+fn shell_quote(word: &str) -> String {
+    let plain = |c: char| c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '/' | '-');
+    if !word.is_empty() && word.chars().all(plain) {
+        word.to_string()
+    } else {
+        format!("'{}'", word.replace('\'', r"'\''"))
+    }
+}
+
+macro_rules! languages {
+    (@build) => { None };
+    (@build $compile:literal, $run:literal, $artifacts:literal) => {
+        Some(Build { compile: $compile, run: $run, artifacts: $artifacts })
+    };
+    (@optional) => { None };
+    (@optional $command:literal) => { Some($command) };
+    ($($name:literal => $image:literal, $filename:literal
+        $(, $compile:literal, $run:literal, $artifacts:literal)?
+        $(format $formatter:literal)? $(serve $server:literal)?;)*) => {
+        &[$(Language {
+            name: $name,
+            image: $image,
+            filename: $filename,
+            build: languages!(@build $($compile, $run, $artifacts)?),
+            formatter: languages!(@optional $($formatter)?),
+            language_server: languages!(@optional $($server)?),
+        },)*]
+    };
+}
+
+/// Every language known to the sandbox, mirroring the client's runtime record.
+pub static LANGUAGES: &[Language] = languages! {
+    "javascript" => "toolkithub/javascript:edge", "main.js"
+        format "prettier --stdin-filepath {entrypoint} < {entrypoint}";
+    "go" => "toolkithub/golang:edge", "main.go",
+        "([ -f go.mod ] || go mod init main > /dev/null 2>&1) && go build -o a.out .", "./a.out", "a.out"
+        format "gofmt {entrypoint}" serve "gopls";
+    "python" => "toolkithub/python:edge", "main.py"
+        format "black --quiet - < {entrypoint}" serve "pyright-langserver --stdio";
+    "typescript" => "toolkithub/typescript:edge", "main.ts"
+        format "prettier --stdin-filepath {entrypoint} < {entrypoint}";
+    "c" => "toolkithub/clang:edge", "main.c",
+        "clang -std=c17 -O2 -o a.out $(find . -name '*.c') -lm", "./a.out", "a.out"
+        format "clang-format {entrypoint}" serve "clangd";
+    "cpp" => "toolkithub/clang:edge", "main.cpp",
+        "clang++ -std=c++20 -O2 -o a.out $(find . -name '*.cpp')", "./a.out", "a.out"
+        format "clang-format {entrypoint}" serve "clangd";
+    "php" => "toolkithub/php:edge", "index.php";
+    "ruby" => "toolkithub/ruby:edge", "main.rb";
+    "lua" => "toolkithub/lua:edge", "main.lua";
+    "julia" => "toolkithub/julia:edge", "main.jl";
+    "erlang" => "toolkithub/erlang:edge", "main.erl";
+    "elixir" => "toolkithub/elixir:edge", "main.ex";
+    "java" => "toolkithub/java:edge", "Main.java",
+        "javac -d out $(find . -name '*.java')", "java -cp out {class}", "out";
+    "clisp" => "toolkithub/clisp:edge", "main.lsp";
+    "csharp" => "toolkithub/csharp:edge", "Program.cs";
+    "rust" => "toolkithub/rust:edge", "main.rs",
+        "rustc -O -o a.out {entrypoint}", "./a.out", "a.out"
+        format "rustfmt --edition 2021 < {entrypoint}" serve "rust-analyzer";
+    "kotlin" => "toolkithub/kotlin:edge", "Main.kt";
+    "swift" => "toolkithub/swift:edge", "main.swift";
+    "scala" => "toolkithub/scala:edge", "Main.scala";
+    "dart" => "toolkithub/dart:edge", "main.dart";
+    "haskell" => "toolkithub/haskell:edge", "Main.hs";
+    "perl" => "toolkithub/perl:edge", "main.pl";
+    "bash" => "toolkithub/bash:edge", "main.sh";
+    "clojure" => "toolkithub/clojure:edge", "main.clj";
+    "ats" => "toolkithub/ats:edge", "main.dats";
+    "nim" => "toolkithub/nim:edge", "main.nim";
+    "coffeescript" => "toolkithub/coffeescript:edge", "main.coffee";
+    "crystal" => "toolkithub/crystal:edge", "main.cr";
+    "zig" => "toolkithub/zig:edge", "main.zig";
+    "d" => "toolkithub/dlang:edge", "main.d";
+    "elm" => "toolkithub/elm:edge", "Main.elm";
+    "fsharp" => "toolkithub/fsharp:edge", "Program.fs";
+    "groovy" => "toolkithub/groovy:edge", "Main.groovy";
+    "guile" => "toolkithub/guile:edge", "main.scm";
+    "hare" => "toolkithub/hare:edge", "main.ha";
+    "idris" => "toolkithub/idris:edge", "Main.idr";
+    "mercury" => "toolkithub/mercury:edge", "main.m";
+    "nix" => "toolkithub/nix:edge", "default.nix";
+    "ocaml" => "toolkithub/ocaml:edge", "main.ml";
+    "pascal" => "toolkithub/pascal:edge", "main.pas";
+    "raku" => "toolkithub/raku:edge", "main.raku";
+    "sac" => "toolkithub/sac:edge", "main.sac";
+    "cobol" => "toolkithub/cobol:edge", "main.cob";
+};
+
+/// Looks up a language by name, ignoring case.
+pub fn find_language(name: &str) -> Option<&'static Language> {
+    LANGUAGES
+        .iter()
+        .find(|language| language.name.eq_ignore_ascii_case(name))
+}
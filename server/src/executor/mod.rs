@@ -0,0 +1,9 @@
+mod client;
+mod format;
+mod judge;
+mod language;
+
+pub use client::*;
+pub use format::*;
+pub use judge::*;
+pub use language::*;
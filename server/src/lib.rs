@@ -1,9 +1,16 @@
 pub mod bootstrap;
+pub mod collab;
 pub mod controllers;
 pub mod dto;
+pub mod executor;
+pub mod lsp;
 pub mod middlewares;
 pub mod models;
+pub mod notifications;
 pub mod repositories;
 pub mod services;
+pub mod storage;
 pub mod token;
 pub mod utils;
+pub mod webhooks;
+pub mod workers;
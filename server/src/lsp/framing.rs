@@ -0,0 +1,56 @@
+#![deny(missing_docs)]
+//! The base protocol of LSP: JSON-RPC messages preceded by `Content-Length` headers.
+
+use anyhow::{anyhow, bail};
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+use crate::utils::CaraiResult;
+
+/// Reads the next message body, or `None` once the stream ends between messages.
+///
+/// Messages larger than `max_bytes` are refused rather than buffered.
+pub async fn read_message<R>(reader: &mut R, max_bytes: usize) -> CaraiResult<Option<Vec<u8>>>
+where
+    R: AsyncBufRead + Unpin,
+{
+    let mut length = None;
+    let mut line = String::new();
+    loop {
+        line.clear();
+        if reader.read_line(&mut line).await? == 0 {
+            return match length {
+                None => Ok(None),
+                Some(_) => Err(anyhow!("Stream ended inside a message header")),
+            };
+        }
+        let header = line.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("content-length") {
+                length = Some(value.trim().parse::<usize>()?);
+            }
+        }
+    }
+
+    let length = length.ok_or_else(|| anyhow!("Message without a Content-Length header"))?;
+    if length > max_bytes {
+        bail!("Message of {} bytes exceeds the limit", length);
+    }
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body).await?;
+    Ok(Some(body))
+}
+
+/// Writes a message body with its header, and flushes it.
+pub async fn write_message<W>(writer: &mut W, body: &[u8]) -> CaraiResult<()>
+where
+    W: AsyncWrite + Unpin,
+{
+    let header = format!("Content-Length: {}\r\n\r\n", body.len());
+    writer.write_all(header.as_bytes()).await?;
+    writer.write_all(body).await?;
+    writer.flush().await?;
+    Ok(())
+}
@@ -0,0 +1,5 @@
+mod framing;
+mod session;
+
+pub use framing::*;
+pub use session::*;
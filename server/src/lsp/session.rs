@@ -0,0 +1,230 @@
+#![deny(missing_docs)]
+//! Proxies a client's WebSocket to a language server running over the workspace files.
+
+use std::{
+    path::{Path, PathBuf},
+    process::Stdio,
+    time::Duration,
+};
+
+use axum::extract::ws::{close_code, CloseFrame, Message, WebSocket};
+use futures_util::{SinkExt, StreamExt};
+use tokio::{
+    io::BufReader,
+    process::{Child, Command},
+    sync::mpsc,
+    time::{interval, Instant},
+};
+use uuid::Uuid;
+
+use super::{read_message, write_message};
+use crate::{
+    bootstrap::AppState,
+    executor::Language,
+    models::WorkspaceRole,
+    services,
+    token::Claims,
+    utils::{CaraiResult, Permit},
+};
+
+/// The root under which clients address workspace files, wherever they are materialized.
+pub const VIRTUAL_ROOT: &str = "file:///workspace";
+/// The least role that may run a language server over a workspace.
+///
+/// Servers such as rust-analyzer run build scripts and procedural macros from the
+/// workspace files, so serving them is as good as running the code.
+pub const LANGUAGE_SERVER_ROLE: WorkspaceRole = WorkspaceRole::Editor;
+/// How often the client's access to the workspace is checked again.
+const ACCESS_CHECK_INTERVAL: Duration = Duration::from_secs(10);
+/// The only environment language servers get, besides `HOME` pointing to their directory.
+const SERVER_PATH: &str = "/usr/local/sbin:/usr/local/bin:/usr/sbin:/usr/bin:/sbin:/bin";
+
+/// Serves a client until it disconnects, goes idle or the language server exits.
+///
+/// The workspace files are written to a fresh directory, and the language server is
+/// started there through the configured sandbox command. Every WebSocket text message is
+/// one JSON-RPC message, forwarded with its LSP header; the server's messages come back
+/// the same way. URIs under [`VIRTUAL_ROOT`] are translated to and from the directory,
+/// so clients never learn where it is. The directory is removed once the session ends,
+/// and the permit is released with it. Clients who lose access to the workspace, or fall
+/// below [`LANGUAGE_SERVER_ROLE`], are disconnected within seconds.
+pub async fn serve_lsp_connection(
+    socket: WebSocket,
+    state: AppState,
+    workspace_id: Uuid,
+    claims: Claims,
+    language: &'static Language,
+    permit: Permit,
+) {
+    let dir = Path::new(state.config().lsp().root_dir()).join(Uuid::new_v4().to_string());
+    tracing::debug!("Starting {} language server in {:?}", language.name, dir);
+    if let Err(e) = run_session(socket, &state, workspace_id, &claims, language, &dir).await {
+        tracing::warn!(
+            "Language server session in {:?} ended with an error: {}",
+            dir,
+            e
+        );
+    }
+    if let Err(e) = tokio::fs::remove_dir_all(&dir).await {
+        tracing::warn!(
+            "Unable to remove language server directory {:?}: {}",
+            dir,
+            e
+        );
+    }
+    drop(permit);
+}
+
+async fn run_session(
+    socket: WebSocket,
+    state: &AppState,
+    workspace_id: Uuid,
+    claims: &Claims,
+    language: &'static Language,
+    dir: &Path,
+) -> CaraiResult<()> {
+    materialize_workspace(state, workspace_id, dir).await?;
+    let mut server = spawn_server(state, language, dir)?;
+    let mut stdin = server.stdin.take().expect("stdin is piped");
+    let stdout = server.stdout.take().expect("stdout is piped");
+
+    // Reading a message is not cancel-safe, so it happens on its own task
+    let max_bytes = *state.config().lsp().max_message_bytes();
+    let (sender, mut receiver) = mpsc::channel::<Vec<u8>>(64);
+    let reader = tokio::spawn(async move {
+        let mut stdout = BufReader::new(stdout);
+        while let Ok(Some(body)) = read_message(&mut stdout, max_bytes).await {
+            if sender.send(body).await.is_err() {
+                break;
+            }
+        }
+    });
+
+    let dir_uri = format!("file://{}", dir.display());
+    let (mut sink, mut stream) = socket.split();
+    // A zero timeout would make the idle check's period zero, which panics
+    let idle_timeout =
+        Duration::from_secs(*state.config().lsp().idle_timeout_secs()).max(Duration::from_secs(1));
+    let mut last_active = Instant::now();
+    let mut idle_check = interval(idle_timeout / 4);
+    let mut access_check = interval(ACCESS_CHECK_INTERVAL);
+    let result = loop {
+        tokio::select! {
+            message = stream.next() => {
+                let text = match message {
+                    Some(Ok(Message::Text(text))) => text,
+                    Some(Ok(Message::Close(_))) | None => break Ok(()),
+                    Some(Ok(_)) => continue,
+                    Some(Err(e)) => break Err(e.into()),
+                };
+                last_active = Instant::now();
+                let body = rewrite_uris(&text, VIRTUAL_ROOT, &dir_uri);
+                if let Err(e) = write_message(&mut stdin, body.as_bytes()).await {
+                    break Err(e);
+                }
+            }
+            body = receiver.recv() => {
+                let Some(body) = body else {
+                    break close(&mut sink, close_code::ERROR, "Language server exited").await;
+                };
+                let text = rewrite_uris(&String::from_utf8_lossy(&body), &dir_uri, VIRTUAL_ROOT);
+                if let Err(e) = sink.send(Message::Text(text)).await {
+                    break Err(e.into());
+                }
+            }
+            _ = access_check.tick() => {
+                let role = services::refresh_workspace_role(
+                    state.db_pool(),
+                    workspace_id,
+                    *claims.jti(),
+                    *claims.is_admin(),
+                )
+                .await;
+                match role {
+                    Ok(Some(role)) if role >= LANGUAGE_SERVER_ROLE => {}
+                    Ok(_) => {
+                        let reason = "Access to the workspace was revoked";
+                        break close(&mut sink, close_code::POLICY, reason).await;
+                    }
+                    Err(e) => break Err(e),
+                }
+            }
+            _ = idle_check.tick() => {
+                if last_active.elapsed() >= idle_timeout {
+                    break close(&mut sink, close_code::NORMAL, "Idle timeout").await;
+                }
+            }
+        }
+    };
+
+    reader.abort();
+    if let Err(e) = server.kill().await {
+        tracing::debug!("Language server had already exited: {}", e);
+    }
+    result
+}
+
+/// Writes every file of the workspace below `dir`.
+async fn materialize_workspace(
+    state: &AppState,
+    workspace_id: Uuid,
+    dir: &Path,
+) -> CaraiResult<()> {
+    tokio::fs::create_dir_all(dir).await?;
+    for file in services::get_workspace_files(state.db_pool(), workspace_id).await? {
+        // Stored paths are validated to be relative and free of `..`
+        let path: PathBuf = dir.join(&file.path);
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        tokio::fs::write(&path, file.content).await?;
+    }
+    Ok(())
+}
+
+fn spawn_server(state: &AppState, language: &'static Language, dir: &Path) -> CaraiResult<Child> {
+    let server = language
+        .language_server
+        .ok_or_else(|| anyhow::anyhow!("{} has no language server", language.name))?;
+    let command = state
+        .config()
+        .lsp()
+        .sandbox_command()
+        .replace("{dir}", &dir.display().to_string())
+        .replace("{command}", server);
+    // The server's own environment holds secrets, none of which language servers need
+    Ok(Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .env_clear()
+        .env("PATH", SERVER_PATH)
+        .env("HOME", dir)
+        .current_dir(dir)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .kill_on_drop(true)
+        .spawn()?)
+}
+
+/// Replaces the root `from` by `to` in the URIs of a message.
+///
+/// Only the root itself and paths below it are replaced, so a sibling such as
+/// `file:///workspace2` is left alone.
+fn rewrite_uris(text: &str, from: &str, to: &str) -> String {
+    text.replace(&format!("{}/", from), &format!("{}/", to))
+        .replace(&format!("\"{}\"", from), &format!("\"{}\"", to))
+}
+
+async fn close(
+    sink: &mut (impl SinkExt<Message, Error = axum::Error> + Unpin),
+    code: u16,
+    reason: &'static str,
+) -> CaraiResult<()> {
+    let frame = CloseFrame {
+        code,
+        reason: reason.into(),
+    };
+    sink.send(Message::Close(Some(frame))).await?;
+    Ok(())
+}
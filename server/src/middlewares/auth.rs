@@ -35,15 +35,23 @@ impl FromRequestParts<AppState> for Claims {
             .await
             .map_err(|e| AppError::new(StatusCode::UNAUTHORIZED, format!("{}", e)))?;
 
-        // Configure the TokenManager
-        let token_manager = TokenManager::new(state.config().jwt().secret().as_bytes(), None);
-
-        token_manager
-            .validate_access_token(bearer.token())
-            .map_err(|e| AppError::new(StatusCode::UNAUTHORIZED, format!("{}", e)))
+        validate_access_token(state, bearer.token())
     }
 }
 
+/// Validates an access token received outside the `Authorization` header.
+///
+/// Browsers cannot set headers on WebSocket handshakes, so those endpoints take the
+/// token from the query string and validate it here instead.
+pub fn validate_access_token(state: &AppState, token: &str) -> Result<Claims, AppError> {
+    // Configure the TokenManager
+    let token_manager = TokenManager::new(state.config().jwt().secret().as_bytes(), None);
+
+    token_manager
+        .validate_access_token(token)
+        .map_err(|e| AppError::new(StatusCode::UNAUTHORIZED, format!("{}", e)))
+}
+
 /// A wrapper type to signal that the contained `Claims` come from a refresh token.
 pub struct RefreshClaims(pub Claims);
 
@@ -0,0 +1,62 @@
+use chrono::{DateTime, Utc};
+use derive_more::Display;
+use serde::{Deserialize, Serialize};
+
+/// Name of the manifest stored at the root of exported archives.
+pub const ARCHIVE_MANIFEST_PATH: &str = "carai.json";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, Display)]
+pub enum ArchiveFormat {
+    #[default]
+    #[serde(rename = "zip")]
+    #[display("zip")]
+    Zip,
+    #[serde(rename = "tar.gz")]
+    #[display("tar.gz")]
+    TarGz,
+}
+
+impl ArchiveFormat {
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            ArchiveFormat::Zip => "application/zip",
+            ArchiveFormat::TarGz => "application/gzip",
+        }
+    }
+}
+
+/// Describes the project an archive holds, so importing it restores the workspace settings.
+#[derive(Debug, Clone, Serialize, Deserialize, Display)]
+#[display("ArchiveManifest: {{ name: {}, language: {:?} }}", name, language)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveManifest {
+    pub name: String,
+    #[serde(default)]
+    pub language: Option<String>,
+    /// The file that runs the project, when the language has a conventional one.
+    #[serde(default)]
+    pub entrypoint: Option<String>,
+    #[serde(default)]
+    pub exported_at: Option<DateTime<Utc>>,
+}
+
+impl ArchiveManifest {
+    pub fn new(name: String, language: Option<String>, entrypoint: Option<String>) -> Self {
+        Self {
+            name,
+            language,
+            entrypoint,
+            exported_at: Some(Utc::now()),
+        }
+    }
+}
+
+/// The files read from an imported archive.
+#[derive(Debug, Default)]
+pub struct UnpackedArchive {
+    pub manifest: Option<ArchiveManifest>,
+    /// Paths and contents of the text files, in archive order.
+    pub files: Vec<(String, String)>,
+    /// Paths of the binary files left out under the `skip` policy.
+    pub skipped: Vec<String>,
+}
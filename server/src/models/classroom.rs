@@ -0,0 +1,269 @@
+use chrono::{DateTime, Utc};
+use derive_more::Display;
+use rand::{distributions::Alphanumeric, Rng};
+use serde::{Deserialize, Serialize};
+use sqlx::{prelude::FromRow, types::Json};
+use uuid::Uuid;
+
+use super::{CaseResult, ExecutionFile, JudgeSpec, TemplateFile, Verdict};
+
+const JOIN_CODE_LENGTH: usize = 8;
+
+/// Access level within a classroom; variants are ordered from least to most privileged.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, sqlx::Type, Display,
+)]
+#[sqlx(type_name = "classroom_role", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum ClassroomRole {
+    #[display("student")]
+    Student,
+    #[display("instructor")]
+    Instructor,
+}
+
+/// A course whose instructors publish assignments for its students to submit.
+#[derive(Debug, Serialize, FromRow, Display)]
+#[display(
+    "Classroom: {{ id: {}, slug: {}, name: {}, created_at: {} }}",
+    id,
+    slug,
+    name,
+    created_at
+)]
+#[serde(rename_all = "camelCase")]
+pub struct Classroom {
+    pub id: Uuid,
+    pub slug: String,
+    pub name: String,
+    /// Lets students enroll themselves; only shown to instructors.
+    pub join_code: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Classroom {
+    pub fn new(slug: impl Into<String>, name: impl Into<String>) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            slug: slug.into(),
+            name: name.into(),
+            join_code: generate_join_code(),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, FromRow, Display)]
+#[display(
+    "ClassroomMember: {{ classroom_id: {}, user_id: {}, role: {} }}",
+    classroom_id,
+    user_id,
+    role
+)]
+#[serde(rename_all = "camelCase")]
+pub struct ClassroomMember {
+    pub classroom_id: Uuid,
+    pub user_id: Uuid,
+    pub role: ClassroomRole,
+    pub created_at: DateTime<Utc>,
+}
+
+impl ClassroomMember {
+    pub fn new(classroom_id: Uuid, user_id: Uuid, role: ClassroomRole) -> Self {
+        Self {
+            classroom_id,
+            user_id,
+            role,
+            created_at: Utc::now(),
+        }
+    }
+}
+
+/// A member joined with their user profile, as listed by the roster endpoint.
+#[derive(Debug, Serialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct ClassroomMemberProfile {
+    pub user_id: Uuid,
+    pub username: String,
+    pub avatar_url: Option<String>,
+    pub role: ClassroomRole,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A classroom as seen by one of its members.
+#[derive(Debug, Serialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct ClassroomMembership {
+    pub id: Uuid,
+    pub slug: String,
+    pub name: String,
+    pub role: ClassroomRole,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Work set for a classroom, graded against test cases its students never see.
+#[derive(Debug, Serialize, FromRow, Display)]
+#[display(
+    "Assignment: {{ id: {}, classroom_id: {}, title: {}, deadline: {} }}",
+    id,
+    classroom_id,
+    title,
+    deadline
+)]
+#[serde(rename_all = "camelCase")]
+pub struct Assignment {
+    pub id: Uuid,
+    pub classroom_id: Uuid,
+    pub title: String,
+    pub description: String,
+    pub language: String,
+    /// The file submissions are run from; defaults to the first file.
+    pub entrypoint: Option<String>,
+    pub starter_files: Json<Vec<TemplateFile>>,
+    pub judge: Json<JudgeSpec>,
+    pub deadline: DateTime<Utc>,
+    pub author_id: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Assignment {
+    pub fn new(
+        classroom_id: Uuid,
+        title: impl Into<String>,
+        language: impl Into<String>,
+        judge: JudgeSpec,
+        deadline: DateTime<Utc>,
+        author_id: Uuid,
+    ) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            classroom_id,
+            title: title.into(),
+            description: String::new(),
+            language: language.into(),
+            entrypoint: None,
+            starter_files: Json(Vec::new()),
+            judge: Json(judge),
+            deadline,
+            author_id: Some(author_id),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.deadline <= Utc::now()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type, Display)]
+#[sqlx(type_name = "submission_status", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum SubmissionStatus {
+    #[display("pending")]
+    Pending,
+    #[display("graded")]
+    Graded,
+    #[display("failed")]
+    Failed,
+}
+
+/// A snapshot of a student's workspace handed in for an assignment, and its grade.
+#[derive(Debug, Serialize, FromRow, Display)]
+#[display(
+    "Submission: {{ id: {}, assignment_id: {}, user_id: {}, status: {} }}",
+    id,
+    assignment_id,
+    user_id,
+    status
+)]
+#[serde(rename_all = "camelCase")]
+pub struct Submission {
+    pub id: Uuid,
+    pub assignment_id: Uuid,
+    pub user_id: Uuid,
+    pub workspace_id: Option<Uuid>,
+    /// The judged run grading the submission, until it is pruned.
+    pub execution_id: Option<Uuid>,
+    pub files: Json<Vec<ExecutionFile>>,
+    pub status: SubmissionStatus,
+    pub passed_cases: i32,
+    pub total_cases: i32,
+    /// The percentage of test cases passed, once graded.
+    pub score: Option<f64>,
+    pub created_at: DateTime<Utc>,
+    pub graded_at: Option<DateTime<Utc>>,
+}
+
+impl Submission {
+    pub fn new(
+        assignment: &Assignment,
+        user_id: Uuid,
+        workspace_id: Uuid,
+        execution_id: Uuid,
+        files: Vec<ExecutionFile>,
+    ) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            assignment_id: assignment.id,
+            user_id,
+            workspace_id: Some(workspace_id),
+            execution_id: Some(execution_id),
+            files: Json(files),
+            status: SubmissionStatus::Pending,
+            passed_cases: 0,
+            total_cases: assignment.judge.cases.len() as i32,
+            score: None,
+            created_at: Utc::now(),
+            graded_at: None,
+        }
+    }
+}
+
+/// The grade of a judged run: how many cases passed and the resulting score.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Grade {
+    pub passed_cases: i32,
+    pub score: f64,
+}
+
+impl Grade {
+    /// Grades the case results of a run; a run that never got past the build scores zero.
+    pub fn from_results(results: Option<&[CaseResult]>, total_cases: i32) -> Self {
+        let passed_cases = results.map_or(0, |results| {
+            results
+                .iter()
+                .filter(|result| result.verdict == Verdict::Accepted)
+                .count() as i32
+        });
+        // Assignments always have at least one case, the guard only keeps this finite
+        let score = 100.0 * f64::from(passed_cases) / f64::from(total_cases.max(1));
+        Self {
+            passed_cases,
+            score,
+        }
+    }
+}
+
+/// A student's standing on one assignment, as shown in the gradebook.
+#[derive(Debug, Serialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct GradebookEntry {
+    pub assignment_id: Uuid,
+    pub user_id: Uuid,
+    /// The best score over the student's graded submissions.
+    pub best_score: Option<f64>,
+    pub submissions: i64,
+    pub last_submitted_at: DateTime<Utc>,
+}
+
+fn generate_join_code() -> String {
+    rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(JOIN_CODE_LENGTH)
+        .map(char::from)
+        .collect()
+}
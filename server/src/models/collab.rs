@@ -0,0 +1,51 @@
+use chrono::{DateTime, Utc};
+use derive_more::Display;
+use serde::Serialize;
+use sqlx::prelude::FromRow;
+use uuid::Uuid;
+
+#[derive(Debug, Serialize, FromRow, Display)]
+#[display(
+    "CollabSnapshot: {{ file_id: {}, seq: {}, updated_at: {} }}",
+    file_id,
+    seq,
+    updated_at
+)]
+#[serde(rename_all = "camelCase")]
+pub struct CollabSnapshot {
+    pub file_id: Uuid,
+    /// The last update sequence number merged into `data`.
+    pub seq: i64,
+    #[serde(skip_serializing)]
+    pub data: Vec<u8>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl CollabSnapshot {
+    pub fn new(file_id: Uuid, seq: i64, data: Vec<u8>) -> Self {
+        Self {
+            file_id,
+            seq,
+            data,
+            updated_at: Utc::now(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, FromRow, Display)]
+#[display(
+    "CollabUpdate: {{ seq: {}, file_id: {}, author_id: {:?}, created_at: {} }}",
+    seq,
+    file_id,
+    author_id,
+    created_at
+)]
+#[serde(rename_all = "camelCase")]
+pub struct CollabUpdate {
+    pub seq: i64,
+    pub file_id: Uuid,
+    pub author_id: Option<Uuid>,
+    #[serde(skip_serializing)]
+    pub data: Vec<u8>,
+    pub created_at: DateTime<Utc>,
+}
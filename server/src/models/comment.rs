@@ -0,0 +1,165 @@
+use chrono::{DateTime, Utc};
+use derive_more::Display;
+use serde::Serialize;
+use sqlx::prelude::FromRow;
+use uuid::Uuid;
+
+/// A discussion anchored to a range of lines in a workspace file.
+///
+/// Lines are one-based and inclusive. The range follows the file as it is edited;
+/// when every anchored line is deleted the thread is marked `outdated` and
+/// collapses onto the line where the code used to be.
+#[derive(Debug, Serialize, FromRow, Display)]
+#[display(
+    "CommentThread: {{ id: {}, file_id: {}, lines: {}-{}, outdated: {}, resolved_at: {:?} }}",
+    id,
+    file_id,
+    start_line,
+    end_line,
+    outdated,
+    resolved_at
+)]
+#[serde(rename_all = "camelCase")]
+pub struct CommentThread {
+    pub id: Uuid,
+    pub workspace_id: Uuid,
+    pub file_id: Uuid,
+    pub author_id: Option<Uuid>,
+    pub start_line: i32,
+    pub end_line: i32,
+    pub outdated: bool,
+    pub resolved_at: Option<DateTime<Utc>>,
+    pub resolved_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl CommentThread {
+    pub fn new(
+        workspace_id: Uuid,
+        file_id: Uuid,
+        author_id: Uuid,
+        start_line: i32,
+        end_line: i32,
+    ) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            workspace_id,
+            file_id,
+            author_id: Some(author_id),
+            start_line,
+            end_line,
+            outdated: false,
+            resolved_at: None,
+            resolved_by: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, FromRow, Display)]
+#[display(
+    "Comment: {{ id: {}, thread_id: {}, author_id: {:?}, created_at: {} }}",
+    id,
+    thread_id,
+    author_id,
+    created_at
+)]
+#[serde(rename_all = "camelCase")]
+pub struct Comment {
+    pub id: Uuid,
+    pub thread_id: Uuid,
+    pub author_id: Option<Uuid>,
+    pub body: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Comment {
+    pub fn new(thread_id: Uuid, author_id: Uuid, body: impl Into<String>) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            thread_id,
+            author_id: Some(author_id),
+            body: body.into(),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, FromRow, Display)]
+#[display(
+    "ChatMessage: {{ id: {}, workspace_id: {}, author_id: {:?}, created_at: {} }}",
+    id,
+    workspace_id,
+    author_id,
+    created_at
+)]
+#[serde(rename_all = "camelCase")]
+pub struct ChatMessage {
+    pub id: Uuid,
+    pub workspace_id: Uuid,
+    pub author_id: Option<Uuid>,
+    pub body: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl ChatMessage {
+    pub fn new(workspace_id: Uuid, author_id: Uuid, body: impl Into<String>) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            workspace_id,
+            author_id: Some(author_id),
+            body: body.into(),
+            created_at: Utc::now(),
+        }
+    }
+}
+
+/// Records that a user was `@mentioned` in a comment or chat message.
+#[derive(Debug, Serialize, FromRow, Display)]
+#[display(
+    "Mention: {{ id: {}, user_id: {}, workspace_id: {}, read_at: {:?} }}",
+    id,
+    user_id,
+    workspace_id,
+    read_at
+)]
+#[serde(rename_all = "camelCase")]
+pub struct Mention {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub workspace_id: Uuid,
+    pub author_id: Option<Uuid>,
+    pub comment_id: Option<Uuid>,
+    pub chat_message_id: Option<Uuid>,
+    pub read_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Extracts the distinct, lowercased usernames `@mentioned` in a text.
+pub fn mentioned_usernames(text: &str) -> Vec<String> {
+    let mut usernames: Vec<String> = Vec::new();
+    for (i, _) in text.match_indices('@') {
+        // An `@` inside a word is part of something else, e.g. an email address
+        if text[..i]
+            .chars()
+            .next_back()
+            .is_some_and(char::is_alphanumeric)
+        {
+            continue;
+        }
+        let username: String = text[i + 1..]
+            .chars()
+            .take_while(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'))
+            .collect::<String>()
+            .trim_end_matches('.')
+            .to_lowercase();
+        if !username.is_empty() && !usernames.contains(&username) {
+            usernames.push(username);
+        }
+    }
+    usernames
+}
@@ -0,0 +1,179 @@
+use chrono::{DateTime, Utc};
+use derive_more::Display;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::{prelude::FromRow, types::Json};
+use uuid::Uuid;
+
+use super::{CachedExecution, CaseResult, JudgeSpec};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type, Display)]
+#[sqlx(type_name = "execution_status", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum ExecutionStatus {
+    #[display("queued")]
+    Queued,
+    #[display("running")]
+    Running,
+    #[display("completed")]
+    Completed,
+    #[display("failed")]
+    Failed,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExecutionFile {
+    pub name: String,
+    pub content: String,
+}
+
+/// Output of the compile phase of a project built before it runs.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CompileOutput {
+    pub stdout: String,
+    pub stderr: String,
+    pub error: Option<String>,
+    pub exit_code: Option<i32>,
+    pub duration_ms: i64,
+}
+
+impl CompileOutput {
+    pub fn succeeded(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+impl ExecutionFile {
+    /// Computes a stable SHA-256 digest over the names and contents of a file set.
+    pub fn hash_all(files: &[ExecutionFile]) -> String {
+        let mut hasher = Sha256::new();
+        for file in files {
+            // Length prefixes keep ("ab", "c") and ("a", "bc") from colliding
+            for part in [&file.name, &file.content] {
+                hasher.update((part.len() as u64).to_be_bytes());
+                hasher.update(part.as_bytes());
+            }
+        }
+        hex::encode(hasher.finalize())
+    }
+}
+
+#[derive(Debug, Serialize, FromRow, Display)]
+#[display(
+    "Execution: {{ id: {}, user_id: {}, language: {}, status: {}, attempts: {}, created_at: {} }}",
+    id,
+    user_id,
+    language,
+    status,
+    attempts,
+    created_at
+)]
+#[serde(rename_all = "camelCase")]
+pub struct Execution {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    /// The workspace the run was started from, whose recording it shows up in.
+    pub workspace_id: Option<Uuid>,
+    /// The organization the run is billed to, that of its workspace.
+    pub organization_id: Option<Uuid>,
+    pub language: String,
+    pub files: Json<Vec<ExecutionFile>>,
+    pub files_hash: String,
+    pub stdin: Option<String>,
+    pub status: ExecutionStatus,
+    pub stdout: Option<String>,
+    pub stderr: Option<String>,
+    pub error: Option<String>,
+    pub exit_code: Option<i32>,
+    pub duration_ms: Option<i64>,
+    pub cached: bool,
+    pub compile: Option<Json<CompileOutput>>,
+    pub judge: Option<Json<JudgeSpec>>,
+    pub case_results: Option<Json<Vec<CaseResult>>>,
+    pub attempts: i32,
+    pub locked_until: Option<DateTime<Utc>>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub finished_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Execution {
+    pub fn new(
+        user_id: Uuid,
+        language: impl Into<String>,
+        files: Vec<ExecutionFile>,
+        stdin: Option<String>,
+    ) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            user_id,
+            workspace_id: None,
+            organization_id: None,
+            language: language.into(),
+            files_hash: ExecutionFile::hash_all(&files),
+            files: Json(files),
+            stdin,
+            status: ExecutionStatus::Queued,
+            stdout: None,
+            stderr: None,
+            error: None,
+            exit_code: None,
+            duration_ms: None,
+            cached: false,
+            compile: None,
+            judge: None,
+            case_results: None,
+            attempts: 0,
+            locked_until: None,
+            started_at: None,
+            finished_at: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    /// Completes the execution with a previously recorded result instead of running it.
+    pub fn complete_from_cache(&mut self, cached: CachedExecution) {
+        let now = Utc::now();
+        self.status = ExecutionStatus::Completed;
+        self.stdout = Some(cached.stdout);
+        self.stderr = Some(cached.stderr);
+        self.error = cached.error;
+        self.exit_code = cached.exit_code;
+        self.duration_ms = Some(cached.duration_ms);
+        self.compile = cached.compile;
+        self.cached = true;
+        self.started_at = Some(now);
+        self.finished_at = Some(now);
+    }
+
+    /// The file the sandbox runs first, which is always stored at the front.
+    pub fn entrypoint(&self) -> &str {
+        self.files.first().map_or("", |file| file.name.as_str())
+    }
+
+    /// Whether the project was built and the build failed, so it never ran.
+    pub fn failed_to_compile(&self) -> bool {
+        self.compile
+            .as_ref()
+            .is_some_and(|compile| !compile.succeeded())
+    }
+
+    /// Whether the program, or its failed build, ran to a real exit rather than timing
+    /// out or being stopped by the sandbox, so running it again would give the same result.
+    pub fn finished_cleanly(&self) -> bool {
+        match &self.compile {
+            Some(compile) if !compile.succeeded() => compile.exit_code.is_some(),
+            _ => self.exit_code.is_some(),
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        matches!(
+            self.status,
+            ExecutionStatus::Completed | ExecutionStatus::Failed
+        )
+    }
+}
@@ -0,0 +1,47 @@
+use chrono::{DateTime, Duration, Utc};
+use derive_more::Display;
+use serde::Serialize;
+use sqlx::{prelude::FromRow, types::Json};
+
+use super::{CompileOutput, Execution};
+
+#[derive(Debug, Serialize, FromRow, Display)]
+#[display(
+    "CachedExecution: {{ key: {}, language: {}, hits: {}, expires_at: {} }}",
+    key,
+    language,
+    hits,
+    expires_at
+)]
+#[serde(rename_all = "camelCase")]
+pub struct CachedExecution {
+    pub key: String,
+    pub language: String,
+    pub stdout: String,
+    pub stderr: String,
+    pub error: Option<String>,
+    pub exit_code: Option<i32>,
+    pub duration_ms: i64,
+    pub compile: Option<Json<CompileOutput>>,
+    pub hits: i64,
+    pub expires_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl CachedExecution {
+    pub fn new(key: impl Into<String>, execution: &Execution, ttl: Duration) -> Self {
+        Self {
+            key: key.into(),
+            language: execution.language.to_owned(),
+            stdout: execution.stdout.to_owned().unwrap_or_default(),
+            stderr: execution.stderr.to_owned().unwrap_or_default(),
+            error: execution.error.to_owned(),
+            exit_code: execution.exit_code,
+            duration_ms: execution.duration_ms.unwrap_or_default(),
+            compile: execution.compile.clone(),
+            hits: 0,
+            expires_at: Utc::now() + ttl,
+            created_at: Utc::now(),
+        }
+    }
+}
@@ -0,0 +1,74 @@
+use chrono::{DateTime, Utc};
+use derive_more::Display;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use sqlx::prelude::FromRow;
+use uuid::Uuid;
+
+#[derive(Debug, Serialize, FromRow, Display)]
+#[display(
+    "Blob: {{ hash: {}, size: {}, created_at: {} }}",
+    hash,
+    size,
+    created_at
+)]
+#[serde(rename_all = "camelCase")]
+pub struct Blob {
+    pub hash: String,
+    pub content: String,
+    pub size: i64,
+    pub created_at: DateTime<Utc>,
+}
+
+impl Blob {
+    pub fn new(content: impl Into<String>) -> Self {
+        let content = content.into();
+        Self {
+            hash: hex::encode(Sha256::digest(content.as_bytes())),
+            size: content.len() as i64,
+            content,
+            created_at: Utc::now(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, FromRow, Display)]
+#[display(
+    "FileRevision: {{ id: {}, file_id: {}, number: {}, path: {}, author_id: {:?}, created_at: {} }}",
+    id,
+    file_id,
+    number,
+    path,
+    author_id,
+    created_at
+)]
+#[serde(rename_all = "camelCase")]
+pub struct FileRevision {
+    pub id: Uuid,
+    pub file_id: Uuid,
+    pub number: i32,
+    pub path: String,
+    pub blob_hash: String,
+    pub author_id: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl FileRevision {
+    pub fn new(
+        file_id: Uuid,
+        number: i32,
+        path: impl Into<String>,
+        blob_hash: impl Into<String>,
+        author_id: Uuid,
+    ) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            file_id,
+            number,
+            path: path.into(),
+            blob_hash: blob_hash.into(),
+            author_id: Some(author_id),
+            created_at: Utc::now(),
+        }
+    }
+}
@@ -0,0 +1,74 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ComparisonMode {
+    #[default]
+    Exact,
+    Trimmed,
+    Float,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TestCase {
+    pub stdin: String,
+    pub expected_stdout: String,
+    pub time_limit_ms: Option<i64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JudgeSpec {
+    pub comparison: ComparisonMode,
+    pub tolerance: f64,
+    pub cases: Vec<TestCase>,
+    /// Hidden cases are graded without showing their input, expected output or what
+    /// the program printed for them, as for classroom assignments.
+    #[serde(default)]
+    pub hidden: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Verdict {
+    Accepted,
+    WrongAnswer,
+    RuntimeError,
+    TimeLimitExceeded,
+    MemoryLimitExceeded,
+    CompileError,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CaseResult {
+    pub verdict: Verdict,
+    pub stdout: String,
+    pub stderr: String,
+    pub error: Option<String>,
+    pub duration_ms: i64,
+    /// Unified diff of expected against actual output, present on wrong answers.
+    pub diff: Option<String>,
+}
+
+impl CaseResult {
+    /// Keeps only the verdict and timing, which is all that is shown of hidden cases.
+    pub fn redacted(self) -> Self {
+        Self {
+            verdict: self.verdict,
+            stdout: String::new(),
+            stderr: String::new(),
+            error: None,
+            duration_ms: self.duration_ms,
+            diff: None,
+        }
+    }
+}
+
+/// The overall verdict of a judged run: the first failing case, or accepted.
+pub fn overall_verdict(results: &[CaseResult]) -> Verdict {
+    results
+        .iter()
+        .map(|result| result.verdict)
+        .find(|verdict| *verdict != Verdict::Accepted)
+        .unwrap_or(Verdict::Accepted)
+}
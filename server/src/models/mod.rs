@@ -1,5 +1,41 @@
+mod archive;
+mod classroom;
+mod collab;
+mod comment;
+mod execution;
+mod execution_cache;
+mod file_revision;
+mod judge;
+mod notification;
+mod organization;
+mod search;
 mod session;
+mod session_event;
+mod snippet;
+mod usage;
 mod user;
+mod webhook;
+mod workspace;
+mod workspace_member;
+mod workspace_template;
 
+pub use archive::*;
+pub use classroom::*;
+pub use collab::*;
+pub use comment::*;
+pub use execution::*;
+pub use execution_cache::*;
+pub use file_revision::*;
+pub use judge::*;
+pub use notification::*;
+pub use organization::*;
+pub use search::*;
 pub use session::*;
+pub use session_event::*;
+pub use snippet::*;
+pub use usage::*;
 pub use user::*;
+pub use webhook::*;
+pub use workspace::*;
+pub use workspace_member::*;
+pub use workspace_template::*;
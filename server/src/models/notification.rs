@@ -0,0 +1,126 @@
+use chrono::{DateTime, Utc};
+use derive_more::Display;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sqlx::{prelude::FromRow, types::Json};
+use uuid::Uuid;
+
+use super::{Execution, ExecutionStatus, Mention, WorkspaceInvitation};
+
+/// What a notification is about; users can mute each category separately.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, sqlx::Type, Display)]
+#[sqlx(type_name = "notification_category", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationCategory {
+    #[display("invitation")]
+    Invitation,
+    #[display("mention")]
+    Mention,
+    #[display("security")]
+    Security,
+    #[display("run")]
+    Run,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, Display)]
+#[display(
+    "Notification: {{ id: {}, user_id: {}, category: {}, created_at: {} }}",
+    id,
+    user_id,
+    category,
+    created_at
+)]
+#[serde(rename_all = "camelCase")]
+pub struct Notification {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub category: NotificationCategory,
+    pub title: String,
+    pub body: String,
+    /// IDs of what the notification is about, for clients to link to.
+    pub data: Json<Value>,
+    pub read_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl Notification {
+    pub fn new(
+        user_id: Uuid,
+        category: NotificationCategory,
+        title: impl Into<String>,
+        body: impl Into<String>,
+        data: Value,
+    ) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            user_id,
+            category,
+            title: title.into(),
+            body: body.into(),
+            data: Json(data),
+            read_at: None,
+            created_at: Utc::now(),
+        }
+    }
+
+    /// Tells the invitee of a direct invitation about it; invite links have nobody to tell.
+    pub fn invitation(invitation: &WorkspaceInvitation) -> Option<Self> {
+        Some(Self::new(
+            invitation.invitee_id?,
+            NotificationCategory::Invitation,
+            "New invitation",
+            format!(
+                "You were invited to join a workspace as {}",
+                invitation.role
+            ),
+            json!({ "invitationId": invitation.id, "workspaceId": invitation.workspace_id }),
+        ))
+    }
+
+    pub fn mention(mention: &Mention) -> Self {
+        let place = if mention.comment_id.is_some() {
+            "a comment"
+        } else {
+            "the workspace chat"
+        };
+        Self::new(
+            mention.user_id,
+            NotificationCategory::Mention,
+            "New mention",
+            format!("You were mentioned in {}", place),
+            json!({
+                "mentionId": mention.id,
+                "workspaceId": mention.workspace_id,
+                "commentId": mention.comment_id,
+                "chatMessageId": mention.chat_message_id,
+            }),
+        )
+    }
+
+    /// Warns a user that they were signed out everywhere by an administrator.
+    pub fn sessions_revoked(user_id: Uuid) -> Self {
+        Self::new(
+            user_id,
+            NotificationCategory::Security,
+            "Signed out",
+            "An administrator revoked your sessions; sign in again to continue",
+            json!({}),
+        )
+    }
+
+    /// Tells a user that a run they queued has finished; `None` while it has not.
+    pub fn run_finished(execution: &Execution) -> Option<Self> {
+        let outcome = match execution.status {
+            ExecutionStatus::Completed => "completed",
+            ExecutionStatus::Failed => "failed",
+            ExecutionStatus::Queued | ExecutionStatus::Running => return None,
+        };
+        Some(Self::new(
+            execution.user_id,
+            NotificationCategory::Run,
+            format!("Run {}", outcome),
+            format!("Your {} run {}", execution.language, outcome),
+            json!({ "executionId": execution.id, "workspaceId": execution.workspace_id }),
+        ))
+    }
+}
@@ -0,0 +1,120 @@
+use chrono::{DateTime, Utc};
+use derive_more::Display;
+use serde::{Deserialize, Serialize};
+use sqlx::prelude::FromRow;
+use uuid::Uuid;
+
+use super::WorkspaceRole;
+use crate::utils::QuotaLimits;
+
+/// Access level within an organization; variants are ordered from least to most privileged.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, sqlx::Type, Display,
+)]
+#[sqlx(type_name = "organization_role", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum OrganizationRole {
+    #[display("member")]
+    Member,
+    #[display("admin")]
+    Admin,
+    #[display("owner")]
+    Owner,
+}
+
+impl OrganizationRole {
+    /// The role this grants on the organization's workspaces: members edit them, and
+    /// admins manage them as if they owned them.
+    pub fn workspace_role(self) -> WorkspaceRole {
+        match self {
+            OrganizationRole::Member => WorkspaceRole::Editor,
+            OrganizationRole::Admin | OrganizationRole::Owner => WorkspaceRole::Owner,
+        }
+    }
+}
+
+/// A group of users sharing workspaces, templates and execution quotas.
+#[derive(Debug, Serialize, FromRow, Display)]
+#[display(
+    "Organization: {{ id: {}, slug: {}, name: {}, created_at: {} }}",
+    id,
+    slug,
+    name,
+    created_at
+)]
+#[serde(rename_all = "camelCase")]
+pub struct Organization {
+    pub id: Uuid,
+    pub slug: String,
+    pub name: String,
+    pub executions_per_hour: i64,
+    pub cpu_seconds_per_day: i64,
+    pub concurrent_runs: i64,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Organization {
+    pub fn new(slug: impl Into<String>, name: impl Into<String>, limits: &QuotaLimits) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            slug: slug.into(),
+            name: name.into(),
+            executions_per_hour: *limits.executions_per_hour(),
+            cpu_seconds_per_day: *limits.cpu_seconds_per_day(),
+            concurrent_runs: *limits.concurrent_runs(),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, FromRow, Display)]
+#[display(
+    "OrganizationMember: {{ organization_id: {}, user_id: {}, role: {} }}",
+    organization_id,
+    user_id,
+    role
+)]
+#[serde(rename_all = "camelCase")]
+pub struct OrganizationMember {
+    pub organization_id: Uuid,
+    pub user_id: Uuid,
+    pub role: OrganizationRole,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl OrganizationMember {
+    pub fn new(organization_id: Uuid, user_id: Uuid, role: OrganizationRole) -> Self {
+        Self {
+            organization_id,
+            user_id,
+            role,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+}
+
+/// A member joined with their user profile, as listed by the members endpoint.
+#[derive(Debug, Serialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct OrganizationMemberProfile {
+    pub user_id: Uuid,
+    pub username: String,
+    pub avatar_url: Option<String>,
+    pub role: OrganizationRole,
+    pub created_at: DateTime<Utc>,
+}
+
+/// An organization as seen by one of its members.
+#[derive(Debug, Serialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct OrganizationMembership {
+    pub id: Uuid,
+    pub slug: String,
+    pub name: String,
+    pub role: OrganizationRole,
+    pub created_at: DateTime<Utc>,
+}
@@ -0,0 +1,86 @@
+use serde::Serialize;
+use sqlx::prelude::FromRow;
+use uuid::Uuid;
+
+/// A file matching a code search, taken from either a workspace or a snippet.
+#[derive(Debug, Serialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchHit {
+    pub workspace_id: Option<Uuid>,
+    pub snippet_slug: Option<String>,
+    /// The name of the workspace or the title of the snippet.
+    pub title: String,
+    pub owner_id: Uuid,
+    pub owner_username: String,
+    pub language: Option<String>,
+    pub path: String,
+    pub content: String,
+    pub rank: f32,
+}
+
+/// Narrows a code search down; unset fields match everything the caller can access.
+#[derive(Debug, Default)]
+pub struct SearchFilter {
+    pub language: Option<String>,
+    pub workspace_id: Option<Uuid>,
+    pub owner_id: Option<Uuid>,
+}
+
+/// A search string in the forms the database matches it in.
+#[derive(Debug)]
+pub struct SearchTerms {
+    /// The search as typed, parsed as a web search into words.
+    pub query: String,
+    /// A `LIKE` pattern finding the search anywhere in a text.
+    pub pattern: String,
+    /// The pattern as it appears inside JSON strings, for matching encoded snippet files.
+    pub json_pattern: String,
+}
+
+impl SearchTerms {
+    pub fn new(query: &str) -> Self {
+        let encoded = serde_json::to_string(query).unwrap_or_default();
+        let encoded = encoded
+            .strip_prefix('"')
+            .and_then(|encoded| encoded.strip_suffix('"'))
+            .unwrap_or(query);
+        Self {
+            query: query.to_owned(),
+            pattern: like_pattern(query),
+            json_pattern: like_pattern(encoded),
+        }
+    }
+
+    /// The words to highlight, leaving out excluded words and the `or` operator.
+    pub fn words(&self) -> Vec<String> {
+        let mut words: Vec<String> = self
+            .query
+            .split_whitespace()
+            .filter(|word| !word.starts_with('-') && !word.eq_ignore_ascii_case("or"))
+            .map(|word| word.trim_matches('"').to_ascii_lowercase())
+            .filter(|word| !word.is_empty())
+            .collect();
+        words.sort();
+        words.dedup();
+        words
+    }
+}
+
+fn like_pattern(text: &str) -> String {
+    let escaped = text
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_");
+    format!("%{}%", escaped)
+}
+
+/// A line of a hit containing the searched terms.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MatchedLine {
+    /// One-based line number.
+    pub number: usize,
+    pub text: String,
+    /// Character ranges of the terms within `text`, as `[start, end)` pairs.
+    pub highlights: Vec<(usize, usize)>,
+}
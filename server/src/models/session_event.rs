@@ -0,0 +1,131 @@
+use chrono::{DateTime, Utc};
+use derive_more::Display;
+use serde::{Deserialize, Serialize};
+use sqlx::prelude::FromRow;
+use uuid::Uuid;
+
+use super::{Execution, ExecutionStatus, FileRevision};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type, Display)]
+#[sqlx(type_name = "session_event_kind", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum SessionEventKind {
+    /// A Yjs update from a collaborator.
+    #[display("edit")]
+    Edit,
+    /// A merged Yjs document covering every edit of the file up to its `seq`.
+    #[display("snapshot")]
+    Snapshot,
+    /// A new revision saved through the files API.
+    #[display("save")]
+    Save,
+    #[display("run_queued")]
+    RunQueued,
+    #[display("run_completed")]
+    RunCompleted,
+    #[display("run_failed")]
+    RunFailed,
+}
+
+/// Something that happened in a workspace, recorded so editing sessions can be replayed.
+#[derive(Debug, Serialize, FromRow, Display)]
+#[display(
+    "SessionEvent: {{ id: {}, workspace_id: {}, file_id: {:?}, kind: {}, recorded_at: {} }}",
+    id,
+    workspace_id,
+    file_id,
+    kind,
+    recorded_at
+)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionEvent {
+    /// Assigned by the database, so it orders events recorded within the same instant.
+    pub id: i64,
+    pub workspace_id: Uuid,
+    pub file_id: Option<Uuid>,
+    pub user_id: Option<Uuid>,
+    pub kind: SessionEventKind,
+    /// The collab sequence number of edits and snapshots, or the revision number of saves.
+    pub seq: Option<i64>,
+    /// The Yjs update of an edit or the merged document of a snapshot.
+    #[serde(skip_serializing)]
+    pub data: Option<Vec<u8>>,
+    pub execution_id: Option<Uuid>,
+    pub recorded_at: DateTime<Utc>,
+}
+
+impl SessionEvent {
+    pub fn edit(workspace_id: Uuid, file_id: Uuid, user_id: Uuid, seq: i64, data: Vec<u8>) -> Self {
+        let mut event = Self::new(workspace_id, Some(file_id), user_id, SessionEventKind::Edit);
+        event.seq = Some(seq);
+        event.data = Some(data);
+        event
+    }
+
+    pub fn snapshot(
+        workspace_id: Uuid,
+        file_id: Uuid,
+        user_id: Uuid,
+        seq: i64,
+        data: Vec<u8>,
+    ) -> Self {
+        let mut event = Self::new(
+            workspace_id,
+            Some(file_id),
+            user_id,
+            SessionEventKind::Snapshot,
+        );
+        event.seq = Some(seq);
+        event.data = Some(data);
+        event
+    }
+
+    pub fn save(workspace_id: Uuid, file_id: Uuid, user_id: Uuid, revision: i32) -> Self {
+        let mut event = Self::new(workspace_id, Some(file_id), user_id, SessionEventKind::Save);
+        event.seq = Some(revision.into());
+        event
+    }
+
+    /// Records a run started from a workspace as it is queued or once it finishes.
+    pub fn run(execution: &Execution) -> Option<Self> {
+        let kind = match execution.status {
+            ExecutionStatus::Queued => SessionEventKind::RunQueued,
+            ExecutionStatus::Completed => SessionEventKind::RunCompleted,
+            ExecutionStatus::Failed => SessionEventKind::RunFailed,
+            ExecutionStatus::Running => return None,
+        };
+        let mut event = Self::new(execution.workspace_id?, None, execution.user_id, kind);
+        event.execution_id = Some(execution.id);
+        Some(event)
+    }
+
+    fn new(
+        workspace_id: Uuid,
+        file_id: Option<Uuid>,
+        user_id: Uuid,
+        kind: SessionEventKind,
+    ) -> Self {
+        Self {
+            id: 0,
+            workspace_id,
+            file_id,
+            user_id: Some(user_id),
+            kind,
+            seq: None,
+            data: None,
+            execution_id: None,
+            recorded_at: Utc::now(),
+        }
+    }
+}
+
+/// A file as of some moment, assembled from the recording.
+#[derive(Debug)]
+pub struct DocumentState {
+    /// The revision saved last, with its content.
+    pub revision: Option<(FileRevision, String)>,
+    /// The most complete collaborative snapshot recorded so far.
+    pub snapshot: Option<SessionEvent>,
+    /// Edits recorded since, in sequence order.
+    pub edits: Vec<SessionEvent>,
+}
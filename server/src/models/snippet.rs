@@ -0,0 +1,93 @@
+use chrono::{DateTime, Utc};
+use derive_more::Display;
+use rand::{distributions::Alphanumeric, Rng};
+use serde::{Deserialize, Serialize};
+use sqlx::{prelude::FromRow, types::Json};
+use uuid::Uuid;
+
+/// Length of generated slugs; 62^12 possibilities keep them unguessable.
+const SLUG_LENGTH: usize = 12;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type, Display)]
+#[sqlx(type_name = "snippet_visibility", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum SnippetVisibility {
+    #[display("private")]
+    Private,
+    #[display("unlisted")]
+    Unlisted,
+    #[display("public")]
+    Public,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SnippetFile {
+    pub name: String,
+    pub content: String,
+}
+
+#[derive(Debug, Serialize, FromRow, Display)]
+#[display(
+    "Snippet: {{ id: {}, owner_id: {}, slug: {}, visibility: {}, expires_at: {:?}, created_at: {} }}",
+    id,
+    owner_id,
+    slug,
+    visibility,
+    expires_at,
+    created_at
+)]
+#[serde(rename_all = "camelCase")]
+pub struct Snippet {
+    pub id: Uuid,
+    pub owner_id: Uuid,
+    pub slug: String,
+    pub title: String,
+    pub language: Option<String>,
+    pub files: Json<Vec<SnippetFile>>,
+    pub visibility: SnippetVisibility,
+    #[serde(skip_serializing)]
+    pub password_hash: Option<String>,
+    pub expires_at: Option<DateTime<Utc>>,
+    /// How many workspaces were forked from the snippet.
+    pub fork_count: i32,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Snippet {
+    pub fn new(
+        owner_id: Uuid,
+        title: impl Into<String>,
+        language: Option<String>,
+        files: Vec<SnippetFile>,
+        visibility: SnippetVisibility,
+    ) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            owner_id,
+            slug: generate_slug(),
+            title: title.into(),
+            language,
+            files: Json(files),
+            visibility,
+            password_hash: None,
+            expires_at: None,
+            fork_count: 0,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.expires_at
+            .is_some_and(|expires_at| expires_at <= Utc::now())
+    }
+}
+
+fn generate_slug() -> String {
+    rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(SLUG_LENGTH)
+        .map(char::from)
+        .collect()
+}
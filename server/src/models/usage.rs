@@ -0,0 +1,90 @@
+use chrono::{DateTime, Utc};
+use derive_more::Display;
+use serde::{Deserialize, Serialize};
+use sqlx::prelude::FromRow;
+use uuid::Uuid;
+
+#[derive(Debug, Serialize, FromRow, Display)]
+#[display(
+    "ExecutionUsage: {{ id: {}, user_id: {}, execution_id: {:?}, language: {}, cpu_ms: {}, created_at: {} }}",
+    id,
+    user_id,
+    execution_id,
+    language,
+    cpu_ms,
+    created_at
+)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecutionUsage {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub execution_id: Option<Uuid>,
+    /// The organization whose quotas the usage counts against, instead of the user's.
+    pub organization_id: Option<Uuid>,
+    pub language: String,
+    pub cpu_ms: i64,
+    pub created_at: DateTime<Utc>,
+}
+
+impl ExecutionUsage {
+    pub fn new(
+        user_id: Uuid,
+        execution_id: Option<Uuid>,
+        language: impl Into<String>,
+        cpu_ms: i64,
+    ) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            user_id,
+            execution_id,
+            organization_id: None,
+            language: language.into(),
+            cpu_ms,
+            created_at: Utc::now(),
+        }
+    }
+}
+
+#[derive(Debug, FromRow)]
+pub struct UsageTotals {
+    pub executions_since: i64,
+    pub oldest_execution_at: Option<DateTime<Utc>>,
+    pub cpu_ms_since: i64,
+    pub active_runs: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct UserUsageReport {
+    pub user_id: Uuid,
+    pub username: String,
+    pub executions: i64,
+    pub cpu_ms: i64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Display)]
+#[serde(rename_all = "snake_case")]
+pub enum QuotaKind {
+    #[display("executions_per_hour")]
+    ExecutionsPerHour,
+    #[display("cpu_seconds_per_day")]
+    CpuSecondsPerDay,
+    #[display("concurrent_runs")]
+    ConcurrentRuns,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuotaUsage {
+    pub quota: QuotaKind,
+    pub used: i64,
+    pub limit: i64,
+    /// `None` when the quota frees up as soon as a running job finishes.
+    pub resets_at: Option<DateTime<Utc>>,
+}
+
+impl QuotaUsage {
+    pub fn is_exhausted(&self) -> bool {
+        self.used >= self.limit
+    }
+}
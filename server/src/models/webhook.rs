@@ -0,0 +1,213 @@
+use chrono::{DateTime, Utc};
+use derive_more::Display;
+use rand::{distributions::Alphanumeric, Rng};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sqlx::{prelude::FromRow, types::Json};
+use uuid::Uuid;
+
+use super::{Submission, Workspace, WorkspaceFile};
+
+const SECRET_LENGTH: usize = 32;
+
+/// Something that happened, which webhooks subscribe to by name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, sqlx::Type, Display)]
+#[sqlx(type_name = "webhook_event")]
+pub enum WebhookEvent {
+    #[display("workspace.created")]
+    #[serde(rename = "workspace.created")]
+    #[sqlx(rename = "workspace.created")]
+    WorkspaceCreated,
+    #[display("workspace.updated")]
+    #[serde(rename = "workspace.updated")]
+    #[sqlx(rename = "workspace.updated")]
+    WorkspaceUpdated,
+    #[display("workspace.deleted")]
+    #[serde(rename = "workspace.deleted")]
+    #[sqlx(rename = "workspace.deleted")]
+    WorkspaceDeleted,
+    #[display("file.created")]
+    #[serde(rename = "file.created")]
+    #[sqlx(rename = "file.created")]
+    FileCreated,
+    #[display("file.updated")]
+    #[serde(rename = "file.updated")]
+    #[sqlx(rename = "file.updated")]
+    FileUpdated,
+    #[display("file.deleted")]
+    #[serde(rename = "file.deleted")]
+    #[sqlx(rename = "file.deleted")]
+    FileDeleted,
+    #[display("submission.created")]
+    #[serde(rename = "submission.created")]
+    #[sqlx(rename = "submission.created")]
+    SubmissionCreated,
+    #[display("submission.graded")]
+    #[serde(rename = "submission.graded")]
+    #[sqlx(rename = "submission.graded")]
+    SubmissionGraded,
+}
+
+impl WebhookEvent {
+    /// Wraps the data of an event into the body delivered to webhooks.
+    pub fn payload(self, data: Value) -> Value {
+        json!({ "event": self, "createdAt": Utc::now(), "data": data })
+    }
+
+    pub fn workspace(workspace: &Workspace) -> Value {
+        json!({ "workspace": workspace })
+    }
+
+    /// Describes a file without its content, which receivers can fetch if they need it.
+    pub fn file(file: &WorkspaceFile) -> Value {
+        json!({
+            "file": {
+                "id": file.id,
+                "workspaceId": file.workspace_id,
+                "path": file.path,
+                "updatedAt": file.updated_at,
+            }
+        })
+    }
+
+    /// Describes a submission without the submitted files.
+    pub fn submission(submission: &Submission) -> Value {
+        json!({
+            "submission": {
+                "id": submission.id,
+                "assignmentId": submission.assignment_id,
+                "userId": submission.user_id,
+                "workspaceId": submission.workspace_id,
+                "status": submission.status,
+                "passedCases": submission.passed_cases,
+                "totalCases": submission.total_cases,
+                "score": submission.score,
+                "createdAt": submission.created_at,
+                "gradedAt": submission.graded_at,
+            }
+        })
+    }
+}
+
+/// An endpoint that events are delivered to, owned by a user or an organization.
+#[derive(Debug, Clone, Serialize, FromRow, Display)]
+#[display(
+    "Webhook: {{ id: {}, url: {}, active: {}, created_at: {} }}",
+    id,
+    url,
+    active,
+    created_at
+)]
+#[serde(rename_all = "camelCase")]
+pub struct Webhook {
+    pub id: Uuid,
+    pub user_id: Option<Uuid>,
+    pub organization_id: Option<Uuid>,
+    pub url: String,
+    /// Key of the signature sent with every delivery; only shown on creation.
+    #[serde(skip)]
+    pub secret: String,
+    pub events: Json<Vec<WebhookEvent>>,
+    pub active: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Webhook {
+    pub fn new(
+        user_id: Option<Uuid>,
+        organization_id: Option<Uuid>,
+        url: impl Into<String>,
+        secret: Option<String>,
+        events: Vec<WebhookEvent>,
+    ) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            user_id,
+            organization_id,
+            url: url.into(),
+            secret: secret.unwrap_or_else(generate_secret),
+            events: Json(events),
+            active: true,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type, Display)]
+#[sqlx(type_name = "webhook_delivery_status", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookDeliveryStatus {
+    #[display("pending")]
+    Pending,
+    #[display("delivered")]
+    Delivered,
+    #[display("failed")]
+    Failed,
+}
+
+/// One event queued for a webhook, kept afterwards as its delivery log.
+#[derive(Debug, Clone, Serialize, FromRow, Display)]
+#[display(
+    "WebhookDelivery: {{ id: {}, webhook_id: {}, event: {}, status: {}, attempts: {} }}",
+    id,
+    webhook_id,
+    event,
+    status,
+    attempts
+)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookDelivery {
+    pub id: Uuid,
+    pub webhook_id: Uuid,
+    pub event: WebhookEvent,
+    pub payload: Json<Value>,
+    pub status: WebhookDeliveryStatus,
+    pub attempts: i32,
+    pub next_attempt_at: DateTime<Utc>,
+    pub locked_until: Option<DateTime<Utc>>,
+    /// The HTTP status the receiver answered the latest attempt with.
+    pub response_status: Option<i32>,
+    pub response_body: Option<String>,
+    /// Why the latest attempt failed before getting a response, or got a bad one.
+    pub error: Option<String>,
+    pub duration_ms: Option<i64>,
+    /// The delivery this one manually repeats.
+    pub redelivery_of: Option<Uuid>,
+    pub finished_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl WebhookDelivery {
+    /// Queues the event of an earlier delivery again, with the same payload.
+    pub fn redeliver(delivery: &WebhookDelivery) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            webhook_id: delivery.webhook_id,
+            event: delivery.event,
+            payload: delivery.payload.clone(),
+            status: WebhookDeliveryStatus::Pending,
+            attempts: 0,
+            next_attempt_at: Utc::now(),
+            locked_until: None,
+            response_status: None,
+            response_body: None,
+            error: None,
+            duration_ms: None,
+            redelivery_of: Some(delivery.id),
+            finished_at: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+}
+
+fn generate_secret() -> String {
+    rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(SECRET_LENGTH)
+        .map(char::from)
+        .collect()
+}
@@ -0,0 +1,83 @@
+use chrono::{DateTime, Utc};
+use derive_more::Display;
+use serde::Serialize;
+use sqlx::prelude::FromRow;
+use uuid::Uuid;
+
+#[derive(Debug, Serialize, FromRow, Display)]
+#[display(
+    "Workspace: {{ id: {}, owner_id: {}, name: {}, language: {:?}, created_at: {}, updated_at: {} }}",
+    id,
+    owner_id,
+    name,
+    language,
+    created_at,
+    updated_at
+)]
+#[serde(rename_all = "camelCase")]
+pub struct Workspace {
+    pub id: Uuid,
+    pub owner_id: Uuid,
+    pub name: String,
+    pub language: Option<String>,
+    /// The organization the workspace belongs to, whose members share it.
+    pub organization_id: Option<Uuid>,
+    pub forked_from_workspace_id: Option<Uuid>,
+    pub forked_from_snippet_id: Option<Uuid>,
+    /// The template the workspace was created from.
+    pub template_id: Option<Uuid>,
+    /// How many times the workspace was forked.
+    pub fork_count: i32,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Workspace {
+    pub fn new(owner_id: Uuid, name: impl Into<String>, language: Option<String>) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            owner_id,
+            name: name.into(),
+            language,
+            organization_id: None,
+            forked_from_workspace_id: None,
+            forked_from_snippet_id: None,
+            template_id: None,
+            fork_count: 0,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, FromRow, Display)]
+#[display(
+    "WorkspaceFile: {{ id: {}, workspace_id: {}, path: {}, created_at: {}, updated_at: {} }}",
+    id,
+    workspace_id,
+    path,
+    created_at,
+    updated_at
+)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceFile {
+    pub id: Uuid,
+    pub workspace_id: Uuid,
+    pub path: String,
+    pub content: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl WorkspaceFile {
+    pub fn new(workspace_id: Uuid, path: impl Into<String>, content: impl Into<String>) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            workspace_id,
+            path: path.into(),
+            content: content.into(),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+}
@@ -0,0 +1,170 @@
+use chrono::{DateTime, Utc};
+use derive_more::Display;
+use rand::{distributions::Alphanumeric, Rng};
+use serde::{Deserialize, Serialize};
+use sqlx::prelude::FromRow;
+use uuid::Uuid;
+
+/// Length of invite link tokens, long enough that they cannot be guessed.
+const TOKEN_LENGTH: usize = 32;
+
+/// Access level within a workspace; variants are ordered from least to most privileged.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, sqlx::Type, Display,
+)]
+#[sqlx(type_name = "workspace_role", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum WorkspaceRole {
+    #[display("viewer")]
+    Viewer,
+    #[display("commenter")]
+    Commenter,
+    #[display("editor")]
+    Editor,
+    #[display("owner")]
+    Owner,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type, Display)]
+#[sqlx(type_name = "invitation_status", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum InvitationStatus {
+    #[display("pending")]
+    Pending,
+    #[display("accepted")]
+    Accepted,
+    #[display("declined")]
+    Declined,
+    #[display("revoked")]
+    Revoked,
+}
+
+#[derive(Debug, Serialize, FromRow, Display)]
+#[display(
+    "WorkspaceMember: {{ workspace_id: {}, user_id: {}, role: {} }}",
+    workspace_id,
+    user_id,
+    role
+)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceMember {
+    pub workspace_id: Uuid,
+    pub user_id: Uuid,
+    pub role: WorkspaceRole,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl WorkspaceMember {
+    pub fn new(workspace_id: Uuid, user_id: Uuid, role: WorkspaceRole) -> Self {
+        Self {
+            workspace_id,
+            user_id,
+            role,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+}
+
+/// A member joined with their user profile, as listed by the members endpoint.
+#[derive(Debug, Serialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceMemberProfile {
+    pub user_id: Uuid,
+    pub username: String,
+    pub avatar_url: Option<String>,
+    pub role: WorkspaceRole,
+    pub created_at: DateTime<Utc>,
+}
+
+/// An invitation to join a workspace, addressed either to a user or to whoever holds its link.
+#[derive(Debug, Serialize, FromRow, Display)]
+#[display(
+    "WorkspaceInvitation: {{ id: {}, workspace_id: {}, invitee_id: {:?}, role: {}, status: {}, uses: {}, created_at: {} }}",
+    id,
+    workspace_id,
+    invitee_id,
+    role,
+    status,
+    uses,
+    created_at
+)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceInvitation {
+    pub id: Uuid,
+    pub workspace_id: Uuid,
+    pub inviter_id: Option<Uuid>,
+    pub invitee_id: Option<Uuid>,
+    pub token: Option<String>,
+    pub role: WorkspaceRole,
+    pub status: InvitationStatus,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub max_uses: Option<i32>,
+    pub uses: i32,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl WorkspaceInvitation {
+    /// Invites a specific user, who has to accept or decline.
+    pub fn direct(
+        workspace_id: Uuid,
+        inviter_id: Uuid,
+        invitee_id: Uuid,
+        role: WorkspaceRole,
+    ) -> Self {
+        Self::new(workspace_id, inviter_id, Some(invitee_id), None, role)
+    }
+
+    /// Creates a link that anybody can redeem until it expires or runs out of uses.
+    pub fn link(
+        workspace_id: Uuid,
+        inviter_id: Uuid,
+        role: WorkspaceRole,
+        expires_at: Option<DateTime<Utc>>,
+        max_uses: Option<i32>,
+    ) -> Self {
+        let mut invitation =
+            Self::new(workspace_id, inviter_id, None, Some(generate_token()), role);
+        invitation.expires_at = expires_at;
+        invitation.max_uses = max_uses;
+        invitation
+    }
+
+    fn new(
+        workspace_id: Uuid,
+        inviter_id: Uuid,
+        invitee_id: Option<Uuid>,
+        token: Option<String>,
+        role: WorkspaceRole,
+    ) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            workspace_id,
+            inviter_id: Some(inviter_id),
+            invitee_id,
+            token,
+            role,
+            status: InvitationStatus::Pending,
+            expires_at: None,
+            max_uses: None,
+            uses: 0,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.expires_at
+            .is_some_and(|expires_at| expires_at <= Utc::now())
+    }
+}
+
+fn generate_token() -> String {
+    rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(TOKEN_LENGTH)
+        .map(char::from)
+        .collect()
+}
@@ -0,0 +1,65 @@
+use chrono::{DateTime, Utc};
+use derive_more::Display;
+use serde::{Deserialize, Serialize};
+use sqlx::{prelude::FromRow, types::Json};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TemplateFile {
+    pub path: String,
+    pub content: String,
+}
+
+/// A starter project that new workspaces can be created from.
+///
+/// Templates without a workspace or organization are part of the public catalogue
+/// managed by admins; the others are private to the members of the workspace or
+/// organization that published them.
+#[derive(Debug, Serialize, FromRow, Display)]
+#[display(
+    "WorkspaceTemplate: {{ id: {}, slug: {}, workspace_id: {:?}, created_at: {} }}",
+    id,
+    slug,
+    workspace_id,
+    created_at
+)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceTemplate {
+    pub id: Uuid,
+    pub slug: String,
+    pub name: String,
+    pub description: String,
+    pub language: Option<String>,
+    pub files: Json<Vec<TemplateFile>>,
+    pub tags: Vec<String>,
+    pub workspace_id: Option<Uuid>,
+    pub organization_id: Option<Uuid>,
+    pub author_id: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl WorkspaceTemplate {
+    pub fn new(
+        slug: impl Into<String>,
+        name: impl Into<String>,
+        language: Option<String>,
+        files: Vec<TemplateFile>,
+        author_id: Uuid,
+    ) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            slug: slug.into(),
+            name: name.into(),
+            description: String::new(),
+            language,
+            files: Json(files),
+            tags: Vec::new(),
+            workspace_id: None,
+            organization_id: None,
+            author_id: Some(author_id),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+}
@@ -0,0 +1,119 @@
+#![deny(missing_docs)]
+//! Fans out newly created notifications to the streams their recipients have open.
+//!
+//! Notifications are announced through Postgres `NOTIFY`, so a stream receives
+//! them whichever server instance created them. Each instance listens once, on
+//! its first subscriber, and dispatches to per-user channels.
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use sqlx::{postgres::PgListener, PgPool};
+use tokio::sync::{
+    broadcast::{self, error::RecvError},
+    OnceCell,
+};
+use uuid::Uuid;
+
+use crate::{models::Notification, repositories::NOTIFICATIONS_CHANNEL, utils::CaraiResult};
+
+/// Capacity of each user's channel; slower streams skip what they missed.
+const USER_CAPACITY: usize = 64;
+
+/// Tracks the users with an open stream on this server.
+#[derive(Debug, Clone, Default)]
+pub struct NotificationHub {
+    users: Arc<Mutex<HashMap<Uuid, broadcast::Sender<Notification>>>>,
+    listening: Arc<OnceCell<()>>,
+}
+
+impl NotificationHub {
+    /// Subscribes to the notifications of a user, listening for them if nobody did yet.
+    pub async fn subscribe(&self, pool: &PgPool, user_id: Uuid) -> CaraiResult<Subscription> {
+        self.listening.get_or_try_init(|| self.listen(pool)).await?;
+        let mut users = self.users.lock().unwrap_or_else(|e| e.into_inner());
+        let receiver = users
+            .entry(user_id)
+            .or_insert_with(|| broadcast::channel(USER_CAPACITY).0)
+            .subscribe();
+        Ok(Subscription {
+            hub: self.clone(),
+            user_id,
+            receiver,
+        })
+    }
+
+    async fn listen(&self, pool: &PgPool) -> CaraiResult<()> {
+        let mut listener = PgListener::connect_with(pool).await?;
+        listener.listen(NOTIFICATIONS_CHANNEL).await?;
+        let hub = self.clone();
+        tokio::spawn(async move {
+            loop {
+                // The listener reconnects by itself; whatever was sent meanwhile is lost
+                let payload = match listener.recv().await {
+                    Ok(message) => message,
+                    Err(e) => {
+                        tracing::warn!("Unable to receive notifications: {}", e);
+                        tokio::time::sleep(Duration::from_secs(1)).await;
+                        continue;
+                    }
+                };
+                match serde_json::from_str::<Notification>(payload.payload()) {
+                    Ok(notification) => hub.dispatch(notification),
+                    Err(e) => tracing::warn!("Unable to decode notification: {}", e),
+                }
+            }
+        });
+        Ok(())
+    }
+
+    fn dispatch(&self, notification: Notification) {
+        let users = self.users.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(sender) = users.get(&notification.user_id) {
+            // Sending only fails when the last stream is closing, which removes the channel
+            let _ = sender.send(notification);
+        }
+    }
+}
+
+/// The notifications of one user, received until dropped.
+#[derive(Debug)]
+pub struct Subscription {
+    hub: NotificationHub,
+    user_id: Uuid,
+    receiver: broadcast::Receiver<Notification>,
+}
+
+impl Subscription {
+    /// Waits for the next notification, skipping any the stream lagged behind on.
+    pub async fn recv(&mut self) -> Option<Notification> {
+        loop {
+            match self.receiver.recv().await {
+                Ok(notification) => return Some(notification),
+                Err(RecvError::Lagged(skipped)) => {
+                    tracing::debug!(
+                        "Notification stream of {} skipped {} notifications",
+                        self.user_id,
+                        skipped
+                    );
+                }
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    }
+}
+
+impl Drop for Subscription {
+    fn drop(&mut self) {
+        let mut users = self.hub.users.lock().unwrap_or_else(|e| e.into_inner());
+        if users
+            .get(&self.user_id)
+            .is_some_and(|sender| sender.receiver_count() <= 1)
+        {
+            users.remove(&self.user_id);
+        }
+    }
+}
@@ -0,0 +1,5 @@
+mod hub;
+mod session;
+
+pub use hub::*;
+pub use session::*;
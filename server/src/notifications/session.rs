@@ -0,0 +1,80 @@
+#![deny(missing_docs)]
+//! Drives a user's notification stream over a WebSocket.
+
+use axum::extract::ws::{Message, WebSocket};
+use futures_util::{stream::SplitSink, SinkExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{bootstrap::AppState, dto::NotificationResDto, services, utils::CaraiResult};
+
+/// A message sent to the client, as a JSON text message.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum NotificationEvent {
+    /// How many notifications are unread, sent once when the stream opens.
+    Unread {
+        /// The number of unread notifications.
+        count: i64,
+    },
+    /// A notification created while the stream is open.
+    Notification {
+        /// The notification, as listed by `GET /notifications`.
+        notification: NotificationResDto,
+    },
+}
+
+/// Serves a user's stream until they disconnect.
+///
+/// The client first receives a [`NotificationEvent::Unread`] count, then a
+/// [`NotificationEvent::Notification`] for every notification created for them
+/// afterwards. Messages from the client are ignored.
+pub async fn serve_notification_stream(socket: WebSocket, state: AppState, user_id: Uuid) {
+    if let Err(e) = run_session(socket, &state, user_id).await {
+        tracing::warn!(
+            "Notification stream of {} ended with an error: {}",
+            user_id,
+            e
+        );
+    }
+}
+
+async fn run_session(socket: WebSocket, state: &AppState, user_id: Uuid) -> CaraiResult<()> {
+    let (mut sink, mut stream) = socket.split();
+
+    // Subscribing before counting means nothing created meanwhile is missed
+    let mut subscription = state
+        .notifications()
+        .subscribe(state.db_pool(), user_id)
+        .await?;
+    let count = services::count_unread_notifications(state.db_pool(), user_id).await?;
+    send(&mut sink, &NotificationEvent::Unread { count }).await?;
+
+    loop {
+        tokio::select! {
+            message = stream.next() => match message {
+                Some(Ok(Message::Close(_))) | None => return Ok(()),
+                Some(Ok(_)) => continue,
+                Some(Err(e)) => return Err(e.into()),
+            },
+            notification = subscription.recv() => {
+                let Some(notification) = notification else {
+                    return Ok(());
+                };
+                let event = NotificationEvent::Notification {
+                    notification: notification.into(),
+                };
+                send(&mut sink, &event).await?;
+            }
+        }
+    }
+}
+
+async fn send(
+    sink: &mut SplitSink<WebSocket, Message>,
+    event: &NotificationEvent,
+) -> CaraiResult<()> {
+    sink.send(Message::Text(serde_json::to_string(event)?))
+        .await?;
+    Ok(())
+}
@@ -0,0 +1,51 @@
+use anyhow::anyhow;
+use sqlx::{PgExecutor, PgPool};
+
+use crate::{models::Blob, utils::CaraiResult};
+
+/// Stores a blob unless identical content is already stored under the same hash.
+pub async fn create_blob<'e>(executor: impl PgExecutor<'e>, blob: &Blob) -> CaraiResult<()> {
+    sqlx::query!(
+        r#"
+        INSERT INTO blobs (hash, content, size, created_at)
+        VALUES ($1, $2, $3, $4)
+        ON CONFLICT (hash) DO NOTHING
+        "#,
+        blob.hash,
+        blob.content,
+        blob.size,
+        blob.created_at
+    )
+    .execute(executor)
+    .await
+    .map_err(|e| anyhow!("Unable to create blob ({})", e))?;
+    Ok(())
+}
+
+pub async fn get_blob_by_hash(pool: &PgPool, hash: &str) -> CaraiResult<Option<Blob>> {
+    sqlx::query_as!(
+        Blob,
+        r#"
+        SELECT * FROM blobs
+        WHERE hash = $1
+        "#,
+        hash
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to get blob by hash ({})", e))
+}
+
+/// Deletes blobs that no revision refers to any more.
+pub async fn delete_unreferenced_blobs(pool: &PgPool) -> CaraiResult<u64> {
+    let result = sqlx::query!(
+        r#"
+        DELETE FROM blobs b
+        WHERE NOT EXISTS (SELECT 1 FROM file_revisions r WHERE r.blob_hash = b.hash)
+        "#
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to delete unreferenced blobs ({})", e))?;
+    Ok(result.rows_affected())
+}
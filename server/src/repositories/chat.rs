@@ -0,0 +1,49 @@
+use anyhow::anyhow;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{models::ChatMessage, utils::CaraiResult};
+
+pub async fn create_chat_message(pool: &PgPool, message: &ChatMessage) -> CaraiResult<ChatMessage> {
+    sqlx::query_as!(
+        ChatMessage,
+        r#"
+        INSERT INTO chat_messages (id, workspace_id, author_id, body, created_at)
+        VALUES ($1, $2, $3, $4, $5)
+        RETURNING *
+        "#,
+        message.id,
+        message.workspace_id,
+        message.author_id,
+        message.body,
+        message.created_at
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to create chat message ({})", e))
+}
+
+/// Lists a workspace's messages sent before the given time, newest first.
+pub async fn get_chat_messages(
+    pool: &PgPool,
+    workspace_id: Uuid,
+    before: Option<DateTime<Utc>>,
+    limit: i64,
+) -> CaraiResult<Vec<ChatMessage>> {
+    sqlx::query_as!(
+        ChatMessage,
+        r#"
+        SELECT * FROM chat_messages
+        WHERE workspace_id = $1 AND ($2::TIMESTAMPTZ IS NULL OR created_at < $2)
+        ORDER BY created_at DESC
+        LIMIT $3
+        "#,
+        workspace_id,
+        before,
+        limit
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to get chat messages ({})", e))
+}
@@ -0,0 +1,422 @@
+use anyhow::anyhow;
+use chrono::Utc;
+use sqlx::{types::Json, PgPool};
+use uuid::Uuid;
+
+use crate::{
+    models::{
+        Assignment, Classroom, ClassroomMember, ClassroomMemberProfile, ClassroomMembership,
+        ClassroomRole, ExecutionFile, Grade, GradebookEntry, JudgeSpec, Submission,
+        SubmissionStatus, TemplateFile,
+    },
+    utils::CaraiResult,
+};
+
+pub async fn create_classroom(pool: &PgPool, classroom: &Classroom) -> CaraiResult<Classroom> {
+    sqlx::query_as!(
+        Classroom,
+        r#"
+        INSERT INTO classrooms (id, slug, name, join_code, created_at, updated_at)
+        VALUES ($1, $2, $3, $4, $5, $6)
+        RETURNING *
+        "#,
+        classroom.id,
+        classroom.slug,
+        classroom.name,
+        classroom.join_code,
+        classroom.created_at,
+        classroom.updated_at
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to create classroom ({})", e))
+}
+
+pub async fn get_classroom_by_slug(pool: &PgPool, slug: &str) -> CaraiResult<Option<Classroom>> {
+    sqlx::query_as!(
+        Classroom,
+        r#"
+        SELECT * FROM classrooms
+        WHERE slug = $1
+        "#,
+        slug
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to get classroom by slug ({})", e))
+}
+
+pub async fn get_classroom_by_join_code(
+    pool: &PgPool,
+    join_code: &str,
+) -> CaraiResult<Option<Classroom>> {
+    sqlx::query_as!(
+        Classroom,
+        r#"
+        SELECT * FROM classrooms
+        WHERE join_code = $1
+        "#,
+        join_code
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to get classroom by join code ({})", e))
+}
+
+/// Lists the classrooms a user belongs to, with their role in each.
+pub async fn get_classrooms_by_member_id(
+    pool: &PgPool,
+    user_id: Uuid,
+) -> CaraiResult<Vec<ClassroomMembership>> {
+    sqlx::query_as!(
+        ClassroomMembership,
+        r#"
+        SELECT c.id, c.slug, c.name, m.role AS "role: ClassroomRole", c.created_at
+        FROM classroom_members m
+        JOIN classrooms c ON c.id = m.classroom_id
+        WHERE m.user_id = $1
+        ORDER BY c.name
+        "#,
+        user_id
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to get classrooms by member ID ({})", e))
+}
+
+pub async fn delete_classroom(pool: &PgPool, id: Uuid) -> CaraiResult<()> {
+    sqlx::query!(
+        r#"
+        DELETE FROM classrooms
+        WHERE id = $1
+        "#,
+        id
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to delete classroom ({})", e))?;
+    Ok(())
+}
+
+pub async fn create_classroom_member(
+    pool: &PgPool,
+    member: &ClassroomMember,
+) -> CaraiResult<ClassroomMember> {
+    sqlx::query_as!(
+        ClassroomMember,
+        r#"
+        INSERT INTO classroom_members (classroom_id, user_id, role, created_at)
+        VALUES ($1, $2, $3, $4)
+        RETURNING classroom_id, user_id, role AS "role: ClassroomRole", created_at
+        "#,
+        member.classroom_id,
+        member.user_id,
+        member.role as _,
+        member.created_at
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to create classroom member ({})", e))
+}
+
+pub async fn get_classroom_member(
+    pool: &PgPool,
+    classroom_id: Uuid,
+    user_id: Uuid,
+) -> CaraiResult<Option<ClassroomMember>> {
+    sqlx::query_as!(
+        ClassroomMember,
+        r#"
+        SELECT classroom_id, user_id, role AS "role: ClassroomRole", created_at
+        FROM classroom_members
+        WHERE classroom_id = $1 AND user_id = $2
+        "#,
+        classroom_id,
+        user_id
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to get classroom member ({})", e))
+}
+
+/// Lists the members of a classroom, instructors first.
+pub async fn get_classroom_member_profiles(
+    pool: &PgPool,
+    classroom_id: Uuid,
+) -> CaraiResult<Vec<ClassroomMemberProfile>> {
+    sqlx::query_as!(
+        ClassroomMemberProfile,
+        r#"
+        SELECT
+            u.id AS user_id, u.username, u.avatar_url, m.role AS "role: ClassroomRole",
+            m.created_at
+        FROM classroom_members m
+        JOIN users u ON u.id = m.user_id
+        WHERE m.classroom_id = $1
+        ORDER BY m.role DESC, u.username
+        "#,
+        classroom_id
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to get classroom members ({})", e))
+}
+
+pub async fn delete_classroom_member(
+    pool: &PgPool,
+    classroom_id: Uuid,
+    user_id: Uuid,
+) -> CaraiResult<()> {
+    sqlx::query!(
+        r#"
+        DELETE FROM classroom_members
+        WHERE classroom_id = $1 AND user_id = $2
+        "#,
+        classroom_id,
+        user_id
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to delete classroom member ({})", e))?;
+    Ok(())
+}
+
+pub async fn create_assignment(pool: &PgPool, assignment: &Assignment) -> CaraiResult<Assignment> {
+    sqlx::query_as!(
+        Assignment,
+        r#"
+        INSERT INTO assignments (
+            id, classroom_id, title, description, language, entrypoint, starter_files, judge,
+            deadline, author_id, created_at, updated_at
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+        RETURNING
+            id, classroom_id, title, description, language, entrypoint,
+            starter_files AS "starter_files: Json<Vec<TemplateFile>>",
+            judge AS "judge: Json<JudgeSpec>", deadline, author_id, created_at, updated_at
+        "#,
+        assignment.id,
+        assignment.classroom_id,
+        assignment.title,
+        assignment.description,
+        assignment.language,
+        assignment.entrypoint,
+        assignment.starter_files as _,
+        assignment.judge as _,
+        assignment.deadline,
+        assignment.author_id,
+        assignment.created_at,
+        assignment.updated_at
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to create assignment ({})", e))
+}
+
+pub async fn get_assignment_by_id(pool: &PgPool, id: Uuid) -> CaraiResult<Option<Assignment>> {
+    sqlx::query_as!(
+        Assignment,
+        r#"
+        SELECT
+            id, classroom_id, title, description, language, entrypoint,
+            starter_files AS "starter_files: Json<Vec<TemplateFile>>",
+            judge AS "judge: Json<JudgeSpec>", deadline, author_id, created_at, updated_at
+        FROM assignments
+        WHERE id = $1
+        "#,
+        id
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to get assignment by ID ({})", e))
+}
+
+/// Lists the assignments of a classroom, the one due first first.
+pub async fn get_assignments_by_classroom_id(
+    pool: &PgPool,
+    classroom_id: Uuid,
+) -> CaraiResult<Vec<Assignment>> {
+    sqlx::query_as!(
+        Assignment,
+        r#"
+        SELECT
+            id, classroom_id, title, description, language, entrypoint,
+            starter_files AS "starter_files: Json<Vec<TemplateFile>>",
+            judge AS "judge: Json<JudgeSpec>", deadline, author_id, created_at, updated_at
+        FROM assignments
+        WHERE classroom_id = $1
+        ORDER BY deadline, created_at
+        "#,
+        classroom_id
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to get assignments by classroom ID ({})", e))
+}
+
+pub async fn delete_assignment(pool: &PgPool, id: Uuid) -> CaraiResult<()> {
+    sqlx::query!(
+        r#"
+        DELETE FROM assignments
+        WHERE id = $1
+        "#,
+        id
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to delete assignment ({})", e))?;
+    Ok(())
+}
+
+pub async fn create_submission(pool: &PgPool, submission: &Submission) -> CaraiResult<Submission> {
+    sqlx::query_as!(
+        Submission,
+        r#"
+        INSERT INTO submissions (
+            id, assignment_id, user_id, workspace_id, execution_id, files, status,
+            passed_cases, total_cases, score, created_at, graded_at
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+        RETURNING
+            id, assignment_id, user_id, workspace_id, execution_id,
+            files AS "files: Json<Vec<ExecutionFile>>", status AS "status: SubmissionStatus",
+            passed_cases, total_cases, score, created_at, graded_at
+        "#,
+        submission.id,
+        submission.assignment_id,
+        submission.user_id,
+        submission.workspace_id,
+        submission.execution_id,
+        submission.files as _,
+        submission.status as _,
+        submission.passed_cases,
+        submission.total_cases,
+        submission.score,
+        submission.created_at,
+        submission.graded_at
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to create submission ({})", e))
+}
+
+pub async fn get_submission_by_id(pool: &PgPool, id: Uuid) -> CaraiResult<Option<Submission>> {
+    sqlx::query_as!(
+        Submission,
+        r#"
+        SELECT
+            id, assignment_id, user_id, workspace_id, execution_id,
+            files AS "files: Json<Vec<ExecutionFile>>", status AS "status: SubmissionStatus",
+            passed_cases, total_cases, score, created_at, graded_at
+        FROM submissions
+        WHERE id = $1
+        "#,
+        id
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to get submission by ID ({})", e))
+}
+
+pub async fn get_submission_by_execution_id(
+    pool: &PgPool,
+    execution_id: Uuid,
+) -> CaraiResult<Option<Submission>> {
+    sqlx::query_as!(
+        Submission,
+        r#"
+        SELECT
+            id, assignment_id, user_id, workspace_id, execution_id,
+            files AS "files: Json<Vec<ExecutionFile>>", status AS "status: SubmissionStatus",
+            passed_cases, total_cases, score, created_at, graded_at
+        FROM submissions
+        WHERE execution_id = $1
+        "#,
+        execution_id
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to get submission by execution ID ({})", e))
+}
+
+/// Lists the submissions for an assignment, latest first, optionally those of one student.
+pub async fn get_submissions_by_assignment_id(
+    pool: &PgPool,
+    assignment_id: Uuid,
+    user_id: Option<Uuid>,
+    limit: i64,
+    offset: i64,
+) -> CaraiResult<Vec<Submission>> {
+    sqlx::query_as!(
+        Submission,
+        r#"
+        SELECT
+            id, assignment_id, user_id, workspace_id, execution_id,
+            files AS "files: Json<Vec<ExecutionFile>>", status AS "status: SubmissionStatus",
+            passed_cases, total_cases, score, created_at, graded_at
+        FROM submissions
+        WHERE assignment_id = $1 AND ($2::UUID IS NULL OR user_id = $2)
+        ORDER BY created_at DESC
+        LIMIT $3 OFFSET $4
+        "#,
+        assignment_id,
+        user_id,
+        limit,
+        offset
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to get submissions by assignment ID ({})", e))
+}
+
+/// Records the outcome of grading a submission; failed runs are left without a grade.
+pub async fn grade_submission(
+    pool: &PgPool,
+    id: Uuid,
+    status: SubmissionStatus,
+    grade: Option<Grade>,
+) -> CaraiResult<Submission> {
+    sqlx::query_as!(
+        Submission,
+        r#"
+        UPDATE submissions
+        SET status = $2, passed_cases = COALESCE($3, passed_cases), score = $4, graded_at = $5
+        WHERE id = $1
+        RETURNING
+            id, assignment_id, user_id, workspace_id, execution_id,
+            files AS "files: Json<Vec<ExecutionFile>>", status AS "status: SubmissionStatus",
+            passed_cases, total_cases, score, created_at, graded_at
+        "#,
+        id,
+        status as _,
+        grade.map(|grade| grade.passed_cases),
+        grade.map(|grade| grade.score),
+        Utc::now()
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to grade submission ({})", e))
+}
+
+/// Summarises the submissions of every student in a classroom, per assignment.
+pub async fn get_gradebook_entries(
+    pool: &PgPool,
+    classroom_id: Uuid,
+) -> CaraiResult<Vec<GradebookEntry>> {
+    sqlx::query_as!(
+        GradebookEntry,
+        r#"
+        SELECT
+            s.assignment_id, s.user_id, MAX(s.score) AS best_score,
+            COUNT(*) AS "submissions!", MAX(s.created_at) AS "last_submitted_at!"
+        FROM submissions s
+        JOIN assignments a ON a.id = s.assignment_id
+        WHERE a.classroom_id = $1
+        GROUP BY s.assignment_id, s.user_id
+        "#,
+        classroom_id
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to get gradebook entries ({})", e))
+}
@@ -0,0 +1,193 @@
+use anyhow::anyhow;
+use chrono::Utc;
+use sqlx::{PgExecutor, PgPool};
+use uuid::Uuid;
+
+use crate::{
+    models::{CollabSnapshot, CollabUpdate},
+    utils::CaraiResult,
+};
+
+pub async fn get_collab_snapshot<'e>(
+    executor: impl PgExecutor<'e>,
+    file_id: Uuid,
+) -> CaraiResult<Option<CollabSnapshot>> {
+    sqlx::query_as!(
+        CollabSnapshot,
+        r#"
+        SELECT * FROM collab_snapshots
+        WHERE file_id = $1
+        "#,
+        file_id
+    )
+    .fetch_optional(executor)
+    .await
+    .map_err(|e| anyhow!("Unable to get collab snapshot ({})", e))
+}
+
+/// The stored snapshot of a file's document together with every update after it.
+///
+/// Both are read from one repeatable-read transaction, so a compaction committing in
+/// between cannot pair an older snapshot with updates it already deleted.
+pub async fn get_collab_document(
+    pool: &PgPool,
+    file_id: Uuid,
+) -> CaraiResult<(Option<CollabSnapshot>, Vec<CollabUpdate>)> {
+    let error = |e: sqlx::Error| anyhow!("Unable to get collab document ({})", e);
+    let mut tx = pool.begin().await.map_err(error)?;
+    sqlx::query!("SET TRANSACTION ISOLATION LEVEL REPEATABLE READ")
+        .execute(&mut *tx)
+        .await
+        .map_err(error)?;
+    let snapshot = get_collab_snapshot(&mut *tx, file_id).await?;
+    let seq = snapshot.as_ref().map_or(0, |snapshot| snapshot.seq);
+    let updates = get_collab_updates_after(&mut *tx, file_id, seq).await?;
+    tx.commit().await.map_err(error)?;
+    Ok((snapshot, updates))
+}
+
+/// Stores a snapshot unless a snapshot covering later updates is already stored.
+///
+/// Returns whether the snapshot was stored.
+pub async fn upsert_collab_snapshot(pool: &PgPool, snapshot: &CollabSnapshot) -> CaraiResult<bool> {
+    let result = sqlx::query!(
+        r#"
+        INSERT INTO collab_snapshots (file_id, seq, data, updated_at)
+        VALUES ($1, $2, $3, $4)
+        ON CONFLICT (file_id) DO UPDATE
+        SET seq = EXCLUDED.seq, data = EXCLUDED.data, updated_at = EXCLUDED.updated_at
+        WHERE collab_snapshots.seq < EXCLUDED.seq
+        "#,
+        snapshot.file_id,
+        snapshot.seq,
+        snapshot.data,
+        snapshot.updated_at
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to store collab snapshot ({})", e))?;
+    Ok(result.rows_affected() > 0)
+}
+
+pub async fn create_collab_update<'e>(
+    executor: impl PgExecutor<'e>,
+    file_id: Uuid,
+    author_id: Uuid,
+    data: &[u8],
+) -> CaraiResult<CollabUpdate> {
+    sqlx::query_as!(
+        CollabUpdate,
+        r#"
+        INSERT INTO collab_updates (file_id, author_id, data, created_at)
+        VALUES ($1, $2, $3, $4)
+        RETURNING *
+        "#,
+        file_id,
+        author_id,
+        data,
+        Utc::now()
+    )
+    .fetch_one(executor)
+    .await
+    .map_err(|e| anyhow!("Unable to create collab update ({})", e))
+}
+
+/// Drops a file's stored document, starting it over from `seed` if given.
+pub async fn reset_collab_document(
+    pool: &PgPool,
+    file_id: Uuid,
+    author_id: Uuid,
+    seed: Option<&[u8]>,
+) -> CaraiResult<Option<CollabUpdate>> {
+    let error = |e: sqlx::Error| anyhow!("Unable to reset collab document ({})", e);
+    let mut tx = pool.begin().await.map_err(error)?;
+    sqlx::query!("DELETE FROM collab_snapshots WHERE file_id = $1", file_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(error)?;
+    sqlx::query!("DELETE FROM collab_updates WHERE file_id = $1", file_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(error)?;
+    let update = match seed {
+        Some(seed) => Some(create_collab_update(&mut *tx, file_id, author_id, seed).await?),
+        None => None,
+    };
+    tx.commit().await.map_err(error)?;
+    Ok(update)
+}
+
+pub async fn get_collab_updates_after<'e>(
+    executor: impl PgExecutor<'e>,
+    file_id: Uuid,
+    seq: i64,
+) -> CaraiResult<Vec<CollabUpdate>> {
+    sqlx::query_as!(
+        CollabUpdate,
+        r#"
+        SELECT * FROM collab_updates
+        WHERE file_id = $1 AND seq > $2
+        ORDER BY seq
+        "#,
+        file_id,
+        seq
+    )
+    .fetch_all(executor)
+    .await
+    .map_err(|e| anyhow!("Unable to get collab updates ({})", e))
+}
+
+pub async fn count_collab_updates_after(
+    pool: &PgPool,
+    file_id: Uuid,
+    seq: i64,
+) -> CaraiResult<i64> {
+    sqlx::query_scalar!(
+        r#"
+        SELECT COUNT(*) AS "count!" FROM collab_updates
+        WHERE file_id = $1 AND seq > $2
+        "#,
+        file_id,
+        seq
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to count collab updates ({})", e))
+}
+
+pub async fn delete_collab_updates_through(
+    pool: &PgPool,
+    file_id: Uuid,
+    seq: i64,
+) -> CaraiResult<u64> {
+    let result = sqlx::query!(
+        r#"
+        DELETE FROM collab_updates
+        WHERE file_id = $1 AND seq <= $2
+        "#,
+        file_id,
+        seq
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to delete collab updates ({})", e))?;
+    Ok(result.rows_affected())
+}
+
+/// The sequence number of the latest update stored for a file, or of its snapshot once
+/// those updates were compacted, or zero when nothing was stored.
+pub async fn get_latest_collab_seq(pool: &PgPool, file_id: Uuid) -> CaraiResult<i64> {
+    sqlx::query_scalar!(
+        r#"
+        SELECT GREATEST(
+            (SELECT MAX(seq) FROM collab_updates WHERE file_id = $1),
+            (SELECT seq FROM collab_snapshots WHERE file_id = $1),
+            0
+        ) AS "seq!"
+        "#,
+        file_id
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to get latest collab sequence number ({})", e))
+}
@@ -0,0 +1,181 @@
+use anyhow::anyhow;
+use chrono::Utc;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    models::{Comment, CommentThread},
+    utils::CaraiResult,
+};
+
+pub async fn create_comment_thread(
+    pool: &PgPool,
+    thread: &CommentThread,
+) -> CaraiResult<CommentThread> {
+    sqlx::query_as!(
+        CommentThread,
+        r#"
+        INSERT INTO comment_threads (
+            id, workspace_id, file_id, author_id, start_line, end_line, outdated,
+            resolved_at, resolved_by, created_at, updated_at
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+        RETURNING *
+        "#,
+        thread.id,
+        thread.workspace_id,
+        thread.file_id,
+        thread.author_id,
+        thread.start_line,
+        thread.end_line,
+        thread.outdated,
+        thread.resolved_at,
+        thread.resolved_by,
+        thread.created_at,
+        thread.updated_at
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to create comment thread ({})", e))
+}
+
+pub async fn get_comment_thread_by_id(
+    pool: &PgPool,
+    file_id: Uuid,
+    id: Uuid,
+) -> CaraiResult<Option<CommentThread>> {
+    sqlx::query_as!(
+        CommentThread,
+        r#"
+        SELECT * FROM comment_threads
+        WHERE file_id = $1 AND id = $2
+        "#,
+        file_id,
+        id
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to get comment thread by id ({})", e))
+}
+
+/// Lists the threads of a file in line order, optionally filtered by resolution.
+pub async fn get_comment_threads(
+    pool: &PgPool,
+    file_id: Uuid,
+    resolved: Option<bool>,
+) -> CaraiResult<Vec<CommentThread>> {
+    sqlx::query_as!(
+        CommentThread,
+        r#"
+        SELECT * FROM comment_threads
+        WHERE file_id = $1 AND ($2::BOOLEAN IS NULL OR (resolved_at IS NOT NULL) = $2)
+        ORDER BY start_line, created_at
+        "#,
+        file_id,
+        resolved
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to get comment threads ({})", e))
+}
+
+pub async fn update_comment_thread_anchor(
+    pool: &PgPool,
+    thread: &CommentThread,
+) -> CaraiResult<()> {
+    sqlx::query!(
+        r#"
+        UPDATE comment_threads
+        SET start_line = $2, end_line = $3, outdated = $4, updated_at = $5
+        WHERE id = $1
+        "#,
+        thread.id,
+        thread.start_line,
+        thread.end_line,
+        thread.outdated,
+        Utc::now()
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to update comment thread anchor ({})", e))?;
+    Ok(())
+}
+
+/// Resolves a thread on behalf of `resolved_by`, or reopens it when that is `None`.
+pub async fn update_comment_thread_resolution(
+    pool: &PgPool,
+    id: Uuid,
+    resolved_by: Option<Uuid>,
+) -> CaraiResult<CommentThread> {
+    let now = Utc::now();
+    sqlx::query_as!(
+        CommentThread,
+        r#"
+        UPDATE comment_threads
+        SET resolved_by = $2,
+            resolved_at = CASE WHEN $2::UUID IS NULL THEN NULL ELSE $3::TIMESTAMPTZ END,
+            updated_at = $3
+        WHERE id = $1
+        RETURNING *
+        "#,
+        id,
+        resolved_by,
+        now
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to update comment thread resolution ({})", e))
+}
+
+pub async fn delete_comment_thread(pool: &PgPool, id: Uuid) -> CaraiResult<()> {
+    sqlx::query!(
+        r#"
+        DELETE FROM comment_threads
+        WHERE id = $1
+        "#,
+        id
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to delete comment thread ({})", e))?;
+    Ok(())
+}
+
+pub async fn create_comment(pool: &PgPool, comment: &Comment) -> CaraiResult<Comment> {
+    sqlx::query_as!(
+        Comment,
+        r#"
+        INSERT INTO comments (id, thread_id, author_id, body, created_at, updated_at)
+        VALUES ($1, $2, $3, $4, $5, $6)
+        RETURNING *
+        "#,
+        comment.id,
+        comment.thread_id,
+        comment.author_id,
+        comment.body,
+        comment.created_at,
+        comment.updated_at
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to create comment ({})", e))
+}
+
+/// Lists the comments of several threads, oldest first.
+pub async fn get_comments_by_thread_ids(
+    pool: &PgPool,
+    thread_ids: &[Uuid],
+) -> CaraiResult<Vec<Comment>> {
+    sqlx::query_as!(
+        Comment,
+        r#"
+        SELECT * FROM comments
+        WHERE thread_id = ANY($1)
+        ORDER BY created_at
+        "#,
+        thread_ids
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to get comments by thread IDs ({})", e))
+}
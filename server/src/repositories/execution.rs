@@ -0,0 +1,314 @@
+use anyhow::anyhow;
+use chrono::{DateTime, Utc};
+use sqlx::{types::Json, PgExecutor, PgPool};
+use uuid::Uuid;
+
+use crate::{
+    models::{CaseResult, CompileOutput, Execution, ExecutionFile, ExecutionStatus, JudgeSpec},
+    utils::CaraiResult,
+};
+
+pub async fn create_execution<'e>(
+    executor: impl PgExecutor<'e>,
+    execution: &Execution,
+) -> CaraiResult<Execution> {
+    sqlx::query_as!(
+        Execution,
+        r#"
+        INSERT INTO executions (
+            id, user_id, workspace_id, organization_id, language, files, files_hash, stdin,
+            status, stdout, stderr, error, exit_code, duration_ms, cached, compile, judge,
+            case_results, attempts, started_at, finished_at, created_at, updated_at
+        )
+        VALUES (
+            $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11,
+            $12, $13, $14, $15, $16, $17, $18, $19, $20, $21, $22, $23
+        )
+        RETURNING
+            id, user_id, workspace_id, organization_id, language,
+            files AS "files: Json<Vec<ExecutionFile>>", files_hash,
+            stdin, status AS "status: ExecutionStatus", stdout, stderr, error, exit_code,
+            duration_ms, cached, compile AS "compile: Json<CompileOutput>",
+            judge AS "judge: Json<JudgeSpec>",
+            case_results AS "case_results: Json<Vec<CaseResult>>", attempts, locked_until,
+            started_at, finished_at, created_at, updated_at
+        "#,
+        execution.id,
+        execution.user_id,
+        execution.workspace_id,
+        execution.organization_id,
+        execution.language,
+        execution.files as _,
+        execution.files_hash,
+        execution.stdin,
+        execution.status as _,
+        execution.stdout,
+        execution.stderr,
+        execution.error,
+        execution.exit_code,
+        execution.duration_ms,
+        execution.cached,
+        execution.compile as _,
+        execution.judge as _,
+        execution.case_results as _,
+        execution.attempts,
+        execution.started_at,
+        execution.finished_at,
+        execution.created_at,
+        execution.updated_at
+    )
+    .fetch_one(executor)
+    .await
+    .map_err(|e| anyhow!("Unable to create execution ({})", e))
+}
+
+/// Inserts an execution unless whoever it is billed to already has `concurrent_runs`
+/// runs queued or running, returning `None` in that case.
+///
+/// The user or organization row stays locked from the count until the insert commits,
+/// so parallel requests are admitted one at a time and cannot all slip under the limit.
+pub async fn create_execution_within_limit(
+    pool: &PgPool,
+    execution: &Execution,
+    concurrent_runs: i64,
+) -> CaraiResult<Option<Execution>> {
+    let error = |e: sqlx::Error| anyhow!("Unable to create execution ({})", e);
+    let mut tx = pool.begin().await.map_err(error)?;
+    let active_runs = match execution.organization_id {
+        Some(organization_id) => {
+            sqlx::query!(
+                "SELECT id FROM organizations WHERE id = $1 FOR UPDATE",
+                organization_id
+            )
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(error)?;
+            sqlx::query_scalar!(
+                r#"
+                SELECT COUNT(*) AS "count!" FROM executions
+                WHERE organization_id = $1 AND status IN ('queued', 'running')
+                "#,
+                organization_id
+            )
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(error)?
+        }
+        None => {
+            sqlx::query!(
+                "SELECT id FROM users WHERE id = $1 FOR UPDATE",
+                execution.user_id
+            )
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(error)?;
+            sqlx::query_scalar!(
+                r#"
+                SELECT COUNT(*) AS "count!" FROM executions
+                WHERE user_id = $1 AND organization_id IS NULL
+                    AND status IN ('queued', 'running')
+                "#,
+                execution.user_id
+            )
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(error)?
+        }
+    };
+    if active_runs >= concurrent_runs {
+        return Ok(None);
+    }
+    let execution = create_execution(&mut *tx, execution).await?;
+    tx.commit().await.map_err(error)?;
+    Ok(Some(execution))
+}
+
+pub async fn get_execution_by_id(pool: &PgPool, id: Uuid) -> CaraiResult<Option<Execution>> {
+    sqlx::query_as!(
+        Execution,
+        r#"
+        SELECT
+            id, user_id, workspace_id, organization_id, language,
+            files AS "files: Json<Vec<ExecutionFile>>", files_hash,
+            stdin, status AS "status: ExecutionStatus", stdout, stderr, error, exit_code,
+            duration_ms, cached, compile AS "compile: Json<CompileOutput>",
+            judge AS "judge: Json<JudgeSpec>",
+            case_results AS "case_results: Json<Vec<CaseResult>>", attempts, locked_until,
+            started_at, finished_at, created_at, updated_at
+        FROM executions
+        WHERE id = $1
+        "#,
+        id
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to get execution by id ({})", e))
+}
+
+/// Claims the next runnable execution, skipping rows locked by other workers.
+///
+/// Queued jobs are ordered by how many runs their owner already has in flight and
+/// then by age, so a single user flooding the queue cannot starve everybody else.
+/// Running jobs whose lease has lapsed (e.g. after a crash or restart) are claimable again.
+pub async fn claim_next_execution(
+    pool: &PgPool,
+    locked_until: DateTime<Utc>,
+) -> CaraiResult<Option<Execution>> {
+    sqlx::query_as!(
+        Execution,
+        r#"
+        UPDATE executions
+        SET status = 'running', attempts = attempts + 1, locked_until = $1,
+            started_at = $2, updated_at = $2
+        WHERE id = (
+            SELECT e.id FROM executions e
+            LEFT JOIN (
+                SELECT user_id, COUNT(*) AS running FROM executions
+                WHERE status = 'running' AND locked_until >= $2
+                GROUP BY user_id
+            ) r ON r.user_id = e.user_id
+            WHERE e.status = 'queued' OR (e.status = 'running' AND e.locked_until < $2)
+            ORDER BY COALESCE(r.running, 0), e.created_at
+            LIMIT 1
+            FOR UPDATE OF e SKIP LOCKED
+        )
+        RETURNING
+            id, user_id, workspace_id, organization_id, language,
+            files AS "files: Json<Vec<ExecutionFile>>", files_hash,
+            stdin, status AS "status: ExecutionStatus", stdout, stderr, error, exit_code,
+            duration_ms, cached, compile AS "compile: Json<CompileOutput>",
+            judge AS "judge: Json<JudgeSpec>",
+            case_results AS "case_results: Json<Vec<CaseResult>>", attempts, locked_until,
+            started_at, finished_at, created_at, updated_at
+        "#,
+        locked_until,
+        Utc::now()
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to claim execution ({})", e))
+}
+
+/// Extends the lease of a claimed execution, returning `false` if the claim was lost.
+pub async fn extend_execution_lease(
+    pool: &PgPool,
+    execution: &Execution,
+    locked_until: DateTime<Utc>,
+) -> CaraiResult<bool> {
+    let result = sqlx::query!(
+        r#"
+        UPDATE executions
+        SET locked_until = $3, updated_at = $4
+        WHERE id = $1 AND status = 'running' AND attempts = $2
+        "#,
+        execution.id,
+        execution.attempts,
+        locked_until,
+        Utc::now()
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to extend execution lease ({})", e))?;
+    Ok(result.rows_affected() > 0)
+}
+
+/// Stores the outcome of a claimed execution, returning `false` if the claim was lost,
+/// e.g. because the lease lapsed and another worker took the job over.
+pub async fn finish_execution(pool: &PgPool, execution: &Execution) -> CaraiResult<bool> {
+    let result = sqlx::query!(
+        r#"
+        UPDATE executions
+        SET status = $2, stdout = $3, stderr = $4, error = $5, exit_code = $6,
+            duration_ms = $7, compile = $8, case_results = $9, locked_until = NULL,
+            finished_at = $10, updated_at = $10
+        WHERE id = $1 AND status = 'running' AND attempts = $11
+        "#,
+        execution.id,
+        execution.status as _,
+        execution.stdout,
+        execution.stderr,
+        execution.error,
+        execution.exit_code,
+        execution.duration_ms,
+        execution.compile as _,
+        execution.case_results as _,
+        Utc::now(),
+        execution.attempts
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to finish execution ({})", e))?;
+    Ok(result.rows_affected() > 0)
+}
+
+/// Queues a claimed execution again, returning `false` if the claim was lost.
+pub async fn requeue_execution(pool: &PgPool, execution: &Execution) -> CaraiResult<bool> {
+    let result = sqlx::query!(
+        r#"
+        UPDATE executions
+        SET status = 'queued', locked_until = NULL, updated_at = $3
+        WHERE id = $1 AND status = 'running' AND attempts = $2
+        "#,
+        execution.id,
+        execution.attempts,
+        Utc::now()
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to requeue execution ({})", e))?;
+    Ok(result.rows_affected() > 0)
+}
+
+pub async fn get_executions_by_user_id(
+    pool: &PgPool,
+    user_id: Uuid,
+    language: Option<&str>,
+    status: Option<ExecutionStatus>,
+    limit: i64,
+    offset: i64,
+) -> CaraiResult<Vec<Execution>> {
+    sqlx::query_as!(
+        Execution,
+        r#"
+        SELECT
+            id, user_id, workspace_id, organization_id, language,
+            files AS "files: Json<Vec<ExecutionFile>>", files_hash,
+            stdin, status AS "status: ExecutionStatus", stdout, stderr, error, exit_code,
+            duration_ms, cached, compile AS "compile: Json<CompileOutput>",
+            judge AS "judge: Json<JudgeSpec>",
+            case_results AS "case_results: Json<Vec<CaseResult>>", attempts, locked_until,
+            started_at, finished_at, created_at, updated_at
+        FROM executions
+        WHERE user_id = $1
+            AND ($2::TEXT IS NULL OR language = $2)
+            AND ($3::execution_status IS NULL OR status = $3)
+        ORDER BY created_at DESC
+        LIMIT $4 OFFSET $5
+        "#,
+        user_id,
+        language,
+        status as _,
+        limit,
+        offset
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to get executions by user ID ({})", e))
+}
+
+pub async fn delete_executions_finished_before(
+    pool: &PgPool,
+    before: DateTime<Utc>,
+) -> CaraiResult<u64> {
+    let result = sqlx::query!(
+        r#"
+        DELETE FROM executions
+        WHERE status IN ('completed', 'failed') AND finished_at < $1
+        "#,
+        before
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to delete finished executions ({})", e))?;
+    Ok(result.rows_affected())
+}
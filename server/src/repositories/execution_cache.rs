@@ -0,0 +1,86 @@
+use anyhow::anyhow;
+use chrono::{DateTime, Utc};
+use sqlx::{types::Json, PgPool};
+
+use crate::{
+    models::{CachedExecution, CompileOutput},
+    utils::CaraiResult,
+};
+
+pub async fn upsert_cached_execution(
+    pool: &PgPool,
+    cached: &CachedExecution,
+) -> CaraiResult<CachedExecution> {
+    sqlx::query_as!(
+        CachedExecution,
+        r#"
+        INSERT INTO execution_cache (
+            key, language, stdout, stderr, error, exit_code,
+            duration_ms, compile, hits, expires_at, created_at
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+        ON CONFLICT (key) DO UPDATE
+        SET stdout = EXCLUDED.stdout, stderr = EXCLUDED.stderr, error = EXCLUDED.error,
+            exit_code = EXCLUDED.exit_code, duration_ms = EXCLUDED.duration_ms,
+            compile = EXCLUDED.compile, expires_at = EXCLUDED.expires_at,
+            created_at = EXCLUDED.created_at
+        RETURNING
+            key, language, stdout, stderr, error, exit_code, duration_ms,
+            compile AS "compile: Json<CompileOutput>", hits, expires_at, created_at
+        "#,
+        cached.key,
+        cached.language,
+        cached.stdout,
+        cached.stderr,
+        cached.error,
+        cached.exit_code,
+        cached.duration_ms,
+        cached.compile as _,
+        cached.hits,
+        cached.expires_at,
+        cached.created_at
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to cache execution ({})", e))
+}
+
+/// Fetches a live cache entry and counts the lookup as a hit.
+pub async fn hit_cached_execution(
+    pool: &PgPool,
+    key: &str,
+) -> CaraiResult<Option<CachedExecution>> {
+    sqlx::query_as!(
+        CachedExecution,
+        r#"
+        UPDATE execution_cache
+        SET hits = hits + 1
+        WHERE key = $1 AND expires_at > $2
+        RETURNING
+            key, language, stdout, stderr, error, exit_code, duration_ms,
+            compile AS "compile: Json<CompileOutput>", hits, expires_at, created_at
+        "#,
+        key,
+        Utc::now()
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to get cached execution ({})", e))
+}
+
+pub async fn delete_cached_executions_expired_before(
+    pool: &PgPool,
+    before: DateTime<Utc>,
+) -> CaraiResult<u64> {
+    let result = sqlx::query!(
+        r#"
+        DELETE FROM execution_cache
+        WHERE expires_at < $1
+        "#,
+        before
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to delete expired cache entries ({})", e))?;
+    Ok(result.rows_affected())
+}
@@ -0,0 +1,113 @@
+use anyhow::anyhow;
+use chrono::{DateTime, Utc};
+use sqlx::{PgExecutor, PgPool};
+use uuid::Uuid;
+
+use crate::{models::FileRevision, utils::CaraiResult};
+
+pub async fn create_file_revision<'e>(
+    executor: impl PgExecutor<'e>,
+    revision: &FileRevision,
+) -> CaraiResult<FileRevision> {
+    sqlx::query_as!(
+        FileRevision,
+        r#"
+        INSERT INTO file_revisions (id, file_id, number, path, blob_hash, author_id, created_at)
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        RETURNING *
+        "#,
+        revision.id,
+        revision.file_id,
+        revision.number,
+        revision.path,
+        revision.blob_hash,
+        revision.author_id,
+        revision.created_at
+    )
+    .fetch_one(executor)
+    .await
+    .map_err(|e| anyhow!("Unable to create file revision ({})", e))
+}
+
+pub async fn get_file_revision_by_number(
+    pool: &PgPool,
+    file_id: Uuid,
+    number: i32,
+) -> CaraiResult<Option<FileRevision>> {
+    sqlx::query_as!(
+        FileRevision,
+        r#"
+        SELECT * FROM file_revisions
+        WHERE file_id = $1 AND number = $2
+        "#,
+        file_id,
+        number
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to get file revision by number ({})", e))
+}
+
+pub async fn get_latest_file_revision<'e>(
+    executor: impl PgExecutor<'e>,
+    file_id: Uuid,
+) -> CaraiResult<Option<FileRevision>> {
+    sqlx::query_as!(
+        FileRevision,
+        r#"
+        SELECT * FROM file_revisions
+        WHERE file_id = $1
+        ORDER BY number DESC
+        LIMIT 1
+        "#,
+        file_id
+    )
+    .fetch_optional(executor)
+    .await
+    .map_err(|e| anyhow!("Unable to get latest file revision ({})", e))
+}
+
+/// Gets the revision a file was at, at the given moment.
+pub async fn get_file_revision_at(
+    pool: &PgPool,
+    file_id: Uuid,
+    at: DateTime<Utc>,
+) -> CaraiResult<Option<FileRevision>> {
+    sqlx::query_as!(
+        FileRevision,
+        r#"
+        SELECT * FROM file_revisions
+        WHERE file_id = $1 AND created_at <= $2
+        ORDER BY number DESC
+        LIMIT 1
+        "#,
+        file_id,
+        at
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to get file revision at {} ({})", at, e))
+}
+
+pub async fn get_file_revisions(
+    pool: &PgPool,
+    file_id: Uuid,
+    limit: i64,
+    offset: i64,
+) -> CaraiResult<Vec<FileRevision>> {
+    sqlx::query_as!(
+        FileRevision,
+        r#"
+        SELECT * FROM file_revisions
+        WHERE file_id = $1
+        ORDER BY number DESC
+        LIMIT $2 OFFSET $3
+        "#,
+        file_id,
+        limit,
+        offset
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to get file revisions ({})", e))
+}
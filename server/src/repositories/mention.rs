@@ -0,0 +1,94 @@
+use anyhow::anyhow;
+use chrono::Utc;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{models::Mention, utils::CaraiResult};
+
+/// Records mentions of the given usernames, skipping the author and anybody who
+/// is neither the owner nor a member of the workspace.
+pub async fn create_mentions(
+    pool: &PgPool,
+    workspace_id: Uuid,
+    author_id: Uuid,
+    usernames: &[String],
+    comment_id: Option<Uuid>,
+    chat_message_id: Option<Uuid>,
+) -> CaraiResult<Vec<Mention>> {
+    sqlx::query_as!(
+        Mention,
+        r#"
+        INSERT INTO mentions (
+            id, user_id, workspace_id, author_id, comment_id, chat_message_id, created_at
+        )
+        SELECT gen_random_uuid(), u.id, w.id, $2, $4, $5, $6
+        FROM users u
+        JOIN workspaces w ON w.id = $1
+        WHERE u.username = ANY($3) AND u.id <> $2
+            AND (
+                u.id = w.owner_id
+                OR EXISTS (
+                    SELECT 1 FROM workspace_members m
+                    WHERE m.workspace_id = w.id AND m.user_id = u.id
+                )
+                OR EXISTS (
+                    SELECT 1 FROM organization_members o
+                    WHERE o.organization_id = w.organization_id AND o.user_id = u.id
+                )
+            )
+        RETURNING *
+        "#,
+        workspace_id,
+        author_id,
+        usernames,
+        comment_id,
+        chat_message_id,
+        Utc::now()
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to create mentions ({})", e))
+}
+
+pub async fn get_mentions_by_user_id(
+    pool: &PgPool,
+    user_id: Uuid,
+    unread_only: bool,
+    limit: i64,
+    offset: i64,
+) -> CaraiResult<Vec<Mention>> {
+    sqlx::query_as!(
+        Mention,
+        r#"
+        SELECT * FROM mentions
+        WHERE user_id = $1 AND (NOT $2 OR read_at IS NULL)
+        ORDER BY created_at DESC
+        LIMIT $3 OFFSET $4
+        "#,
+        user_id,
+        unread_only,
+        limit,
+        offset
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to get mentions by user ID ({})", e))
+}
+
+/// Marks a user's mention as read, returning whether it existed.
+pub async fn mark_mention_read(pool: &PgPool, user_id: Uuid, id: Uuid) -> CaraiResult<bool> {
+    let result = sqlx::query!(
+        r#"
+        UPDATE mentions
+        SET read_at = COALESCE(read_at, $3)
+        WHERE id = $1 AND user_id = $2
+        "#,
+        id,
+        user_id,
+        Utc::now()
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to mark mention as read ({})", e))?;
+    Ok(result.rows_affected() > 0)
+}
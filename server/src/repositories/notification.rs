@@ -0,0 +1,194 @@
+use anyhow::anyhow;
+use chrono::Utc;
+use serde_json::Value;
+use sqlx::{types::Json, PgPool};
+use uuid::Uuid;
+
+use crate::{
+    models::{Notification, NotificationCategory},
+    utils::CaraiResult,
+};
+
+/// Postgres channel that newly created notifications are announced on.
+pub const NOTIFICATIONS_CHANNEL: &str = "notifications";
+
+pub async fn create_notification(pool: &PgPool, notification: &Notification) -> CaraiResult<()> {
+    sqlx::query!(
+        r#"
+        INSERT INTO notifications (id, user_id, category, title, body, data, read_at, created_at)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+        "#,
+        notification.id,
+        notification.user_id,
+        notification.category as _,
+        notification.title,
+        notification.body,
+        notification.data as _,
+        notification.read_at,
+        notification.created_at
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to create notification ({})", e))?;
+    Ok(())
+}
+
+pub async fn get_notifications_by_user_id(
+    pool: &PgPool,
+    user_id: Uuid,
+    unread_only: bool,
+    category: Option<NotificationCategory>,
+    limit: i64,
+    offset: i64,
+) -> CaraiResult<Vec<Notification>> {
+    sqlx::query_as!(
+        Notification,
+        r#"
+        SELECT
+            id, user_id, category AS "category: NotificationCategory", title, body,
+            data AS "data: Json<Value>", read_at, created_at
+        FROM notifications
+        WHERE user_id = $1
+            AND (NOT $2 OR read_at IS NULL)
+            AND ($3::notification_category IS NULL OR category = $3)
+        ORDER BY created_at DESC
+        LIMIT $4 OFFSET $5
+        "#,
+        user_id,
+        unread_only,
+        category as _,
+        limit,
+        offset
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to get notifications by user ID ({})", e))
+}
+
+pub async fn count_unread_notifications(pool: &PgPool, user_id: Uuid) -> CaraiResult<i64> {
+    sqlx::query_scalar!(
+        r#"
+        SELECT COUNT(*) AS "count!" FROM notifications
+        WHERE user_id = $1 AND read_at IS NULL
+        "#,
+        user_id
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to count unread notifications ({})", e))
+}
+
+/// Marks a user's notification as read, returning whether it existed.
+pub async fn mark_notification_read(pool: &PgPool, user_id: Uuid, id: Uuid) -> CaraiResult<bool> {
+    let result = sqlx::query!(
+        r#"
+        UPDATE notifications
+        SET read_at = COALESCE(read_at, $3)
+        WHERE id = $1 AND user_id = $2
+        "#,
+        id,
+        user_id,
+        Utc::now()
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to mark notification as read ({})", e))?;
+    Ok(result.rows_affected() > 0)
+}
+
+pub async fn mark_all_notifications_read(pool: &PgPool, user_id: Uuid) -> CaraiResult<()> {
+    sqlx::query!(
+        r#"
+        UPDATE notifications
+        SET read_at = $2
+        WHERE user_id = $1 AND read_at IS NULL
+        "#,
+        user_id,
+        Utc::now()
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to mark all notifications as read ({})", e))?;
+    Ok(())
+}
+
+pub async fn get_muted_categories(
+    pool: &PgPool,
+    user_id: Uuid,
+) -> CaraiResult<Vec<NotificationCategory>> {
+    sqlx::query_scalar!(
+        r#"
+        SELECT category AS "category: NotificationCategory" FROM notification_mutes
+        WHERE user_id = $1
+        ORDER BY category
+        "#,
+        user_id
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to get muted notification categories ({})", e))
+}
+
+/// Replaces the set of categories a user has muted.
+pub async fn set_muted_categories(
+    pool: &PgPool,
+    user_id: Uuid,
+    categories: &[NotificationCategory],
+) -> CaraiResult<()> {
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| anyhow!("Unable to set muted notification categories ({})", e))?;
+    sqlx::query!("DELETE FROM notification_mutes WHERE user_id = $1", user_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| anyhow!("Unable to set muted notification categories ({})", e))?;
+    for category in categories {
+        sqlx::query!(
+            r#"
+            INSERT INTO notification_mutes (user_id, category)
+            VALUES ($1, $2)
+            ON CONFLICT DO NOTHING
+            "#,
+            user_id,
+            *category as _
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| anyhow!("Unable to set muted notification categories ({})", e))?;
+    }
+    tx.commit()
+        .await
+        .map_err(|e| anyhow!("Unable to set muted notification categories ({})", e))
+}
+
+pub async fn is_category_muted(
+    pool: &PgPool,
+    user_id: Uuid,
+    category: NotificationCategory,
+) -> CaraiResult<bool> {
+    sqlx::query_scalar!(
+        r#"
+        SELECT EXISTS (
+            SELECT 1 FROM notification_mutes WHERE user_id = $1 AND category = $2
+        ) AS "muted!"
+        "#,
+        user_id,
+        category as _
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to check muted notification category ({})", e))
+}
+
+/// Announces a stored notification on [`NOTIFICATIONS_CHANNEL`] so that every
+/// server instance can push it to the user's open streams.
+pub async fn publish_notification(pool: &PgPool, notification: &Notification) -> CaraiResult<()> {
+    let payload = serde_json::to_string(notification)
+        .map_err(|e| anyhow!("Unable to publish notification ({})", e))?;
+    sqlx::query!("SELECT pg_notify($1, $2)", NOTIFICATIONS_CHANNEL, payload)
+        .execute(pool)
+        .await
+        .map_err(|e| anyhow!("Unable to publish notification ({})", e))?;
+    Ok(())
+}
@@ -0,0 +1,256 @@
+use anyhow::anyhow;
+use chrono::Utc;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    models::{
+        Organization, OrganizationMember, OrganizationMemberProfile, OrganizationMembership,
+        OrganizationRole,
+    },
+    utils::CaraiResult,
+};
+
+pub async fn create_organization(
+    pool: &PgPool,
+    organization: &Organization,
+) -> CaraiResult<Organization> {
+    sqlx::query_as!(
+        Organization,
+        r#"
+        INSERT INTO organizations (
+            id, slug, name, executions_per_hour, cpu_seconds_per_day, concurrent_runs,
+            created_at, updated_at
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+        RETURNING *
+        "#,
+        organization.id,
+        organization.slug,
+        organization.name,
+        organization.executions_per_hour,
+        organization.cpu_seconds_per_day,
+        organization.concurrent_runs,
+        organization.created_at,
+        organization.updated_at
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to create organization ({})", e))
+}
+
+pub async fn get_organization_by_id(pool: &PgPool, id: Uuid) -> CaraiResult<Option<Organization>> {
+    sqlx::query_as!(
+        Organization,
+        r#"
+        SELECT * FROM organizations
+        WHERE id = $1
+        "#,
+        id
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to get organization by id ({})", e))
+}
+
+pub async fn get_organization_by_slug(
+    pool: &PgPool,
+    slug: &str,
+) -> CaraiResult<Option<Organization>> {
+    sqlx::query_as!(
+        Organization,
+        r#"
+        SELECT * FROM organizations
+        WHERE slug = $1
+        "#,
+        slug
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to get organization by slug ({})", e))
+}
+
+/// Lists the organizations a user belongs to, with their role in each.
+pub async fn get_organizations_by_member_id(
+    pool: &PgPool,
+    user_id: Uuid,
+) -> CaraiResult<Vec<OrganizationMembership>> {
+    sqlx::query_as!(
+        OrganizationMembership,
+        r#"
+        SELECT
+            o.id, o.slug, o.name, m.role AS "role: OrganizationRole", o.created_at
+        FROM organization_members m
+        JOIN organizations o ON o.id = m.organization_id
+        WHERE m.user_id = $1
+        ORDER BY o.name
+        "#,
+        user_id
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to get organizations by member ID ({})", e))
+}
+
+pub async fn update_organization(
+    pool: &PgPool,
+    organization: &Organization,
+) -> CaraiResult<Organization> {
+    sqlx::query_as!(
+        Organization,
+        r#"
+        UPDATE organizations
+        SET name = $2, executions_per_hour = $3, cpu_seconds_per_day = $4,
+            concurrent_runs = $5, updated_at = $6
+        WHERE id = $1
+        RETURNING *
+        "#,
+        organization.id,
+        organization.name,
+        organization.executions_per_hour,
+        organization.cpu_seconds_per_day,
+        organization.concurrent_runs,
+        Utc::now()
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to update organization ({})", e))
+}
+
+pub async fn delete_organization(pool: &PgPool, id: Uuid) -> CaraiResult<()> {
+    sqlx::query!(
+        r#"
+        DELETE FROM organizations
+        WHERE id = $1
+        "#,
+        id
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to delete organization ({})", e))?;
+    Ok(())
+}
+
+pub async fn create_organization_member(
+    pool: &PgPool,
+    member: &OrganizationMember,
+) -> CaraiResult<OrganizationMember> {
+    sqlx::query_as!(
+        OrganizationMember,
+        r#"
+        INSERT INTO organization_members (organization_id, user_id, role, created_at, updated_at)
+        VALUES ($1, $2, $3, $4, $5)
+        RETURNING
+            organization_id, user_id, role AS "role: OrganizationRole", created_at, updated_at
+        "#,
+        member.organization_id,
+        member.user_id,
+        member.role as _,
+        member.created_at,
+        member.updated_at
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to create organization member ({})", e))
+}
+
+pub async fn get_organization_member(
+    pool: &PgPool,
+    organization_id: Uuid,
+    user_id: Uuid,
+) -> CaraiResult<Option<OrganizationMember>> {
+    sqlx::query_as!(
+        OrganizationMember,
+        r#"
+        SELECT
+            organization_id, user_id, role AS "role: OrganizationRole", created_at, updated_at
+        FROM organization_members
+        WHERE organization_id = $1 AND user_id = $2
+        "#,
+        organization_id,
+        user_id
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to get organization member ({})", e))
+}
+
+/// Lists the members of an organization, most privileged first.
+pub async fn get_organization_member_profiles(
+    pool: &PgPool,
+    organization_id: Uuid,
+) -> CaraiResult<Vec<OrganizationMemberProfile>> {
+    sqlx::query_as!(
+        OrganizationMemberProfile,
+        r#"
+        SELECT
+            u.id AS user_id, u.username, u.avatar_url, m.role AS "role: OrganizationRole",
+            m.created_at
+        FROM organization_members m
+        JOIN users u ON u.id = m.user_id
+        WHERE m.organization_id = $1
+        ORDER BY m.role DESC, u.username
+        "#,
+        organization_id
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to get organization members ({})", e))
+}
+
+pub async fn count_organization_owners(pool: &PgPool, organization_id: Uuid) -> CaraiResult<i64> {
+    sqlx::query_scalar!(
+        r#"
+        SELECT COUNT(*) AS "count!" FROM organization_members
+        WHERE organization_id = $1 AND role = 'owner'
+        "#,
+        organization_id
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to count organization owners ({})", e))
+}
+
+pub async fn update_organization_member_role(
+    pool: &PgPool,
+    organization_id: Uuid,
+    user_id: Uuid,
+    role: OrganizationRole,
+) -> CaraiResult<OrganizationMember> {
+    sqlx::query_as!(
+        OrganizationMember,
+        r#"
+        UPDATE organization_members
+        SET role = $3, updated_at = $4
+        WHERE organization_id = $1 AND user_id = $2
+        RETURNING
+            organization_id, user_id, role AS "role: OrganizationRole", created_at, updated_at
+        "#,
+        organization_id,
+        user_id,
+        role as _,
+        Utc::now()
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to update organization member ({})", e))
+}
+
+pub async fn delete_organization_member(
+    pool: &PgPool,
+    organization_id: Uuid,
+    user_id: Uuid,
+) -> CaraiResult<()> {
+    sqlx::query!(
+        r#"
+        DELETE FROM organization_members
+        WHERE organization_id = $1 AND user_id = $2
+        "#,
+        organization_id,
+        user_id
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to delete organization member ({})", e))?;
+    Ok(())
+}
@@ -0,0 +1,101 @@
+use anyhow::anyhow;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    models::{SearchFilter, SearchHit, SearchTerms},
+    utils::CaraiResult,
+};
+
+/// Finds files whose content matches the search words, or whose path or content contain
+/// the search string, ranked by word relevance plus path similarity.
+///
+/// Workspace files are searched when the user owns or belongs to the workspace, and
+/// snippet files when the snippet is public and open, or the user's own.
+pub async fn search_files(
+    pool: &PgPool,
+    terms: &SearchTerms,
+    user_id: Option<Uuid>,
+    filter: &SearchFilter,
+    limit: i64,
+    offset: i64,
+) -> CaraiResult<Vec<SearchHit>> {
+    sqlx::query_as!(
+        SearchHit,
+        r#"
+        WITH search AS (
+            SELECT websearch_to_tsquery('simple', $1) AS query, $2::TEXT AS pattern
+        )
+        SELECT
+            hit.workspace_id, hit.snippet_slug, hit.title AS "title!", hit.owner_id AS "owner_id!",
+            u.username AS owner_username, hit.language, hit.path AS "path!",
+            hit.content AS "content!", hit.rank AS "rank!"
+        FROM (
+            SELECT
+                w.id AS workspace_id, NULL::TEXT AS snippet_slug, w.name AS title, w.owner_id,
+                w.language, f.path, f.content,
+                ts_rank(to_tsvector('simple', f.content), s.query) + similarity(f.path, $1) AS rank
+            FROM workspace_files f
+            JOIN workspaces w ON w.id = f.workspace_id
+            CROSS JOIN search s
+            WHERE (
+                    w.owner_id = $4
+                    OR EXISTS (
+                        SELECT 1 FROM workspace_members m
+                        WHERE m.workspace_id = w.id AND m.user_id = $4
+                    )
+                    OR EXISTS (
+                        SELECT 1 FROM organization_members o
+                        WHERE o.organization_id = w.organization_id AND o.user_id = $4
+                    )
+                )
+                AND (
+                    to_tsvector('simple', f.content) @@ s.query
+                    OR f.content ILIKE s.pattern
+                    OR f.path ILIKE s.pattern
+                )
+                AND ($5::TEXT IS NULL OR w.language = $5)
+                AND ($6::UUID IS NULL OR w.id = $6)
+                AND ($7::UUID IS NULL OR w.owner_id = $7)
+            UNION ALL
+            SELECT
+                NULL::UUID, sn.slug, sn.title, sn.owner_id, sn.language,
+                file->>'name', file->>'content',
+                ts_rank(to_tsvector('simple', file->>'content'), s.query)
+                    + similarity(file->>'name', $1)
+            FROM snippets sn
+            CROSS JOIN search s
+            CROSS JOIN jsonb_array_elements(sn.files) AS file
+            WHERE $6::UUID IS NULL
+                AND (
+                    (sn.visibility = 'public' AND sn.password_hash IS NULL)
+                    OR sn.owner_id = $4
+                )
+                AND (sn.expires_at IS NULL OR sn.expires_at > NOW())
+                AND (to_tsvector('simple', sn.files) @@ s.query OR sn.files::TEXT ILIKE $3)
+                AND (
+                    to_tsvector('simple', file->>'content') @@ s.query
+                    OR file->>'content' ILIKE s.pattern
+                    OR file->>'name' ILIKE s.pattern
+                )
+                AND ($5::TEXT IS NULL OR sn.language = $5)
+                AND ($7::UUID IS NULL OR sn.owner_id = $7)
+        ) hit
+        JOIN users u ON u.id = hit.owner_id
+        ORDER BY hit.rank DESC, hit.title, hit.path
+        LIMIT $8 OFFSET $9
+        "#,
+        terms.query,
+        terms.pattern,
+        terms.json_pattern,
+        user_id,
+        filter.language,
+        filter.workspace_id,
+        filter.owner_id,
+        limit,
+        offset
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to search files ({})", e))
+}
@@ -0,0 +1,127 @@
+use anyhow::anyhow;
+use chrono::{DateTime, Utc};
+use sqlx::{PgExecutor, PgPool};
+use uuid::Uuid;
+
+use crate::{
+    models::{SessionEvent, SessionEventKind},
+    utils::CaraiResult,
+};
+
+pub async fn create_session_event<'e>(
+    executor: impl PgExecutor<'e>,
+    event: &SessionEvent,
+) -> CaraiResult<SessionEvent> {
+    sqlx::query_as!(
+        SessionEvent,
+        r#"
+        INSERT INTO session_events (
+            workspace_id, file_id, user_id, kind, seq, data, execution_id, recorded_at
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+        RETURNING
+            id, workspace_id, file_id, user_id, kind AS "kind: SessionEventKind", seq, data,
+            execution_id, recorded_at
+        "#,
+        event.workspace_id,
+        event.file_id,
+        event.user_id,
+        event.kind as _,
+        event.seq,
+        event.data,
+        event.execution_id,
+        event.recorded_at
+    )
+    .fetch_one(executor)
+    .await
+    .map_err(|e| anyhow!("Unable to create session event ({})", e))
+}
+
+/// Lists the events of a workspace recorded within `[from, to]`, oldest first.
+///
+/// Events are paged by ID: pass the last ID received as `after` to continue.
+pub async fn get_session_events(
+    pool: &PgPool,
+    workspace_id: Uuid,
+    file_id: Option<Uuid>,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+    after: i64,
+    limit: i64,
+) -> CaraiResult<Vec<SessionEvent>> {
+    sqlx::query_as!(
+        SessionEvent,
+        r#"
+        SELECT
+            id, workspace_id, file_id, user_id, kind AS "kind: SessionEventKind", seq, data,
+            execution_id, recorded_at
+        FROM session_events
+        WHERE workspace_id = $1
+            AND ($2::UUID IS NULL OR file_id = $2)
+            AND recorded_at BETWEEN $3 AND $4
+            AND id > $5
+        ORDER BY id
+        LIMIT $6
+        "#,
+        workspace_id,
+        file_id,
+        from,
+        to,
+        after,
+        limit
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to get session events ({})", e))
+}
+
+/// Gets the snapshot recorded by `at` that covers the most edits of a file.
+pub async fn get_session_snapshot_at(
+    pool: &PgPool,
+    file_id: Uuid,
+    at: DateTime<Utc>,
+) -> CaraiResult<Option<SessionEvent>> {
+    sqlx::query_as!(
+        SessionEvent,
+        r#"
+        SELECT
+            id, workspace_id, file_id, user_id, kind AS "kind: SessionEventKind", seq, data,
+            execution_id, recorded_at
+        FROM session_events
+        WHERE file_id = $1 AND kind = 'snapshot' AND recorded_at <= $2
+        ORDER BY seq DESC
+        LIMIT 1
+        "#,
+        file_id,
+        at
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to get session snapshot ({})", e))
+}
+
+/// Lists the edits of a file after sequence number `seq` that were recorded by `at`.
+pub async fn get_session_edits_after(
+    pool: &PgPool,
+    file_id: Uuid,
+    seq: i64,
+    at: DateTime<Utc>,
+) -> CaraiResult<Vec<SessionEvent>> {
+    sqlx::query_as!(
+        SessionEvent,
+        r#"
+        SELECT
+            id, workspace_id, file_id, user_id, kind AS "kind: SessionEventKind", seq, data,
+            execution_id, recorded_at
+        FROM session_events
+        WHERE file_id = $1 AND kind = 'edit' AND seq > $2 AND recorded_at <= $3
+        ORDER BY seq
+        "#,
+        file_id,
+        seq,
+        at
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to get session edits ({})", e))
+}
@@ -0,0 +1,186 @@
+use anyhow::anyhow;
+use chrono::{DateTime, Utc};
+use sqlx::{types::Json, PgPool};
+use uuid::Uuid;
+
+use crate::{
+    models::{Snippet, SnippetFile, SnippetVisibility},
+    utils::CaraiResult,
+};
+
+pub async fn create_snippet(pool: &PgPool, snippet: &Snippet) -> CaraiResult<Snippet> {
+    sqlx::query_as!(
+        Snippet,
+        r#"
+        INSERT INTO snippets (
+            id, owner_id, slug, title, language, files, visibility,
+            password_hash, expires_at, created_at, updated_at
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+        RETURNING
+            id, owner_id, slug, title, language, files AS "files: Json<Vec<SnippetFile>>",
+            visibility AS "visibility: SnippetVisibility", password_hash, expires_at,
+            fork_count, created_at, updated_at
+        "#,
+        snippet.id,
+        snippet.owner_id,
+        snippet.slug,
+        snippet.title,
+        snippet.language,
+        snippet.files as _,
+        snippet.visibility as _,
+        snippet.password_hash,
+        snippet.expires_at,
+        snippet.created_at,
+        snippet.updated_at
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to create snippet ({})", e))
+}
+
+pub async fn get_snippet_by_slug(pool: &PgPool, slug: &str) -> CaraiResult<Option<Snippet>> {
+    sqlx::query_as!(
+        Snippet,
+        r#"
+        SELECT
+            id, owner_id, slug, title, language, files AS "files: Json<Vec<SnippetFile>>",
+            visibility AS "visibility: SnippetVisibility", password_hash, expires_at,
+            fork_count, created_at, updated_at
+        FROM snippets
+        WHERE slug = $1
+        "#,
+        slug
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to get snippet by slug ({})", e))
+}
+
+pub async fn get_snippets_by_owner_id(
+    pool: &PgPool,
+    owner_id: Uuid,
+    limit: i64,
+    offset: i64,
+) -> CaraiResult<Vec<Snippet>> {
+    sqlx::query_as!(
+        Snippet,
+        r#"
+        SELECT
+            id, owner_id, slug, title, language, files AS "files: Json<Vec<SnippetFile>>",
+            visibility AS "visibility: SnippetVisibility", password_hash, expires_at,
+            fork_count, created_at, updated_at
+        FROM snippets
+        WHERE owner_id = $1
+        ORDER BY created_at DESC
+        LIMIT $2 OFFSET $3
+        "#,
+        owner_id,
+        limit,
+        offset
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to get snippets by owner ID ({})", e))
+}
+
+/// Lists public snippets that anyone can open without a password.
+pub async fn get_public_snippets(
+    pool: &PgPool,
+    limit: i64,
+    offset: i64,
+) -> CaraiResult<Vec<Snippet>> {
+    sqlx::query_as!(
+        Snippet,
+        r#"
+        SELECT
+            id, owner_id, slug, title, language, files AS "files: Json<Vec<SnippetFile>>",
+            visibility AS "visibility: SnippetVisibility", password_hash, expires_at,
+            fork_count, created_at, updated_at
+        FROM snippets
+        WHERE visibility = 'public' AND password_hash IS NULL
+            AND (expires_at IS NULL OR expires_at > $1)
+        ORDER BY created_at DESC
+        LIMIT $2 OFFSET $3
+        "#,
+        Utc::now(),
+        limit,
+        offset
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to get public snippets ({})", e))
+}
+
+pub async fn update_snippet(pool: &PgPool, snippet: &Snippet) -> CaraiResult<Snippet> {
+    sqlx::query_as!(
+        Snippet,
+        r#"
+        UPDATE snippets
+        SET title = $2, language = $3, files = $4, visibility = $5, password_hash = $6,
+            expires_at = $7, updated_at = $8
+        WHERE id = $1
+        RETURNING
+            id, owner_id, slug, title, language, files AS "files: Json<Vec<SnippetFile>>",
+            visibility AS "visibility: SnippetVisibility", password_hash, expires_at,
+            fork_count, created_at, updated_at
+        "#,
+        snippet.id,
+        snippet.title,
+        snippet.language,
+        snippet.files as _,
+        snippet.visibility as _,
+        snippet.password_hash,
+        snippet.expires_at,
+        Utc::now()
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to update snippet ({})", e))
+}
+
+pub async fn increment_snippet_fork_count(pool: &PgPool, id: Uuid) -> CaraiResult<()> {
+    sqlx::query!(
+        r#"
+        UPDATE snippets
+        SET fork_count = fork_count + 1
+        WHERE id = $1
+        "#,
+        id
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to increment snippet fork count ({})", e))?;
+    Ok(())
+}
+
+pub async fn delete_snippet(pool: &PgPool, id: Uuid) -> CaraiResult<()> {
+    sqlx::query!(
+        r#"
+        DELETE FROM snippets
+        WHERE id = $1
+        "#,
+        id
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to delete snippet ({})", e))?;
+    Ok(())
+}
+
+pub async fn delete_snippets_expired_before(
+    pool: &PgPool,
+    before: DateTime<Utc>,
+) -> CaraiResult<u64> {
+    let result = sqlx::query!(
+        r#"
+        DELETE FROM snippets
+        WHERE expires_at < $1
+        "#,
+        before
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to delete expired snippets ({})", e))?;
+    Ok(result.rows_affected())
+}
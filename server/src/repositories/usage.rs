@@ -0,0 +1,166 @@
+use anyhow::anyhow;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    models::{ExecutionUsage, UsageTotals, UserUsageReport},
+    utils::CaraiResult,
+};
+
+/// Charges the time an attempt at an execution took, adding it to what earlier
+/// attempts at the same execution were charged.
+pub async fn record_execution_usage(
+    pool: &PgPool,
+    usage: &ExecutionUsage,
+) -> CaraiResult<ExecutionUsage> {
+    sqlx::query_as!(
+        ExecutionUsage,
+        r#"
+        INSERT INTO execution_usage (
+            id, user_id, execution_id, organization_id, language, cpu_ms, created_at
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        ON CONFLICT (execution_id) DO UPDATE
+        SET cpu_ms = execution_usage.cpu_ms + EXCLUDED.cpu_ms
+        RETURNING *
+        "#,
+        usage.id,
+        usage.user_id,
+        usage.execution_id,
+        usage.organization_id,
+        usage.language,
+        usage.cpu_ms,
+        usage.created_at
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to record execution usage ({})", e))
+}
+
+/// Sums up the usage a user is charged for, leaving out what organizations are billed.
+pub async fn get_usage_totals(
+    pool: &PgPool,
+    user_id: Uuid,
+    executions_since: DateTime<Utc>,
+    cpu_since: DateTime<Utc>,
+) -> CaraiResult<UsageTotals> {
+    sqlx::query_as!(
+        UsageTotals,
+        r#"
+        SELECT
+            (SELECT COUNT(*) FROM executions
+             WHERE user_id = $1 AND organization_id IS NULL
+                AND created_at >= $2) AS "executions_since!",
+            (SELECT MIN(created_at) FROM executions
+             WHERE user_id = $1 AND organization_id IS NULL
+                AND created_at >= $2) AS oldest_execution_at,
+            (SELECT COALESCE(SUM(cpu_ms), 0)::BIGINT FROM execution_usage
+             WHERE user_id = $1 AND organization_id IS NULL
+                AND created_at >= $3) AS "cpu_ms_since!",
+            (SELECT COUNT(*) FROM executions
+             WHERE user_id = $1 AND organization_id IS NULL
+                AND status IN ('queued', 'running')) AS "active_runs!"
+        "#,
+        user_id,
+        executions_since,
+        cpu_since
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to get usage totals ({})", e))
+}
+
+/// Sums up the usage billed to an organization, whoever of its members ran it.
+pub async fn get_organization_usage_totals(
+    pool: &PgPool,
+    organization_id: Uuid,
+    executions_since: DateTime<Utc>,
+    cpu_since: DateTime<Utc>,
+) -> CaraiResult<UsageTotals> {
+    sqlx::query_as!(
+        UsageTotals,
+        r#"
+        SELECT
+            (SELECT COUNT(*) FROM executions
+             WHERE organization_id = $1 AND created_at >= $2) AS "executions_since!",
+            (SELECT MIN(created_at) FROM executions
+             WHERE organization_id = $1 AND created_at >= $2) AS oldest_execution_at,
+            (SELECT COALESCE(SUM(cpu_ms), 0)::BIGINT FROM execution_usage
+             WHERE organization_id = $1 AND created_at >= $3) AS "cpu_ms_since!",
+            (SELECT COUNT(*) FROM executions
+             WHERE organization_id = $1 AND status IN ('queued', 'running')) AS "active_runs!"
+        "#,
+        organization_id,
+        executions_since,
+        cpu_since
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to get organization usage totals ({})", e))
+}
+
+pub async fn get_usage_report(
+    pool: &PgPool,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+    limit: i64,
+    offset: i64,
+) -> CaraiResult<Vec<UserUsageReport>> {
+    sqlx::query_as!(
+        UserUsageReport,
+        r#"
+        SELECT
+            u.id AS user_id, u.username,
+            COUNT(eu.id) AS "executions!",
+            COALESCE(SUM(eu.cpu_ms), 0)::BIGINT AS "cpu_ms!"
+        FROM execution_usage eu
+        JOIN users u ON u.id = eu.user_id
+        WHERE eu.created_at >= $1 AND eu.created_at < $2
+        GROUP BY u.id, u.username
+        ORDER BY 4 DESC, 3 DESC
+        LIMIT $3 OFFSET $4
+        "#,
+        from,
+        to,
+        limit,
+        offset
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to get usage report ({})", e))
+}
+
+/// Breaks the usage billed to an organization down by member.
+pub async fn get_organization_usage_report(
+    pool: &PgPool,
+    organization_id: Uuid,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+    limit: i64,
+    offset: i64,
+) -> CaraiResult<Vec<UserUsageReport>> {
+    sqlx::query_as!(
+        UserUsageReport,
+        r#"
+        SELECT
+            u.id AS user_id, u.username,
+            COUNT(eu.id) AS "executions!",
+            COALESCE(SUM(eu.cpu_ms), 0)::BIGINT AS "cpu_ms!"
+        FROM execution_usage eu
+        JOIN users u ON u.id = eu.user_id
+        WHERE eu.organization_id = $1 AND eu.created_at >= $2 AND eu.created_at < $3
+        GROUP BY u.id, u.username
+        ORDER BY 4 DESC, 3 DESC
+        LIMIT $4 OFFSET $5
+        "#,
+        organization_id,
+        from,
+        to,
+        limit,
+        offset
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to get organization usage report ({})", e))
+}
@@ -0,0 +1,306 @@
+use anyhow::anyhow;
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+use sqlx::{types::Json, PgPool};
+use uuid::Uuid;
+
+use crate::{
+    models::{Webhook, WebhookDelivery, WebhookDeliveryStatus, WebhookEvent},
+    utils::CaraiResult,
+};
+
+pub async fn create_webhook(pool: &PgPool, webhook: &Webhook) -> CaraiResult<Webhook> {
+    sqlx::query_as!(
+        Webhook,
+        r#"
+        INSERT INTO webhooks (
+            id, user_id, organization_id, url, secret, events, active, created_at, updated_at
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+        RETURNING
+            id, user_id, organization_id, url, secret,
+            events AS "events: Json<Vec<WebhookEvent>>", active, created_at, updated_at
+        "#,
+        webhook.id,
+        webhook.user_id,
+        webhook.organization_id,
+        webhook.url,
+        webhook.secret,
+        webhook.events as _,
+        webhook.active,
+        webhook.created_at,
+        webhook.updated_at
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to create webhook ({})", e))
+}
+
+pub async fn get_webhook_by_id(pool: &PgPool, id: Uuid) -> CaraiResult<Option<Webhook>> {
+    sqlx::query_as!(
+        Webhook,
+        r#"
+        SELECT
+            id, user_id, organization_id, url, secret,
+            events AS "events: Json<Vec<WebhookEvent>>", active, created_at, updated_at
+        FROM webhooks
+        WHERE id = $1
+        "#,
+        id
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to get webhook by ID ({})", e))
+}
+
+/// Lists the webhooks of a user, or of an organization when `organization_id` is given.
+pub async fn get_webhooks_by_owner(
+    pool: &PgPool,
+    user_id: Option<Uuid>,
+    organization_id: Option<Uuid>,
+) -> CaraiResult<Vec<Webhook>> {
+    sqlx::query_as!(
+        Webhook,
+        r#"
+        SELECT
+            id, user_id, organization_id, url, secret,
+            events AS "events: Json<Vec<WebhookEvent>>", active, created_at, updated_at
+        FROM webhooks
+        WHERE user_id IS NOT DISTINCT FROM $1 AND organization_id IS NOT DISTINCT FROM $2
+        ORDER BY created_at
+        "#,
+        user_id,
+        organization_id
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to get webhooks by owner ({})", e))
+}
+
+pub async fn update_webhook(pool: &PgPool, webhook: &Webhook) -> CaraiResult<Webhook> {
+    sqlx::query_as!(
+        Webhook,
+        r#"
+        UPDATE webhooks
+        SET url = $2, secret = $3, events = $4, active = $5, updated_at = $6
+        WHERE id = $1
+        RETURNING
+            id, user_id, organization_id, url, secret,
+            events AS "events: Json<Vec<WebhookEvent>>", active, created_at, updated_at
+        "#,
+        webhook.id,
+        webhook.url,
+        webhook.secret,
+        webhook.events as _,
+        webhook.active,
+        Utc::now()
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to update webhook ({})", e))
+}
+
+pub async fn delete_webhook(pool: &PgPool, id: Uuid) -> CaraiResult<()> {
+    sqlx::query!("DELETE FROM webhooks WHERE id = $1", id)
+        .execute(pool)
+        .await
+        .map_err(|e| anyhow!("Unable to delete webhook ({})", e))?;
+    Ok(())
+}
+
+/// Queues a delivery of the payload for every active webhook subscribed to the
+/// event that belongs to one of the users or to the organization.
+pub async fn enqueue_webhook_deliveries(
+    pool: &PgPool,
+    event: WebhookEvent,
+    payload: &Value,
+    user_ids: &[Uuid],
+    organization_id: Option<Uuid>,
+) -> CaraiResult<u64> {
+    let result = sqlx::query!(
+        r#"
+        INSERT INTO webhook_deliveries (
+            id, webhook_id, event, payload, status, attempts, next_attempt_at,
+            created_at, updated_at
+        )
+        SELECT gen_random_uuid(), w.id, $1, $2, 'pending', 0, $6, $6, $6
+        FROM webhooks w
+        WHERE w.active AND w.events ? $3
+            AND (w.user_id = ANY($4) OR w.organization_id = $5)
+        "#,
+        event as _,
+        payload,
+        event.to_string(),
+        user_ids,
+        organization_id,
+        Utc::now()
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to enqueue webhook deliveries ({})", e))?;
+    Ok(result.rows_affected())
+}
+
+pub async fn create_webhook_delivery(
+    pool: &PgPool,
+    delivery: &WebhookDelivery,
+) -> CaraiResult<WebhookDelivery> {
+    sqlx::query_as!(
+        WebhookDelivery,
+        r#"
+        INSERT INTO webhook_deliveries (
+            id, webhook_id, event, payload, status, attempts, next_attempt_at,
+            redelivery_of, created_at, updated_at
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+        RETURNING
+            id, webhook_id, event AS "event: WebhookEvent", payload AS "payload: Json<Value>",
+            status AS "status: WebhookDeliveryStatus", attempts, next_attempt_at, locked_until,
+            response_status, response_body, error, duration_ms, redelivery_of, finished_at,
+            created_at, updated_at
+        "#,
+        delivery.id,
+        delivery.webhook_id,
+        delivery.event as _,
+        delivery.payload as _,
+        delivery.status as _,
+        delivery.attempts,
+        delivery.next_attempt_at,
+        delivery.redelivery_of,
+        delivery.created_at,
+        delivery.updated_at
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to create webhook delivery ({})", e))
+}
+
+/// Leases the pending delivery that is due the longest, counting the attempt.
+///
+/// Deliveries whose worker died mid-attempt become claimable again once the lease expires.
+pub async fn claim_next_webhook_delivery(
+    pool: &PgPool,
+    locked_until: DateTime<Utc>,
+) -> CaraiResult<Option<WebhookDelivery>> {
+    sqlx::query_as!(
+        WebhookDelivery,
+        r#"
+        UPDATE webhook_deliveries
+        SET attempts = attempts + 1, locked_until = $1, updated_at = $2
+        WHERE id = (
+            SELECT id FROM webhook_deliveries
+            WHERE status = 'pending' AND next_attempt_at <= $2
+                AND (locked_until IS NULL OR locked_until < $2)
+            ORDER BY next_attempt_at
+            LIMIT 1
+            FOR UPDATE SKIP LOCKED
+        )
+        RETURNING
+            id, webhook_id, event AS "event: WebhookEvent", payload AS "payload: Json<Value>",
+            status AS "status: WebhookDeliveryStatus", attempts, next_attempt_at, locked_until,
+            response_status, response_body, error, duration_ms, redelivery_of, finished_at,
+            created_at, updated_at
+        "#,
+        locked_until,
+        Utc::now()
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to claim webhook delivery ({})", e))
+}
+
+/// Records the outcome of an attempt and releases the lease.
+pub async fn update_webhook_delivery_attempt(
+    pool: &PgPool,
+    delivery: &WebhookDelivery,
+) -> CaraiResult<()> {
+    sqlx::query!(
+        r#"
+        UPDATE webhook_deliveries
+        SET status = $2, next_attempt_at = $3, locked_until = NULL, response_status = $4,
+            response_body = $5, error = $6, duration_ms = $7, finished_at = $8, updated_at = $9
+        WHERE id = $1
+        "#,
+        delivery.id,
+        delivery.status as _,
+        delivery.next_attempt_at,
+        delivery.response_status,
+        delivery.response_body,
+        delivery.error,
+        delivery.duration_ms,
+        delivery.finished_at,
+        Utc::now()
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to update webhook delivery attempt ({})", e))?;
+    Ok(())
+}
+
+pub async fn get_webhook_deliveries(
+    pool: &PgPool,
+    webhook_id: Uuid,
+    status: Option<WebhookDeliveryStatus>,
+    limit: i64,
+    offset: i64,
+) -> CaraiResult<Vec<WebhookDelivery>> {
+    sqlx::query_as!(
+        WebhookDelivery,
+        r#"
+        SELECT
+            id, webhook_id, event AS "event: WebhookEvent", payload AS "payload: Json<Value>",
+            status AS "status: WebhookDeliveryStatus", attempts, next_attempt_at, locked_until,
+            response_status, response_body, error, duration_ms, redelivery_of, finished_at,
+            created_at, updated_at
+        FROM webhook_deliveries
+        WHERE webhook_id = $1 AND ($2::webhook_delivery_status IS NULL OR status = $2)
+        ORDER BY created_at DESC
+        LIMIT $3 OFFSET $4
+        "#,
+        webhook_id,
+        status as _,
+        limit,
+        offset
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to get webhook deliveries ({})", e))
+}
+
+pub async fn get_webhook_delivery_by_id(
+    pool: &PgPool,
+    webhook_id: Uuid,
+    id: Uuid,
+) -> CaraiResult<Option<WebhookDelivery>> {
+    sqlx::query_as!(
+        WebhookDelivery,
+        r#"
+        SELECT
+            id, webhook_id, event AS "event: WebhookEvent", payload AS "payload: Json<Value>",
+            status AS "status: WebhookDeliveryStatus", attempts, next_attempt_at, locked_until,
+            response_status, response_body, error, duration_ms, redelivery_of, finished_at,
+            created_at, updated_at
+        FROM webhook_deliveries
+        WHERE id = $1 AND webhook_id = $2
+        "#,
+        id,
+        webhook_id
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to get webhook delivery by ID ({})", e))
+}
+
+pub async fn delete_webhook_deliveries_finished_before(
+    pool: &PgPool,
+    before: DateTime<Utc>,
+) -> CaraiResult<u64> {
+    let result = sqlx::query!(
+        "DELETE FROM webhook_deliveries WHERE finished_at < $1",
+        before
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to delete webhook deliveries ({})", e))?;
+    Ok(result.rows_affected())
+}
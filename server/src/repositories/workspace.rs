@@ -0,0 +1,212 @@
+use anyhow::anyhow;
+use chrono::Utc;
+use sqlx::{PgExecutor, PgPool};
+use uuid::Uuid;
+
+use crate::{models::Workspace, utils::CaraiResult};
+
+pub async fn create_workspace<'e>(
+    executor: impl PgExecutor<'e>,
+    workspace: &Workspace,
+) -> CaraiResult<Workspace> {
+    sqlx::query_as!(
+        Workspace,
+        r#"
+        INSERT INTO workspaces (
+            id, owner_id, name, language, organization_id, forked_from_workspace_id,
+            forked_from_snippet_id, template_id, created_at, updated_at
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+        RETURNING *
+        "#,
+        workspace.id,
+        workspace.owner_id,
+        workspace.name,
+        workspace.language,
+        workspace.organization_id,
+        workspace.forked_from_workspace_id,
+        workspace.forked_from_snippet_id,
+        workspace.template_id,
+        workspace.created_at,
+        workspace.updated_at
+    )
+    .fetch_one(executor)
+    .await
+    .map_err(|e| anyhow!("Unable to create workspace ({})", e))
+}
+
+pub async fn get_workspace_by_id(pool: &PgPool, id: Uuid) -> CaraiResult<Option<Workspace>> {
+    sqlx::query_as!(
+        Workspace,
+        r#"
+        SELECT * FROM workspaces
+        WHERE id = $1
+        "#,
+        id
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to get workspace by id ({})", e))
+}
+
+pub async fn get_workspace_by_name(
+    pool: &PgPool,
+    owner_id: Uuid,
+    name: &str,
+) -> CaraiResult<Option<Workspace>> {
+    sqlx::query_as!(
+        Workspace,
+        r#"
+        SELECT * FROM workspaces
+        WHERE owner_id = $1 AND name = $2
+        "#,
+        owner_id,
+        name
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to get workspace by name ({})", e))
+}
+
+/// Lists the workspaces a user owns or is a member of, directly or through their
+/// organization, most recently updated first.
+pub async fn get_workspaces_by_member_id(
+    pool: &PgPool,
+    user_id: Uuid,
+    limit: i64,
+    offset: i64,
+) -> CaraiResult<Vec<Workspace>> {
+    sqlx::query_as!(
+        Workspace,
+        r#"
+        SELECT * FROM workspaces w
+        WHERE w.owner_id = $1
+            OR EXISTS (
+                SELECT 1 FROM workspace_members m
+                WHERE m.workspace_id = w.id AND m.user_id = $1
+            )
+            OR EXISTS (
+                SELECT 1 FROM organization_members o
+                WHERE o.organization_id = w.organization_id AND o.user_id = $1
+            )
+        ORDER BY updated_at DESC
+        LIMIT $2 OFFSET $3
+        "#,
+        user_id,
+        limit,
+        offset
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to get workspaces by member ID ({})", e))
+}
+
+pub async fn get_workspaces_by_organization_id(
+    pool: &PgPool,
+    organization_id: Uuid,
+    limit: i64,
+    offset: i64,
+) -> CaraiResult<Vec<Workspace>> {
+    sqlx::query_as!(
+        Workspace,
+        r#"
+        SELECT * FROM workspaces
+        WHERE organization_id = $1
+        ORDER BY updated_at DESC
+        LIMIT $2 OFFSET $3
+        "#,
+        organization_id,
+        limit,
+        offset
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to get workspaces by organization ID ({})", e))
+}
+
+pub async fn update_workspace(pool: &PgPool, workspace: &Workspace) -> CaraiResult<Workspace> {
+    sqlx::query_as!(
+        Workspace,
+        r#"
+        UPDATE workspaces
+        SET name = $2, language = $3, updated_at = $4
+        WHERE id = $1
+        RETURNING *
+        "#,
+        workspace.id,
+        workspace.name,
+        workspace.language,
+        Utc::now()
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to update workspace ({})", e))
+}
+
+pub async fn update_workspace_owner(
+    pool: &PgPool,
+    id: Uuid,
+    owner_id: Uuid,
+) -> CaraiResult<Workspace> {
+    sqlx::query_as!(
+        Workspace,
+        r#"
+        UPDATE workspaces
+        SET owner_id = $2, updated_at = $3
+        WHERE id = $1
+        RETURNING *
+        "#,
+        id,
+        owner_id,
+        Utc::now()
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to update workspace owner ({})", e))
+}
+
+pub async fn increment_workspace_fork_count(pool: &PgPool, id: Uuid) -> CaraiResult<()> {
+    sqlx::query!(
+        r#"
+        UPDATE workspaces
+        SET fork_count = fork_count + 1
+        WHERE id = $1
+        "#,
+        id
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to increment workspace fork count ({})", e))?;
+    Ok(())
+}
+
+/// Bumps the workspace's `updated_at`, e.g. after one of its files changed.
+pub async fn touch_workspace<'e>(executor: impl PgExecutor<'e>, id: Uuid) -> CaraiResult<()> {
+    sqlx::query!(
+        r#"
+        UPDATE workspaces
+        SET updated_at = $2
+        WHERE id = $1
+        "#,
+        id,
+        Utc::now()
+    )
+    .execute(executor)
+    .await
+    .map_err(|e| anyhow!("Unable to touch workspace ({})", e))?;
+    Ok(())
+}
+
+pub async fn delete_workspace(pool: &PgPool, id: Uuid) -> CaraiResult<()> {
+    sqlx::query!(
+        r#"
+        DELETE FROM workspaces
+        WHERE id = $1
+        "#,
+        id
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to delete workspace ({})", e))?;
+    Ok(())
+}
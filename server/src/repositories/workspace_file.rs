@@ -0,0 +1,134 @@
+use anyhow::anyhow;
+use chrono::Utc;
+use sqlx::{PgExecutor, PgPool};
+use uuid::Uuid;
+
+use crate::{models::WorkspaceFile, utils::CaraiResult};
+
+pub async fn create_workspace_file<'e>(
+    executor: impl PgExecutor<'e>,
+    file: &WorkspaceFile,
+) -> CaraiResult<WorkspaceFile> {
+    sqlx::query_as!(
+        WorkspaceFile,
+        r#"
+        INSERT INTO workspace_files (id, workspace_id, path, content, created_at, updated_at)
+        VALUES ($1, $2, $3, $4, $5, $6)
+        RETURNING *
+        "#,
+        file.id,
+        file.workspace_id,
+        file.path,
+        file.content,
+        file.created_at,
+        file.updated_at
+    )
+    .fetch_one(executor)
+    .await
+    .map_err(|e| anyhow!("Unable to create workspace file ({})", e))
+}
+
+pub async fn get_workspace_file_by_id(
+    pool: &PgPool,
+    workspace_id: Uuid,
+    id: Uuid,
+) -> CaraiResult<Option<WorkspaceFile>> {
+    sqlx::query_as!(
+        WorkspaceFile,
+        r#"
+        SELECT * FROM workspace_files
+        WHERE workspace_id = $1 AND id = $2
+        "#,
+        workspace_id,
+        id
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to get workspace file by id ({})", e))
+}
+
+pub async fn get_workspace_file_by_path(
+    pool: &PgPool,
+    workspace_id: Uuid,
+    path: &str,
+) -> CaraiResult<Option<WorkspaceFile>> {
+    sqlx::query_as!(
+        WorkspaceFile,
+        r#"
+        SELECT * FROM workspace_files
+        WHERE workspace_id = $1 AND path = $2
+        "#,
+        workspace_id,
+        path
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to get workspace file by path ({})", e))
+}
+
+pub async fn get_workspace_files(
+    pool: &PgPool,
+    workspace_id: Uuid,
+) -> CaraiResult<Vec<WorkspaceFile>> {
+    sqlx::query_as!(
+        WorkspaceFile,
+        r#"
+        SELECT * FROM workspace_files
+        WHERE workspace_id = $1
+        ORDER BY path
+        "#,
+        workspace_id
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to get workspace files ({})", e))
+}
+
+pub async fn count_workspace_files(pool: &PgPool, workspace_id: Uuid) -> CaraiResult<i64> {
+    sqlx::query_scalar!(
+        r#"
+        SELECT COUNT(*) AS "count!" FROM workspace_files
+        WHERE workspace_id = $1
+        "#,
+        workspace_id
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to count workspace files ({})", e))
+}
+
+pub async fn update_workspace_file(
+    pool: &PgPool,
+    file: &WorkspaceFile,
+) -> CaraiResult<WorkspaceFile> {
+    sqlx::query_as!(
+        WorkspaceFile,
+        r#"
+        UPDATE workspace_files
+        SET path = $2, content = $3, updated_at = $4
+        WHERE id = $1
+        RETURNING *
+        "#,
+        file.id,
+        file.path,
+        file.content,
+        Utc::now()
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to update workspace file ({})", e))
+}
+
+pub async fn delete_workspace_file(pool: &PgPool, id: Uuid) -> CaraiResult<()> {
+    sqlx::query!(
+        r#"
+        DELETE FROM workspace_files
+        WHERE id = $1
+        "#,
+        id
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to delete workspace file ({})", e))?;
+    Ok(())
+}
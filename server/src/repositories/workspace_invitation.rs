@@ -0,0 +1,208 @@
+use anyhow::anyhow;
+use chrono::Utc;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    models::{InvitationStatus, WorkspaceInvitation, WorkspaceRole},
+    utils::CaraiResult,
+};
+
+pub async fn create_workspace_invitation(
+    pool: &PgPool,
+    invitation: &WorkspaceInvitation,
+) -> CaraiResult<WorkspaceInvitation> {
+    sqlx::query_as!(
+        WorkspaceInvitation,
+        r#"
+        INSERT INTO workspace_invitations (
+            id, workspace_id, inviter_id, invitee_id, token, role, status, expires_at,
+            max_uses, uses, created_at, updated_at
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+        RETURNING
+            id, workspace_id, inviter_id, invitee_id, token, role AS "role: WorkspaceRole",
+            status AS "status: InvitationStatus", expires_at, max_uses, uses, created_at,
+            updated_at
+        "#,
+        invitation.id,
+        invitation.workspace_id,
+        invitation.inviter_id,
+        invitation.invitee_id,
+        invitation.token,
+        invitation.role as _,
+        invitation.status as _,
+        invitation.expires_at,
+        invitation.max_uses,
+        invitation.uses,
+        invitation.created_at,
+        invitation.updated_at
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to create workspace invitation ({})", e))
+}
+
+pub async fn get_workspace_invitation_by_id(
+    pool: &PgPool,
+    id: Uuid,
+) -> CaraiResult<Option<WorkspaceInvitation>> {
+    sqlx::query_as!(
+        WorkspaceInvitation,
+        r#"
+        SELECT
+            id, workspace_id, inviter_id, invitee_id, token, role AS "role: WorkspaceRole",
+            status AS "status: InvitationStatus", expires_at, max_uses, uses, created_at,
+            updated_at
+        FROM workspace_invitations
+        WHERE id = $1
+        "#,
+        id
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to get workspace invitation by id ({})", e))
+}
+
+pub async fn get_workspace_invitation_by_token(
+    pool: &PgPool,
+    token: &str,
+) -> CaraiResult<Option<WorkspaceInvitation>> {
+    sqlx::query_as!(
+        WorkspaceInvitation,
+        r#"
+        SELECT
+            id, workspace_id, inviter_id, invitee_id, token, role AS "role: WorkspaceRole",
+            status AS "status: InvitationStatus", expires_at, max_uses, uses, created_at,
+            updated_at
+        FROM workspace_invitations
+        WHERE token = $1
+        "#,
+        token
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to get workspace invitation by token ({})", e))
+}
+
+pub async fn get_pending_invitation_for_user(
+    pool: &PgPool,
+    workspace_id: Uuid,
+    invitee_id: Uuid,
+) -> CaraiResult<Option<WorkspaceInvitation>> {
+    sqlx::query_as!(
+        WorkspaceInvitation,
+        r#"
+        SELECT
+            id, workspace_id, inviter_id, invitee_id, token, role AS "role: WorkspaceRole",
+            status AS "status: InvitationStatus", expires_at, max_uses, uses, created_at,
+            updated_at
+        FROM workspace_invitations
+        WHERE workspace_id = $1 AND invitee_id = $2 AND status = 'pending'
+        "#,
+        workspace_id,
+        invitee_id
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to get pending invitation ({})", e))
+}
+
+/// Lists the pending invitations of a workspace, newest first.
+pub async fn get_workspace_invitations(
+    pool: &PgPool,
+    workspace_id: Uuid,
+) -> CaraiResult<Vec<WorkspaceInvitation>> {
+    sqlx::query_as!(
+        WorkspaceInvitation,
+        r#"
+        SELECT
+            id, workspace_id, inviter_id, invitee_id, token, role AS "role: WorkspaceRole",
+            status AS "status: InvitationStatus", expires_at, max_uses, uses, created_at,
+            updated_at
+        FROM workspace_invitations
+        WHERE workspace_id = $1 AND status = 'pending'
+        ORDER BY created_at DESC
+        "#,
+        workspace_id
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to get workspace invitations ({})", e))
+}
+
+/// Lists the pending, unexpired invitations addressed to a user, newest first.
+pub async fn get_invitations_by_invitee_id(
+    pool: &PgPool,
+    invitee_id: Uuid,
+) -> CaraiResult<Vec<WorkspaceInvitation>> {
+    sqlx::query_as!(
+        WorkspaceInvitation,
+        r#"
+        SELECT
+            id, workspace_id, inviter_id, invitee_id, token, role AS "role: WorkspaceRole",
+            status AS "status: InvitationStatus", expires_at, max_uses, uses, created_at,
+            updated_at
+        FROM workspace_invitations
+        WHERE invitee_id = $1 AND status = 'pending'
+            AND (expires_at IS NULL OR expires_at > $2)
+        ORDER BY created_at DESC
+        "#,
+        invitee_id,
+        Utc::now()
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to get invitations by invitee ID ({})", e))
+}
+
+pub async fn update_workspace_invitation_status(
+    pool: &PgPool,
+    id: Uuid,
+    status: InvitationStatus,
+) -> CaraiResult<WorkspaceInvitation> {
+    sqlx::query_as!(
+        WorkspaceInvitation,
+        r#"
+        UPDATE workspace_invitations
+        SET status = $2, updated_at = $3
+        WHERE id = $1
+        RETURNING
+            id, workspace_id, inviter_id, invitee_id, token, role AS "role: WorkspaceRole",
+            status AS "status: InvitationStatus", expires_at, max_uses, uses, created_at,
+            updated_at
+        "#,
+        id,
+        status as _,
+        Utc::now()
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to update workspace invitation ({})", e))
+}
+
+/// Counts one use of an invite link, unless it was revoked, expired or used up meanwhile.
+pub async fn redeem_workspace_invitation(
+    pool: &PgPool,
+    id: Uuid,
+) -> CaraiResult<Option<WorkspaceInvitation>> {
+    sqlx::query_as!(
+        WorkspaceInvitation,
+        r#"
+        UPDATE workspace_invitations
+        SET uses = uses + 1, updated_at = $2
+        WHERE id = $1 AND status = 'pending'
+            AND (expires_at IS NULL OR expires_at > $2)
+            AND (max_uses IS NULL OR uses < max_uses)
+        RETURNING
+            id, workspace_id, inviter_id, invitee_id, token, role AS "role: WorkspaceRole",
+            status AS "status: InvitationStatus", expires_at, max_uses, uses, created_at,
+            updated_at
+        "#,
+        id,
+        Utc::now()
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to redeem workspace invitation ({})", e))
+}
@@ -0,0 +1,122 @@
+use anyhow::anyhow;
+use chrono::Utc;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    models::{WorkspaceMember, WorkspaceMemberProfile, WorkspaceRole},
+    utils::CaraiResult,
+};
+
+pub async fn create_workspace_member(
+    pool: &PgPool,
+    member: &WorkspaceMember,
+) -> CaraiResult<WorkspaceMember> {
+    sqlx::query_as!(
+        WorkspaceMember,
+        r#"
+        INSERT INTO workspace_members (workspace_id, user_id, role, created_at, updated_at)
+        VALUES ($1, $2, $3, $4, $5)
+        RETURNING workspace_id, user_id, role AS "role: WorkspaceRole", created_at, updated_at
+        "#,
+        member.workspace_id,
+        member.user_id,
+        member.role as _,
+        member.created_at,
+        member.updated_at
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to create workspace member ({})", e))
+}
+
+pub async fn get_workspace_member(
+    pool: &PgPool,
+    workspace_id: Uuid,
+    user_id: Uuid,
+) -> CaraiResult<Option<WorkspaceMember>> {
+    sqlx::query_as!(
+        WorkspaceMember,
+        r#"
+        SELECT workspace_id, user_id, role AS "role: WorkspaceRole", created_at, updated_at
+        FROM workspace_members
+        WHERE workspace_id = $1 AND user_id = $2
+        "#,
+        workspace_id,
+        user_id
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to get workspace member ({})", e))
+}
+
+/// Lists the owner and every member of a workspace, owner first.
+pub async fn get_workspace_member_profiles(
+    pool: &PgPool,
+    workspace_id: Uuid,
+) -> CaraiResult<Vec<WorkspaceMemberProfile>> {
+    sqlx::query_as!(
+        WorkspaceMemberProfile,
+        r#"
+        SELECT
+            u.id AS "user_id!", u.username AS "username!", u.avatar_url,
+            'owner'::workspace_role AS "role!: WorkspaceRole", w.created_at AS "created_at!"
+        FROM workspaces w
+        JOIN users u ON u.id = w.owner_id
+        WHERE w.id = $1
+        UNION ALL
+        SELECT u.id, u.username, u.avatar_url, m.role, m.created_at
+        FROM workspace_members m
+        JOIN users u ON u.id = m.user_id
+        WHERE m.workspace_id = $1
+        ORDER BY 4, 5
+        "#,
+        workspace_id
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to get workspace members ({})", e))
+}
+
+pub async fn update_workspace_member_role(
+    pool: &PgPool,
+    workspace_id: Uuid,
+    user_id: Uuid,
+    role: WorkspaceRole,
+) -> CaraiResult<WorkspaceMember> {
+    sqlx::query_as!(
+        WorkspaceMember,
+        r#"
+        UPDATE workspace_members
+        SET role = $3, updated_at = $4
+        WHERE workspace_id = $1 AND user_id = $2
+        RETURNING workspace_id, user_id, role AS "role: WorkspaceRole", created_at, updated_at
+        "#,
+        workspace_id,
+        user_id,
+        role as _,
+        Utc::now()
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to update workspace member ({})", e))
+}
+
+pub async fn delete_workspace_member(
+    pool: &PgPool,
+    workspace_id: Uuid,
+    user_id: Uuid,
+) -> CaraiResult<()> {
+    sqlx::query!(
+        r#"
+        DELETE FROM workspace_members
+        WHERE workspace_id = $1 AND user_id = $2
+        "#,
+        workspace_id,
+        user_id
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to delete workspace member ({})", e))?;
+    Ok(())
+}
@@ -0,0 +1,152 @@
+use anyhow::anyhow;
+use chrono::Utc;
+use sqlx::{types::Json, PgPool};
+use uuid::Uuid;
+
+use crate::{
+    models::{TemplateFile, WorkspaceTemplate},
+    utils::CaraiResult,
+};
+
+pub async fn create_workspace_template(
+    pool: &PgPool,
+    template: &WorkspaceTemplate,
+) -> CaraiResult<WorkspaceTemplate> {
+    sqlx::query_as!(
+        WorkspaceTemplate,
+        r#"
+        INSERT INTO workspace_templates (
+            id, slug, name, description, language, files, tags, workspace_id, organization_id,
+            author_id, created_at, updated_at
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+        RETURNING
+            id, slug, name, description, language, files AS "files: Json<Vec<TemplateFile>>",
+            tags, workspace_id, organization_id, author_id, created_at, updated_at
+        "#,
+        template.id,
+        template.slug,
+        template.name,
+        template.description,
+        template.language,
+        template.files as _,
+        &template.tags,
+        template.workspace_id,
+        template.organization_id,
+        template.author_id,
+        template.created_at,
+        template.updated_at
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to create workspace template ({})", e))
+}
+
+pub async fn get_workspace_template_by_slug(
+    pool: &PgPool,
+    slug: &str,
+) -> CaraiResult<Option<WorkspaceTemplate>> {
+    sqlx::query_as!(
+        WorkspaceTemplate,
+        r#"
+        SELECT
+            id, slug, name, description, language, files AS "files: Json<Vec<TemplateFile>>",
+            tags, workspace_id, organization_id, author_id, created_at, updated_at
+        FROM workspace_templates
+        WHERE slug = $1
+        "#,
+        slug
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to get workspace template by slug ({})", e))
+}
+
+/// Lists the public templates along with those of the workspaces and organizations the
+/// user belongs to.
+pub async fn get_workspace_templates(
+    pool: &PgPool,
+    user_id: Option<Uuid>,
+    language: Option<&str>,
+    tag: Option<&str>,
+    limit: i64,
+    offset: i64,
+) -> CaraiResult<Vec<WorkspaceTemplate>> {
+    sqlx::query_as!(
+        WorkspaceTemplate,
+        r#"
+        SELECT
+            t.id, t.slug, t.name, t.description, t.language,
+            t.files AS "files: Json<Vec<TemplateFile>>", t.tags, t.workspace_id,
+            t.organization_id, t.author_id,
+            t.created_at, t.updated_at
+        FROM workspace_templates t
+        LEFT JOIN workspaces w ON w.id = t.workspace_id
+        WHERE (
+                (t.workspace_id IS NULL AND t.organization_id IS NULL)
+                OR w.owner_id = $1
+                OR EXISTS (
+                    SELECT 1 FROM workspace_members m
+                    WHERE m.workspace_id = t.workspace_id AND m.user_id = $1
+                )
+                OR EXISTS (
+                    SELECT 1 FROM organization_members o
+                    WHERE o.organization_id = t.organization_id AND o.user_id = $1
+                )
+            )
+            AND ($2::TEXT IS NULL OR t.language = $2)
+            AND ($3::TEXT IS NULL OR $3 = ANY(t.tags))
+        ORDER BY t.workspace_id NULLS FIRST, t.organization_id NULLS FIRST, t.name
+        LIMIT $4 OFFSET $5
+        "#,
+        user_id,
+        language,
+        tag,
+        limit,
+        offset
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to get workspace templates ({})", e))
+}
+
+pub async fn update_workspace_template(
+    pool: &PgPool,
+    template: &WorkspaceTemplate,
+) -> CaraiResult<WorkspaceTemplate> {
+    sqlx::query_as!(
+        WorkspaceTemplate,
+        r#"
+        UPDATE workspace_templates
+        SET name = $2, description = $3, language = $4, files = $5, tags = $6, updated_at = $7
+        WHERE id = $1
+        RETURNING
+            id, slug, name, description, language, files AS "files: Json<Vec<TemplateFile>>",
+            tags, workspace_id, organization_id, author_id, created_at, updated_at
+        "#,
+        template.id,
+        template.name,
+        template.description,
+        template.language,
+        template.files as _,
+        &template.tags,
+        Utc::now()
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to update workspace template ({})", e))
+}
+
+pub async fn delete_workspace_template(pool: &PgPool, id: Uuid) -> CaraiResult<()> {
+    sqlx::query!(
+        r#"
+        DELETE FROM workspace_templates
+        WHERE id = $1
+        "#,
+        id
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| anyhow!("Unable to delete workspace template ({})", e))?;
+    Ok(())
+}
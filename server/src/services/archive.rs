@@ -0,0 +1,342 @@
+use std::{
+    collections::HashSet,
+    io::{self, Cursor, Read, Seek, SeekFrom, Write},
+    mem,
+};
+
+use derive_more::Display;
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use futures_util::stream::{self, BoxStream, StreamExt};
+use sqlx::PgPool;
+use tar::EntryType;
+use tokio::sync::mpsc;
+use zip::{write::SimpleFileOptions, CompressionMethod, ZipArchive, ZipWriter};
+
+use crate::{
+    dto::validate_file_path,
+    executor::find_language,
+    models::{
+        ArchiveFormat, ArchiveManifest, UnpackedArchive, Workspace, WorkspaceFile,
+        ARCHIVE_MANIFEST_PATH,
+    },
+    utils::{ArchiveConfig, BinaryFilePolicy, CaraiResult},
+};
+
+/// Largest file an archive may hold, matching what the file endpoints accept.
+const MAX_FILE_BYTES: usize = 1048576;
+const MAX_PATH_LENGTH: usize = 255;
+/// Chunks of an export that may wait for the client before packing pauses.
+const EXPORT_BUFFERED_CHUNKS: usize = 4;
+
+/// Why an uploaded archive was refused.
+#[derive(Debug, Display)]
+pub enum ArchiveError {
+    #[display("Archives must be zip or tar.gz files")]
+    UnknownFormat,
+    #[display("The archive is corrupt: {}", _0)]
+    Malformed(String),
+    #[display("The archive manifest is invalid: {}", _0)]
+    InvalidManifest(String),
+    #[display("Archive entry {} is not a relative path inside the project", _0)]
+    UnsafePath(String),
+    #[display("Archive entry {} is not a regular file", _0)]
+    UnsupportedEntry(String),
+    #[display("Archive entry {} appears more than once", _0)]
+    DuplicatePath(String),
+    #[display("Archive entry {} is larger than {} bytes", _0, MAX_FILE_BYTES)]
+    FileTooLarge(String),
+    #[display("Archives may hold at most {} files", _0)]
+    TooManyFiles(usize),
+    #[display("The archive unpacks to more than {} bytes", _0)]
+    TooLarge(u64),
+    #[display("The archive holds binary files: {}", _0.join(", "))]
+    BinaryFiles(Vec<String>),
+}
+
+/// Packs the files of a workspace together with a manifest describing it.
+///
+/// The archive is packed on a blocking thread and streamed as it grows, one file at a
+/// time, so it is never held in memory as a whole. Packing errors abort the stream.
+pub async fn export_workspace(
+    pool: &PgPool,
+    workspace: &Workspace,
+    format: ArchiveFormat,
+) -> CaraiResult<BoxStream<'static, io::Result<Vec<u8>>>> {
+    let files = super::get_workspace_files(pool, workspace.id).await?;
+    let entrypoint = workspace
+        .language
+        .as_deref()
+        .and_then(find_language)
+        .map(|language| language.filename)
+        .filter(|filename| files.iter().any(|file| file.path == *filename))
+        .map(str::to_owned);
+    let manifest = ArchiveManifest::new(
+        workspace.name.clone(),
+        workspace.language.clone(),
+        entrypoint,
+    );
+    let manifest = serde_json::to_vec_pretty(&manifest)?;
+
+    let (sender, receiver) = mpsc::channel(EXPORT_BUFFERED_CHUNKS);
+    tokio::task::spawn_blocking(move || {
+        let chunks = ChunkSender::new(sender.clone());
+        if let Err(e) = pack_archive(format, &manifest, &files, chunks) {
+            tracing::warn!("Unable to export workspace: {}", e);
+            let _ = sender.blocking_send(Err(io::Error::other(e.to_string())));
+        }
+    });
+    Ok(stream::unfold(receiver, |mut receiver| async move {
+        receiver.recv().await.map(|chunk| (chunk, receiver))
+    })
+    .boxed())
+}
+
+fn pack_archive(
+    format: ArchiveFormat,
+    manifest: &[u8],
+    files: &[WorkspaceFile],
+    mut chunks: ChunkSender,
+) -> CaraiResult<()> {
+    match format {
+        ArchiveFormat::Zip => {
+            let mut writer = ZipWriter::new(chunks);
+            // Each entry is sent once the next one starts, after its header was completed
+            writer.set_flush_on_finish_file(true);
+            let options = SimpleFileOptions::default()
+                .compression_method(CompressionMethod::Deflated)
+                .unix_permissions(0o644);
+            writer.start_file(ARCHIVE_MANIFEST_PATH, options)?;
+            writer.write_all(manifest)?;
+            for file in files {
+                writer.start_file(file.path.as_str(), options)?;
+                writer.write_all(file.content.as_bytes())?;
+            }
+            writer.finish()?.flush()?;
+        }
+        ArchiveFormat::TarGz => {
+            let mut builder = tar::Builder::new(GzEncoder::new(chunks, Compression::default()));
+            let mut append = |path: &str, content: &[u8], mtime: i64| {
+                let mut header = tar::Header::new_gnu();
+                header.set_size(content.len() as u64);
+                header.set_mode(0o644);
+                header.set_mtime(mtime.max(0) as u64);
+                header.set_cksum();
+                builder.append_data(&mut header, path, content)?;
+                // Sends what the encoder produced so far, without flushing the encoder itself
+                builder.get_mut().get_mut().flush()
+            };
+            append(
+                ARCHIVE_MANIFEST_PATH,
+                manifest,
+                chrono::Utc::now().timestamp(),
+            )?;
+            for file in files {
+                append(
+                    &file.path,
+                    file.content.as_bytes(),
+                    file.updated_at.timestamp(),
+                )?;
+            }
+            chunks = builder.into_inner()?.finish()?;
+            chunks.flush()?;
+        }
+    }
+    Ok(())
+}
+
+/// Sends what an archive writer produced to the export stream on every flush.
+///
+/// Writers may seek back into data they have not flushed yet, which is how zip entries
+/// get their sizes filled in, but data already sent cannot be revisited.
+struct ChunkSender {
+    sender: mpsc::Sender<io::Result<Vec<u8>>>,
+    sent: u64,
+    buffer: Vec<u8>,
+    position: usize,
+}
+
+impl ChunkSender {
+    fn new(sender: mpsc::Sender<io::Result<Vec<u8>>>) -> Self {
+        Self {
+            sender,
+            sent: 0,
+            buffer: Vec::new(),
+            position: 0,
+        }
+    }
+}
+
+impl Write for ChunkSender {
+    fn write(&mut self, data: &[u8]) -> io::Result<usize> {
+        let end = self.position + data.len();
+        if end > self.buffer.len() {
+            self.buffer.resize(end, 0);
+        }
+        self.buffer[self.position..end].copy_from_slice(data);
+        self.position = end;
+        Ok(data.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        if self.buffer.is_empty() {
+            return Ok(());
+        }
+        let chunk = mem::take(&mut self.buffer);
+        self.sent += chunk.len() as u64;
+        self.position = 0;
+        self.sender
+            .blocking_send(Ok(chunk))
+            .map_err(|_| io::Error::new(io::ErrorKind::BrokenPipe, "The export was cancelled"))
+    }
+}
+
+// Zip writers only flush finished entries over a readable sink, though they never read back
+impl Read for ChunkSender {
+    fn read(&mut self, _: &mut [u8]) -> io::Result<usize> {
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "Exports cannot be read back",
+        ))
+    }
+}
+
+impl Seek for ChunkSender {
+    fn seek(&mut self, position: SeekFrom) -> io::Result<u64> {
+        let end = self.sent + self.buffer.len() as u64;
+        let target = match position {
+            SeekFrom::Start(offset) => Some(offset),
+            SeekFrom::End(offset) => end.checked_add_signed(offset),
+            SeekFrom::Current(offset) => {
+                (self.sent + self.position as u64).checked_add_signed(offset)
+            }
+        };
+        match target {
+            Some(target) if (self.sent..=end).contains(&target) => {
+                self.position = (target - self.sent) as usize;
+                Ok(target)
+            }
+            _ => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "Cannot seek into data that was already sent",
+            )),
+        }
+    }
+}
+
+/// Reads the files of an uploaded zip or tar.gz archive, recognised by its leading bytes.
+///
+/// Every entry must be a regular file at a relative path inside the project. Sizes are
+/// counted while decompressing, so archives that claim to be small cannot slip through.
+pub fn unpack_archive(
+    bytes: &[u8],
+    config: &ArchiveConfig,
+    max_files: usize,
+) -> Result<UnpackedArchive, ArchiveError> {
+    let mut unpacker = Unpacker {
+        config,
+        max_files,
+        entries: 0,
+        unpacked_bytes: 0,
+        paths: HashSet::new(),
+        binary: Vec::new(),
+        archive: UnpackedArchive::default(),
+    };
+    if bytes.starts_with(b"PK\x03\x04") || bytes.starts_with(b"PK\x05\x06") {
+        let mut zip = ZipArchive::new(Cursor::new(bytes)).map_err(malformed)?;
+        for index in 0..zip.len() {
+            let entry = zip.by_index(index).map_err(malformed)?;
+            let path = entry.name().to_owned();
+            if entry.is_dir() {
+                continue;
+            }
+            if entry.is_symlink() {
+                return Err(ArchiveError::UnsupportedEntry(path));
+            }
+            unpacker.add(&path, entry)?;
+        }
+    } else if bytes.starts_with(&[0x1f, 0x8b]) {
+        let mut tar = tar::Archive::new(GzDecoder::new(bytes));
+        for entry in tar.entries().map_err(malformed)? {
+            let entry = entry.map_err(malformed)?;
+            let path = String::from_utf8_lossy(&entry.path_bytes()).into_owned();
+            match entry.header().entry_type() {
+                EntryType::Directory => continue,
+                EntryType::Regular | EntryType::Continuous => unpacker.add(&path, entry)?,
+                _ => return Err(ArchiveError::UnsupportedEntry(path)),
+            }
+        }
+    } else {
+        return Err(ArchiveError::UnknownFormat);
+    }
+    unpacker.finish()
+}
+
+fn malformed(error: impl ToString) -> ArchiveError {
+    ArchiveError::Malformed(error.to_string())
+}
+
+/// Accumulates archive entries while enforcing the import limits.
+struct Unpacker<'a> {
+    config: &'a ArchiveConfig,
+    max_files: usize,
+    entries: usize,
+    unpacked_bytes: u64,
+    paths: HashSet<String>,
+    binary: Vec<String>,
+    archive: UnpackedArchive,
+}
+
+impl Unpacker<'_> {
+    fn add(&mut self, path: &str, reader: impl Read) -> Result<(), ArchiveError> {
+        let path = path.strip_prefix("./").unwrap_or(path);
+        if path.len() > MAX_PATH_LENGTH || validate_file_path(path).is_err() {
+            return Err(ArchiveError::UnsafePath(path.to_owned()));
+        }
+        if path != ARCHIVE_MANIFEST_PATH {
+            self.entries += 1;
+            if self.entries > self.max_files {
+                return Err(ArchiveError::TooManyFiles(self.max_files));
+            }
+            if !self.paths.insert(path.to_owned()) {
+                return Err(ArchiveError::DuplicatePath(path.to_owned()));
+            }
+        }
+
+        let max_unpacked = *self.config.max_unpacked_bytes();
+        let remaining = max_unpacked.saturating_sub(self.unpacked_bytes);
+        let mut content = Vec::new();
+        reader
+            .take((MAX_FILE_BYTES as u64).min(remaining) + 1)
+            .read_to_end(&mut content)
+            .map_err(malformed)?;
+        if content.len() > MAX_FILE_BYTES {
+            return Err(ArchiveError::FileTooLarge(path.to_owned()));
+        }
+        self.unpacked_bytes += content.len() as u64;
+        if self.unpacked_bytes > max_unpacked {
+            return Err(ArchiveError::TooLarge(max_unpacked));
+        }
+
+        if path == ARCHIVE_MANIFEST_PATH {
+            let manifest = serde_json::from_slice(&content)
+                .map_err(|e| ArchiveError::InvalidManifest(e.to_string()))?;
+            self.archive.manifest = Some(manifest);
+            return Ok(());
+        }
+        match String::from_utf8(content) {
+            Ok(text) if !text.contains('\0') => self.archive.files.push((path.to_owned(), text)),
+            _ => self.binary.push(path.to_owned()),
+        }
+        Ok(())
+    }
+
+    fn finish(mut self) -> Result<UnpackedArchive, ArchiveError> {
+        if !self.binary.is_empty() {
+            match self.config.binary_files() {
+                BinaryFilePolicy::Reject => return Err(ArchiveError::BinaryFiles(self.binary)),
+                BinaryFilePolicy::Skip => self.archive.skipped = self.binary,
+            }
+        }
+        Ok(self.archive)
+    }
+}
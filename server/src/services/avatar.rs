@@ -0,0 +1,116 @@
+use std::io::Cursor;
+
+use derive_more::Display;
+use image::{imageops::FilterType, DynamicImage, ImageFormat, ImageReader, Limits};
+use sha2::{Digest, Sha256};
+
+use crate::{
+    storage::BlobStore,
+    utils::{AvatarConfig, CaraiResult},
+};
+
+/// Widths and heights, in pixels, every avatar is stored at.
+pub const AVATAR_SIZES: [u32; 3] = [64, 128, 256];
+
+/// Why an uploaded avatar was refused.
+#[derive(Debug, Display)]
+pub enum AvatarError {
+    #[display("Avatars must be PNG, JPEG or WebP images")]
+    UnsupportedFormat,
+    #[display("The image is corrupt: {}", _0)]
+    Malformed(String),
+    #[display("Avatars must be at least {} pixels wide and high", _0)]
+    TooSmall(u32),
+    #[display("Avatars may be at most {} pixels wide and high", _0)]
+    TooLarge(u32),
+}
+
+/// An uploaded image cropped to a square and resized to every avatar size.
+#[derive(Debug, Clone)]
+pub struct ProcessedAvatar {
+    /// Hex SHA-256 of the largest size, which names the avatar in storage and URLs.
+    pub hash: String,
+    /// PNG encodings, in the order of [`AVATAR_SIZES`].
+    pub images: Vec<(u32, Vec<u8>)>,
+}
+
+/// Checks an uploaded image and renders it at every avatar size.
+///
+/// The format is sniffed from the bytes rather than trusted from the request, and the
+/// dimensions are checked before decoding so oversized images cost nothing to refuse.
+/// Decoding is CPU-bound, so async callers should run this on a blocking thread.
+pub fn process_avatar(bytes: &[u8], config: &AvatarConfig) -> Result<ProcessedAvatar, AvatarError> {
+    let reader = || {
+        let reader = ImageReader::new(Cursor::new(bytes))
+            .with_guessed_format()
+            .map_err(|e| AvatarError::Malformed(e.to_string()))?;
+        match reader.format() {
+            Some(ImageFormat::Png | ImageFormat::Jpeg | ImageFormat::WebP) => Ok(reader),
+            _ => Err(AvatarError::UnsupportedFormat),
+        }
+    };
+
+    let (width, height) = reader()?
+        .into_dimensions()
+        .map_err(|e| AvatarError::Malformed(e.to_string()))?;
+    let (min, max) = (*config.min_dimension(), *config.max_dimension());
+    if width < min || height < min {
+        return Err(AvatarError::TooSmall(min));
+    }
+    if width > max || height > max {
+        return Err(AvatarError::TooLarge(max));
+    }
+
+    let mut limits = Limits::default();
+    limits.max_image_width = Some(max);
+    limits.max_image_height = Some(max);
+    let mut reader = reader()?;
+    reader.limits(limits);
+    let image = reader
+        .decode()
+        .map_err(|e| AvatarError::Malformed(e.to_string()))?;
+
+    // Avatars are shown in circles and squares, so the centre of the image is kept
+    let side = width.min(height);
+    let square = image.crop_imm((width - side) / 2, (height - side) / 2, side, side);
+    let mut images = Vec::with_capacity(AVATAR_SIZES.len());
+    for size in AVATAR_SIZES {
+        let resized = square.resize_exact(size, size, FilterType::Lanczos3);
+        let mut png = Vec::new();
+        DynamicImage::ImageRgba8(resized.to_rgba8())
+            .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+            .map_err(|e| AvatarError::Malformed(e.to_string()))?;
+        images.push((size, png));
+    }
+    let hash = hex::encode(Sha256::digest(&images[images.len() - 1].1));
+    Ok(ProcessedAvatar { hash, images })
+}
+
+/// Where one size of an avatar is kept in the blob store.
+pub fn avatar_key(hash: &str, size: u32) -> String {
+    format!("avatars/{}/{}.png", hash, size)
+}
+
+/// The public URL of the largest size of an avatar.
+pub fn avatar_url(config: &AvatarConfig, hash: &str) -> String {
+    let size = AVATAR_SIZES[AVATAR_SIZES.len() - 1];
+    format!(
+        "{}/{}/{}.png",
+        config.base_url().trim_end_matches('/'),
+        hash,
+        size
+    )
+}
+
+/// Stores every size of an avatar.
+///
+/// Keys are derived from the content, so storing an avatar that already exists,
+/// perhaps uploaded by another user, rewrites identical objects.
+pub async fn store_avatar(blobs: &dyn BlobStore, avatar: &ProcessedAvatar) -> CaraiResult<()> {
+    for (size, png) in &avatar.images {
+        blobs
+            .put(&avatar_key(&avatar.hash, *size), png.clone(), "image/png")
+            .await?;
+    }
+    Ok(())
+}
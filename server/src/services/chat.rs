@@ -0,0 +1,39 @@
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    models::{mentioned_usernames, ChatMessage, Notification},
+    repositories,
+    utils::CaraiResult,
+};
+
+/// Stores a chat message and records and notifies the members it mentions.
+pub async fn create_chat_message(pool: &PgPool, message: &ChatMessage) -> CaraiResult<ChatMessage> {
+    let message = repositories::create_chat_message(pool, message).await?;
+    let usernames = mentioned_usernames(&message.body);
+    if let (Some(author_id), false) = (message.author_id, usernames.is_empty()) {
+        let mentions = repositories::create_mentions(
+            pool,
+            message.workspace_id,
+            author_id,
+            &usernames,
+            None,
+            Some(message.id),
+        )
+        .await?;
+        for mention in &mentions {
+            super::notify(pool, &Notification::mention(mention)).await?;
+        }
+    }
+    Ok(message)
+}
+
+pub async fn get_chat_messages(
+    pool: &PgPool,
+    workspace_id: Uuid,
+    before: Option<DateTime<Utc>>,
+    limit: i64,
+) -> CaraiResult<Vec<ChatMessage>> {
+    repositories::get_chat_messages(pool, workspace_id, before, limit).await
+}
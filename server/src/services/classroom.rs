@@ -0,0 +1,158 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    models::{
+        Assignment, Classroom, ClassroomMember, ClassroomMemberProfile, ClassroomMembership,
+        ClassroomRole, Execution, ExecutionStatus, Grade, GradebookEntry, Submission,
+        SubmissionStatus, WebhookEvent,
+    },
+    repositories,
+    utils::CaraiResult,
+};
+
+/// Creates a classroom with its creator as the first instructor.
+pub async fn create_classroom(
+    pool: &PgPool,
+    classroom: &Classroom,
+    instructor_id: Uuid,
+) -> CaraiResult<Classroom> {
+    let classroom = repositories::create_classroom(pool, classroom).await?;
+    let instructor = ClassroomMember::new(classroom.id, instructor_id, ClassroomRole::Instructor);
+    repositories::create_classroom_member(pool, &instructor).await?;
+    Ok(classroom)
+}
+
+pub async fn get_classroom_by_slug(pool: &PgPool, slug: &str) -> CaraiResult<Option<Classroom>> {
+    repositories::get_classroom_by_slug(pool, slug).await
+}
+
+pub async fn get_classroom_by_join_code(
+    pool: &PgPool,
+    join_code: &str,
+) -> CaraiResult<Option<Classroom>> {
+    repositories::get_classroom_by_join_code(pool, join_code).await
+}
+
+pub async fn get_classrooms_by_member_id(
+    pool: &PgPool,
+    user_id: Uuid,
+) -> CaraiResult<Vec<ClassroomMembership>> {
+    repositories::get_classrooms_by_member_id(pool, user_id).await
+}
+
+pub async fn delete_classroom(pool: &PgPool, id: Uuid) -> CaraiResult<()> {
+    repositories::delete_classroom(pool, id).await
+}
+
+pub async fn create_classroom_member(
+    pool: &PgPool,
+    member: &ClassroomMember,
+) -> CaraiResult<ClassroomMember> {
+    repositories::create_classroom_member(pool, member).await
+}
+
+pub async fn get_classroom_member(
+    pool: &PgPool,
+    classroom_id: Uuid,
+    user_id: Uuid,
+) -> CaraiResult<Option<ClassroomMember>> {
+    repositories::get_classroom_member(pool, classroom_id, user_id).await
+}
+
+pub async fn get_classroom_member_profiles(
+    pool: &PgPool,
+    classroom_id: Uuid,
+) -> CaraiResult<Vec<ClassroomMemberProfile>> {
+    repositories::get_classroom_member_profiles(pool, classroom_id).await
+}
+
+pub async fn delete_classroom_member(
+    pool: &PgPool,
+    classroom_id: Uuid,
+    user_id: Uuid,
+) -> CaraiResult<()> {
+    repositories::delete_classroom_member(pool, classroom_id, user_id).await
+}
+
+pub async fn create_assignment(pool: &PgPool, assignment: &Assignment) -> CaraiResult<Assignment> {
+    repositories::create_assignment(pool, assignment).await
+}
+
+pub async fn get_assignment_by_id(pool: &PgPool, id: Uuid) -> CaraiResult<Option<Assignment>> {
+    repositories::get_assignment_by_id(pool, id).await
+}
+
+pub async fn get_assignments_by_classroom_id(
+    pool: &PgPool,
+    classroom_id: Uuid,
+) -> CaraiResult<Vec<Assignment>> {
+    repositories::get_assignments_by_classroom_id(pool, classroom_id).await
+}
+
+pub async fn delete_assignment(pool: &PgPool, id: Uuid) -> CaraiResult<()> {
+    repositories::delete_assignment(pool, id).await
+}
+
+/// Stores a submission together with the judged run that grades it, or returns `None`
+/// when the run would exceed `concurrent_runs` (see [`super::create_queued_execution`]).
+pub async fn create_submission(
+    pool: &PgPool,
+    submission: &Submission,
+    execution: &Execution,
+    concurrent_runs: i64,
+) -> CaraiResult<Option<Submission>> {
+    if super::create_queued_execution(pool, execution, concurrent_runs)
+        .await?
+        .is_none()
+    {
+        return Ok(None);
+    }
+    let submission = repositories::create_submission(pool, submission).await?;
+    super::emit_submission_event(pool, WebhookEvent::SubmissionCreated, &submission).await?;
+    Ok(Some(submission))
+}
+
+pub async fn get_submission_by_id(pool: &PgPool, id: Uuid) -> CaraiResult<Option<Submission>> {
+    repositories::get_submission_by_id(pool, id).await
+}
+
+pub async fn get_submissions_by_assignment_id(
+    pool: &PgPool,
+    assignment_id: Uuid,
+    user_id: Option<Uuid>,
+    limit: i64,
+    offset: i64,
+) -> CaraiResult<Vec<Submission>> {
+    repositories::get_submissions_by_assignment_id(pool, assignment_id, user_id, limit, offset)
+        .await
+}
+
+/// Grades the submission a finished run was judging, if it was judging one.
+pub async fn grade_submission(pool: &PgPool, execution: &Execution) -> CaraiResult<()> {
+    // Only assignment runs hide their cases, so other runs need no lookup
+    if !execution.judge.as_ref().is_some_and(|spec| spec.hidden) {
+        return Ok(());
+    }
+    let Some(submission) = repositories::get_submission_by_execution_id(pool, execution.id).await?
+    else {
+        return Ok(());
+    };
+    let (status, grade) = match execution.status {
+        ExecutionStatus::Completed => {
+            let results = execution.case_results.as_deref().map(Vec::as_slice);
+            let grade = Grade::from_results(results, submission.total_cases);
+            (SubmissionStatus::Graded, Some(grade))
+        }
+        _ => (SubmissionStatus::Failed, None),
+    };
+    let submission = repositories::grade_submission(pool, submission.id, status, grade).await?;
+    super::emit_submission_event(pool, WebhookEvent::SubmissionGraded, &submission).await
+}
+
+pub async fn get_gradebook_entries(
+    pool: &PgPool,
+    classroom_id: Uuid,
+) -> CaraiResult<Vec<GradebookEntry>> {
+    repositories::get_gradebook_entries(pool, classroom_id).await
+}
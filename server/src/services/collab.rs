@@ -0,0 +1,120 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    collab::seed_update,
+    models::{CollabSnapshot, CollabUpdate, WorkspaceFile},
+    repositories,
+    utils::CaraiResult,
+};
+
+pub async fn get_collab_snapshot(
+    pool: &PgPool,
+    file_id: Uuid,
+) -> CaraiResult<Option<CollabSnapshot>> {
+    repositories::get_collab_snapshot(pool, file_id).await
+}
+
+/// The stored snapshot of a file's document with every update after it, read together.
+pub async fn get_collab_document(
+    pool: &PgPool,
+    file_id: Uuid,
+) -> CaraiResult<(Option<CollabSnapshot>, Vec<CollabUpdate>)> {
+    repositories::get_collab_document(pool, file_id).await
+}
+
+/// Replaces the stored document with a merged snapshot and drops the updates it covers.
+///
+/// Snapshots claiming to cover updates that were never stored are refused, since
+/// storing one would drop every update up to it, including those yet to come.
+pub async fn compact_collab_document(
+    pool: &PgPool,
+    snapshot: &CollabSnapshot,
+) -> CaraiResult<bool> {
+    if snapshot.seq > repositories::get_latest_collab_seq(pool, snapshot.file_id).await?
+        || !repositories::upsert_collab_snapshot(pool, snapshot).await?
+    {
+        return Ok(false);
+    }
+    repositories::delete_collab_updates_through(pool, snapshot.file_id, snapshot.seq).await?;
+    Ok(true)
+}
+
+pub async fn create_collab_update(
+    pool: &PgPool,
+    file_id: Uuid,
+    author_id: Uuid,
+    data: &[u8],
+) -> CaraiResult<CollabUpdate> {
+    repositories::create_collab_update(pool, file_id, author_id, data).await
+}
+
+pub async fn get_collab_updates_after(
+    pool: &PgPool,
+    file_id: Uuid,
+    seq: i64,
+) -> CaraiResult<Vec<CollabUpdate>> {
+    repositories::get_collab_updates_after(pool, file_id, seq).await
+}
+
+pub async fn count_collab_updates_after(
+    pool: &PgPool,
+    file_id: Uuid,
+    seq: i64,
+) -> CaraiResult<i64> {
+    repositories::count_collab_updates_after(pool, file_id, seq).await
+}
+
+pub async fn get_latest_collab_seq(pool: &PgPool, file_id: Uuid) -> CaraiResult<i64> {
+    repositories::get_latest_collab_seq(pool, file_id).await
+}
+
+/// Stores the file's content as the first update of a document with nothing stored yet.
+///
+/// Returns the stored update, or `None` when the document already exists or the file is
+/// empty. Callers hold the room's write lock so that the document is seeded only once.
+pub async fn seed_collab_document(
+    pool: &PgPool,
+    file: &WorkspaceFile,
+    author_id: Uuid,
+) -> CaraiResult<Option<CollabUpdate>> {
+    if file.content.is_empty() || repositories::get_latest_collab_seq(pool, file.id).await? > 0 {
+        return Ok(None);
+    }
+    let update =
+        repositories::create_collab_update(pool, file.id, author_id, &seed_update(&file.content))
+            .await?;
+    Ok(Some(update))
+}
+
+/// Starts a file's document over from its content, after the file was written from
+/// outside its room. Sequence numbers keep increasing, so none is ever reused.
+///
+/// Callers hold the room's write lock, with nobody else in the room.
+pub async fn reset_collab_document(
+    pool: &PgPool,
+    file: &WorkspaceFile,
+    author_id: Uuid,
+) -> CaraiResult<Option<CollabUpdate>> {
+    let seed = (!file.content.is_empty()).then(|| seed_update(&file.content));
+    repositories::reset_collab_document(pool, file.id, author_id, seed.as_deref()).await
+}
+
+/// Saves a document's merged text as its file's content, which also re-anchors its
+/// comment threads and records a revision.
+///
+/// Returns `None` when the text is unchanged.
+pub async fn save_collab_content(
+    pool: &PgPool,
+    mut file: WorkspaceFile,
+    content: String,
+    author_id: Uuid,
+) -> CaraiResult<Option<WorkspaceFile>> {
+    if file.content == content {
+        return Ok(None);
+    }
+    file.content = content;
+    super::update_workspace_file(pool, &file, author_id)
+        .await
+        .map(Some)
+}
@@ -0,0 +1,165 @@
+use std::ops::Range;
+
+use similar::{DiffTag, TextDiff};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    models::{mentioned_usernames, Comment, CommentThread, Notification},
+    repositories,
+    utils::CaraiResult,
+};
+
+/// Opens a thread on a range of lines with its first comment.
+pub async fn create_comment_thread(
+    pool: &PgPool,
+    thread: &CommentThread,
+    body: &str,
+    author_id: Uuid,
+) -> CaraiResult<(CommentThread, Comment)> {
+    let thread = repositories::create_comment_thread(pool, thread).await?;
+    let comment = create_comment(pool, &thread, body, author_id).await?;
+    Ok((thread, comment))
+}
+
+pub async fn get_comment_thread_by_id(
+    pool: &PgPool,
+    file_id: Uuid,
+    id: Uuid,
+) -> CaraiResult<Option<CommentThread>> {
+    repositories::get_comment_thread_by_id(pool, file_id, id).await
+}
+
+pub async fn get_comment_threads(
+    pool: &PgPool,
+    file_id: Uuid,
+    resolved: Option<bool>,
+) -> CaraiResult<Vec<CommentThread>> {
+    repositories::get_comment_threads(pool, file_id, resolved).await
+}
+
+pub async fn get_comments_by_thread_ids(
+    pool: &PgPool,
+    thread_ids: &[Uuid],
+) -> CaraiResult<Vec<Comment>> {
+    repositories::get_comments_by_thread_ids(pool, thread_ids).await
+}
+
+pub async fn update_comment_thread_resolution(
+    pool: &PgPool,
+    id: Uuid,
+    resolved_by: Option<Uuid>,
+) -> CaraiResult<CommentThread> {
+    repositories::update_comment_thread_resolution(pool, id, resolved_by).await
+}
+
+pub async fn delete_comment_thread(pool: &PgPool, id: Uuid) -> CaraiResult<()> {
+    repositories::delete_comment_thread(pool, id).await
+}
+
+/// Adds a comment to a thread and records and notifies the members it mentions.
+pub async fn create_comment(
+    pool: &PgPool,
+    thread: &CommentThread,
+    body: &str,
+    author_id: Uuid,
+) -> CaraiResult<Comment> {
+    let comment =
+        repositories::create_comment(pool, &Comment::new(thread.id, author_id, body)).await?;
+    let usernames = mentioned_usernames(body);
+    if !usernames.is_empty() {
+        let mentions = repositories::create_mentions(
+            pool,
+            thread.workspace_id,
+            author_id,
+            &usernames,
+            Some(comment.id),
+            None,
+        )
+        .await?;
+        for mention in &mentions {
+            super::notify(pool, &Notification::mention(mention)).await?;
+        }
+    }
+    Ok(comment)
+}
+
+/// Moves the comment threads of a file along with an edit of its content.
+pub async fn reanchor_comment_threads(
+    pool: &PgPool,
+    file_id: Uuid,
+    old_content: &str,
+    new_content: &str,
+) -> CaraiResult<()> {
+    let threads = repositories::get_comment_threads(pool, file_id, None).await?;
+    if threads.is_empty() || old_content == new_content {
+        return Ok(());
+    }
+
+    let line_map = LineMap::new(old_content, new_content);
+    for mut thread in threads {
+        let (start_line, end_line, outdated) = line_map.remap(thread.start_line, thread.end_line);
+        let outdated = outdated || thread.outdated;
+        if (start_line, end_line, outdated) != (thread.start_line, thread.end_line, thread.outdated)
+        {
+            thread.start_line = start_line;
+            thread.end_line = end_line;
+            thread.outdated = outdated;
+            repositories::update_comment_thread_anchor(pool, &thread).await?;
+        }
+    }
+    Ok(())
+}
+
+/// Maps line numbers of a file's old content onto its new content.
+struct LineMap {
+    ops: Vec<(DiffTag, Range<usize>, Range<usize>)>,
+    new_lines: usize,
+}
+
+impl LineMap {
+    fn new(old: &str, new: &str) -> Self {
+        let ops = TextDiff::from_lines(old, new)
+            .ops()
+            .iter()
+            .map(|op| op.as_tag_tuple())
+            .collect();
+        Self {
+            ops,
+            new_lines: new.lines().count().max(1),
+        }
+    }
+
+    /// Maps a zero-based old line to its new position.
+    ///
+    /// Unchanged lines keep their offset and edited lines stay within the block that
+    /// replaced them. Deleted lines yield `Err` with the position they collapsed to.
+    fn map_line(&self, line: usize) -> Result<usize, usize> {
+        let Some((tag, old, new)) = self.ops.iter().find(|(_, old, _)| old.contains(&line)) else {
+            return Err(self.new_lines - 1);
+        };
+        let offset = line - old.start;
+        match tag {
+            DiffTag::Equal => Ok(new.start + offset),
+            DiffTag::Replace => Ok(new.start + offset.min(new.len() - 1)),
+            DiffTag::Delete | DiffTag::Insert => Err(new.start.min(self.new_lines - 1)),
+        }
+    }
+
+    /// Maps a one-based, inclusive line range, reporting whether all of it was deleted.
+    fn remap(&self, start_line: i32, end_line: i32) -> (i32, i32, bool) {
+        let start = (start_line.max(1) - 1) as usize;
+        let end = (end_line.max(start_line) - 1) as usize;
+        let mut surviving = (start..=end).filter_map(|line| self.map_line(line).ok());
+        match surviving.next() {
+            Some(first) => {
+                let last = surviving.next_back().unwrap_or(first);
+                (first as i32 + 1, last as i32 + 1, false)
+            }
+            None => {
+                let collapsed = self.map_line(start).unwrap_or_else(|line| line) as i32 + 1;
+                (collapsed, collapsed, true)
+            }
+        }
+    }
+}
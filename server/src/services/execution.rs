@@ -0,0 +1,94 @@
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    models::{Execution, ExecutionStatus, Notification, SessionEvent},
+    repositories,
+    utils::CaraiResult,
+};
+
+/// Stores a new execution, recording it in its workspace's session if it has one.
+pub async fn create_execution(pool: &PgPool, execution: &Execution) -> CaraiResult<Execution> {
+    let execution = repositories::create_execution(pool, execution).await?;
+    if let Some(event) = SessionEvent::run(&execution) {
+        repositories::create_session_event(pool, &event).await?;
+    }
+    Ok(execution)
+}
+
+/// Queues a run, or returns `None` when whoever it is billed to already has
+/// `concurrent_runs` runs queued or running.
+pub async fn create_queued_execution(
+    pool: &PgPool,
+    execution: &Execution,
+    concurrent_runs: i64,
+) -> CaraiResult<Option<Execution>> {
+    let Some(execution) =
+        repositories::create_execution_within_limit(pool, execution, concurrent_runs).await?
+    else {
+        return Ok(None);
+    };
+    if let Some(event) = SessionEvent::run(&execution) {
+        repositories::create_session_event(pool, &event).await?;
+    }
+    Ok(Some(execution))
+}
+
+pub async fn get_execution_by_id(pool: &PgPool, id: Uuid) -> CaraiResult<Option<Execution>> {
+    repositories::get_execution_by_id(pool, id).await
+}
+
+pub async fn claim_next_execution(
+    pool: &PgPool,
+    locked_until: DateTime<Utc>,
+) -> CaraiResult<Option<Execution>> {
+    repositories::claim_next_execution(pool, locked_until).await
+}
+
+/// Stores the outcome of a run, grading the assignment submission it was judging if any.
+pub async fn extend_execution_lease(
+    pool: &PgPool,
+    execution: &Execution,
+    locked_until: DateTime<Utc>,
+) -> CaraiResult<bool> {
+    repositories::extend_execution_lease(pool, execution, locked_until).await
+}
+
+/// Stores the outcome of a claimed execution and announces it, unless the claim was lost
+/// to another worker, in which case nothing happens and `false` is returned.
+pub async fn finish_execution(pool: &PgPool, execution: &Execution) -> CaraiResult<bool> {
+    if !repositories::finish_execution(pool, execution).await? {
+        return Ok(false);
+    }
+    if let Some(event) = SessionEvent::run(execution) {
+        repositories::create_session_event(pool, &event).await?;
+    }
+    if let Some(notification) = Notification::run_finished(execution) {
+        super::notify(pool, &notification).await?;
+    }
+    super::grade_submission(pool, execution).await?;
+    Ok(true)
+}
+
+pub async fn requeue_execution(pool: &PgPool, execution: &Execution) -> CaraiResult<bool> {
+    repositories::requeue_execution(pool, execution).await
+}
+
+pub async fn get_executions_by_user_id(
+    pool: &PgPool,
+    user_id: Uuid,
+    language: Option<&str>,
+    status: Option<ExecutionStatus>,
+    limit: i64,
+    offset: i64,
+) -> CaraiResult<Vec<Execution>> {
+    repositories::get_executions_by_user_id(pool, user_id, language, status, limit, offset).await
+}
+
+pub async fn delete_executions_finished_before(
+    pool: &PgPool,
+    before: DateTime<Utc>,
+) -> CaraiResult<u64> {
+    repositories::delete_executions_finished_before(pool, before).await
+}
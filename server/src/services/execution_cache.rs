@@ -0,0 +1,49 @@
+use chrono::{DateTime, Utc};
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+
+use crate::{
+    executor::Language,
+    models::{CachedExecution, Execution},
+    repositories,
+    utils::CaraiResult,
+};
+
+/// Derives the content address of an execution from everything that determines its output.
+///
+/// The sandbox image stands in for the runtime version, so bumping an image
+/// invalidates every result produced by the previous one.
+pub fn cache_key(language: &Language, execution: &Execution) -> String {
+    let mut hasher = Sha256::new();
+    for part in [
+        language.image,
+        language.name,
+        &execution.files_hash,
+        execution.stdin.as_deref().unwrap_or_default(),
+    ] {
+        hasher.update((part.len() as u64).to_be_bytes());
+        hasher.update(part.as_bytes());
+    }
+    hex::encode(hasher.finalize())
+}
+
+pub async fn upsert_cached_execution(
+    pool: &PgPool,
+    cached: &CachedExecution,
+) -> CaraiResult<CachedExecution> {
+    repositories::upsert_cached_execution(pool, cached).await
+}
+
+pub async fn hit_cached_execution(
+    pool: &PgPool,
+    key: &str,
+) -> CaraiResult<Option<CachedExecution>> {
+    repositories::hit_cached_execution(pool, key).await
+}
+
+pub async fn delete_cached_executions_expired_before(
+    pool: &PgPool,
+    before: DateTime<Utc>,
+) -> CaraiResult<u64> {
+    repositories::delete_cached_executions_expired_before(pool, before).await
+}
@@ -0,0 +1,89 @@
+use similar::TextDiff;
+use sqlx::{PgConnection, PgPool};
+use uuid::Uuid;
+
+use crate::{
+    models::{Blob, FileRevision, SessionEvent, WorkspaceFile},
+    repositories,
+    utils::CaraiResult,
+};
+
+/// Records the current state of a file as its next revision.
+///
+/// New revisions also show up as saves in the workspace's session recording. Content is stored once per distinct hash, and a save that changes neither the
+/// content nor the path does not produce a new revision.
+pub async fn record_file_revision(
+    conn: &mut PgConnection,
+    file: &WorkspaceFile,
+    author_id: Uuid,
+) -> CaraiResult<FileRevision> {
+    let blob = Blob::new(file.content.as_str());
+    let latest = repositories::get_latest_file_revision(&mut *conn, file.id).await?;
+    if let Some(latest) = latest {
+        if latest.blob_hash == blob.hash && latest.path == file.path {
+            return Ok(latest);
+        }
+        return create_revision(conn, file, &blob, latest.number + 1, author_id).await;
+    }
+    create_revision(conn, file, &blob, 1, author_id).await
+}
+
+async fn create_revision(
+    conn: &mut PgConnection,
+    file: &WorkspaceFile,
+    blob: &Blob,
+    number: i32,
+    author_id: Uuid,
+) -> CaraiResult<FileRevision> {
+    repositories::create_blob(&mut *conn, blob).await?;
+    let revision = FileRevision::new(file.id, number, file.path.as_str(), &blob.hash, author_id);
+    let revision = repositories::create_file_revision(&mut *conn, &revision).await?;
+    let event = SessionEvent::save(file.workspace_id, file.id, author_id, number);
+    repositories::create_session_event(&mut *conn, &event).await?;
+    Ok(revision)
+}
+
+pub async fn get_file_revision_by_number(
+    pool: &PgPool,
+    file_id: Uuid,
+    number: i32,
+) -> CaraiResult<Option<FileRevision>> {
+    repositories::get_file_revision_by_number(pool, file_id, number).await
+}
+
+pub async fn get_latest_file_revision(
+    pool: &PgPool,
+    file_id: Uuid,
+) -> CaraiResult<Option<FileRevision>> {
+    repositories::get_latest_file_revision(pool, file_id).await
+}
+
+pub async fn get_file_revisions(
+    pool: &PgPool,
+    file_id: Uuid,
+    limit: i64,
+    offset: i64,
+) -> CaraiResult<Vec<FileRevision>> {
+    repositories::get_file_revisions(pool, file_id, limit, offset).await
+}
+
+pub async fn get_blob_by_hash(pool: &PgPool, hash: &str) -> CaraiResult<Option<Blob>> {
+    repositories::get_blob_by_hash(pool, hash).await
+}
+
+pub async fn delete_unreferenced_blobs(pool: &PgPool) -> CaraiResult<u64> {
+    repositories::delete_unreferenced_blobs(pool).await
+}
+
+/// Renders a unified diff between two revisions, labelled with their paths and numbers.
+pub fn diff_revisions(from: (&FileRevision, &str), to: (&FileRevision, &str)) -> String {
+    let (from, from_content) = from;
+    let (to, to_content) = to;
+    TextDiff::from_lines(from_content, to_content)
+        .unified_diff()
+        .header(
+            &format!("a/{}@{}", from.path, from.number),
+            &format!("b/{}@{}", to.path, to.number),
+        )
+        .to_string()
+}
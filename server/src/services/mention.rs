@@ -0,0 +1,18 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{models::Mention, repositories, utils::CaraiResult};
+
+pub async fn get_mentions_by_user_id(
+    pool: &PgPool,
+    user_id: Uuid,
+    unread_only: bool,
+    limit: i64,
+    offset: i64,
+) -> CaraiResult<Vec<Mention>> {
+    repositories::get_mentions_by_user_id(pool, user_id, unread_only, limit, offset).await
+}
+
+pub async fn mark_mention_read(pool: &PgPool, user_id: Uuid, id: Uuid) -> CaraiResult<bool> {
+    repositories::mark_mention_read(pool, user_id, id).await
+}
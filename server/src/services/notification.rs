@@ -0,0 +1,61 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    models::{Notification, NotificationCategory},
+    repositories,
+    utils::CaraiResult,
+};
+
+/// Stores a notification and pushes it to the recipient's open streams, unless
+/// they have muted its category. Returns the notification if it was delivered.
+pub async fn notify(
+    pool: &PgPool,
+    notification: &Notification,
+) -> CaraiResult<Option<Notification>> {
+    if repositories::is_category_muted(pool, notification.user_id, notification.category).await? {
+        return Ok(None);
+    }
+    repositories::create_notification(pool, notification).await?;
+    repositories::publish_notification(pool, notification).await?;
+    Ok(Some(notification.clone()))
+}
+
+pub async fn get_notifications_by_user_id(
+    pool: &PgPool,
+    user_id: Uuid,
+    unread_only: bool,
+    category: Option<NotificationCategory>,
+    limit: i64,
+    offset: i64,
+) -> CaraiResult<Vec<Notification>> {
+    repositories::get_notifications_by_user_id(pool, user_id, unread_only, category, limit, offset)
+        .await
+}
+
+pub async fn count_unread_notifications(pool: &PgPool, user_id: Uuid) -> CaraiResult<i64> {
+    repositories::count_unread_notifications(pool, user_id).await
+}
+
+pub async fn mark_notification_read(pool: &PgPool, user_id: Uuid, id: Uuid) -> CaraiResult<bool> {
+    repositories::mark_notification_read(pool, user_id, id).await
+}
+
+pub async fn mark_all_notifications_read(pool: &PgPool, user_id: Uuid) -> CaraiResult<()> {
+    repositories::mark_all_notifications_read(pool, user_id).await
+}
+
+pub async fn get_muted_categories(
+    pool: &PgPool,
+    user_id: Uuid,
+) -> CaraiResult<Vec<NotificationCategory>> {
+    repositories::get_muted_categories(pool, user_id).await
+}
+
+pub async fn set_muted_categories(
+    pool: &PgPool,
+    user_id: Uuid,
+    categories: &[NotificationCategory],
+) -> CaraiResult<()> {
+    repositories::set_muted_categories(pool, user_id, categories).await
+}
@@ -0,0 +1,95 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    models::{
+        Organization, OrganizationMember, OrganizationMemberProfile, OrganizationMembership,
+        OrganizationRole,
+    },
+    repositories,
+    utils::CaraiResult,
+};
+
+/// Creates an organization with its creator as the first owner.
+pub async fn create_organization(
+    pool: &PgPool,
+    organization: &Organization,
+    owner_id: Uuid,
+) -> CaraiResult<Organization> {
+    let organization = repositories::create_organization(pool, organization).await?;
+    let owner = OrganizationMember::new(organization.id, owner_id, OrganizationRole::Owner);
+    repositories::create_organization_member(pool, &owner).await?;
+    Ok(organization)
+}
+
+pub async fn get_organization_by_id(pool: &PgPool, id: Uuid) -> CaraiResult<Option<Organization>> {
+    repositories::get_organization_by_id(pool, id).await
+}
+
+pub async fn get_organization_by_slug(
+    pool: &PgPool,
+    slug: &str,
+) -> CaraiResult<Option<Organization>> {
+    repositories::get_organization_by_slug(pool, slug).await
+}
+
+pub async fn get_organizations_by_member_id(
+    pool: &PgPool,
+    user_id: Uuid,
+) -> CaraiResult<Vec<OrganizationMembership>> {
+    repositories::get_organizations_by_member_id(pool, user_id).await
+}
+
+pub async fn update_organization(
+    pool: &PgPool,
+    organization: &Organization,
+) -> CaraiResult<Organization> {
+    repositories::update_organization(pool, organization).await
+}
+
+pub async fn delete_organization(pool: &PgPool, id: Uuid) -> CaraiResult<()> {
+    repositories::delete_organization(pool, id).await
+}
+
+pub async fn create_organization_member(
+    pool: &PgPool,
+    member: &OrganizationMember,
+) -> CaraiResult<OrganizationMember> {
+    repositories::create_organization_member(pool, member).await
+}
+
+pub async fn get_organization_member(
+    pool: &PgPool,
+    organization_id: Uuid,
+    user_id: Uuid,
+) -> CaraiResult<Option<OrganizationMember>> {
+    repositories::get_organization_member(pool, organization_id, user_id).await
+}
+
+pub async fn get_organization_member_profiles(
+    pool: &PgPool,
+    organization_id: Uuid,
+) -> CaraiResult<Vec<OrganizationMemberProfile>> {
+    repositories::get_organization_member_profiles(pool, organization_id).await
+}
+
+pub async fn count_organization_owners(pool: &PgPool, organization_id: Uuid) -> CaraiResult<i64> {
+    repositories::count_organization_owners(pool, organization_id).await
+}
+
+pub async fn update_organization_member_role(
+    pool: &PgPool,
+    organization_id: Uuid,
+    user_id: Uuid,
+    role: OrganizationRole,
+) -> CaraiResult<OrganizationMember> {
+    repositories::update_organization_member_role(pool, organization_id, user_id, role).await
+}
+
+pub async fn delete_organization_member(
+    pool: &PgPool,
+    organization_id: Uuid,
+    user_id: Uuid,
+) -> CaraiResult<()> {
+    repositories::delete_organization_member(pool, organization_id, user_id).await
+}
@@ -0,0 +1,86 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    models::{MatchedLine, SearchFilter, SearchHit, SearchTerms},
+    repositories,
+    utils::CaraiResult,
+};
+
+/// How many matching lines are shown for each hit.
+const CONTEXT_LINES: usize = 3;
+/// Lines are cut at this many characters, so minified files do not bloat results.
+const MAX_LINE_CHARS: usize = 300;
+
+/// Searches the files the user can access, returning each hit with its matching lines.
+pub async fn search_code(
+    pool: &PgPool,
+    query: &str,
+    user_id: Option<Uuid>,
+    filter: &SearchFilter,
+    limit: i64,
+    offset: i64,
+) -> CaraiResult<Vec<(SearchHit, Vec<MatchedLine>)>> {
+    let terms = SearchTerms::new(query);
+    let hits = repositories::search_files(pool, &terms, user_id, filter, limit, offset).await?;
+    let words = terms.words();
+    Ok(hits
+        .into_iter()
+        .map(|hit| {
+            let lines = matched_lines(&hit.content, &words);
+            (hit, lines)
+        })
+        .collect())
+}
+
+fn matched_lines(content: &str, words: &[String]) -> Vec<MatchedLine> {
+    content
+        .lines()
+        .enumerate()
+        .filter_map(|(index, line)| {
+            let highlights = highlight(line, words);
+            (!highlights.is_empty()).then(|| truncate_line(index + 1, line, highlights))
+        })
+        .take(CONTEXT_LINES)
+        .collect()
+}
+
+/// Finds the character ranges of `words` in `line`, ignoring ASCII case.
+fn highlight(line: &str, words: &[String]) -> Vec<(usize, usize)> {
+    let lowered = line.to_ascii_lowercase();
+    let mut ranges: Vec<(usize, usize)> = words
+        .iter()
+        .flat_map(|word| {
+            lowered
+                .match_indices(word.as_str())
+                .map(|(start, word)| (start, start + word.len()))
+        })
+        .collect();
+    ranges.sort_unstable();
+
+    let mut merged: Vec<(usize, usize)> = Vec::new();
+    for (start, end) in ranges {
+        match merged.last_mut() {
+            Some(last) if start <= last.1 => last.1 = last.1.max(end),
+            _ => merged.push((start, end)),
+        }
+    }
+    merged
+        .into_iter()
+        .map(|(start, end)| (line[..start].chars().count(), line[..end].chars().count()))
+        .collect()
+}
+
+fn truncate_line(number: usize, line: &str, highlights: Vec<(usize, usize)>) -> MatchedLine {
+    let text: String = line.chars().take(MAX_LINE_CHARS).collect();
+    let highlights = highlights
+        .into_iter()
+        .filter(|(start, _)| *start < MAX_LINE_CHARS)
+        .map(|(start, end)| (start, end.min(MAX_LINE_CHARS)))
+        .collect();
+    MatchedLine {
+        number,
+        text,
+        highlights,
+    }
+}
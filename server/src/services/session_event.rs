@@ -0,0 +1,72 @@
+use std::io::Write;
+
+use chrono::{DateTime, Utc};
+use flate2::{write::GzEncoder, Compression};
+use serde::Serialize;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    models::{DocumentState, SessionEvent},
+    repositories,
+    utils::CaraiResult,
+};
+
+pub async fn record_session_event(pool: &PgPool, event: &SessionEvent) -> CaraiResult<()> {
+    repositories::create_session_event(pool, event).await?;
+    Ok(())
+}
+
+pub async fn get_session_events(
+    pool: &PgPool,
+    workspace_id: Uuid,
+    file_id: Option<Uuid>,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+    after: i64,
+    limit: i64,
+) -> CaraiResult<Vec<SessionEvent>> {
+    repositories::get_session_events(pool, workspace_id, file_id, from, to, after, limit).await
+}
+
+/// Reconstructs what a file looked like at `at`.
+///
+/// The saved text comes from the revision current at the time, while the collaborative
+/// document is the latest recorded snapshot plus every edit it does not cover yet.
+pub async fn get_document_state_at(
+    pool: &PgPool,
+    file_id: Uuid,
+    at: DateTime<Utc>,
+) -> CaraiResult<DocumentState> {
+    let revision = match repositories::get_file_revision_at(pool, file_id, at).await? {
+        Some(revision) => {
+            let content = repositories::get_blob_by_hash(pool, &revision.blob_hash)
+                .await?
+                .map(|blob| blob.content)
+                .ok_or_else(|| anyhow::anyhow!("Missing blob {}", revision.blob_hash))?;
+            Some((revision, content))
+        }
+        None => None,
+    };
+    let snapshot = repositories::get_session_snapshot_at(pool, file_id, at).await?;
+    let seq = snapshot
+        .as_ref()
+        .and_then(|snapshot| snapshot.seq)
+        .unwrap_or(0);
+    let edits = repositories::get_session_edits_after(pool, file_id, seq, at).await?;
+    Ok(DocumentState {
+        revision,
+        snapshot,
+        edits,
+    })
+}
+
+/// Serializes events as newline-delimited JSON and gzips the result.
+pub fn compress_session_log<T: Serialize>(events: &[T]) -> CaraiResult<Vec<u8>> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    for event in events {
+        serde_json::to_writer(&mut encoder, event)?;
+        encoder.write_all(b"\n")?;
+    }
+    Ok(encoder.finish()?)
+}
@@ -0,0 +1,45 @@
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{models::Snippet, repositories, utils::CaraiResult};
+
+pub async fn create_snippet(pool: &PgPool, snippet: &Snippet) -> CaraiResult<Snippet> {
+    repositories::create_snippet(pool, snippet).await
+}
+
+pub async fn get_snippet_by_slug(pool: &PgPool, slug: &str) -> CaraiResult<Option<Snippet>> {
+    repositories::get_snippet_by_slug(pool, slug).await
+}
+
+pub async fn get_snippets_by_owner_id(
+    pool: &PgPool,
+    owner_id: Uuid,
+    limit: i64,
+    offset: i64,
+) -> CaraiResult<Vec<Snippet>> {
+    repositories::get_snippets_by_owner_id(pool, owner_id, limit, offset).await
+}
+
+pub async fn get_public_snippets(
+    pool: &PgPool,
+    limit: i64,
+    offset: i64,
+) -> CaraiResult<Vec<Snippet>> {
+    repositories::get_public_snippets(pool, limit, offset).await
+}
+
+pub async fn update_snippet(pool: &PgPool, snippet: &Snippet) -> CaraiResult<Snippet> {
+    repositories::update_snippet(pool, snippet).await
+}
+
+pub async fn delete_snippet(pool: &PgPool, id: Uuid) -> CaraiResult<()> {
+    repositories::delete_snippet(pool, id).await
+}
+
+pub async fn delete_snippets_expired_before(
+    pool: &PgPool,
+    before: DateTime<Utc>,
+) -> CaraiResult<u64> {
+    repositories::delete_snippets_expired_before(pool, before).await
+}
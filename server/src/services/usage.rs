@@ -0,0 +1,115 @@
+use chrono::{DateTime, Duration, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    models::{ExecutionUsage, Organization, QuotaKind, QuotaUsage, UsageTotals, UserUsageReport},
+    repositories,
+    utils::{CaraiResult, QuotaLimits},
+};
+
+pub async fn record_execution_usage(
+    pool: &PgPool,
+    usage: &ExecutionUsage,
+) -> CaraiResult<ExecutionUsage> {
+    repositories::record_execution_usage(pool, usage).await
+}
+
+pub async fn get_usage_report(
+    pool: &PgPool,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+    limit: i64,
+    offset: i64,
+) -> CaraiResult<Vec<UserUsageReport>> {
+    repositories::get_usage_report(pool, from, to, limit, offset).await
+}
+
+/// Evaluates every execution quota of a user against the given limits.
+///
+/// Executions are counted over a rolling hour, CPU time over the current UTC day. Runs
+/// billed to an organization count against its quotas instead.
+pub async fn get_quota_usage(
+    pool: &PgPool,
+    user_id: Uuid,
+    limits: &QuotaLimits,
+) -> CaraiResult<Vec<QuotaUsage>> {
+    let (hour_ago, today) = quota_windows();
+    let totals = repositories::get_usage_totals(pool, user_id, hour_ago, today).await?;
+    Ok(evaluate_quotas(
+        totals,
+        today,
+        *limits.executions_per_hour(),
+        *limits.cpu_seconds_per_day(),
+        *limits.concurrent_runs(),
+    ))
+}
+
+/// Evaluates the execution quotas shared by the members of an organization.
+pub async fn get_organization_quota_usage(
+    pool: &PgPool,
+    organization: &Organization,
+) -> CaraiResult<Vec<QuotaUsage>> {
+    let (hour_ago, today) = quota_windows();
+    let totals =
+        repositories::get_organization_usage_totals(pool, organization.id, hour_ago, today).await?;
+    Ok(evaluate_quotas(
+        totals,
+        today,
+        organization.executions_per_hour,
+        organization.cpu_seconds_per_day,
+        organization.concurrent_runs,
+    ))
+}
+
+pub async fn get_organization_usage_report(
+    pool: &PgPool,
+    organization_id: Uuid,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+    limit: i64,
+    offset: i64,
+) -> CaraiResult<Vec<UserUsageReport>> {
+    repositories::get_organization_usage_report(pool, organization_id, from, to, limit, offset)
+        .await
+}
+
+/// Returns when the hourly and daily quota windows started.
+fn quota_windows() -> (DateTime<Utc>, DateTime<Utc>) {
+    let now = Utc::now();
+    let today = now
+        .date_naive()
+        .and_hms_opt(0, 0, 0)
+        .unwrap_or_default()
+        .and_utc();
+    (now - Duration::hours(1), today)
+}
+
+fn evaluate_quotas(
+    totals: UsageTotals,
+    today: DateTime<Utc>,
+    executions_per_hour: i64,
+    cpu_seconds_per_day: i64,
+    concurrent_runs: i64,
+) -> Vec<QuotaUsage> {
+    vec![
+        QuotaUsage {
+            quota: QuotaKind::ExecutionsPerHour,
+            used: totals.executions_since,
+            limit: executions_per_hour,
+            resets_at: totals.oldest_execution_at.map(|at| at + Duration::hours(1)),
+        },
+        QuotaUsage {
+            quota: QuotaKind::CpuSecondsPerDay,
+            used: totals.cpu_ms_since / 1000,
+            limit: cpu_seconds_per_day,
+            resets_at: Some(today + Duration::days(1)),
+        },
+        QuotaUsage {
+            quota: QuotaKind::ConcurrentRuns,
+            used: totals.active_runs,
+            limit: concurrent_runs,
+            resets_at: None,
+        },
+    ]
+}
@@ -0,0 +1,161 @@
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    models::{
+        ClassroomRole, Submission, Webhook, WebhookDelivery, WebhookDeliveryStatus, WebhookEvent,
+        Workspace, WorkspaceFile,
+    },
+    repositories,
+    utils::CaraiResult,
+};
+
+pub async fn create_webhook(pool: &PgPool, webhook: &Webhook) -> CaraiResult<Webhook> {
+    repositories::create_webhook(pool, webhook).await
+}
+
+pub async fn get_webhook_by_id(pool: &PgPool, id: Uuid) -> CaraiResult<Option<Webhook>> {
+    repositories::get_webhook_by_id(pool, id).await
+}
+
+pub async fn get_webhooks_by_owner(
+    pool: &PgPool,
+    user_id: Option<Uuid>,
+    organization_id: Option<Uuid>,
+) -> CaraiResult<Vec<Webhook>> {
+    repositories::get_webhooks_by_owner(pool, user_id, organization_id).await
+}
+
+pub async fn update_webhook(pool: &PgPool, webhook: &Webhook) -> CaraiResult<Webhook> {
+    repositories::update_webhook(pool, webhook).await
+}
+
+pub async fn delete_webhook(pool: &PgPool, id: Uuid) -> CaraiResult<()> {
+    repositories::delete_webhook(pool, id).await
+}
+
+/// Queues the event for the subscribed webhooks of the users and the organization.
+pub async fn emit_webhook_event(
+    pool: &PgPool,
+    event: WebhookEvent,
+    data: Value,
+    user_ids: &[Uuid],
+    organization_id: Option<Uuid>,
+) -> CaraiResult<()> {
+    let payload = event.payload(data);
+    let queued =
+        repositories::enqueue_webhook_deliveries(pool, event, &payload, user_ids, organization_id)
+            .await?;
+    if queued > 0 {
+        tracing::debug!("Queued {} deliveries of {}", queued, event);
+    }
+    Ok(())
+}
+
+/// Tells the owner and the organization of a workspace about it.
+pub async fn emit_workspace_event(
+    pool: &PgPool,
+    event: WebhookEvent,
+    workspace: &Workspace,
+) -> CaraiResult<()> {
+    let data = WebhookEvent::workspace(workspace);
+    emit_webhook_event(
+        pool,
+        event,
+        data,
+        &[workspace.owner_id],
+        workspace.organization_id,
+    )
+    .await
+}
+
+/// Tells the owner and the organization of a file's workspace about the file.
+pub async fn emit_file_event(
+    pool: &PgPool,
+    event: WebhookEvent,
+    file: &WorkspaceFile,
+) -> CaraiResult<()> {
+    let Some(workspace) = repositories::get_workspace_by_id(pool, file.workspace_id).await? else {
+        return Ok(());
+    };
+    let data = WebhookEvent::file(file);
+    emit_webhook_event(
+        pool,
+        event,
+        data,
+        &[workspace.owner_id],
+        workspace.organization_id,
+    )
+    .await
+}
+
+/// Tells the student and the instructors of the classroom about a submission.
+pub async fn emit_submission_event(
+    pool: &PgPool,
+    event: WebhookEvent,
+    submission: &Submission,
+) -> CaraiResult<()> {
+    let mut user_ids = vec![submission.user_id];
+    if let Some(assignment) =
+        repositories::get_assignment_by_id(pool, submission.assignment_id).await?
+    {
+        let members =
+            repositories::get_classroom_member_profiles(pool, assignment.classroom_id).await?;
+        user_ids.extend(
+            members
+                .into_iter()
+                .filter(|member| member.role == ClassroomRole::Instructor)
+                .map(|member| member.user_id),
+        );
+    }
+    let data = WebhookEvent::submission(submission);
+    emit_webhook_event(pool, event, data, &user_ids, None).await
+}
+
+pub async fn redeliver_webhook_delivery(
+    pool: &PgPool,
+    delivery: &WebhookDelivery,
+) -> CaraiResult<WebhookDelivery> {
+    repositories::create_webhook_delivery(pool, &WebhookDelivery::redeliver(delivery)).await
+}
+
+pub async fn claim_next_webhook_delivery(
+    pool: &PgPool,
+    locked_until: DateTime<Utc>,
+) -> CaraiResult<Option<WebhookDelivery>> {
+    repositories::claim_next_webhook_delivery(pool, locked_until).await
+}
+
+pub async fn update_webhook_delivery_attempt(
+    pool: &PgPool,
+    delivery: &WebhookDelivery,
+) -> CaraiResult<()> {
+    repositories::update_webhook_delivery_attempt(pool, delivery).await
+}
+
+pub async fn get_webhook_deliveries(
+    pool: &PgPool,
+    webhook_id: Uuid,
+    status: Option<WebhookDeliveryStatus>,
+    limit: i64,
+    offset: i64,
+) -> CaraiResult<Vec<WebhookDelivery>> {
+    repositories::get_webhook_deliveries(pool, webhook_id, status, limit, offset).await
+}
+
+pub async fn get_webhook_delivery_by_id(
+    pool: &PgPool,
+    webhook_id: Uuid,
+    id: Uuid,
+) -> CaraiResult<Option<WebhookDelivery>> {
+    repositories::get_webhook_delivery_by_id(pool, webhook_id, id).await
+}
+
+pub async fn delete_webhook_deliveries_finished_before(
+    pool: &PgPool,
+    before: DateTime<Utc>,
+) -> CaraiResult<u64> {
+    repositories::delete_webhook_deliveries_finished_before(pool, before).await
+}
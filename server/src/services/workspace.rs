@@ -0,0 +1,157 @@
+use anyhow::anyhow;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    models::{
+        Assignment, Snippet, WebhookEvent, Workspace, WorkspaceFile, WorkspaceMember,
+        WorkspaceRole, WorkspaceTemplate,
+    },
+    repositories,
+    utils::CaraiResult,
+};
+
+pub async fn create_workspace(pool: &PgPool, workspace: &Workspace) -> CaraiResult<Workspace> {
+    let workspace = repositories::create_workspace(pool, workspace).await?;
+    super::emit_workspace_event(pool, WebhookEvent::WorkspaceCreated, &workspace).await?;
+    Ok(workspace)
+}
+
+pub async fn get_workspace_by_id(pool: &PgPool, id: Uuid) -> CaraiResult<Option<Workspace>> {
+    repositories::get_workspace_by_id(pool, id).await
+}
+
+pub async fn get_workspace_by_name(
+    pool: &PgPool,
+    owner_id: Uuid,
+    name: &str,
+) -> CaraiResult<Option<Workspace>> {
+    repositories::get_workspace_by_name(pool, owner_id, name).await
+}
+
+pub async fn get_workspaces_by_member_id(
+    pool: &PgPool,
+    user_id: Uuid,
+    limit: i64,
+    offset: i64,
+) -> CaraiResult<Vec<Workspace>> {
+    repositories::get_workspaces_by_member_id(pool, user_id, limit, offset).await
+}
+
+pub async fn get_workspaces_by_organization_id(
+    pool: &PgPool,
+    organization_id: Uuid,
+    limit: i64,
+    offset: i64,
+) -> CaraiResult<Vec<Workspace>> {
+    repositories::get_workspaces_by_organization_id(pool, organization_id, limit, offset).await
+}
+
+pub async fn update_workspace(pool: &PgPool, workspace: &Workspace) -> CaraiResult<Workspace> {
+    let workspace = repositories::update_workspace(pool, workspace).await?;
+    super::emit_workspace_event(pool, WebhookEvent::WorkspaceUpdated, &workspace).await?;
+    Ok(workspace)
+}
+
+pub async fn delete_workspace(pool: &PgPool, workspace: &Workspace) -> CaraiResult<()> {
+    repositories::delete_workspace(pool, workspace.id).await?;
+    super::emit_workspace_event(pool, WebhookEvent::WorkspaceDeleted, workspace).await
+}
+
+/// Hands a workspace over to one of its members; the previous owner stays on as an editor.
+pub async fn transfer_workspace_ownership(
+    pool: &PgPool,
+    workspace: &Workspace,
+    new_owner_id: Uuid,
+) -> CaraiResult<Workspace> {
+    repositories::delete_workspace_member(pool, workspace.id, new_owner_id).await?;
+    let transferred =
+        repositories::update_workspace_owner(pool, workspace.id, new_owner_id).await?;
+    let previous_owner =
+        WorkspaceMember::new(workspace.id, workspace.owner_id, WorkspaceRole::Editor);
+    repositories::create_workspace_member(pool, &previous_owner).await?;
+    super::emit_workspace_event(pool, WebhookEvent::WorkspaceUpdated, &transferred).await?;
+    Ok(transferred)
+}
+
+/// Creates `fork` with a copy of every file of `source`, and counts the fork on the source.
+pub async fn fork_workspace(
+    pool: &PgPool,
+    source: &Workspace,
+    fork: &Workspace,
+) -> CaraiResult<Workspace> {
+    let files = repositories::get_workspace_files(pool, source.id).await?;
+    let files = files.into_iter().map(|file| (file.path, file.content));
+    let fork = create_workspace_with_files(pool, fork, files).await?;
+    repositories::increment_workspace_fork_count(pool, source.id).await?;
+    Ok(fork)
+}
+
+/// Creates `fork` from the files of a snippet, and counts the fork on the snippet.
+pub async fn fork_snippet(
+    pool: &PgPool,
+    snippet: &Snippet,
+    fork: &Workspace,
+) -> CaraiResult<Workspace> {
+    let files = snippet
+        .files
+        .iter()
+        .map(|file| (file.name.clone(), file.content.clone()));
+    let fork = create_workspace_with_files(pool, fork, files).await?;
+    repositories::increment_snippet_fork_count(pool, snippet.id).await?;
+    Ok(fork)
+}
+
+/// Creates a workspace holding a copy of the template's files.
+pub async fn create_workspace_from_template(
+    pool: &PgPool,
+    workspace: &Workspace,
+    template: &WorkspaceTemplate,
+) -> CaraiResult<Workspace> {
+    let files = template
+        .files
+        .iter()
+        .map(|file| (file.path.clone(), file.content.clone()));
+    create_workspace_with_files(pool, workspace, files).await
+}
+
+/// Creates a workspace holding a copy of an assignment's starter files.
+pub async fn create_workspace_from_assignment(
+    pool: &PgPool,
+    workspace: &Workspace,
+    assignment: &Assignment,
+) -> CaraiResult<Workspace> {
+    let files = assignment
+        .starter_files
+        .iter()
+        .map(|file| (file.path.clone(), file.content.clone()));
+    create_workspace_with_files(pool, workspace, files).await
+}
+
+/// Creates a workspace holding the files read from an uploaded archive.
+pub async fn import_workspace(
+    pool: &PgPool,
+    workspace: &Workspace,
+    files: Vec<(String, String)>,
+) -> CaraiResult<Workspace> {
+    create_workspace_with_files(pool, workspace, files.into_iter()).await
+}
+
+/// Creates a workspace and its files in one transaction, so a failure halfway leaves
+/// no partial workspace behind.
+async fn create_workspace_with_files(
+    pool: &PgPool,
+    workspace: &Workspace,
+    files: impl Iterator<Item = (String, String)>,
+) -> CaraiResult<Workspace> {
+    let error = |e: sqlx::Error| anyhow!("Unable to create workspace ({})", e);
+    let mut tx = pool.begin().await.map_err(error)?;
+    let workspace = repositories::create_workspace(&mut *tx, workspace).await?;
+    for (path, content) in files {
+        let file = WorkspaceFile::new(workspace.id, path, content);
+        super::store_workspace_file(&mut tx, &file, workspace.owner_id).await?;
+    }
+    tx.commit().await.map_err(error)?;
+    super::emit_workspace_event(pool, WebhookEvent::WorkspaceCreated, &workspace).await?;
+    Ok(workspace)
+}
@@ -0,0 +1,79 @@
+use sqlx::{PgConnection, PgPool};
+use uuid::Uuid;
+
+use crate::{
+    models::{WebhookEvent, WorkspaceFile},
+    repositories,
+    utils::CaraiResult,
+};
+
+pub async fn create_workspace_file(
+    pool: &PgPool,
+    file: &WorkspaceFile,
+    author_id: Uuid,
+) -> CaraiResult<WorkspaceFile> {
+    let file = store_workspace_file(&mut *pool.acquire().await?, file, author_id).await?;
+    super::emit_file_event(pool, WebhookEvent::FileCreated, &file).await?;
+    Ok(file)
+}
+
+/// Creates a file without announcing it, for files created along with their workspace.
+pub(super) async fn store_workspace_file(
+    conn: &mut PgConnection,
+    file: &WorkspaceFile,
+    author_id: Uuid,
+) -> CaraiResult<WorkspaceFile> {
+    let file = repositories::create_workspace_file(&mut *conn, file).await?;
+    super::record_file_revision(conn, &file, author_id).await?;
+    repositories::touch_workspace(&mut *conn, file.workspace_id).await?;
+    Ok(file)
+}
+
+pub async fn get_workspace_file_by_id(
+    pool: &PgPool,
+    workspace_id: Uuid,
+    id: Uuid,
+) -> CaraiResult<Option<WorkspaceFile>> {
+    repositories::get_workspace_file_by_id(pool, workspace_id, id).await
+}
+
+pub async fn get_workspace_file_by_path(
+    pool: &PgPool,
+    workspace_id: Uuid,
+    path: &str,
+) -> CaraiResult<Option<WorkspaceFile>> {
+    repositories::get_workspace_file_by_path(pool, workspace_id, path).await
+}
+
+pub async fn get_workspace_files(
+    pool: &PgPool,
+    workspace_id: Uuid,
+) -> CaraiResult<Vec<WorkspaceFile>> {
+    repositories::get_workspace_files(pool, workspace_id).await
+}
+
+pub async fn count_workspace_files(pool: &PgPool, workspace_id: Uuid) -> CaraiResult<i64> {
+    repositories::count_workspace_files(pool, workspace_id).await
+}
+
+pub async fn update_workspace_file(
+    pool: &PgPool,
+    file: &WorkspaceFile,
+    author_id: Uuid,
+) -> CaraiResult<WorkspaceFile> {
+    let previous = repositories::get_workspace_file_by_id(pool, file.workspace_id, file.id).await?;
+    let file = repositories::update_workspace_file(pool, file).await?;
+    if let Some(previous) = previous {
+        super::reanchor_comment_threads(pool, file.id, &previous.content, &file.content).await?;
+    }
+    super::record_file_revision(&mut *pool.acquire().await?, &file, author_id).await?;
+    repositories::touch_workspace(pool, file.workspace_id).await?;
+    super::emit_file_event(pool, WebhookEvent::FileUpdated, &file).await?;
+    Ok(file)
+}
+
+pub async fn delete_workspace_file(pool: &PgPool, file: &WorkspaceFile) -> CaraiResult<()> {
+    repositories::delete_workspace_file(pool, file.id).await?;
+    repositories::touch_workspace(pool, file.workspace_id).await?;
+    super::emit_file_event(pool, WebhookEvent::FileDeleted, file).await
+}
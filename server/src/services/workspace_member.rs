@@ -0,0 +1,182 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    models::{
+        InvitationStatus, Notification, Workspace, WorkspaceInvitation, WorkspaceMember,
+        WorkspaceMemberProfile, WorkspaceRole,
+    },
+    repositories,
+    utils::CaraiResult,
+};
+
+pub async fn create_workspace_member(
+    pool: &PgPool,
+    member: &WorkspaceMember,
+) -> CaraiResult<WorkspaceMember> {
+    repositories::create_workspace_member(pool, member).await
+}
+
+pub async fn get_workspace_member(
+    pool: &PgPool,
+    workspace_id: Uuid,
+    user_id: Uuid,
+) -> CaraiResult<Option<WorkspaceMember>> {
+    repositories::get_workspace_member(pool, workspace_id, user_id).await
+}
+
+/// The role a user holds in a workspace as its owner, as a member or through its
+/// organization, whichever grants the most, or `None` when they have no access.
+pub async fn get_workspace_role(
+    pool: &PgPool,
+    workspace: &Workspace,
+    user_id: Uuid,
+) -> CaraiResult<Option<WorkspaceRole>> {
+    if workspace.owner_id == user_id {
+        return Ok(Some(WorkspaceRole::Owner));
+    }
+    let member_role = repositories::get_workspace_member(pool, workspace.id, user_id)
+        .await?
+        .map(|member| member.role);
+    let organization_role = match workspace.organization_id {
+        Some(organization_id) => {
+            repositories::get_organization_member(pool, organization_id, user_id)
+                .await?
+                .map(|member| member.role.workspace_role())
+        }
+        None => None,
+    };
+    Ok(member_role.max(organization_role))
+}
+
+/// Looks a user's role in a workspace up again, for connections that outlive the
+/// request that authorized them. Admins hold the owner role in every workspace.
+pub async fn refresh_workspace_role(
+    pool: &PgPool,
+    workspace_id: Uuid,
+    user_id: Uuid,
+    is_admin: bool,
+) -> CaraiResult<Option<WorkspaceRole>> {
+    let Some(workspace) = repositories::get_workspace_by_id(pool, workspace_id).await? else {
+        return Ok(None);
+    };
+    if is_admin {
+        return Ok(Some(WorkspaceRole::Owner));
+    }
+    get_workspace_role(pool, &workspace, user_id).await
+}
+
+pub async fn get_workspace_member_profiles(
+    pool: &PgPool,
+    workspace_id: Uuid,
+) -> CaraiResult<Vec<WorkspaceMemberProfile>> {
+    repositories::get_workspace_member_profiles(pool, workspace_id).await
+}
+
+pub async fn update_workspace_member_role(
+    pool: &PgPool,
+    workspace_id: Uuid,
+    user_id: Uuid,
+    role: WorkspaceRole,
+) -> CaraiResult<WorkspaceMember> {
+    repositories::update_workspace_member_role(pool, workspace_id, user_id, role).await
+}
+
+pub async fn delete_workspace_member(
+    pool: &PgPool,
+    workspace_id: Uuid,
+    user_id: Uuid,
+) -> CaraiResult<()> {
+    repositories::delete_workspace_member(pool, workspace_id, user_id).await
+}
+
+/// Creates an invitation, notifying the invitee when it is addressed to somebody.
+pub async fn create_workspace_invitation(
+    pool: &PgPool,
+    invitation: &WorkspaceInvitation,
+) -> CaraiResult<WorkspaceInvitation> {
+    let invitation = repositories::create_workspace_invitation(pool, invitation).await?;
+    if let Some(notification) = Notification::invitation(&invitation) {
+        super::notify(pool, &notification).await?;
+    }
+    Ok(invitation)
+}
+
+pub async fn get_workspace_invitation_by_id(
+    pool: &PgPool,
+    id: Uuid,
+) -> CaraiResult<Option<WorkspaceInvitation>> {
+    repositories::get_workspace_invitation_by_id(pool, id).await
+}
+
+pub async fn get_workspace_invitation_by_token(
+    pool: &PgPool,
+    token: &str,
+) -> CaraiResult<Option<WorkspaceInvitation>> {
+    repositories::get_workspace_invitation_by_token(pool, token).await
+}
+
+pub async fn get_pending_invitation_for_user(
+    pool: &PgPool,
+    workspace_id: Uuid,
+    invitee_id: Uuid,
+) -> CaraiResult<Option<WorkspaceInvitation>> {
+    repositories::get_pending_invitation_for_user(pool, workspace_id, invitee_id).await
+}
+
+pub async fn get_workspace_invitations(
+    pool: &PgPool,
+    workspace_id: Uuid,
+) -> CaraiResult<Vec<WorkspaceInvitation>> {
+    repositories::get_workspace_invitations(pool, workspace_id).await
+}
+
+pub async fn get_invitations_by_invitee_id(
+    pool: &PgPool,
+    invitee_id: Uuid,
+) -> CaraiResult<Vec<WorkspaceInvitation>> {
+    repositories::get_invitations_by_invitee_id(pool, invitee_id).await
+}
+
+pub async fn update_workspace_invitation_status(
+    pool: &PgPool,
+    id: Uuid,
+    status: InvitationStatus,
+) -> CaraiResult<WorkspaceInvitation> {
+    repositories::update_workspace_invitation_status(pool, id, status).await
+}
+
+/// Adds the invitee to the workspace and marks the invitation as accepted.
+pub async fn accept_workspace_invitation(
+    pool: &PgPool,
+    invitation: &WorkspaceInvitation,
+    user_id: Uuid,
+) -> CaraiResult<WorkspaceMember> {
+    let member = WorkspaceMember::new(invitation.workspace_id, user_id, invitation.role);
+    let member = repositories::create_workspace_member(pool, &member).await?;
+    repositories::update_workspace_invitation_status(
+        pool,
+        invitation.id,
+        InvitationStatus::Accepted,
+    )
+    .await?;
+    Ok(member)
+}
+
+/// Adds whoever holds an invite link to the workspace, counting one use of the link.
+///
+/// Returns `None` if the link was revoked, expired or used up.
+pub async fn redeem_workspace_invitation(
+    pool: &PgPool,
+    invitation: &WorkspaceInvitation,
+    user_id: Uuid,
+) -> CaraiResult<Option<WorkspaceMember>> {
+    let Some(invitation) = repositories::redeem_workspace_invitation(pool, invitation.id).await?
+    else {
+        return Ok(None);
+    };
+    let member = WorkspaceMember::new(invitation.workspace_id, user_id, invitation.role);
+    repositories::create_workspace_member(pool, &member)
+        .await
+        .map(Some)
+}
@@ -0,0 +1,40 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{models::WorkspaceTemplate, repositories, utils::CaraiResult};
+
+pub async fn create_workspace_template(
+    pool: &PgPool,
+    template: &WorkspaceTemplate,
+) -> CaraiResult<WorkspaceTemplate> {
+    repositories::create_workspace_template(pool, template).await
+}
+
+pub async fn get_workspace_template_by_slug(
+    pool: &PgPool,
+    slug: &str,
+) -> CaraiResult<Option<WorkspaceTemplate>> {
+    repositories::get_workspace_template_by_slug(pool, slug).await
+}
+
+pub async fn get_workspace_templates(
+    pool: &PgPool,
+    user_id: Option<Uuid>,
+    language: Option<&str>,
+    tag: Option<&str>,
+    limit: i64,
+    offset: i64,
+) -> CaraiResult<Vec<WorkspaceTemplate>> {
+    repositories::get_workspace_templates(pool, user_id, language, tag, limit, offset).await
+}
+
+pub async fn update_workspace_template(
+    pool: &PgPool,
+    template: &WorkspaceTemplate,
+) -> CaraiResult<WorkspaceTemplate> {
+    repositories::update_workspace_template(pool, template).await
+}
+
+pub async fn delete_workspace_template(pool: &PgPool, id: Uuid) -> CaraiResult<()> {
+    repositories::delete_workspace_template(pool, id).await
+}
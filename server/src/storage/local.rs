@@ -0,0 +1,79 @@
+#![deny(missing_docs)]
+//! Stores objects as files below a root directory.
+
+use std::{
+    io::ErrorKind,
+    path::{Path, PathBuf},
+};
+
+use anyhow::bail;
+use futures_util::{future::BoxFuture, FutureExt};
+use uuid::Uuid;
+
+use super::BlobStore;
+use crate::utils::CaraiResult;
+
+/// A [`BlobStore`] keeping every object in a file named after its key.
+#[derive(Debug, Clone)]
+pub struct LocalBlobStore {
+    root: PathBuf,
+}
+
+impl LocalBlobStore {
+    /// Creates a store below `root`, which is created on the first write.
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    fn path(&self, key: &str) -> CaraiResult<PathBuf> {
+        let escapes_root = key
+            .split('/')
+            .any(|component| matches!(component, "" | "." | ".."));
+        if escapes_root || key.contains('\\') {
+            bail!("Invalid blob key {}", key);
+        }
+        Ok(self.root.join(key))
+    }
+}
+
+impl BlobStore for LocalBlobStore {
+    fn put<'a>(
+        &'a self,
+        key: &'a str,
+        bytes: Vec<u8>,
+        _content_type: &'a str,
+    ) -> BoxFuture<'a, CaraiResult<()>> {
+        async move {
+            let path = self.path(key)?;
+            let dir = path.parent().unwrap_or(Path::new("."));
+            tokio::fs::create_dir_all(dir).await?;
+            // Readers never see a partly written file, since renaming is atomic
+            let temporary = dir.join(format!(".{}.tmp", Uuid::new_v4()));
+            tokio::fs::write(&temporary, bytes).await?;
+            tokio::fs::rename(&temporary, &path).await?;
+            Ok(())
+        }
+        .boxed()
+    }
+
+    fn get<'a>(&'a self, key: &'a str) -> BoxFuture<'a, CaraiResult<Option<Vec<u8>>>> {
+        async move {
+            match tokio::fs::read(self.path(key)?).await {
+                Ok(bytes) => Ok(Some(bytes)),
+                Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+                Err(e) => Err(e.into()),
+            }
+        }
+        .boxed()
+    }
+
+    fn delete<'a>(&'a self, key: &'a str) -> BoxFuture<'a, CaraiResult<()>> {
+        async move {
+            match tokio::fs::remove_file(self.path(key)?).await {
+                Err(e) if e.kind() != ErrorKind::NotFound => Err(e.into()),
+                _ => Ok(()),
+            }
+        }
+        .boxed()
+    }
+}
@@ -0,0 +1,42 @@
+#![deny(missing_docs)]
+//! Object storage for uploaded files, behind a backend chosen by configuration.
+
+mod local;
+mod s3;
+
+pub use local::*;
+pub use s3::*;
+
+use std::{fmt::Debug, sync::Arc};
+
+use futures_util::future::BoxFuture;
+
+use crate::utils::{CaraiResult, StorageBackend, StorageConfig};
+
+/// Stores immutable objects under `/`-separated keys.
+///
+/// Keys are chosen by the server, never by clients, and are stable for a given
+/// content, so writing the same key twice stores the same bytes.
+pub trait BlobStore: Debug + Send + Sync {
+    /// Stores an object, replacing any object under the same key.
+    fn put<'a>(
+        &'a self,
+        key: &'a str,
+        bytes: Vec<u8>,
+        content_type: &'a str,
+    ) -> BoxFuture<'a, CaraiResult<()>>;
+
+    /// Reads an object, or `None` if there is none under the key.
+    fn get<'a>(&'a self, key: &'a str) -> BoxFuture<'a, CaraiResult<Option<Vec<u8>>>>;
+
+    /// Deletes an object; deleting a missing object is not an error.
+    fn delete<'a>(&'a self, key: &'a str) -> BoxFuture<'a, CaraiResult<()>>;
+}
+
+/// Creates the backend selected in the `storage` section of the configuration.
+pub fn create_blob_store(config: &StorageConfig) -> CaraiResult<Arc<dyn BlobStore>> {
+    Ok(match config.backend() {
+        StorageBackend::Local => Arc::new(LocalBlobStore::new(config.local_root())),
+        StorageBackend::S3 => Arc::new(S3BlobStore::new(config)?),
+    })
+}
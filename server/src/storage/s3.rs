@@ -0,0 +1,211 @@
+#![deny(missing_docs)]
+//! Stores objects in a bucket of an S3-compatible object store.
+//!
+//! Requests are signed with AWS Signature Version 4 and address the bucket by
+//! path, which every S3-compatible service (AWS, MinIO, R2, ...) understands.
+
+use std::time::Duration;
+
+use anyhow::{bail, Context};
+use chrono::{DateTime, Utc};
+use futures_util::{future::BoxFuture, FutureExt};
+use hmac::{Hmac, Mac};
+use reqwest::{
+    header::{AUTHORIZATION, CONTENT_TYPE},
+    Method, StatusCode, Url,
+};
+use sha2::{Digest, Sha256};
+
+use super::BlobStore;
+use crate::utils::{CaraiResult, StorageConfig};
+
+/// What Signature Version 4 covers of a request.
+#[derive(Debug, Clone, Copy)]
+pub struct SignedRequest<'a> {
+    /// The HTTP method, e.g. `GET`.
+    pub method: &'a str,
+    /// The URI-encoded path.
+    pub path: &'a str,
+    /// The canonical query string, with sorted and URI-encoded parameters.
+    pub query: &'a str,
+    /// Lowercase names and trimmed values of the signed headers, sorted by name.
+    pub headers: &'a [(&'a str, &'a str)],
+    /// The hex SHA-256 of the body, as sent in `x-amz-content-sha256`.
+    pub payload_hash: &'a str,
+}
+
+/// A [`BlobStore`] keeping every object in an S3 bucket, under its key.
+#[derive(Debug, Clone)]
+pub struct S3BlobStore {
+    http: reqwest::Client,
+    endpoint: String,
+    bucket: String,
+    region: String,
+    access_key: String,
+    secret_key: String,
+}
+
+impl S3BlobStore {
+    /// Creates a store from the `storage` section of the application configuration.
+    pub fn new(config: &StorageConfig) -> CaraiResult<Self> {
+        let http = reqwest::Client::builder()
+            .timeout(Duration::from_secs(30))
+            .build()
+            .context("Failed to build object storage client")?;
+        Ok(Self {
+            http,
+            endpoint: config.s3_endpoint().trim_end_matches('/').to_string(),
+            bucket: config.s3_bucket().clone(),
+            region: config.s3_region().clone(),
+            access_key: config.s3_access_key().clone(),
+            secret_key: config.s3_secret_key().clone(),
+        })
+    }
+
+    /// Sends a signed request for the object under `key`.
+    async fn request(
+        &self,
+        method: Method,
+        key: &str,
+        body: Vec<u8>,
+        content_type: Option<&str>,
+    ) -> CaraiResult<reqwest::Response> {
+        let url = Url::parse(&format!("{}/{}/{}", self.endpoint, self.bucket, key))
+            .context("Invalid object storage endpoint")?;
+        let host = match (url.host_str(), url.port()) {
+            (Some(host), Some(port)) => format!("{}:{}", host, port),
+            (Some(host), None) => host.to_string(),
+            (None, _) => bail!("Object storage endpoint {} has no host", self.endpoint),
+        };
+
+        let now = Utc::now();
+        let timestamp = now.format("%Y%m%dT%H%M%SZ").to_string();
+        let payload_hash = hex::encode(Sha256::digest(&body));
+        let authorization = self.authorization(
+            &SignedRequest {
+                method: method.as_str(),
+                path: url.path(),
+                query: "",
+                headers: &[
+                    ("host", &host),
+                    ("x-amz-content-sha256", &payload_hash),
+                    ("x-amz-date", &timestamp),
+                ],
+                payload_hash: &payload_hash,
+            },
+            now,
+        );
+
+        let mut request = self
+            .http
+            .request(method, url)
+            .header(AUTHORIZATION, authorization)
+            .header("x-amz-content-sha256", payload_hash)
+            .header("x-amz-date", timestamp);
+        if let Some(content_type) = content_type {
+            request = request.header(CONTENT_TYPE, content_type);
+        }
+        request
+            .body(body)
+            .send()
+            .await
+            .context("Unable to reach object storage")
+    }
+
+    /// The `Authorization` header signing a request made at `now`.
+    pub fn authorization(&self, request: &SignedRequest, now: DateTime<Utc>) -> String {
+        let timestamp = now.format("%Y%m%dT%H%M%SZ").to_string();
+        let date = now.format("%Y%m%d").to_string();
+        let canonical_headers: String = request
+            .headers
+            .iter()
+            .map(|(name, value)| format!("{}:{}\n", name, value))
+            .collect();
+        let signed_headers = request
+            .headers
+            .iter()
+            .map(|(name, _)| *name)
+            .collect::<Vec<_>>()
+            .join(";");
+        let canonical_request = format!(
+            "{}\n{}\n{}\n{}\n{}\n{}",
+            request.method,
+            request.path,
+            request.query,
+            canonical_headers,
+            signed_headers,
+            request.payload_hash
+        );
+        let scope = format!("{}/{}/s3/aws4_request", date, self.region);
+        let string_to_sign = format!(
+            "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+            timestamp,
+            scope,
+            hex::encode(Sha256::digest(canonical_request.as_bytes()))
+        );
+        let signing_key = [self.region.as_str(), "s3", "aws4_request"].iter().fold(
+            hmac_sha256(
+                format!("AWS4{}", self.secret_key).as_bytes(),
+                date.as_bytes(),
+            ),
+            |key, part| hmac_sha256(&key, part.as_bytes()),
+        );
+        format!(
+            "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+            self.access_key,
+            scope,
+            signed_headers,
+            hex::encode(hmac_sha256(&signing_key, string_to_sign.as_bytes()))
+        )
+    }
+}
+
+fn hmac_sha256(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any size");
+    mac.update(data);
+    mac.finalize().into_bytes().to_vec()
+}
+
+impl BlobStore for S3BlobStore {
+    fn put<'a>(
+        &'a self,
+        key: &'a str,
+        bytes: Vec<u8>,
+        content_type: &'a str,
+    ) -> BoxFuture<'a, CaraiResult<()>> {
+        async move {
+            let response = self
+                .request(Method::PUT, key, bytes, Some(content_type))
+                .await?;
+            if !response.status().is_success() {
+                bail!("Unable to store object {} ({})", key, response.status());
+            }
+            Ok(())
+        }
+        .boxed()
+    }
+
+    fn get<'a>(&'a self, key: &'a str) -> BoxFuture<'a, CaraiResult<Option<Vec<u8>>>> {
+        async move {
+            let response = self.request(Method::GET, key, Vec::new(), None).await?;
+            match response.status() {
+                StatusCode::NOT_FOUND => Ok(None),
+                status if status.is_success() => Ok(Some(response.bytes().await?.to_vec())),
+                status => bail!("Unable to read object {} ({})", key, status),
+            }
+        }
+        .boxed()
+    }
+
+    fn delete<'a>(&'a self, key: &'a str) -> BoxFuture<'a, CaraiResult<()>> {
+        async move {
+            let response = self.request(Method::DELETE, key, Vec::new(), None).await?;
+            let status = response.status();
+            if !status.is_success() && status != StatusCode::NOT_FOUND {
+                bail!("Unable to delete object {} ({})", key, status);
+            }
+            Ok(())
+        }
+        .boxed()
+    }
+}
@@ -4,7 +4,7 @@ use derive_more::derive::TryFrom;
 use getset::{Getters, MutGetters, Setters};
 use serde::Deserialize;
 use sqlx::postgres::{PgConnectOptions, PgSslMode};
-use std::{str::FromStr, sync::LazyLock};
+use std::{str::FromStr, sync::LazyLock, time::Duration};
 
 use crate::utils::CaraiResult;
 
@@ -19,6 +19,24 @@ pub struct AppConfig {
     pub environment: AppEnvironment,
     #[getset(get = "pub")]
     jwt: JwtConfig,
+    #[getset(get = "pub", get_mut = "pub")]
+    execution: ExecutionConfig,
+    #[getset(get = "pub", get_mut = "pub")]
+    rce: RceConfig,
+    #[getset(get = "pub", get_mut = "pub")]
+    quota: QuotaConfig,
+    #[getset(get = "pub", get_mut = "pub")]
+    collab: CollabConfig,
+    #[getset(get = "pub", get_mut = "pub")]
+    archive: ArchiveConfig,
+    #[getset(get = "pub", get_mut = "pub")]
+    lsp: LspConfig,
+    #[getset(get = "pub", get_mut = "pub")]
+    webhook: WebhookConfig,
+    #[getset(get = "pub", get_mut = "pub")]
+    storage: StorageConfig,
+    #[getset(get = "pub", get_mut = "pub")]
+    avatar: AvatarConfig,
 }
 
 impl AppConfig {
@@ -47,6 +65,62 @@ impl AppConfig {
             .set_default("rate_limit.window_size", 60)?
             .set_default("rate_limit.redis_uri", "redis://127.0.0.1")?
             .set_default("rate_limit.key_strategy", "token")?
+            .set_default("execution.workers", 4)?
+            .set_default("execution.poll_interval_ms", 500)?
+            .set_default("execution.max_attempts", 3)?
+            .set_default("execution.retention_days", 30)?
+            .set_default("execution.cleanup_interval_secs", 3600)?
+            .set_default("execution.cache_ttl_secs", 3600)?
+            .set_default("execution.uncached_languages", "")?
+            .set_default("rce.base_url", "http://127.0.0.1:8080/run")?
+            .set_default("rce.access_token", "")?
+            .set_default("rce.timeout_in_secs", 30)?
+            .set_default("rce.compile_timeout_in_secs", 60)?
+            .set_default("quota.user.executions_per_hour", 60)?
+            .set_default("quota.user.cpu_seconds_per_day", 600)?
+            .set_default("quota.user.concurrent_runs", 2)?
+            .set_default("quota.admin.executions_per_hour", 600)?
+            .set_default("quota.admin.cpu_seconds_per_day", 6000)?
+            .set_default("quota.admin.concurrent_runs", 10)?
+            .set_default("quota.organization.executions_per_hour", 2400)?
+            .set_default("quota.organization.cpu_seconds_per_day", 24000)?
+            .set_default("quota.organization.concurrent_runs", 20)?
+            .set_default("collab.snapshot_interval_updates", 200)?
+            .set_default("collab.max_frame_bytes", 1048576)?
+            .set_default("collab.presence_idle_timeout_secs", 60)?
+            .set_default("archive.max_upload_bytes", 10485760)?
+            .set_default("archive.max_unpacked_bytes", 52428800)?
+            .set_default("archive.binary_files", "reject")?
+            .set_default("lsp.root_dir", "/tmp/carai-lsp")?
+            .set_default(
+                "lsp.sandbox_command",
+                "bwrap --ro-bind /usr /usr --symlink usr/bin /bin --symlink usr/sbin /sbin \
+                 --symlink usr/lib /lib --symlink usr/lib64 /lib64 --ro-bind-try /opt /opt \
+                 --dev /dev --proc /proc --tmpfs /tmp --bind {dir} {dir} --chdir {dir} \
+                 --unshare-all --die-with-parent -- {command}",
+            )?
+            .set_default("lsp.idle_timeout_secs", 600)?
+            .set_default("lsp.max_servers_per_user", 2)?
+            .set_default("lsp.max_message_bytes", 4194304)?
+            .set_default("webhook.workers", 2)?
+            .set_default("webhook.poll_interval_ms", 1000)?
+            .set_default("webhook.timeout_secs", 10)?
+            .set_default("webhook.max_attempts", 8)?
+            .set_default("webhook.retry_base_delay_ms", 10000)?
+            .set_default("webhook.retry_max_delay_ms", 3600000)?
+            .set_default("webhook.retention_days", 30)?
+            .set_default("webhook.allow_private_addresses", false)?
+            .set_default("storage.backend", "local")?
+            .set_default("storage.local_root", "/tmp/carai-blobs")?
+            .set_default("storage.s3_endpoint", "http://127.0.0.1:9000")?
+            .set_default("storage.s3_bucket", "carai")?
+            .set_default("storage.s3_region", "us-east-1")?
+            .set_default("storage.s3_access_key", "")?
+            .set_default("storage.s3_secret_key", "")?
+            .set_default("avatar.max_upload_bytes", 5242880)?
+            .set_default("avatar.min_dimension", 64)?
+            .set_default("avatar.max_dimension", 4096)?
+            .set_default("avatar.base_url", "/avatars")?
             .add_source(Environment::with_prefix("APP").separator("__"))
             .build()?
             .try_deserialize()
@@ -148,3 +222,214 @@ pub struct JwtConfig {
     #[getset(get = "pub")]
     refresh_token_expiration_secs: i64,
 }
+
+#[derive(Debug, Deserialize, Getters, Setters, Clone)]
+pub struct ExecutionConfig {
+    #[getset(get = "pub", set = "pub")]
+    workers: usize,
+    #[getset(get = "pub", set = "pub")]
+    poll_interval_ms: u64,
+    #[getset(get = "pub", set = "pub")]
+    max_attempts: i32,
+    #[getset(get = "pub", set = "pub")]
+    retention_days: i64,
+    #[getset(get = "pub")]
+    cleanup_interval_secs: u64,
+    #[getset(get = "pub", set = "pub")]
+    cache_ttl_secs: i64,
+    #[getset(get = "pub", set = "pub")]
+    uncached_languages: String,
+}
+
+impl ExecutionConfig {
+    /// Whether results for the given language may be served from the result cache.
+    ///
+    /// Caching is off entirely when the TTL is zero; otherwise languages listed in
+    /// `uncached_languages` (comma-separated) opt out, e.g. non-deterministic runtimes.
+    pub fn is_cacheable(&self, language: &str) -> bool {
+        self.cache_ttl_secs > 0
+            && !self
+                .uncached_languages
+                .split(',')
+                .map(str::trim)
+                .any(|uncached| uncached.eq_ignore_ascii_case(language))
+    }
+}
+
+#[derive(Debug, Deserialize, Getters, Setters, Clone)]
+pub struct RceConfig {
+    #[getset(get = "pub", set = "pub")]
+    base_url: String,
+    #[getset(get = "pub")]
+    access_token: String,
+    #[getset(get = "pub", set = "pub")]
+    timeout_in_secs: u64,
+    #[getset(get = "pub", set = "pub")]
+    compile_timeout_in_secs: u64,
+}
+
+#[derive(Debug, Deserialize, Getters, Setters, Clone)]
+pub struct CollabConfig {
+    /// Number of stored updates after which a client is asked for a merged snapshot.
+    #[getset(get = "pub", set = "pub")]
+    snapshot_interval_updates: i64,
+    #[getset(get = "pub")]
+    max_frame_bytes: usize,
+    /// Seconds without any message from a collaborator before their presence expires;
+    /// values below one second count as one.
+    #[getset(get = "pub", set = "pub")]
+    presence_idle_timeout_secs: u64,
+}
+
+#[derive(Debug, Deserialize, Getters, Setters, Clone)]
+pub struct ArchiveConfig {
+    /// Largest archive accepted for import.
+    #[getset(get = "pub", set = "pub")]
+    max_upload_bytes: usize,
+    /// How much an imported archive may unpack to, so compressed bombs are caught early.
+    #[getset(get = "pub", set = "pub")]
+    max_unpacked_bytes: u64,
+    #[getset(get = "pub", set = "pub")]
+    binary_files: BinaryFilePolicy,
+}
+
+#[derive(Debug, Deserialize, Getters, Setters, Clone)]
+pub struct LspConfig {
+    /// Where workspace files are written for the language servers to read.
+    #[getset(get = "pub", set = "pub")]
+    root_dir: String,
+    /// Shell command confining a language server to its directory; `{dir}` is replaced
+    /// by the directory and `{command}` by the server's own command. It should only
+    /// expose the toolchains the servers need, never the host's files or configuration.
+    #[getset(get = "pub", set = "pub")]
+    sandbox_command: String,
+    /// Seconds without a message from the client before its language server is stopped;
+    /// values below one second count as one.
+    #[getset(get = "pub", set = "pub")]
+    idle_timeout_secs: u64,
+    #[getset(get = "pub", set = "pub")]
+    max_servers_per_user: usize,
+    #[getset(get = "pub")]
+    max_message_bytes: usize,
+}
+
+#[derive(Debug, Deserialize, Getters, Setters, Clone)]
+pub struct WebhookConfig {
+    #[getset(get = "pub", set = "pub")]
+    workers: usize,
+    #[getset(get = "pub", set = "pub")]
+    poll_interval_ms: u64,
+    /// Seconds an endpoint has to answer a delivery before the attempt fails.
+    #[getset(get = "pub", set = "pub")]
+    timeout_secs: u64,
+    /// Attempts after which a delivery is given up on.
+    #[getset(get = "pub", set = "pub")]
+    max_attempts: i32,
+    /// Delay before the first retry, doubled after every further failure.
+    #[getset(get = "pub", set = "pub")]
+    retry_base_delay_ms: u64,
+    #[getset(get = "pub", set = "pub")]
+    retry_max_delay_ms: u64,
+    /// Days finished deliveries are kept in the delivery log.
+    #[getset(get = "pub", set = "pub")]
+    retention_days: i64,
+    /// Lets webhooks point to private, loopback and link-local addresses, for local testing.
+    #[getset(get = "pub", set = "pub")]
+    allow_private_addresses: bool,
+}
+
+impl WebhookConfig {
+    /// How long to wait before retrying a delivery that failed `attempts` times.
+    pub fn retry_delay(&self, attempts: i32) -> Duration {
+        let exponent = attempts.saturating_sub(1).clamp(0, 30) as u32;
+        let delay = self.retry_base_delay_ms.saturating_mul(1 << exponent);
+        Duration::from_millis(delay.min(self.retry_max_delay_ms))
+    }
+}
+
+#[derive(Debug, Deserialize, Getters, Setters, Clone)]
+pub struct StorageConfig {
+    #[getset(get = "pub", set = "pub")]
+    backend: StorageBackend,
+    /// Directory the `local` backend keeps objects in.
+    #[getset(get = "pub", set = "pub")]
+    local_root: String,
+    /// Base URL of the S3-compatible service; buckets are addressed by path.
+    #[getset(get = "pub", set = "pub")]
+    s3_endpoint: String,
+    #[getset(get = "pub", set = "pub")]
+    s3_bucket: String,
+    #[getset(get = "pub", set = "pub")]
+    s3_region: String,
+    #[getset(get = "pub", set = "pub")]
+    s3_access_key: String,
+    #[getset(get = "pub", set = "pub")]
+    s3_secret_key: String,
+}
+
+/// Where uploaded files such as avatars are stored.
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum StorageBackend {
+    /// Files below a directory on the server.
+    Local,
+    /// A bucket of an S3-compatible object store.
+    S3,
+}
+
+#[derive(Debug, Deserialize, Getters, Setters, Clone)]
+pub struct AvatarConfig {
+    #[getset(get = "pub", set = "pub")]
+    max_upload_bytes: usize,
+    /// Smallest width and height accepted, in pixels.
+    #[getset(get = "pub", set = "pub")]
+    min_dimension: u32,
+    /// Largest width and height accepted, so huge images are refused before decoding.
+    #[getset(get = "pub", set = "pub")]
+    max_dimension: u32,
+    /// Where avatars are served from; a CDN in front of the server can be put here.
+    #[getset(get = "pub", set = "pub")]
+    base_url: String,
+}
+
+/// What importing does with files that are not UTF-8 text, which workspaces cannot hold.
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum BinaryFilePolicy {
+    /// Fails the whole import.
+    Reject,
+    /// Leaves the files out and reports them.
+    Skip,
+}
+
+#[derive(Debug, Deserialize, Getters, MutGetters, Clone)]
+pub struct QuotaConfig {
+    #[getset(get = "pub", get_mut = "pub")]
+    user: QuotaLimits,
+    #[getset(get = "pub", get_mut = "pub")]
+    admin: QuotaLimits,
+    /// The quotas new organizations start with, shared by all of their members.
+    #[getset(get = "pub", get_mut = "pub")]
+    organization: QuotaLimits,
+}
+
+impl QuotaConfig {
+    /// Returns the limits that apply to a user with the given role.
+    pub fn limits_for(&self, is_admin: bool) -> &QuotaLimits {
+        if is_admin {
+            &self.admin
+        } else {
+            &self.user
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Getters, Setters, Clone)]
+pub struct QuotaLimits {
+    #[getset(get = "pub", set = "pub")]
+    executions_per_hour: i64,
+    #[getset(get = "pub", set = "pub")]
+    cpu_seconds_per_day: i64,
+    #[getset(get = "pub", set = "pub")]
+    concurrent_runs: i64,
+}
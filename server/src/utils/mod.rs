@@ -1,7 +1,9 @@
 mod config;
 mod password;
+mod permits;
 mod response;
 
 pub use config::*;
 pub use password::*;
+pub use permits::*;
 pub use response::*;
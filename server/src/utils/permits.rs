@@ -0,0 +1,53 @@
+//! Bookkeeping of the work each user has in flight on this server, to enforce per-user limits.
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use uuid::Uuid;
+
+/// Counts the jobs of one kind, such as language servers, that each user is running.
+#[derive(Debug, Clone, Default)]
+pub struct PermitRegistry {
+    running: Arc<Mutex<HashMap<Uuid, usize>>>,
+}
+
+impl PermitRegistry {
+    /// Reserves a job for a user, unless they already run `limit` of them.
+    pub fn acquire(&self, user_id: Uuid, limit: usize) -> Option<Permit> {
+        let mut jobs = self.running.lock().unwrap_or_else(|e| e.into_inner());
+        let running = jobs.entry(user_id).or_default();
+        if *running >= limit {
+            return None;
+        }
+        *running += 1;
+        Some(Permit {
+            registry: self.clone(),
+            user_id,
+        })
+    }
+}
+
+/// A reserved job, released when dropped.
+#[derive(Debug)]
+pub struct Permit {
+    registry: PermitRegistry,
+    user_id: Uuid,
+}
+
+impl Drop for Permit {
+    fn drop(&mut self) {
+        let mut jobs = self
+            .registry
+            .running
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        if let Some(running) = jobs.get_mut(&self.user_id) {
+            *running -= 1;
+            if *running == 0 {
+                jobs.remove(&self.user_id);
+            }
+        }
+    }
+}
@@ -17,6 +17,8 @@ pub struct ErrorDetails {
     pub status: StatusCode,
     /// A user-facing error message.
     pub message: String,
+    /// Optional machine-readable context for the client.
+    pub data: Option<serde_json::Value>,
 }
 
 impl ErrorDetails {
@@ -25,6 +27,7 @@ impl ErrorDetails {
         Self {
             status,
             message: message.into(),
+            data: None,
         }
     }
 }
@@ -43,6 +46,13 @@ impl AppError {
         }
     }
 
+    /// Creates a new `AppError` carrying structured data alongside the message.
+    pub fn with_data(status: StatusCode, message: impl Into<String>, data: impl Serialize) -> Self {
+        let mut details = ErrorDetails::new(status, message);
+        details.data = serde_json::to_value(data).ok();
+        Self { details }
+    }
+
     /// Logs an internal error and returns a 500 status.
     pub fn internal(log_message: impl Into<anyhow::Error>) -> Self {
         error!("error: {}", log_message.into());
@@ -54,6 +64,7 @@ impl AppError {
         ErrorResponse {
             status: self.details.status.as_u16(),
             message: self.details.message.clone(),
+            data: self.details.data.clone(),
         }
     }
 
@@ -97,6 +108,11 @@ impl<T: Serialize> SuccessResponse<T> {
     pub fn created(body: T) -> Self {
         Self::new(StatusCode::CREATED, body)
     }
+
+    /// Creates a 202 Accepted response.
+    pub fn accepted(body: T) -> Self {
+        Self::new(StatusCode::ACCEPTED, body)
+    }
 }
 
 impl<T: Serialize> IntoResponse for SuccessResponse<T> {
@@ -113,6 +129,9 @@ pub struct ErrorResponse {
     pub status: u16,
     /// A user-readable error message.
     pub message: String,
+    /// Optional machine-readable context, omitted when empty.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<serde_json::Value>,
 }
 
 fn add_security_headers(mut response: Response, status: StatusCode) -> Response {
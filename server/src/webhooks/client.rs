@@ -0,0 +1,252 @@
+#![deny(missing_docs)]
+//! HTTP client delivering signed event payloads to webhook endpoints.
+
+use std::{
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use anyhow::Context;
+use hmac::{Hmac, Mac};
+use reqwest::{
+    dns::{Addrs, Name, Resolve, Resolving},
+    header::CONTENT_TYPE,
+    redirect::Policy,
+    Url,
+};
+use sha2::Sha256;
+
+use crate::{
+    models::{Webhook, WebhookDelivery},
+    utils::{CaraiResult, WebhookConfig},
+};
+
+/// Header carrying the name of the delivered event.
+pub const EVENT_HEADER: &str = "X-Carai-Event";
+/// Header carrying the ID of the delivery, which stays the same across retries.
+pub const DELIVERY_HEADER: &str = "X-Carai-Delivery";
+/// Header carrying `sha256=` followed by the hex HMAC-SHA256 of the body, keyed by the secret.
+pub const SIGNATURE_HEADER: &str = "X-Carai-Signature-256";
+
+/// How much of a receiver's response is kept in the delivery log, enough for an error
+/// message but too little to make webhooks a way of reading other services.
+const MAX_RESPONSE_BODY_CHARS: usize = 256;
+/// How much of a receiver's response is read at most, whatever it sends.
+const MAX_RESPONSE_READ_BYTES: usize = 1024;
+
+/// Signs a request body the way receivers are expected to verify it.
+pub fn sign_payload(secret: &str, body: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any size");
+    mac.update(body);
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+/// Whether an address can be reached from the public internet.
+///
+/// Private, loopback, link-local, shared, multicast and otherwise reserved ranges are
+/// not, nor are IPv6 addresses mapping onto such IPv4 ones.
+pub fn is_public_address(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_public_ipv4(ip),
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(ip) => is_public_ipv4(ip),
+            None => {
+                let [first, second, ..] = ip.segments();
+                !(ip.is_unspecified()
+                    || ip.is_loopback()
+                    || ip.is_multicast()
+                    // Unique local fc00::/7, link-local fe80::/10 and documentation 2001:db8::/32
+                    || first & 0xfe00 == 0xfc00
+                    || first & 0xffc0 == 0xfe80
+                    || (first, second) == (0x2001, 0xdb8))
+            }
+        },
+    }
+}
+
+fn is_public_ipv4(ip: Ipv4Addr) -> bool {
+    let [a, b, ..] = ip.octets();
+    !(ip.is_unspecified()
+        || ip.is_private()
+        || ip.is_loopback()
+        || ip.is_link_local()
+        || ip.is_broadcast()
+        || ip.is_documentation()
+        || ip.is_multicast()
+        // "This network" 0.0.0.0/8, shared 100.64.0.0/10 and reserved 240.0.0.0/4
+        || a == 0
+        || (a == 100 && (64..128).contains(&b))
+        || a >= 240)
+}
+
+/// Refuses webhook URLs whose host is, or resolves to, an address that is not public,
+/// unless `allow_private` is set.
+///
+/// Hosts that do not resolve pass, since deliveries resolve them again before connecting.
+pub async fn check_webhook_url(url: &str, allow_private: bool) -> Result<(), String> {
+    if allow_private {
+        return Ok(());
+    }
+    let url = Url::parse(url).map_err(|e| format!("Invalid webhook URL ({})", e))?;
+    let Some(host) = url.host_str() else {
+        return Err("Webhook URLs must have a host".to_string());
+    };
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    let addresses = match host.parse::<IpAddr>() {
+        Ok(ip) => vec![ip],
+        Err(_) => match tokio::net::lookup_host((host, 0)).await {
+            Ok(addresses) => addresses.map(|address| address.ip()).collect(),
+            Err(_) => return Ok(()),
+        },
+    };
+    if addresses.into_iter().all(is_public_address) {
+        Ok(())
+    } else {
+        Err("Webhook URLs must not point to private or local addresses".to_string())
+    }
+}
+
+/// Resolves endpoint hosts, failing for hosts with any address that is not public.
+///
+/// Connecting through it keeps a host from passing the check at registration and
+/// then resolving to an internal service by the time it is delivered to.
+#[derive(Debug)]
+struct PublicResolver;
+
+impl Resolve for PublicResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        Box::pin(async move {
+            let addresses: Vec<SocketAddr> =
+                tokio::net::lookup_host((name.as_str(), 0)).await?.collect();
+            if !addresses
+                .iter()
+                .all(|address| is_public_address(address.ip()))
+            {
+                return Err(
+                    format!("{} resolves to a private or local address", name.as_str()).into(),
+                );
+            }
+            Ok(Box::new(addresses.into_iter()) as Addrs)
+        })
+    }
+}
+
+/// What came of one attempt to deliver a payload.
+#[derive(Debug, Clone, Default)]
+pub struct DeliveryAttempt {
+    /// The HTTP status of the response, if the receiver answered.
+    pub response_status: Option<i32>,
+    /// The beginning of the response body, if the receiver answered.
+    pub response_body: Option<String>,
+    /// Why the request failed before a response arrived.
+    pub error: Option<String>,
+    /// How long the request took.
+    pub duration_ms: i64,
+}
+
+impl DeliveryAttempt {
+    /// Whether the receiver accepted the payload with a 2xx response.
+    pub fn succeeded(&self) -> bool {
+        self.response_status
+            .is_some_and(|status| (200..300).contains(&status))
+    }
+}
+
+/// Posts deliveries to webhook endpoints.
+#[derive(Debug, Clone)]
+pub struct WebhookClient {
+    http: reqwest::Client,
+    allow_private_addresses: bool,
+}
+
+impl WebhookClient {
+    /// Creates a client from the `webhook` section of the application configuration.
+    ///
+    /// Redirects are not followed, so an endpoint cannot bounce deliveries elsewhere, and
+    /// endpoints on private or local addresses are refused unless the configuration allows them.
+    pub fn new(config: &WebhookConfig) -> CaraiResult<Self> {
+        let allow_private_addresses = *config.allow_private_addresses();
+        let mut builder = reqwest::Client::builder()
+            .timeout(Duration::from_secs(*config.timeout_secs()))
+            .redirect(Policy::none())
+            .user_agent("carai-webhooks");
+        if !allow_private_addresses {
+            builder = builder.dns_resolver(Arc::new(PublicResolver));
+        }
+        let http = builder.build().context("Failed to build webhook client")?;
+        Ok(Self {
+            http,
+            allow_private_addresses,
+        })
+    }
+
+    /// Posts a delivery's payload to its webhook, signed with the webhook's secret.
+    pub async fn deliver(&self, webhook: &Webhook, delivery: &WebhookDelivery) -> DeliveryAttempt {
+        let body = match serde_json::to_vec(&delivery.payload) {
+            Ok(body) => body,
+            Err(e) => {
+                return DeliveryAttempt {
+                    error: Some(format!("Unable to encode payload ({})", e)),
+                    ..Default::default()
+                }
+            }
+        };
+        // Endpoints given as an IP address never reach the resolver
+        if let Err(e) = check_webhook_url(&webhook.url, self.allow_private_addresses).await {
+            return DeliveryAttempt {
+                error: Some(e),
+                ..Default::default()
+            };
+        }
+        let signature = sign_payload(&webhook.secret, &body);
+
+        let started = Instant::now();
+        let result = self
+            .http
+            .post(&webhook.url)
+            .header(CONTENT_TYPE, "application/json")
+            .header(EVENT_HEADER, delivery.event.to_string())
+            .header(DELIVERY_HEADER, delivery.id.to_string())
+            .header(SIGNATURE_HEADER, signature)
+            .body(body)
+            .send()
+            .await;
+        let mut attempt = match result {
+            Ok(response) => {
+                let status = response.status();
+                let text = read_start(response).await;
+                DeliveryAttempt {
+                    response_status: Some(status.as_u16() as i32),
+                    response_body: Some(text.chars().take(MAX_RESPONSE_BODY_CHARS).collect()),
+                    error: (!status.is_success())
+                        .then(|| format!("Endpoint answered with {}", status)),
+                    ..Default::default()
+                }
+            }
+            Err(e) => DeliveryAttempt {
+                error: Some(format!("Unable to reach endpoint ({})", e)),
+                ..Default::default()
+            },
+        };
+        attempt.duration_ms = started.elapsed().as_millis() as i64;
+        attempt
+    }
+}
+
+/// Reads the beginning of a response body, leaving the rest unread.
+///
+/// Receivers could otherwise send bodies large or endless enough to exhaust memory;
+/// the client's timeout bounds how long reading may take.
+async fn read_start(mut response: reqwest::Response) -> String {
+    let mut bytes = Vec::new();
+    while bytes.len() < MAX_RESPONSE_READ_BYTES {
+        match response.chunk().await {
+            Ok(Some(chunk)) => bytes.extend_from_slice(&chunk),
+            Ok(None) | Err(_) => break,
+        }
+    }
+    bytes.truncate(MAX_RESPONSE_READ_BYTES);
+    String::from_utf8_lossy(&bytes).into_owned()
+}
@@ -0,0 +1,60 @@
+use std::time::Duration;
+
+use chrono::Utc;
+use sqlx::PgPool;
+use tokio::{task::JoinHandle, time::interval};
+
+use crate::{
+    services::{
+        delete_cached_executions_expired_before, delete_executions_finished_before,
+        delete_snippets_expired_before, delete_unreferenced_blobs,
+        delete_webhook_deliveries_finished_before,
+    },
+    utils::AppConfig,
+};
+
+/// Periodically deletes finished executions and webhook deliveries older than
+/// their configured retention along with expired result cache entries and
+/// snippets, and blobs no file revision refers to.
+pub fn spawn_cleanup_worker(db_pool: PgPool, config: AppConfig) -> JoinHandle<()> {
+    let retention = chrono::Duration::days(*config.execution().retention_days());
+    let delivery_retention = chrono::Duration::days(*config.webhook().retention_days());
+    let period = Duration::from_secs(*config.execution().cleanup_interval_secs());
+
+    tokio::spawn(async move {
+        let mut ticker = interval(period);
+        loop {
+            ticker.tick().await;
+            match delete_executions_finished_before(&db_pool, Utc::now() - retention).await {
+                Ok(0) => {}
+                Ok(deleted) => tracing::info!("Deleted {} expired executions", deleted),
+                Err(e) => tracing::error!("Failed to delete expired executions: {}", e),
+            }
+            match delete_cached_executions_expired_before(&db_pool, Utc::now()).await {
+                Ok(0) => {}
+                Ok(deleted) => tracing::info!("Deleted {} expired cache entries", deleted),
+                Err(e) => tracing::error!("Failed to delete expired cache entries: {}", e),
+            }
+            match delete_snippets_expired_before(&db_pool, Utc::now()).await {
+                Ok(0) => {}
+                Ok(deleted) => tracing::info!("Deleted {} expired snippets", deleted),
+                Err(e) => tracing::error!("Failed to delete expired snippets: {}", e),
+            }
+            match delete_webhook_deliveries_finished_before(
+                &db_pool,
+                Utc::now() - delivery_retention,
+            )
+            .await
+            {
+                Ok(0) => {}
+                Ok(deleted) => tracing::info!("Deleted {} expired webhook deliveries", deleted),
+                Err(e) => tracing::error!("Failed to delete expired webhook deliveries: {}", e),
+            }
+            match delete_unreferenced_blobs(&db_pool).await {
+                Ok(0) => {}
+                Ok(deleted) => tracing::info!("Deleted {} unreferenced blobs", deleted),
+                Err(e) => tracing::error!("Failed to delete unreferenced blobs: {}", e),
+            }
+        }
+    })
+}
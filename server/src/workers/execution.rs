@@ -0,0 +1,257 @@
+use std::time::{Duration, Instant};
+
+use chrono::Utc;
+use sqlx::{types::Json, PgPool};
+use tokio::{task::JoinHandle, time::sleep};
+
+use crate::{
+    executor::{
+        find_language, grade_case, ExecutionRequest, Language, RceClient, BUILD_ARTIFACT_FILE,
+        CASE_TIMEOUT_MARGIN_MS,
+    },
+    models::{
+        CachedExecution, CaseResult, CompileOutput, Execution, ExecutionFile, ExecutionStatus,
+        ExecutionUsage, JudgeSpec,
+    },
+    services::{
+        cache_key, claim_next_execution, extend_execution_lease, finish_execution,
+        record_execution_usage, requeue_execution, upsert_cached_execution,
+    },
+    utils::{AppConfig, CaraiResult},
+};
+
+/// Extra time a worker keeps its lease on a job beyond the sandbox timeouts.
+const LEASE_MARGIN_SECS: i64 = 30;
+
+pub fn spawn_execution_workers(
+    db_pool: PgPool,
+    config: AppConfig,
+) -> CaraiResult<Vec<JoinHandle<()>>> {
+    let client = RceClient::new(config.rce())?;
+    let workers = *config.execution().workers();
+
+    tracing::info!("Starting {} execution workers", workers);
+    Ok((0..workers)
+        .map(|worker| {
+            let db_pool = db_pool.clone();
+            let client = client.clone();
+            let config = config.clone();
+            tokio::spawn(async move { run_worker(worker, db_pool, client, config).await })
+        })
+        .collect())
+}
+
+async fn run_worker(worker: usize, db_pool: PgPool, client: RceClient, config: AppConfig) {
+    let poll_interval = Duration::from_millis(*config.execution().poll_interval_ms());
+    let lease = lease_duration(&config, 1, |_| None);
+
+    loop {
+        match claim_next_execution(&db_pool, Utc::now() + lease).await {
+            Ok(Some(execution)) => {
+                tracing::debug!("Worker {} claimed {}", worker, execution);
+                if let Err(e) = process_execution(&db_pool, &client, &config, execution).await {
+                    tracing::error!("Worker {} failed to process execution: {}", worker, e);
+                }
+            }
+            Ok(None) => sleep(poll_interval).await,
+            Err(e) => {
+                tracing::error!("Worker {} failed to claim execution: {}", worker, e);
+                sleep(poll_interval).await;
+            }
+        }
+    }
+}
+
+async fn process_execution(
+    db_pool: &PgPool,
+    client: &RceClient,
+    config: &AppConfig,
+    mut execution: Execution,
+) -> CaraiResult<()> {
+    let max_attempts = *config.execution().max_attempts();
+
+    if execution.attempts > max_attempts {
+        execution.status = ExecutionStatus::Failed;
+        execution.error = Some("Execution exceeded the maximum number of attempts".to_string());
+        return finish_execution(db_pool, &execution).await.map(drop);
+    }
+
+    let Some(language) = find_language(&execution.language) else {
+        execution.status = ExecutionStatus::Failed;
+        execution.error = Some(format!("Unsupported language: {}", execution.language));
+        return finish_execution(db_pool, &execution).await.map(drop);
+    };
+
+    // Judged runs make one sandbox request per case, which the claim's lease does not cover
+    if let Some(spec) = execution.judge.as_deref() {
+        let lease = lease_duration(config, spec.cases.len(), |case| {
+            spec.cases[case].time_limit_ms.map(case_timeout)
+        });
+        if !extend_execution_lease(db_pool, &execution, Utc::now() + lease).await? {
+            tracing::warn!("Lost the claim on execution {}", execution.id);
+            return Ok(());
+        }
+    }
+
+    let started = Instant::now();
+    let result = run_execution(client, config, language, &mut execution).await;
+    // The sandbox does not report CPU time, so wall-clock time is what gets charged
+    let cpu_ms = started.elapsed().as_millis() as i64;
+
+    let held = match &result {
+        Ok(()) => {
+            execution.status = ExecutionStatus::Completed;
+            execution.duration_ms = Some(cpu_ms);
+            finish_execution(db_pool, &execution).await?
+        }
+        Err(e) if execution.attempts < max_attempts => {
+            tracing::warn!("Retrying execution {}: {}", execution.id, e);
+            requeue_execution(db_pool, &execution).await?
+        }
+        Err(e) => {
+            tracing::error!("Giving up on execution {}: {}", execution.id, e);
+            execution.status = ExecutionStatus::Failed;
+            execution.error = Some("Execution service unavailable, please try again".to_string());
+            finish_execution(db_pool, &execution).await?
+        }
+    };
+    // Whoever took the job over records the outcome and is charged for it instead
+    if !held {
+        tracing::warn!("Lost the claim on execution {}", execution.id);
+        return Ok(());
+    }
+
+    // Every attempt is charged, since timed-out and failed runs held the sandbox all the same
+    let mut usage = ExecutionUsage::new(
+        execution.user_id,
+        Some(execution.id),
+        &execution.language,
+        cpu_ms,
+    );
+    usage.organization_id = execution.organization_id;
+    record_execution_usage(db_pool, &usage).await?;
+
+    // Timeouts and sandbox failures are transient, so replaying them would be wrong
+    if result.is_ok()
+        && execution.judge.is_none()
+        && execution.finished_cleanly()
+        && config.execution().is_cacheable(language.name)
+    {
+        let ttl = chrono::Duration::seconds(*config.execution().cache_ttl_secs());
+        let key = cache_key(language, &execution);
+        upsert_cached_execution(db_pool, &CachedExecution::new(key, &execution, ttl)).await?;
+    }
+    Ok(())
+}
+
+/// How long a worker may hold a job making `runs` sandbox requests after its build,
+/// where `timeout` gives the cut-off of a run if it has its own.
+fn lease_duration(
+    config: &AppConfig,
+    runs: usize,
+    timeout: impl Fn(usize) -> Option<Duration>,
+) -> chrono::Duration {
+    let default_timeout = Duration::from_secs(*config.rce().timeout_in_secs());
+    let runs: Duration = (0..runs.max(1))
+        .map(|run| timeout(run).unwrap_or(default_timeout))
+        .sum();
+    let compile = Duration::from_secs(*config.rce().compile_timeout_in_secs());
+    chrono::Duration::milliseconds((compile + runs).as_millis() as i64)
+        + chrono::Duration::seconds(LEASE_MARGIN_SECS)
+}
+
+/// How long a test case may run before the sandbox cuts it off.
+fn case_timeout(time_limit_ms: i64) -> Duration {
+    Duration::from_millis(time_limit_ms.saturating_add(CASE_TIMEOUT_MARGIN_MS).max(0) as u64)
+}
+
+/// Builds the project if its language has a compile step, then runs or judges it.
+///
+/// The project is built once, however many test cases it is judged against, and the
+/// run phase only unpacks the build, so time limits apply to running alone. A failed
+/// build is recorded on the execution and skips the run phase entirely.
+async fn run_execution(
+    client: &RceClient,
+    config: &AppConfig,
+    language: &'static Language,
+    execution: &mut Execution,
+) -> CaraiResult<()> {
+    let mut files = execution.files.clone();
+    let mut command = None;
+    if let Some(build) = &language.build {
+        let compile_command = build.compile_command(execution.entrypoint());
+        let request = ExecutionRequest {
+            language,
+            files: &files,
+            stdin: None,
+            command: Some(&compile_command),
+            timeout: Some(Duration::from_secs(*config.rce().compile_timeout_in_secs())),
+        };
+        let started = Instant::now();
+        let output = client.execute(&request).await?;
+        execution.compile = Some(Json(CompileOutput {
+            exit_code: output.exit_code(),
+            // Compilers print to stderr; stdout only carries the packed artifacts
+            stdout: String::new(),
+            stderr: output.stderr,
+            error: Some(output.error).filter(|e| !e.is_empty()),
+            duration_ms: started.elapsed().as_millis() as i64,
+        }));
+        if execution.failed_to_compile() {
+            return Ok(());
+        }
+        files.retain(|file| file.name != BUILD_ARTIFACT_FILE);
+        files.push(ExecutionFile {
+            name: BUILD_ARTIFACT_FILE.to_string(),
+            content: output.stdout,
+        });
+        command = Some(build.run_command(execution.entrypoint()));
+    }
+
+    if let Some(spec) = execution.judge.as_deref() {
+        let results = judge_execution(client, language, &files, spec, command.as_deref()).await?;
+        execution.case_results = Some(Json(results));
+        return Ok(());
+    }
+
+    let request = ExecutionRequest {
+        language,
+        files: &files,
+        stdin: execution.stdin.as_deref(),
+        command: command.as_deref(),
+        timeout: None,
+    };
+    let output = client.execute(&request).await?;
+    execution.exit_code = output.exit_code();
+    execution.stdout = Some(output.stdout);
+    execution.stderr = Some(output.stderr);
+    execution.error = Some(output.error).filter(|e| !e.is_empty());
+    Ok(())
+}
+
+/// Runs the program once per test case and grades each run.
+async fn judge_execution(
+    client: &RceClient,
+    language: &'static Language,
+    files: &[ExecutionFile],
+    spec: &JudgeSpec,
+    command: Option<&str>,
+) -> CaraiResult<Vec<CaseResult>> {
+    let mut results = Vec::with_capacity(spec.cases.len());
+    for case in &spec.cases {
+        let request = ExecutionRequest {
+            language,
+            files,
+            stdin: Some(&case.stdin),
+            command,
+            // A runaway program is cut off soon after its limit instead of holding the
+            // sandbox until the global timeout; the sandbox reports that as a timeout
+            timeout: case.time_limit_ms.map(case_timeout),
+        };
+        let started = Instant::now();
+        let output = client.execute(&request).await?;
+        let duration_ms = started.elapsed().as_millis() as i64;
+        results.push(grade_case(spec, case, output, duration_ms));
+    }
+    Ok(results)
+}
@@ -0,0 +1,7 @@
+mod cleanup;
+mod execution;
+mod webhook;
+
+pub use cleanup::*;
+pub use execution::*;
+pub use webhook::*;
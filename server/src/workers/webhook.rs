@@ -0,0 +1,95 @@
+use std::time::Duration;
+
+use chrono::Utc;
+use sqlx::PgPool;
+use tokio::{task::JoinHandle, time::sleep};
+
+use crate::{
+    models::{WebhookDelivery, WebhookDeliveryStatus},
+    services::{claim_next_webhook_delivery, get_webhook_by_id, update_webhook_delivery_attempt},
+    utils::{AppConfig, CaraiResult},
+    webhooks::WebhookClient,
+};
+
+/// Extra time a worker keeps its lease on a delivery beyond the request timeout.
+const LEASE_MARGIN_SECS: i64 = 30;
+
+pub fn spawn_webhook_workers(
+    db_pool: PgPool,
+    config: AppConfig,
+) -> CaraiResult<Vec<JoinHandle<()>>> {
+    let client = WebhookClient::new(config.webhook())?;
+    let workers = *config.webhook().workers();
+
+    tracing::info!("Starting {} webhook workers", workers);
+    Ok((0..workers)
+        .map(|worker| {
+            let db_pool = db_pool.clone();
+            let client = client.clone();
+            let config = config.clone();
+            tokio::spawn(async move { run_worker(worker, db_pool, client, config).await })
+        })
+        .collect())
+}
+
+async fn run_worker(worker: usize, db_pool: PgPool, client: WebhookClient, config: AppConfig) {
+    let poll_interval = Duration::from_millis(*config.webhook().poll_interval_ms());
+    let lease =
+        chrono::Duration::seconds(*config.webhook().timeout_secs() as i64 + LEASE_MARGIN_SECS);
+
+    loop {
+        match claim_next_webhook_delivery(&db_pool, Utc::now() + lease).await {
+            Ok(Some(delivery)) => {
+                tracing::debug!("Webhook worker {} claimed {}", worker, delivery);
+                if let Err(e) = process_delivery(&db_pool, &client, &config, delivery).await {
+                    tracing::error!("Webhook worker {} failed to deliver: {}", worker, e);
+                }
+            }
+            Ok(None) => sleep(poll_interval).await,
+            Err(e) => {
+                tracing::error!("Webhook worker {} failed to claim delivery: {}", worker, e);
+                sleep(poll_interval).await;
+            }
+        }
+    }
+}
+
+/// Attempts a delivery, then schedules a retry with exponential backoff unless it
+/// succeeded or ran out of attempts.
+async fn process_delivery(
+    db_pool: &PgPool,
+    client: &WebhookClient,
+    config: &AppConfig,
+    mut delivery: WebhookDelivery,
+) -> CaraiResult<()> {
+    // Deleting a webhook deletes its deliveries, so it can only have vanished just now
+    let Some(webhook) = get_webhook_by_id(db_pool, delivery.webhook_id).await? else {
+        return Ok(());
+    };
+    let now = Utc::now();
+    if !webhook.active {
+        delivery.status = WebhookDeliveryStatus::Failed;
+        delivery.error = Some("The webhook was disabled".to_string());
+        delivery.finished_at = Some(now);
+        return update_webhook_delivery_attempt(db_pool, &delivery).await;
+    }
+
+    let attempt = client.deliver(&webhook, &delivery).await;
+    let now = Utc::now();
+    delivery.response_status = attempt.response_status;
+    delivery.response_body = attempt.response_body.clone();
+    delivery.error = attempt.error.clone();
+    delivery.duration_ms = Some(attempt.duration_ms);
+    if attempt.succeeded() {
+        delivery.status = WebhookDeliveryStatus::Delivered;
+        delivery.finished_at = Some(now);
+    } else if delivery.attempts >= *config.webhook().max_attempts() {
+        delivery.status = WebhookDeliveryStatus::Failed;
+        delivery.finished_at = Some(now);
+    } else {
+        let delay = config.webhook().retry_delay(delivery.attempts);
+        delivery.next_attempt_at = now + chrono::Duration::from_std(delay)?;
+    }
+    tracing::debug!("Attempted {}", delivery);
+    update_webhook_delivery_attempt(db_pool, &delivery).await
+}
@@ -0,0 +1,173 @@
+use std::io::{Cursor, Write};
+
+use axum::Router;
+use carai::{
+    bootstrap::create_router,
+    dto::{ImportResDto, WorkspaceFilesResDto, WorkspaceResDto},
+    utils::{BinaryFilePolicy, CaraiResult},
+};
+use common::{body, config, ctx, login_as, send, upload};
+use serde_json::json;
+use sqlx::PgPool;
+use zip::{write::SimpleFileOptions, ZipWriter};
+
+mod common;
+
+fn zip_of(entries: &[(&str, &[u8])]) -> CaraiResult<Vec<u8>> {
+    let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+    for (path, content) in entries {
+        writer.start_file(*path, SimpleFileOptions::default())?;
+        writer.write_all(content)?;
+    }
+    Ok(writer.finish()?.into_inner())
+}
+
+async fn import(app: &mut Router, token: &str, archive: Vec<u8>) -> CaraiResult<(u16, Vec<u8>)> {
+    upload(app, "/workspaces/import", token, "application/zip", archive).await
+}
+
+#[sqlx::test]
+async fn test_export_and_import_workspace(db_pool: PgPool) -> CaraiResult<()> {
+    let mut app = ctx(db_pool)?;
+    let token = login_as(&mut app, "archivist").await?;
+
+    // Arrange: A python workspace with a nested file
+    let (_, bytes) = send(
+        &mut app,
+        "POST",
+        "/workspaces",
+        Some(&token),
+        Some(&json!({ "name": "kata", "language": "python" })),
+    )
+    .await?;
+    let workspace: WorkspaceResDto = body(&bytes)?;
+    let files_uri = format!("/workspaces/{}/files", workspace.id);
+    for (path, content) in [("main.py", "print(1)\n"), ("lib/util.py", "x = 1\n")] {
+        let file_req = json!({ "path": path, "content": content });
+        send(&mut app, "POST", &files_uri, Some(&token), Some(&file_req)).await?;
+    }
+    let file_req = json!({ "path": "carai.json", "content": "{}" });
+    let (status, _) = send(&mut app, "POST", &files_uri, Some(&token), Some(&file_req)).await?;
+    assert_eq!(status, 422, "The manifest path should be reserved");
+
+    for (format, name) in [("zip", "from-zip"), ("tar.gz", "from-tar")] {
+        // Act: Export the workspace and import the archive under a new name
+        let (status, archive) = send::<()>(
+            &mut app,
+            "GET",
+            &format!("/workspaces/{}/export?format={}", workspace.id, format),
+            Some(&token),
+            None,
+        )
+        .await?;
+        assert_eq!(status, 200, "Exporting as {} should succeed", format);
+        let (status, bytes) = upload(
+            &mut app,
+            &format!("/workspaces/import?name={}", name),
+            &token,
+            "application/octet-stream",
+            archive,
+        )
+        .await?;
+
+        // Assert: The copy has the same files, and the manifest restored the language
+        assert_eq!(status, 201, "Importing a {} export should succeed", format);
+        let imported: ImportResDto = body(&bytes)?;
+        assert_eq!(imported.workspace.name, name);
+        assert_eq!(imported.workspace.language.as_deref(), Some("python"));
+        assert_eq!(imported.file_count, 2);
+        let (_, bytes) = send::<()>(
+            &mut app,
+            "GET",
+            &format!("/workspaces/{}/files", imported.workspace.id),
+            Some(&token),
+            None,
+        )
+        .await?;
+        let files: WorkspaceFilesResDto = body(&bytes)?;
+        let mut paths: Vec<&str> = files.files.iter().map(|file| file.path.as_str()).collect();
+        paths.sort();
+        assert_eq!(paths, vec!["lib/util.py", "main.py"]);
+    }
+
+    // Act: Import an archive without a manifest, so nothing names the workspace
+    let archive = zip_of(&[("src/app.js", b"console.log(1)")])?;
+    let (status, bytes) = upload(
+        &mut app,
+        "/workspaces/import",
+        &token,
+        "application/zip",
+        archive,
+    )
+    .await?;
+
+    // Assert: It falls back to a default name and no language
+    assert_eq!(status, 201, "Archives do not need a manifest");
+    let imported: ImportResDto = body(&bytes)?;
+    assert_eq!(imported.workspace.name, "Imported workspace");
+    assert_eq!(imported.workspace.language, None);
+
+    Ok(())
+}
+
+#[sqlx::test]
+async fn test_import_safeguards(db_pool: PgPool) -> CaraiResult<()> {
+    let mut app = ctx(db_pool.clone())?;
+    let token = login_as(&mut app, "archivist").await?;
+
+    // Act & Assert: Entries that escape the project are refused
+    for path in ["../evil.py", "/etc/passwd", "a/./b.py"] {
+        let (status, _) = import(&mut app, &token, zip_of(&[(path, b"x")])?).await?;
+        assert_eq!(status, 422, "{} must not be imported", path);
+    }
+
+    // Act & Assert: Anything that is not an archive is refused
+    let (status, _) = import(&mut app, &token, b"just some text".to_vec()).await?;
+    assert_eq!(status, 422, "Only zip and tar.gz archives are accepted");
+
+    // Act & Assert: Archives with more files than a workspace may hold are refused
+    let names: Vec<String> = (0..201).map(|i| format!("f{}.txt", i)).collect();
+    let entries: Vec<(&str, &[u8])> = names
+        .iter()
+        .map(|name| (name.as_str(), &b"x"[..]))
+        .collect();
+    let (status, _) = import(&mut app, &token, zip_of(&entries)?).await?;
+    assert_eq!(status, 422, "The file count must be limited");
+
+    // Act & Assert: Files too large for a workspace are refused
+    let large = vec![b'a'; 1048577];
+    let (status, _) = import(&mut app, &token, zip_of(&[("big.txt", &large)])?).await?;
+    assert_eq!(status, 422, "Each file must fit in a workspace");
+
+    // Act & Assert: Binary files are refused by default
+    let archive = zip_of(&[("main.py", b"print(1)"), ("logo.png", b"\x89PNG\0\x01")])?;
+    let (status, _) = import(&mut app, &token, archive.clone()).await?;
+    assert_eq!(status, 422, "Binary files are rejected by default");
+
+    // Arrange: A server that skips binary files and limits how much archives unpack to
+    let mut config = config()?;
+    config
+        .archive_mut()
+        .set_binary_files(BinaryFilePolicy::Skip)
+        .set_max_unpacked_bytes(64);
+    let mut app = create_router(db_pool, config);
+    let token = login_as(&mut app, "skipper").await?;
+
+    // Act: Import the archive with the binary file again
+    let (status, bytes) = import(&mut app, &token, archive).await?;
+
+    // Assert: The text file is imported and the binary file is reported as skipped
+    assert_eq!(status, 201, "Binary files are skipped under that policy");
+    let imported: ImportResDto = body(&bytes)?;
+    assert_eq!(imported.file_count, 1);
+    assert_eq!(imported.skipped, vec!["logo.png"]);
+
+    // Act: Import an archive that unpacks to more than allowed
+    let archive = zip_of(&[("big.txt", &[b'a'; 65])])?;
+    let (status, _) = import(&mut app, &token, archive).await?;
+
+    // Assert: It is refused as too large
+    assert_eq!(status, 413, "The unpacked size must be limited");
+
+    Ok(())
+}
@@ -0,0 +1,353 @@
+use std::time::Duration;
+
+use axum::{routing::post, Json, Router};
+use carai::{
+    bootstrap::create_router,
+    dto::{
+        AssignmentResDto, ClassroomMembersResDto, ClassroomResDto, GradebookResDto, RunResDto,
+        SubmissionDetailResDto, SubmissionResDto, SubmissionsResDto, WorkspaceFilesResDto,
+        WorkspaceResDto,
+    },
+    models::{ClassroomRole, SubmissionStatus, Verdict},
+    utils::CaraiResult,
+    workers::spawn_execution_workers,
+};
+use chrono::{Duration as ChronoDuration, Utc};
+use common::{body, config, login_as, send};
+use serde_json::{json, Value};
+use sqlx::PgPool;
+use tokio::net::TcpListener;
+use uuid::Uuid;
+
+mod common;
+
+/// Starts a stand-in for the RCE service that echoes the first file followed by stdin.
+async fn spawn_rce_stub() -> CaraiResult<String> {
+    let app = Router::new().route(
+        "/run",
+        post(|Json(req): Json<Value>| async move {
+            let payload = &req["payload"];
+            let stdout = format!(
+                "{}{}",
+                payload["files"][0]["content"].as_str().unwrap_or_default(),
+                payload["stdin"].as_str().unwrap_or_default()
+            );
+            Json(json!({ "stdout": stdout, "stderr": "", "error": "" }))
+        }),
+    );
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let address = listener.local_addr()?;
+    tokio::spawn(async move { axum::serve(listener, app).await });
+
+    Ok(format!("http://{}/run", address))
+}
+
+/// Creates the `cs101` classroom taught by `token`'s user.
+async fn create_classroom(app: &mut Router, token: &str) -> CaraiResult<ClassroomResDto> {
+    let classroom_req = json!({ "slug": "cs101", "name": "Intro to Programming" });
+    let (status, bytes) = send(app, "POST", "/classes", Some(token), Some(&classroom_req)).await?;
+    assert_eq!(status, 201, "Creating a classroom should succeed");
+    body(&bytes)
+}
+
+/// Polls a submission until the worker pool has graded it.
+async fn wait_for_grade(
+    app: &mut Router,
+    token: &str,
+    id: Uuid,
+) -> CaraiResult<SubmissionDetailResDto> {
+    let uri = format!("/classes/cs101/submissions/{}", id);
+    for _ in 0..50 {
+        let (_, bytes) = send::<()>(app, "GET", &uri, Some(token), None).await?;
+        let submission: SubmissionDetailResDto = body(&bytes)?;
+        if submission.submission.status != SubmissionStatus::Pending {
+            return Ok(submission);
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    anyhow::bail!("Submission {} was not graded in time", id)
+}
+
+#[sqlx::test]
+async fn test_assignment_submission_and_gradebook(db_pool: PgPool) -> CaraiResult<()> {
+    let mut config = config()?;
+    config.rce_mut().set_base_url(spawn_rce_stub().await?);
+    config.execution_mut().set_poll_interval_ms(50);
+    spawn_execution_workers(db_pool.clone(), config.clone())?;
+    let mut app = create_router(db_pool, config);
+    let teacher = login_as(&mut app, "teacher").await?;
+    let pupil = login_as(&mut app, "pupil").await?;
+    let outsider = login_as(&mut app, "outsider").await?;
+
+    // Arrange: The teacher opens a classroom and the pupil joins it with its code
+    let classroom = create_classroom(&mut app, &teacher).await?;
+    let code = classroom
+        .join_code
+        .expect("Instructors should see the join code");
+    let join_req = json!({ "code": code });
+    let (status, bytes) = send(
+        &mut app,
+        "POST",
+        "/classes/join",
+        Some(&pupil),
+        Some(&join_req),
+    )
+    .await?;
+    assert_eq!(status, 200, "Joining with the code should succeed");
+    let joined: ClassroomResDto = body(&bytes)?;
+    assert_eq!(joined.role, ClassroomRole::Student);
+    assert!(
+        joined.join_code.is_none(),
+        "Students should not see the code"
+    );
+    let (status, _) = send::<()>(&mut app, "GET", "/classes/cs101", Some(&outsider), None).await?;
+    assert_eq!(status, 404, "Classrooms should be hidden from non-members");
+
+    // Act: The teacher publishes an assignment starting from the Python template
+    let assignment_req = json!({
+        "title": "Echo",
+        "language": "python",
+        "template": "python",
+        "tests": [
+            { "stdin": "1", "expected_stdout": "1" },
+            { "stdin": "2", "expected_stdout": "3" },
+        ],
+        "deadline": Utc::now() + ChronoDuration::days(7),
+    });
+    let (status, _) = send(
+        &mut app,
+        "POST",
+        "/classes/cs101/assignments",
+        Some(&pupil),
+        Some(&assignment_req),
+    )
+    .await?;
+    assert_eq!(status, 403, "Students should not publish assignments");
+    let (status, bytes) = send(
+        &mut app,
+        "POST",
+        "/classes/cs101/assignments",
+        Some(&teacher),
+        Some(&assignment_req),
+    )
+    .await?;
+    assert_eq!(status, 201, "Publishing an assignment should succeed");
+    let assignment: AssignmentResDto = body(&bytes)?;
+    assert_eq!(assignment.starter_files[0].path, "main.py");
+
+    // Assert: Students see the assignment but not its test cases
+    let uri = format!("/classes/cs101/assignments/{}", assignment.id);
+    let (_, bytes) = send::<()>(&mut app, "GET", &uri, Some(&pupil), None).await?;
+    let seen: AssignmentResDto = body(&bytes)?;
+    assert!(seen.tests.is_none(), "Test cases should be hidden");
+    assert_eq!(seen.test_count, 2);
+
+    // Arrange: The pupil starts the assignment and empties the starter script
+    let (status, bytes) = send::<()>(
+        &mut app,
+        "POST",
+        &format!("{}/workspace", uri),
+        Some(&pupil),
+        None,
+    )
+    .await?;
+    assert_eq!(
+        status, 201,
+        "Starting an assignment should create a workspace"
+    );
+    let workspace: WorkspaceResDto = body(&bytes)?;
+    let files_uri = format!("/workspaces/{}/files", workspace.id);
+    let (_, bytes) = send::<()>(&mut app, "GET", &files_uri, Some(&pupil), None).await?;
+    let files: WorkspaceFilesResDto = body(&bytes)?;
+    let file_uri = format!("{}/{}", files_uri, files.files[0].id);
+    let patch_req = json!({ "content": "" });
+    let (status, _) = send(&mut app, "PATCH", &file_uri, Some(&pupil), Some(&patch_req)).await?;
+    assert_eq!(status, 200);
+
+    // Act: The pupil hands in the workspace twice
+    let submission_req = json!({ "workspace_id": workspace.id });
+    let submissions_uri = format!("{}/submissions", uri);
+    let (status, _) = send(
+        &mut app,
+        "POST",
+        &submissions_uri,
+        Some(&outsider),
+        Some(&submission_req),
+    )
+    .await?;
+    assert_eq!(status, 404, "Non-members should not submit");
+    let mut submission = None;
+    for _ in 0..2 {
+        let (status, bytes) = send(
+            &mut app,
+            "POST",
+            &submissions_uri,
+            Some(&pupil),
+            Some(&submission_req),
+        )
+        .await?;
+        assert_eq!(status, 202, "Submissions should be queued for grading");
+        submission = Some(body::<SubmissionResDto>(&bytes)?);
+    }
+    let submission = submission.expect("A submission was made");
+    assert_eq!(submission.status, SubmissionStatus::Pending);
+
+    // Assert: The submission is graded against the hidden cases without revealing them
+    let graded = wait_for_grade(&mut app, &pupil, submission.id).await?;
+    assert_eq!(graded.submission.status, SubmissionStatus::Graded);
+    assert_eq!(graded.submission.passed_cases, 1);
+    assert_eq!(graded.submission.score, Some(50.0));
+    let cases = graded.cases.expect("Graded submissions should list cases");
+    assert_eq!(cases[1].verdict, Verdict::WrongAnswer);
+    assert!(
+        cases
+            .iter()
+            .all(|case| case.diff.is_none() && case.stdout.is_empty()),
+        "Students should only see verdicts"
+    );
+    let run_uri = format!("/runs/{}", submission.execution_id.expect("Run is kept"));
+    let (_, bytes) = send::<()>(&mut app, "GET", &run_uri, Some(&pupil), None).await?;
+    let run: RunResDto = body(&bytes)?;
+    assert!(
+        run.cases
+            .unwrap_or_default()
+            .iter()
+            .all(|case| case.diff.is_none()),
+        "The run behind a submission should not reveal the cases either"
+    );
+
+    // Assert: Instructors see the full results and everybody's history
+    let graded = wait_for_grade(&mut app, &teacher, submission.id).await?;
+    let cases = graded.cases.expect("Graded submissions should list cases");
+    assert!(cases[1].diff.is_some(), "Instructors should see the diff");
+    let (_, bytes) = send::<()>(&mut app, "GET", &submissions_uri, Some(&pupil), None).await?;
+    let history: SubmissionsResDto = body(&bytes)?;
+    assert_eq!(history.submissions.len(), 2);
+
+    // Assert: The gradebook records the best score, and exports it as CSV
+    let (status, _) = send::<()>(
+        &mut app,
+        "GET",
+        "/classes/cs101/gradebook",
+        Some(&pupil),
+        None,
+    )
+    .await?;
+    assert_eq!(status, 403, "Only instructors see the gradebook");
+    for _ in 0..50 {
+        let (_, bytes) =
+            send::<()>(&mut app, "GET", &submissions_uri, Some(&teacher), None).await?;
+        let history: SubmissionsResDto = body(&bytes)?;
+        if history
+            .submissions
+            .iter()
+            .all(|submission| submission.status == SubmissionStatus::Graded)
+        {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    let (_, bytes) = send::<()>(
+        &mut app,
+        "GET",
+        "/classes/cs101/gradebook",
+        Some(&teacher),
+        None,
+    )
+    .await?;
+    let gradebook: GradebookResDto = body(&bytes)?;
+    assert_eq!(gradebook.students.len(), 1, "Only students are graded");
+    let grade = &gradebook.students[0].grades[0];
+    assert_eq!(grade.best_score, Some(50.0));
+    assert_eq!(grade.submissions, 2);
+    let (status, bytes) = send::<()>(
+        &mut app,
+        "GET",
+        "/classes/cs101/gradebook.csv",
+        Some(&teacher),
+        None,
+    )
+    .await?;
+    assert_eq!(status, 200);
+    let csv = String::from_utf8(bytes)?;
+    assert_eq!(csv, "username,Echo,average\npupil,50.00,50.00\n");
+
+    Ok(())
+}
+
+#[sqlx::test]
+async fn test_submissions_close_at_the_deadline(db_pool: PgPool) -> CaraiResult<()> {
+    let mut app = create_router(db_pool, config()?);
+    let teacher = login_as(&mut app, "teacher").await?;
+    let pupil = login_as(&mut app, "pupil").await?;
+
+    // Arrange: The teacher enrolls the pupil and publishes an assignment already due
+    create_classroom(&mut app, &teacher).await?;
+    let member_req = json!({ "username": "pupil" });
+    let (status, _) = send(
+        &mut app,
+        "POST",
+        "/classes/cs101/members",
+        Some(&teacher),
+        Some(&member_req),
+    )
+    .await?;
+    assert_eq!(status, 201, "Instructors should enroll students");
+    let assignment_req = json!({
+        "title": "Overdue",
+        "language": "python",
+        "tests": [{ "stdin": "", "expected_stdout": "" }],
+        "deadline": Utc::now() - ChronoDuration::minutes(1),
+    });
+    let (_, bytes) = send(
+        &mut app,
+        "POST",
+        "/classes/cs101/assignments",
+        Some(&teacher),
+        Some(&assignment_req),
+    )
+    .await?;
+    let assignment: AssignmentResDto = body(&bytes)?;
+    assert!(assignment.closed);
+    let uri = format!("/classes/cs101/assignments/{}", assignment.id);
+    let (_, bytes) = send::<()>(
+        &mut app,
+        "POST",
+        &format!("{}/workspace", uri),
+        Some(&pupil),
+        None,
+    )
+    .await?;
+    let workspace: WorkspaceResDto = body(&bytes)?;
+
+    // Act: The pupil hands in after the deadline
+    let submission_req = json!({ "workspace_id": workspace.id });
+    let (status, _) = send(
+        &mut app,
+        "POST",
+        &format!("{}/submissions", uri),
+        Some(&pupil),
+        Some(&submission_req),
+    )
+    .await?;
+
+    // Assert: Late work is refused, and the last instructor cannot leave
+    assert_eq!(status, 422, "Submissions should close at the deadline");
+    let (_, bytes) = send::<()>(&mut app, "GET", "/classes/cs101", Some(&teacher), None).await?;
+    let classroom: ClassroomResDto = body(&bytes)?;
+    assert_eq!(classroom.role, ClassroomRole::Instructor);
+    let (_, bytes) = send::<()>(
+        &mut app,
+        "GET",
+        "/classes/cs101/members",
+        Some(&teacher),
+        None,
+    )
+    .await?;
+    let members: ClassroomMembersResDto = body(&bytes)?;
+    let uri = format!("/classes/cs101/members/{}", members.members[0].user_id);
+    let (status, _) = send::<()>(&mut app, "DELETE", &uri, Some(&teacher), None).await?;
+    assert_eq!(status, 422, "Classrooms should keep an instructor");
+
+    Ok(())
+}
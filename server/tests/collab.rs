@@ -0,0 +1,957 @@
+use std::{io::Read, time::Duration};
+
+use axum::http::StatusCode;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use carai::{
+    bootstrap::create_router,
+    collab::{seed_update, Frame, Participant, Position},
+    dto::{
+        ChatMessageResDto, ChatMessagesResDto, CommentThreadsResDto, DocumentStateResDto,
+        InvitationResDto, MembersResDto, PresenceResDto, RecordedEventResDto, WorkspaceFileResDto,
+        WorkspaceResDto,
+    },
+    models::SessionEventKind,
+    utils::CaraiResult,
+};
+use chrono::{DateTime, SecondsFormat, Utc};
+use common::{body, config, login_as, send};
+use flate2::read::GzDecoder;
+use futures_util::{SinkExt, StreamExt};
+use serde_json::json;
+use sqlx::PgPool;
+use tokio::{net::TcpListener, net::TcpStream, time::timeout};
+use tokio_tungstenite::{
+    connect_async,
+    tungstenite::{protocol::frame::coding::CloseCode, Message},
+    MaybeTlsStream, WebSocketStream,
+};
+
+mod common;
+
+type Client = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+async fn recv(client: &mut Client) -> CaraiResult<Frame> {
+    loop {
+        let message = timeout(Duration::from_secs(5), client.next())
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Connection closed"))??;
+        if let Message::Binary(bytes) = message {
+            return Frame::decode(&bytes);
+        }
+    }
+}
+
+/// Receives the next frame that is not about presence.
+async fn recv_document(client: &mut Client) -> CaraiResult<Frame> {
+    loop {
+        match recv(client).await? {
+            Frame::Awareness { .. } | Frame::Departed { .. } => continue,
+            frame => return Ok(frame),
+        }
+    }
+}
+
+async fn push(client: &mut Client, frame: Frame) -> CaraiResult<()> {
+    client.send(Message::Binary(frame.encode())).await?;
+    Ok(())
+}
+
+/// Sends an update and waits until the server confirms it was stored, skipping any
+/// request for a snapshot on the way.
+async fn edit(client: &mut Client, data: &[u8]) -> CaraiResult<i64> {
+    let data = data.to_vec();
+    push(client, Frame::Update { seq: 0, data }).await?;
+    loop {
+        match recv_document(client).await? {
+            Frame::Ack { seq } => return Ok(seq),
+            Frame::RequestSnapshot => continue,
+            frame => return Err(anyhow::anyhow!("Expected an ack, got {:?}", frame)),
+        }
+    }
+}
+
+fn timestamp(at: DateTime<Utc>) -> String {
+    at.to_rfc3339_opts(SecondsFormat::Micros, true)
+}
+
+/// Fetches and unpacks a page of a workspace's event log.
+async fn recording(
+    app: &mut axum::Router,
+    token: &str,
+    uri: &str,
+) -> CaraiResult<Vec<RecordedEventResDto>> {
+    let (status, bytes) = send::<()>(app, "GET", uri, Some(token), None).await?;
+    assert_eq!(status, 200, "Fetching the recording should succeed");
+    let mut log = String::new();
+    GzDecoder::new(bytes.as_slice()).read_to_string(&mut log)?;
+    log.lines()
+        .map(|line| Ok(serde_json::from_str(line)?))
+        .collect()
+}
+
+#[sqlx::test]
+async fn test_collab_sync_and_snapshot_catch_up(db_pool: PgPool) -> CaraiResult<()> {
+    let mut config = config()?;
+    config.collab_mut().set_snapshot_interval_updates(2);
+    let mut app = create_router(db_pool, config);
+    let token = login_as(&mut app, "pair").await?;
+
+    // Arrange: A workspace file served over a real socket
+    let (_, bytes) = send(
+        &mut app,
+        "POST",
+        "/workspaces",
+        Some(&token),
+        Some(&json!({ "name": "live" })),
+    )
+    .await?;
+    let workspace: WorkspaceResDto = body(&bytes)?;
+    let files_uri = format!("/workspaces/{}/files", workspace.id);
+    let file_req = json!({ "path": "main.rs", "content": "" });
+    let (_, bytes) = send(&mut app, "POST", &files_uri, Some(&token), Some(&file_req)).await?;
+    let file: WorkspaceFileResDto = body(&bytes)?;
+
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let address = listener.local_addr()?;
+    tokio::spawn(async move { axum::serve(listener, app).await });
+    let url = format!("ws://{}{}/{}/collab", address, files_uri, file.id);
+
+    // Assert: Connections without a valid token are refused
+    assert!(
+        connect_async(format!("{}?token=invalid", url))
+            .await
+            .is_err(),
+        "Invalid tokens should be rejected"
+    );
+
+    // Act: The first collaborator joins an empty document and edits it
+    let (mut alice, _) = connect_async(format!("{}?token={}", url, token)).await?;
+    assert_eq!(recv_document(&mut alice).await?, Frame::Synced { seq: 0 });
+    push(
+        &mut alice,
+        Frame::Update {
+            seq: 0,
+            data: vec![1, 2],
+        },
+    )
+    .await?;
+    let Frame::Ack { seq: first } = recv_document(&mut alice).await? else {
+        panic!("The author should get an acknowledgement");
+    };
+
+    // Assert: A late joiner catches up from the stored updates
+    let (mut bob, _) = connect_async(format!("{}?token={}", url, token)).await?;
+    assert_eq!(
+        recv_document(&mut bob).await?,
+        Frame::Update {
+            seq: first,
+            data: vec![1, 2]
+        }
+    );
+    assert_eq!(recv_document(&mut bob).await?, Frame::Synced { seq: first });
+
+    // Act: The second collaborator edits, reaching the snapshot interval
+    push(
+        &mut bob,
+        Frame::Update {
+            seq: 0,
+            data: vec![3],
+        },
+    )
+    .await?;
+    let Frame::Update { seq: second, data } = recv_document(&mut alice).await? else {
+        panic!("Edits should be relayed to other collaborators");
+    };
+    assert_eq!(data, vec![3]);
+    let mut bob_frames = vec![
+        recv_document(&mut bob).await?,
+        recv_document(&mut bob).await?,
+    ];
+    bob_frames.sort_by_key(|frame| frame.encode());
+    assert_eq!(
+        bob_frames,
+        vec![Frame::Ack { seq: second }, Frame::RequestSnapshot]
+    );
+
+    // Act: The client answers with a merged snapshot
+    push(
+        &mut bob,
+        Frame::Snapshot {
+            seq: second,
+            data: vec![9],
+        },
+    )
+    .await?;
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    // Assert: New joiners start from the snapshot instead of the compacted updates
+    let (mut carol, _) = connect_async(format!("{}?token={}", url, token)).await?;
+    assert_eq!(
+        recv_document(&mut carol).await?,
+        Frame::Snapshot {
+            seq: second,
+            data: vec![9]
+        }
+    );
+    assert_eq!(
+        recv_document(&mut carol).await?,
+        Frame::Synced { seq: second }
+    );
+
+    Ok(())
+}
+
+#[sqlx::test]
+async fn test_forged_snapshots_are_refused(db_pool: PgPool) -> CaraiResult<()> {
+    let mut config = config()?;
+    config.collab_mut().set_snapshot_interval_updates(2);
+    let mut app = create_router(db_pool, config);
+    let token = login_as(&mut app, "pair").await?;
+
+    // Arrange: A workspace file served over a real socket
+    let (_, bytes) = send(
+        &mut app,
+        "POST",
+        "/workspaces",
+        Some(&token),
+        Some(&json!({ "name": "live" })),
+    )
+    .await?;
+    let workspace: WorkspaceResDto = body(&bytes)?;
+    let files_uri = format!("/workspaces/{}/files", workspace.id);
+    let file_req = json!({ "path": "main.rs", "content": "" });
+    let (_, bytes) = send(&mut app, "POST", &files_uri, Some(&token), Some(&file_req)).await?;
+    let file: WorkspaceFileResDto = body(&bytes)?;
+
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let address = listener.local_addr()?;
+    tokio::spawn(async move { axum::serve(listener, app).await });
+    let url = format!(
+        "ws://{}{}/{}/collab?token={}",
+        address, files_uri, file.id, token
+    );
+    let (mut alice, _) = connect_async(&url).await?;
+    assert_eq!(recv_document(&mut alice).await?, Frame::Synced { seq: 0 });
+
+    // Act: A snapshot nobody asked for, then one claiming updates that do not exist
+    push(
+        &mut alice,
+        Frame::Snapshot {
+            seq: 0,
+            data: vec![7],
+        },
+    )
+    .await?;
+    let first = edit(&mut alice, &[1]).await?;
+    push(
+        &mut alice,
+        Frame::Update {
+            seq: 0,
+            data: vec![2],
+        },
+    )
+    .await?;
+    let mut frames = vec![
+        recv_document(&mut alice).await?,
+        recv_document(&mut alice).await?,
+    ];
+    frames.sort_by_key(|frame| frame.encode());
+    let [Frame::Ack { seq: second }, Frame::RequestSnapshot] = frames[..] else {
+        panic!("The server should ask for a snapshot, got {:?}", frames);
+    };
+    push(
+        &mut alice,
+        Frame::Snapshot {
+            seq: i64::MAX,
+            data: vec![8],
+        },
+    )
+    .await?;
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    // Assert: Neither was stored, so joiners still replay every update
+    let (mut bob, _) = connect_async(&url).await?;
+    assert_eq!(
+        recv_document(&mut bob).await?,
+        Frame::Update {
+            seq: first,
+            data: vec![1]
+        }
+    );
+    assert_eq!(
+        recv_document(&mut bob).await?,
+        Frame::Update {
+            seq: second,
+            data: vec![2]
+        }
+    );
+    assert_eq!(
+        recv_document(&mut bob).await?,
+        Frame::Synced { seq: second }
+    );
+
+    // Assert: The server asks again, and an honest snapshot still compacts the document
+    push(
+        &mut alice,
+        Frame::Update {
+            seq: 0,
+            data: vec![3],
+        },
+    )
+    .await?;
+    let mut frames = vec![
+        recv_document(&mut alice).await?,
+        recv_document(&mut alice).await?,
+    ];
+    frames.sort_by_key(|frame| frame.encode());
+    let [Frame::Ack { seq: third }, Frame::RequestSnapshot] = frames[..] else {
+        panic!(
+            "The server should ask for a snapshot again, got {:?}",
+            frames
+        );
+    };
+    push(
+        &mut alice,
+        Frame::Snapshot {
+            seq: third,
+            data: vec![9],
+        },
+    )
+    .await?;
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    let (mut carol, _) = connect_async(&url).await?;
+    assert_eq!(
+        recv_document(&mut carol).await?,
+        Frame::Snapshot {
+            seq: third,
+            data: vec![9]
+        }
+    );
+    assert_eq!(
+        recv_document(&mut carol).await?,
+        Frame::Synced { seq: third }
+    );
+
+    Ok(())
+}
+
+#[sqlx::test]
+async fn test_collab_documents_start_from_and_save_to_the_file(db_pool: PgPool) -> CaraiResult<()> {
+    let mut app = create_router(db_pool, config()?);
+    let token = login_as(&mut app, "pair").await?;
+
+    // Arrange: A file with content, served over a real socket
+    let (_, bytes) = send(
+        &mut app,
+        "POST",
+        "/workspaces",
+        Some(&token),
+        Some(&json!({ "name": "live" })),
+    )
+    .await?;
+    let workspace: WorkspaceResDto = body(&bytes)?;
+    let files_uri = format!("/workspaces/{}/files", workspace.id);
+    let file_req = json!({ "path": "main.rs", "content": "hi" });
+    let (_, bytes) = send(&mut app, "POST", &files_uri, Some(&token), Some(&file_req)).await?;
+    let file: WorkspaceFileResDto = body(&bytes)?;
+    let file_uri = format!("{}/{}", files_uri, file.id);
+
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let address = listener.local_addr()?;
+    let server = app.clone();
+    tokio::spawn(async move { axum::serve(listener, server).await });
+    let url = format!("ws://{}{}/collab?token={}", address, file_uri, token);
+
+    // Act: The first collaborator opens the file
+    let (mut alice, _) = connect_async(&url).await?;
+
+    // Assert: The document is seeded with the file's text as `Y.Text` "content"
+    let Frame::Update { seq: seed, data } = recv_document(&mut alice).await? else {
+        panic!("A new document should be seeded from the file");
+    };
+    let mut expected = vec![1, 1, 0, 0, 4, 1, 7];
+    expected.extend_from_slice(b"content");
+    expected.push(2);
+    expected.extend_from_slice(b"hi");
+    expected.push(0);
+    assert_eq!(data, expected);
+    assert_eq!(
+        recv_document(&mut alice).await?,
+        Frame::Synced { seq: seed }
+    );
+
+    // Act: The collaborator edits and reports the merged text, then forged and stale text
+    let seq = edit(&mut alice, b"edit").await?;
+    for (seq, text) in [(seq, "hi there"), (i64::MAX, "forged"), (seed, "stale")] {
+        let data = text.as_bytes().to_vec();
+        push(&mut alice, Frame::Content { seq, data }).await?;
+    }
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    alice.close(None).await?;
+
+    // Assert: Reading the file over REST sees the collaborative edit
+    let (status, bytes) = send::<()>(&mut app, "GET", &file_uri, Some(&token), None).await?;
+    assert_eq!(status, 200);
+    let saved: WorkspaceFileResDto = body(&bytes)?;
+    assert_eq!(saved.content, "hi there");
+
+    // Assert: Reopening the file resumes the stored document instead of seeding it again
+    let (mut bob, _) = connect_async(&url).await?;
+    assert!(matches!(
+        recv_document(&mut bob).await?,
+        Frame::Update { seq, .. } if seq == seed
+    ));
+    assert!(matches!(
+        recv_document(&mut bob).await?,
+        Frame::Update { seq: edited, .. } if edited == seq
+    ));
+    assert_eq!(recv_document(&mut bob).await?, Frame::Synced { seq });
+
+    Ok(())
+}
+
+#[sqlx::test]
+async fn test_files_written_outside_the_room_restart_its_document(
+    db_pool: PgPool,
+) -> CaraiResult<()> {
+    let mut app = create_router(db_pool, config()?);
+    let token = login_as(&mut app, "pair").await?;
+
+    // Arrange: A file whose document was seeded by a collaborator who is still editing
+    let (_, bytes) = send(
+        &mut app,
+        "POST",
+        "/workspaces",
+        Some(&token),
+        Some(&json!({ "name": "live" })),
+    )
+    .await?;
+    let workspace: WorkspaceResDto = body(&bytes)?;
+    let files_uri = format!("/workspaces/{}/files", workspace.id);
+    let file_req = json!({ "path": "main.rs", "content": "one" });
+    let (_, bytes) = send(&mut app, "POST", &files_uri, Some(&token), Some(&file_req)).await?;
+    let file: WorkspaceFileResDto = body(&bytes)?;
+    let file_uri = format!("{}/{}", files_uri, file.id);
+
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let address = listener.local_addr()?;
+    let server = app.clone();
+    tokio::spawn(async move { axum::serve(listener, server).await });
+    let url = format!("ws://{}{}/collab?token={}", address, file_uri, token);
+    let (mut alice, _) = connect_async(&url).await?;
+    let Frame::Update { seq: seed, .. } = recv_document(&mut alice).await? else {
+        panic!("A new document should be seeded from the file");
+    };
+    assert_eq!(
+        recv_document(&mut alice).await?,
+        Frame::Synced { seq: seed }
+    );
+
+    // Act & Assert: Its content cannot change over REST while the room is live
+    let patch = json!({ "content": "two" });
+    let (status, _) = send(&mut app, "PATCH", &file_uri, Some(&token), Some(&patch)).await?;
+    assert_eq!(status, 409);
+
+    // Act: The collaborator leaves, then the content changes over REST
+    alice.close(None).await?;
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    let (status, _) = send(&mut app, "PATCH", &file_uri, Some(&token), Some(&patch)).await?;
+    assert_eq!(status, 200);
+
+    // Assert: The next room starts from the new content instead of the old document
+    let (mut bob, _) = connect_async(&url).await?;
+    let Frame::Update { seq, data } = recv_document(&mut bob).await? else {
+        panic!("The document should be seeded again");
+    };
+    assert!(seq > seed);
+    assert_eq!(data, seed_update("two"));
+    assert_eq!(recv_document(&mut bob).await?, Frame::Synced { seq });
+
+    Ok(())
+}
+
+#[sqlx::test]
+async fn test_comment_threads_follow_collab_edits(db_pool: PgPool) -> CaraiResult<()> {
+    let mut app = create_router(db_pool, config()?);
+    let token = login_as(&mut app, "pair").await?;
+
+    // Arrange: A thread on the second line of a file
+    let (_, bytes) = send(
+        &mut app,
+        "POST",
+        "/workspaces",
+        Some(&token),
+        Some(&json!({ "name": "live" })),
+    )
+    .await?;
+    let workspace: WorkspaceResDto = body(&bytes)?;
+    let files_uri = format!("/workspaces/{}/files", workspace.id);
+    let file_req = json!({ "path": "main.py", "content": "a\nb\nc\n" });
+    let (_, bytes) = send(&mut app, "POST", &files_uri, Some(&token), Some(&file_req)).await?;
+    let file: WorkspaceFileResDto = body(&bytes)?;
+    let file_uri = format!("{}/{}", files_uri, file.id);
+    let comments_uri = format!("{}/comments", file_uri);
+    let thread_req = json!({ "startLine": 2, "endLine": 2, "body": "Why b?" });
+    let (status, _) = send(
+        &mut app,
+        "POST",
+        &comments_uri,
+        Some(&token),
+        Some(&thread_req),
+    )
+    .await?;
+    assert_eq!(status, 201);
+
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let address = listener.local_addr()?;
+    let server = app.clone();
+    tokio::spawn(async move { axum::serve(listener, server).await });
+    let url = format!("ws://{}{}/collab?token={}", address, file_uri, token);
+
+    // Act: Two lines are inserted above it through the collaborative document
+    let (mut alice, _) = connect_async(&url).await?;
+    let Frame::Update { .. } = recv_document(&mut alice).await? else {
+        panic!("The document should be seeded from the file");
+    };
+    let Frame::Synced { .. } = recv_document(&mut alice).await? else {
+        panic!("The catch-up should end");
+    };
+    let seq = edit(&mut alice, b"insert").await?;
+    let data = b"x\ny\na\nb\nc\n".to_vec();
+    push(&mut alice, Frame::Content { seq, data }).await?;
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    // Assert: The thread moved along with its line
+    let (_, bytes) = send::<()>(&mut app, "GET", &comments_uri, Some(&token), None).await?;
+    let threads: CommentThreadsResDto = body(&bytes)?;
+    assert_eq!(
+        (threads.threads[0].start_line, threads.threads[0].end_line),
+        (4, 4)
+    );
+    assert!(!threads.threads[0].outdated);
+
+    Ok(())
+}
+
+#[sqlx::test]
+async fn test_removed_members_are_disconnected(db_pool: PgPool) -> CaraiResult<()> {
+    let mut app = create_router(db_pool, config()?);
+    let owner = login_as(&mut app, "owner").await?;
+    let guest = login_as(&mut app, "guest").await?;
+
+    // Arrange: A file and an editor who joined through a link
+    let (_, bytes) = send(
+        &mut app,
+        "POST",
+        "/workspaces",
+        Some(&owner),
+        Some(&json!({ "name": "live" })),
+    )
+    .await?;
+    let workspace: WorkspaceResDto = body(&bytes)?;
+    let uri = format!("/workspaces/{}", workspace.id);
+    let file_req = json!({ "path": "main.rs", "content": "" });
+    let files_uri = format!("{}/files", uri);
+    let (_, bytes) = send(&mut app, "POST", &files_uri, Some(&owner), Some(&file_req)).await?;
+    let file: WorkspaceFileResDto = body(&bytes)?;
+    let link_uri = format!("{}/invite-links", uri);
+    let link_req = json!({ "role": "editor" });
+    let (_, bytes) = send(&mut app, "POST", &link_uri, Some(&owner), Some(&link_req)).await?;
+    let link: InvitationResDto = body(&bytes)?;
+    let redeem_uri = format!("/invitations/links/{}/accept", link.token.unwrap());
+    let (status, _) = send::<()>(&mut app, "POST", &redeem_uri, Some(&guest), None).await?;
+    assert_eq!(status, 200);
+
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let address = listener.local_addr()?;
+    let server = app.clone();
+    tokio::spawn(async move { axum::serve(listener, server).await });
+    let url = format!("ws://{}{}/{}/collab?token=", address, files_uri, file.id);
+    let (mut alice, _) = connect_async(format!("{}{}", url, owner)).await?;
+    assert_eq!(recv_document(&mut alice).await?, Frame::Synced { seq: 0 });
+    let (mut bob, _) = connect_async(format!("{}{}", url, guest)).await?;
+    assert_eq!(recv_document(&mut bob).await?, Frame::Synced { seq: 0 });
+
+    // Act: The owner removes the editor, who then keeps editing
+    let members_uri = format!("{}/members", uri);
+    let (_, bytes) = send::<()>(&mut app, "GET", &members_uri, Some(&owner), None).await?;
+    let members: MembersResDto = body(&bytes)?;
+    let member = members
+        .members
+        .iter()
+        .find(|m| m.username == "guest")
+        .unwrap();
+    let member_uri = format!("{}/{}", members_uri, member.user_id);
+    let (status, _) = send::<()>(&mut app, "DELETE", &member_uri, Some(&owner), None).await?;
+    assert_eq!(status, 204);
+    push(
+        &mut bob,
+        Frame::Update {
+            seq: 0,
+            data: vec![1],
+        },
+    )
+    .await?;
+
+    // Assert: The former member is disconnected without their edit being stored
+    let close = loop {
+        match timeout(Duration::from_secs(5), bob.next()).await? {
+            Some(Ok(Message::Close(close))) => break close,
+            Some(Ok(Message::Binary(bytes))) => {
+                let frame = Frame::decode(&bytes)?;
+                assert!(!matches!(frame, Frame::Ack { .. }), "The edit was stored");
+            }
+            Some(Ok(_)) => {}
+            message => panic!("Expected a close frame, got {:?}", message),
+        }
+    };
+    assert_eq!(close.map(|close| close.code), Some(CloseCode::Policy));
+    let seq = edit(&mut alice, &[2]).await?;
+    let (mut carol, _) = connect_async(format!("{}{}", url, owner)).await?;
+    assert_eq!(
+        recv_document(&mut carol).await?,
+        Frame::Update { seq, data: vec![2] }
+    );
+    assert_eq!(recv_document(&mut carol).await?, Frame::Synced { seq });
+
+    Ok(())
+}
+
+#[sqlx::test]
+async fn test_presence_expires_on_disconnect_and_idle(db_pool: PgPool) -> CaraiResult<()> {
+    let mut config = config()?;
+    config.collab_mut().set_presence_idle_timeout_secs(1);
+    let mut app = create_router(db_pool, config);
+    let token = login_as(&mut app, "pair").await?;
+
+    // Arrange: A workspace file served over a real socket
+    let (_, bytes) = send(
+        &mut app,
+        "POST",
+        "/workspaces",
+        Some(&token),
+        Some(&json!({ "name": "live" })),
+    )
+    .await?;
+    let workspace: WorkspaceResDto = body(&bytes)?;
+    let files_uri = format!("/workspaces/{}/files", workspace.id);
+    let file_req = json!({ "path": "main.rs", "content": "" });
+    let (_, bytes) = send(&mut app, "POST", &files_uri, Some(&token), Some(&file_req)).await?;
+    let file: WorkspaceFileResDto = body(&bytes)?;
+    let presence_uri = format!("/workspaces/{}/presence", workspace.id);
+
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let address = listener.local_addr()?;
+    let server = app.clone();
+    tokio::spawn(async move { axum::serve(listener, server).await });
+    let url = format!(
+        "ws://{}{}/{}/collab?token={}",
+        address, files_uri, file.id, token
+    );
+
+    // Act: Two collaborators join and one reports a cursor
+    let (mut alice, _) = connect_async(&url).await?;
+    assert_eq!(recv(&mut alice).await?, Frame::Synced { seq: 0 });
+    let (mut bob, _) = connect_async(&url).await?;
+    assert_eq!(recv(&mut bob).await?, Frame::Synced { seq: 0 });
+    let Frame::Awareness { data } = recv(&mut bob).await? else {
+        panic!("Joiners should learn who is already present");
+    };
+    let alice_presence: Participant = serde_json::from_slice(&data)?;
+    assert_eq!(alice_presence.username, "pair");
+    let Frame::Awareness { data } = recv(&mut alice).await? else {
+        panic!("Participants should learn about joiners");
+    };
+    let bob_presence: Participant = serde_json::from_slice(&data)?;
+
+    let state = json!({
+        "color": "#123abc",
+        "cursor": { "line": 2, "column": 4 },
+        "selections": [{ "anchor": { "line": 1, "column": 0 }, "head": { "line": 2, "column": 4 } }]
+    });
+    let data = serde_json::to_vec(&state)?;
+    push(&mut alice, Frame::Awareness { data }).await?;
+
+    // Assert: The cursor is relayed and listed by the presence endpoint
+    let Frame::Awareness { data } = recv(&mut bob).await? else {
+        panic!("Awareness should be relayed to other collaborators");
+    };
+    let relayed: Participant = serde_json::from_slice(&data)?;
+    assert_eq!(relayed.connection_id, alice_presence.connection_id);
+    assert_eq!(relayed.state.color, "#123abc");
+    assert_eq!(relayed.state.cursor, Some(Position { line: 2, column: 4 }));
+    assert_eq!(relayed.state.selections.len(), 1);
+
+    let (status, bytes) = send::<()>(&mut app, "GET", &presence_uri, Some(&token), None).await?;
+    assert_eq!(status, StatusCode::OK);
+    let presence: PresenceResDto = body(&bytes)?;
+    assert_eq!(presence.participants.len(), 2);
+
+    // Act: One collaborator disconnects
+    bob.close(None).await?;
+    drop(bob);
+
+    // Assert: The departure is broadcast and the presence shrinks
+    assert_eq!(
+        recv(&mut alice).await?,
+        Frame::Departed {
+            connection_id: bob_presence.connection_id
+        }
+    );
+    let (_, bytes) = send::<()>(&mut app, "GET", &presence_uri, Some(&token), None).await?;
+    let presence: PresenceResDto = body(&bytes)?;
+    assert_eq!(presence.participants.len(), 1);
+    assert_eq!(
+        presence.participants[0].connection_id,
+        alice_presence.connection_id
+    );
+
+    // Assert: Staying silent past the idle timeout expires the remaining presence
+    tokio::time::sleep(Duration::from_millis(2000)).await;
+    let (_, bytes) = send::<()>(&mut app, "GET", &presence_uri, Some(&token), None).await?;
+    let presence: PresenceResDto = body(&bytes)?;
+    assert!(presence.participants.is_empty());
+
+    Ok(())
+}
+
+#[sqlx::test]
+async fn test_workspace_chat_over_socket(db_pool: PgPool) -> CaraiResult<()> {
+    let mut app = create_router(db_pool, config()?);
+    let token = login_as(&mut app, "pair").await?;
+
+    // Arrange: Two files of one workspace, each with a collaborator connected
+    let (_, bytes) = send(
+        &mut app,
+        "POST",
+        "/workspaces",
+        Some(&token),
+        Some(&json!({ "name": "live" })),
+    )
+    .await?;
+    let workspace: WorkspaceResDto = body(&bytes)?;
+    let files_uri = format!("/workspaces/{}/files", workspace.id);
+    let mut files = Vec::new();
+    for path in ["a.rs", "b.rs"] {
+        let file_req = json!({ "path": path, "content": "" });
+        let (_, bytes) = send(&mut app, "POST", &files_uri, Some(&token), Some(&file_req)).await?;
+        files.push(body::<WorkspaceFileResDto>(&bytes)?);
+    }
+
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let address = listener.local_addr()?;
+    let server = app.clone();
+    tokio::spawn(async move { axum::serve(listener, server).await });
+    let url = |file: &WorkspaceFileResDto| {
+        format!(
+            "ws://{}{}/{}/collab?token={}",
+            address, files_uri, file.id, token
+        )
+    };
+    let (mut alice, _) = connect_async(url(&files[0])).await?;
+    assert_eq!(recv_document(&mut alice).await?, Frame::Synced { seq: 0 });
+    let (mut bob, _) = connect_async(url(&files[1])).await?;
+    assert_eq!(recv_document(&mut bob).await?, Frame::Synced { seq: 0 });
+
+    // Act: One collaborator chats over the socket
+    let data = serde_json::to_vec(&json!({ "body": "hello" }))?;
+    push(&mut alice, Frame::Chat { data }).await?;
+
+    // Assert: Both the sender and the other file's room receive the stored message
+    for client in [&mut alice, &mut bob] {
+        let Frame::Chat { data } = recv_document(client).await? else {
+            panic!("Chat should reach every room of the workspace");
+        };
+        let message: ChatMessageResDto = serde_json::from_slice(&data)?;
+        assert_eq!(message.body, "hello");
+    }
+
+    // Act: Another message is posted over HTTP
+    let chat_uri = format!("/workspaces/{}/chat", workspace.id);
+    let (status, _) = send(
+        &mut app,
+        "POST",
+        &chat_uri,
+        Some(&token),
+        Some(&json!({ "body": "bye" })),
+    )
+    .await?;
+    assert_eq!(status, StatusCode::CREATED);
+
+    // Assert: It is relayed too, and the history lists both newest first
+    let Frame::Chat { data } = recv_document(&mut bob).await? else {
+        panic!("Chat posted over HTTP should be relayed");
+    };
+    assert_eq!(
+        serde_json::from_slice::<ChatMessageResDto>(&data)?.body,
+        "bye"
+    );
+    let (_, bytes) = send::<()>(&mut app, "GET", &chat_uri, Some(&token), None).await?;
+    let history: ChatMessagesResDto = body(&bytes)?;
+    let bodies: Vec<&str> = history.messages.iter().map(|m| m.body.as_str()).collect();
+    assert_eq!(bodies, vec!["bye", "hello"]);
+
+    Ok(())
+}
+
+#[sqlx::test]
+async fn test_session_recording_and_playback(db_pool: PgPool) -> CaraiResult<()> {
+    let mut config = config()?;
+    config.collab_mut().set_snapshot_interval_updates(2);
+    let mut app = create_router(db_pool, config);
+    let token = login_as(&mut app, "teacher").await?;
+    let start = Utc::now();
+
+    // Arrange: A file edited live, compacted, run and finally saved
+    let (_, bytes) = send(
+        &mut app,
+        "POST",
+        "/workspaces",
+        Some(&token),
+        Some(&json!({ "name": "lesson" })),
+    )
+    .await?;
+    let workspace: WorkspaceResDto = body(&bytes)?;
+    let files_uri = format!("/workspaces/{}/files", workspace.id);
+    let file_req = json!({ "path": "main.py", "content": "" });
+    let (_, bytes) = send(&mut app, "POST", &files_uri, Some(&token), Some(&file_req)).await?;
+    let file: WorkspaceFileResDto = body(&bytes)?;
+
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let address = listener.local_addr()?;
+    let server = app.clone();
+    tokio::spawn(async move { axum::serve(listener, server).await });
+    let url = format!(
+        "ws://{}{}/{}/collab?token={}",
+        address, files_uri, file.id, token
+    );
+    let (mut alice, _) = connect_async(url).await?;
+    assert_eq!(recv_document(&mut alice).await?, Frame::Synced { seq: 0 });
+
+    let first = edit(&mut alice, b"first").await?;
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    let after_first = Utc::now();
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    let second = edit(&mut alice, b"second").await?;
+    let data = b"merged".to_vec();
+    push(&mut alice, Frame::Snapshot { seq: second, data }).await?;
+    let third = edit(&mut alice, b"third").await?;
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    let after_third = Utc::now();
+
+    let run_req = json!({
+        "language": "python",
+        "files": [{ "name": "main.py", "content": "print(1)" }],
+        "workspace_id": workspace.id,
+    });
+    let (status, _) = send(&mut app, "POST", "/runs", Some(&token), Some(&run_req)).await?;
+    assert_eq!(status, 202);
+    // Saving over REST waits until nobody edits the file live
+    alice.close(None).await?;
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    let file_uri = format!("{}/{}", files_uri, file.id);
+    let (status, _) = send(
+        &mut app,
+        "PATCH",
+        &file_uri,
+        Some(&token),
+        Some(&json!({ "content": "print(1)" })),
+    )
+    .await?;
+    assert_eq!(status, 200);
+
+    // Act: Fetch the event log since the workspace was created
+    let uri = format!(
+        "/workspaces/{}/recording?from={}",
+        workspace.id,
+        timestamp(start)
+    );
+    let events = recording(&mut app, &token, &uri).await?;
+
+    // Assert: Every edit, checkpoint, run and save was recorded in order
+    let kinds: Vec<SessionEventKind> = events.iter().map(|event| event.kind).collect();
+    assert_eq!(
+        kinds,
+        vec![
+            SessionEventKind::Save,
+            SessionEventKind::Edit,
+            SessionEventKind::Edit,
+            SessionEventKind::Snapshot,
+            SessionEventKind::Edit,
+            SessionEventKind::RunQueued,
+            SessionEventKind::Save,
+        ]
+    );
+    assert_eq!(
+        events[1].data.as_deref(),
+        Some(STANDARD.encode(b"first").as_str())
+    );
+    assert!(events[5].execution_id.is_some());
+    assert_eq!(events[6].seq, Some(2), "Saves carry their revision number");
+
+    // Assert: The log can be paged through
+    let page = recording(&mut app, &token, &format!("{}&limit=2", uri)).await?;
+    assert_eq!(page.len(), 2);
+    let next = format!("{}&limit=2&after={}", uri, page[1].id);
+    let page = recording(&mut app, &token, &next).await?;
+    assert_eq!(page[0].id, events[2].id);
+
+    // Act: Scrub to the moment after the first edit
+    let state_uri = format!("{}/state?at=", file_uri);
+    let (_, bytes) = send::<()>(
+        &mut app,
+        "GET",
+        &format!("{}{}", state_uri, timestamp(after_first)),
+        Some(&token),
+        None,
+    )
+    .await?;
+    let state: DocumentStateResDto = body(&bytes)?;
+
+    // Assert: Only the first edit is replayed on top of the initial save
+    assert_eq!(
+        (state.revision, state.content.as_deref()),
+        (Some(1), Some(""))
+    );
+    assert_eq!(state.snapshot, None);
+    assert_eq!(state.updates, vec![STANDARD.encode(b"first")]);
+    assert_eq!(state.seq, first);
+
+    // Act: Scrub past the snapshot
+    let (_, bytes) = send::<()>(
+        &mut app,
+        "GET",
+        &format!("{}{}", state_uri, timestamp(after_third)),
+        Some(&token),
+        None,
+    )
+    .await?;
+    let state: DocumentStateResDto = body(&bytes)?;
+
+    // Assert: The snapshot replaces the edits it covers
+    assert_eq!(state.snapshot, Some(STANDARD.encode(b"merged")));
+    assert_eq!(state.updates, vec![STANDARD.encode(b"third")]);
+    assert_eq!(state.seq, third);
+
+    // Assert: Later saves show up, and backwards windows are rejected
+    let (_, bytes) = send::<()>(
+        &mut app,
+        "GET",
+        &format!("{}{}", state_uri, timestamp(Utc::now())),
+        Some(&token),
+        None,
+    )
+    .await?;
+    let state: DocumentStateResDto = body(&bytes)?;
+    assert_eq!(state.content.as_deref(), Some("print(1)"));
+    let backwards = format!(
+        "{}&to={}",
+        uri,
+        timestamp(start - chrono::Duration::hours(1))
+    );
+    let (status, _) = send::<()>(&mut app, "GET", &backwards, Some(&token), None).await?;
+    assert_eq!(status, 422);
+
+    Ok(())
+}
@@ -0,0 +1,183 @@
+use carai::{
+    dto::{
+        CommentThreadResDto, CommentThreadsResDto, InvitationResDto, MentionsResDto,
+        WorkspaceFileResDto, WorkspaceResDto,
+    },
+    utils::CaraiResult,
+};
+use common::{body, ctx, login_as, send};
+use serde_json::json;
+use sqlx::PgPool;
+
+mod common;
+
+#[sqlx::test]
+async fn test_comment_threads_follow_edits(db_pool: PgPool) -> CaraiResult<()> {
+    let mut app = ctx(db_pool)?;
+    let author = login_as(&mut app, "author").await?;
+    let reviewer = login_as(&mut app, "reviewer").await?;
+
+    // Arrange: A five line file and a reviewer who may comment
+    let (_, bytes) = send(
+        &mut app,
+        "POST",
+        "/workspaces",
+        Some(&author),
+        Some(&json!({ "name": "review" })),
+    )
+    .await?;
+    let workspace: WorkspaceResDto = body(&bytes)?;
+    let uri = format!("/workspaces/{}", workspace.id);
+    let file_req = json!({ "path": "main.py", "content": "a\nb\nc\nd\ne\n" });
+    let (_, bytes) = send(
+        &mut app,
+        "POST",
+        &format!("{}/files", uri),
+        Some(&author),
+        Some(&file_req),
+    )
+    .await?;
+    let file: WorkspaceFileResDto = body(&bytes)?;
+    let file_uri = format!("{}/files/{}", uri, file.id);
+
+    let link_req = json!({ "role": "commenter" });
+    let (_, bytes) = send(
+        &mut app,
+        "POST",
+        &format!("{}/invite-links", uri),
+        Some(&author),
+        Some(&link_req),
+    )
+    .await?;
+    let link: InvitationResDto = body(&bytes)?;
+    let redeem_uri = format!("/invitations/links/{}/accept", link.token.unwrap());
+    let (status, _) = send::<()>(&mut app, "POST", &redeem_uri, Some(&reviewer), None).await?;
+    assert_eq!(status, 200);
+
+    // Act: The reviewer comments on lines 3-4 and mentions the author
+    let comments_uri = format!("{}/comments", file_uri);
+    let (status, _) = send(
+        &mut app,
+        "POST",
+        &comments_uri,
+        Some(&reviewer),
+        Some(&json!({ "startLine": 4, "endLine": 9, "body": "Out of range" })),
+    )
+    .await?;
+    assert_eq!(status, 422, "Threads must stay within the file");
+    let thread_req = json!({ "startLine": 3, "endLine": 4, "body": "Why? @Author" });
+    let (status, bytes) = send(
+        &mut app,
+        "POST",
+        &comments_uri,
+        Some(&reviewer),
+        Some(&thread_req),
+    )
+    .await?;
+    assert_eq!(status, 201, "Opening a thread should return 201 Created");
+    let thread: CommentThreadResDto = body(&bytes)?;
+    assert_eq!(thread.comments.len(), 1);
+
+    // Assert: The author was notified of the mention
+    let (_, bytes) = send::<()>(
+        &mut app,
+        "GET",
+        "/mentions?unread=true",
+        Some(&author),
+        None,
+    )
+    .await?;
+    let mentions: MentionsResDto = body(&bytes)?;
+    assert_eq!(mentions.mentions.len(), 1);
+    assert_eq!(mentions.mentions[0].comment_id, Some(thread.comments[0].id));
+    let read_uri = format!("/mentions/{}/read", mentions.mentions[0].id);
+    let (status, _) = send::<()>(&mut app, "POST", &read_uri, Some(&reviewer), None).await?;
+    assert_eq!(status, 404, "Only the mentioned user can mark a mention");
+    let (status, _) = send::<()>(&mut app, "POST", &read_uri, Some(&author), None).await?;
+    assert_eq!(status, 204);
+    let (_, bytes) = send::<()>(
+        &mut app,
+        "GET",
+        "/mentions?unread=true",
+        Some(&author),
+        None,
+    )
+    .await?;
+    let mentions: MentionsResDto = body(&bytes)?;
+    assert!(mentions.mentions.is_empty());
+
+    // Act: Insert two lines above the commented range
+    let edit = json!({ "content": "x\ny\na\nb\nc\nd\ne\n" });
+    send(&mut app, "PATCH", &file_uri, Some(&author), Some(&edit)).await?;
+
+    // Assert: The thread moved along with its lines
+    let (_, bytes) = send::<()>(&mut app, "GET", &comments_uri, Some(&reviewer), None).await?;
+    let threads: CommentThreadsResDto = body(&bytes)?;
+    assert_eq!(
+        (threads.threads[0].start_line, threads.threads[0].end_line),
+        (5, 6)
+    );
+    assert!(!threads.threads[0].outdated);
+
+    // Act: Delete the commented lines
+    let edit = json!({ "content": "x\ny\na\nb\ne\n" });
+    send(&mut app, "PATCH", &file_uri, Some(&author), Some(&edit)).await?;
+
+    // Assert: The thread collapses where the lines were and is marked outdated
+    let (_, bytes) = send::<()>(&mut app, "GET", &comments_uri, Some(&reviewer), None).await?;
+    let threads: CommentThreadsResDto = body(&bytes)?;
+    assert_eq!(
+        (threads.threads[0].start_line, threads.threads[0].end_line),
+        (5, 5)
+    );
+    assert!(threads.threads[0].outdated);
+
+    // Act: Reply and resolve
+    let thread_uri = format!("{}/{}", comments_uri, thread.id);
+    let reply = json!({ "body": "Removed it" });
+    let (status, _) = send(
+        &mut app,
+        "POST",
+        &format!("{}/replies", thread_uri),
+        Some(&author),
+        Some(&reply),
+    )
+    .await?;
+    assert_eq!(status, 201, "Replying should return 201 Created");
+    let (status, bytes) = send::<()>(
+        &mut app,
+        "POST",
+        &format!("{}/resolve", thread_uri),
+        Some(&author),
+        None,
+    )
+    .await?;
+    assert_eq!(status, 200, "Resolving a thread should succeed");
+    let resolved: CommentThreadResDto = body(&bytes)?;
+    assert!(resolved.resolved_at.is_some());
+    assert_eq!(resolved.comments.len(), 2);
+
+    // Assert: Resolved threads can be filtered out and reopened
+    let (_, bytes) = send::<()>(
+        &mut app,
+        "GET",
+        &format!("{}?resolved=false", comments_uri),
+        Some(&author),
+        None,
+    )
+    .await?;
+    let threads: CommentThreadsResDto = body(&bytes)?;
+    assert!(threads.threads.is_empty());
+    let (_, bytes) = send::<()>(
+        &mut app,
+        "POST",
+        &format!("{}/unresolve", thread_uri),
+        Some(&reviewer),
+        None,
+    )
+    .await?;
+    let reopened: CommentThreadResDto = body(&bytes)?;
+    assert!(reopened.resolved_at.is_none());
+
+    Ok(())
+}
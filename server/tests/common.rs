@@ -1,14 +1,110 @@
+#![allow(dead_code)]
+
+use std::borrow::BorrowMut;
+
 use anyhow::Ok;
-use axum::Router;
+use axum::{
+    body::{to_bytes, Body},
+    http::Request,
+    Router,
+};
 use carai::{
     bootstrap::create_router,
-    utils::{AppConfig, CaraiResult},
+    dto::{LoginReqDto, LoginResDto, UserReqDto},
+    utils::{AppConfig, CaraiResult, SuccessResponse},
 };
+use serde::{de::DeserializeOwned, Serialize};
 use sqlx::PgPool;
+use tower::ServiceExt;
 
 pub fn ctx(db_pool: PgPool) -> CaraiResult<Router> {
+    Ok(create_router(db_pool, config()?))
+}
+
+pub fn config() -> CaraiResult<AppConfig> {
     dotenv::dotenv().ok();
-    let config = AppConfig::new()?;
+    AppConfig::new()
+}
+
+/// Registers a user and logs them in, returning their access token.
+pub async fn login_as(app: &mut Router, username: &str) -> CaraiResult<String> {
+    let register_req_dto = UserReqDto {
+        username: Some(username.to_string()),
+        email: Some(format!("{}@example.com", username)),
+        password: "em9Nie4U".to_string(),
+        github_id: None,
+    };
+    send(
+        app,
+        "POST",
+        "/users/register",
+        None,
+        Some(&register_req_dto),
+    )
+    .await?;
+
+    let login_req_dto = LoginReqDto {
+        username: register_req_dto.username,
+        email: register_req_dto.email,
+        password: "em9Nie4U".to_string(),
+    };
+    let (_, body) = send(app, "POST", "/auth/login", None, Some(&login_req_dto)).await?;
+    let login_res_dto: SuccessResponse<LoginResDto> = serde_json::from_slice(&body)?;
+
+    Ok(login_res_dto.body.access_token)
+}
+
+/// Sends a JSON request through the router and returns the status and raw body.
+pub async fn send<T: Serialize>(
+    app: &mut Router,
+    method: &str,
+    uri: &str,
+    token: Option<&str>,
+    body: Option<&T>,
+) -> CaraiResult<(u16, Vec<u8>)> {
+    let mut builder = Request::builder()
+        .uri(uri)
+        .method(method)
+        .header("Content-Type", "application/json");
+    if let Some(token) = token {
+        builder = builder.header("Authorization", format!("Bearer {}", token));
+    }
+    let body = match body {
+        Some(body) => Body::from(serde_json::to_string(body)?),
+        None => Body::empty(),
+    };
+
+    let response = app.borrow_mut().oneshot(builder.body(body)?).await?;
+    let status = response.status().as_u16();
+    let body = to_bytes(response.into_body(), usize::MAX).await?;
+
+    Ok((status, body.to_vec()))
+}
+
+/// Posts raw bytes through the router, as file uploads are sent.
+pub async fn upload(
+    app: &mut Router,
+    uri: &str,
+    token: &str,
+    content_type: &str,
+    bytes: Vec<u8>,
+) -> CaraiResult<(u16, Vec<u8>)> {
+    let request = Request::builder()
+        .uri(uri)
+        .method("POST")
+        .header("Content-Type", content_type)
+        .header("Authorization", format!("Bearer {}", token))
+        .body(Body::from(bytes))?;
+
+    let response = app.borrow_mut().oneshot(request).await?;
+    let status = response.status().as_u16();
+    let body = to_bytes(response.into_body(), usize::MAX).await?;
+
+    Ok((status, body.to_vec()))
+}
 
-    Ok(create_router(db_pool, config))
+/// Decodes the body of a `SuccessResponse`.
+pub fn body<T: DeserializeOwned + Serialize>(bytes: &[u8]) -> CaraiResult<T> {
+    let response: SuccessResponse<T> = serde_json::from_slice(bytes)?;
+    Ok(response.body)
 }
@@ -0,0 +1,198 @@
+use std::time::Duration;
+
+use axum::{routing::post, Json, Router};
+use carai::{
+    bootstrap::create_router,
+    dto::{FormatErrorsResDto, FormatResDto},
+    utils::CaraiResult,
+};
+use common::{body, config, login_as, send};
+use serde_json::{json, Value};
+use sqlx::PgPool;
+use tokio::net::TcpListener;
+use uuid::Uuid;
+
+mod common;
+
+/// Starts a stand-in for the RCE service that formats by collapsing runs of whitespace.
+///
+/// It only understands the rustfmt command and refuses files containing `syntax error`
+/// the way rustfmt does.
+async fn spawn_formatter_stub() -> CaraiResult<String> {
+    let app = Router::new().route(
+        "/run",
+        post(|Json(req): Json<Value>| async move {
+            let payload = &req["payload"];
+            let command = payload["command"].as_str().unwrap_or_default();
+            if !command.starts_with("rustfmt") || !command.contains("src/lib.rs") {
+                let stderr = format!("sh: {}: not found", command);
+                return Json(json!({ "stdout": "", "stderr": stderr, "error": "exit status 127" }));
+            }
+            let content = payload["files"][0]["content"].as_str().unwrap_or_default();
+            if content.contains("syntax error") {
+                let stderr = "error: expected one of `(`, found `error`\n --> <stdin>:1:11\n  |\n";
+                return Json(json!({ "stdout": "", "stderr": stderr, "error": "exit status 1" }));
+            }
+            let formatted: Vec<String> = content
+                .lines()
+                .map(|line| line.split_whitespace().collect::<Vec<_>>().join(" "))
+                .collect();
+            Json(json!({ "stdout": formatted.join("\n") + "\n", "stderr": "", "error": "" }))
+        }),
+    );
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let address = listener.local_addr()?;
+    tokio::spawn(async move { axum::serve(listener, app).await });
+
+    Ok(format!("http://{}/run", address))
+}
+
+#[sqlx::test]
+async fn test_format_code(db_pool: PgPool) -> CaraiResult<()> {
+    let mut config = config()?;
+    config.rce_mut().set_base_url(spawn_formatter_stub().await?);
+    let mut app = create_router(db_pool.clone(), config.clone());
+    let token = login_as(&mut app, "formatter").await?;
+
+    // Act: Format messy Rust code
+    let format_req = json!({
+        "language": "rust",
+        "path": "src/lib.rs",
+        "code": "fn  main()   {}",
+    });
+    let (status, bytes) =
+        send(&mut app, "POST", "/format", Some(&token), Some(&format_req)).await?;
+
+    // Assert: The registry's rustfmt command produced the formatted code
+    assert_eq!(status, 200, "Formatting should succeed");
+    let formatted: FormatResDto = body(&bytes)?;
+    assert_eq!(formatted.formatted, "fn main() {}\n");
+    assert!(formatted.changed);
+
+    // Act & Assert: Formatting formatted code changes nothing
+    let format_req = json!({
+        "language": "rust",
+        "path": "src/lib.rs",
+        "code": "fn main() {}\n",
+    });
+    let (_, bytes) = send(&mut app, "POST", "/format", Some(&token), Some(&format_req)).await?;
+    let formatted: FormatResDto = body(&bytes)?;
+    assert!(!formatted.changed, "Formatted code should be left alone");
+
+    // Act: Format code that does not parse
+    let format_req = json!({
+        "language": "rust",
+        "path": "src/lib.rs",
+        "code": "fn main syntax error",
+    });
+    let (status, bytes) =
+        send(&mut app, "POST", "/format", Some(&token), Some(&format_req)).await?;
+
+    // Assert: The formatter's complaint is located in the code
+    assert_eq!(status, 422, "Unparsable code cannot be formatted");
+    let error: Value = serde_json::from_slice(&bytes)?;
+    let errors: FormatErrorsResDto = serde_json::from_value(error["data"].clone())?;
+    assert_eq!(errors.diagnostics.len(), 1);
+    assert_eq!(errors.diagnostics[0].line, Some(1));
+    assert_eq!(errors.diagnostics[0].column, Some(11));
+    assert!(errors.diagnostics[0].message.starts_with("error: expected"));
+
+    // Act & Assert: Languages without a formatter are refused up front
+    let format_req = json!({ "language": "ruby", "code": "puts 1" });
+    let (status, _) = send(&mut app, "POST", "/format", Some(&token), Some(&format_req)).await?;
+    assert_eq!(status, 422, "Ruby has no formatter configured");
+
+    // Act & Assert: Formatting requires signing in
+    let (status, _) = send(&mut app, "POST", "/format", None, Some(&format_req)).await?;
+    assert_eq!(status, 401, "Anonymous callers may not use the sandbox");
+
+    // Arrange: A server whose sandbox is down
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let address = listener.local_addr()?;
+    drop(listener);
+    config
+        .rce_mut()
+        .set_base_url(format!("http://{}/run", address));
+    let mut app = create_router(db_pool, config);
+    let token = login_as(&mut app, "unlucky").await?;
+
+    // Act
+    let format_req = json!({ "language": "rust", "code": "fn main() {}" });
+    let (status, _) = send(&mut app, "POST", "/format", Some(&token), Some(&format_req)).await?;
+
+    // Assert: The outage is reported as such
+    assert_eq!(status, 503, "An unreachable sandbox should be reported");
+
+    Ok(())
+}
+
+#[sqlx::test]
+async fn test_formatting_is_held_to_run_quotas(db_pool: PgPool) -> CaraiResult<()> {
+    // Arrange: A formatter slow enough for requests to overlap
+    let stub = Router::new().route(
+        "/run",
+        post(|Json(req): Json<Value>| async move {
+            tokio::time::sleep(Duration::from_millis(300)).await;
+            let content = req["payload"]["files"][0]["content"].clone();
+            Json(json!({ "stdout": content, "stderr": "", "error": "" }))
+        }),
+    );
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let address = listener.local_addr()?;
+    tokio::spawn(async move { axum::serve(listener, stub).await });
+    let mut config = config()?;
+    config
+        .rce_mut()
+        .set_base_url(format!("http://{}/run", address));
+    config.quota_mut().user_mut().set_concurrent_runs(2);
+    let mut app = create_router(db_pool.clone(), config);
+    let token = login_as(&mut app, "hoarder").await?;
+    let format_req = json!({ "language": "rust", "code": "fn main() {}\n" });
+
+    // Act: Format several files at once
+    let requests = (0..5).map(|_| {
+        let mut app = app.clone();
+        let (token, format_req) = (token.clone(), format_req.clone());
+        async move { send(&mut app, "POST", "/format", Some(&token), Some(&format_req)).await }
+    });
+    let responses = futures_util::future::try_join_all(requests).await?;
+
+    // Assert: Only as many ran as the user may run programs at once
+    let statuses: Vec<u16> = responses.iter().map(|(status, _)| *status).collect();
+    assert_eq!(statuses.iter().filter(|&&status| status == 200).count(), 2);
+    assert_eq!(statuses.iter().filter(|&&status| status == 429).count(), 3);
+    let (_, bytes) = responses
+        .iter()
+        .find(|(status, _)| *status == 429)
+        .expect("refused request");
+    let error: Value = serde_json::from_slice(bytes)?;
+    assert_eq!(error["data"]["quota"], "concurrent_runs");
+
+    // Assert: The sandbox time was charged to the user
+    let charged: Vec<i64> =
+        sqlx::query_scalar("SELECT cpu_ms FROM execution_usage WHERE execution_id IS NULL")
+            .fetch_all(&db_pool)
+            .await?;
+    assert_eq!(charged.len(), 2, "Each formatting should be charged");
+    assert!(charged.iter().all(|&cpu_ms| cpu_ms >= 300));
+
+    // Arrange: The user has used up their sandbox time for the day
+    sqlx::query(
+        "INSERT INTO execution_usage (id, user_id, language, cpu_ms, created_at)
+         SELECT $1, id, 'python', 1000000000, NOW() FROM users WHERE username = 'hoarder'",
+    )
+    .bind(Uuid::new_v4())
+    .execute(&db_pool)
+    .await?;
+
+    // Act
+    let (status, bytes) =
+        send(&mut app, "POST", "/format", Some(&token), Some(&format_req)).await?;
+
+    // Assert: Formatting is refused like runs are
+    assert_eq!(status, 429, "An exhausted quota should refuse formatting");
+    let error: Value = serde_json::from_slice(&bytes)?;
+    assert_eq!(error["data"]["quota"], "cpu_seconds_per_day");
+
+    Ok(())
+}
@@ -0,0 +1,178 @@
+use std::time::Duration;
+
+use carai::{
+    bootstrap::create_router,
+    dto::{InvitationResDto, WorkspaceResDto},
+    utils::CaraiResult,
+};
+use common::{body, config, login_as, send};
+use futures_util::{SinkExt, StreamExt};
+use serde_json::{json, Value};
+use sqlx::PgPool;
+use tokio::{net::TcpListener, time::timeout};
+use tokio_tungstenite::{
+    connect_async,
+    tungstenite::{protocol::frame::coding::CloseCode, Message},
+};
+use uuid::Uuid;
+
+mod common;
+
+#[sqlx::test]
+async fn test_language_server_proxy(db_pool: PgPool) -> CaraiResult<()> {
+    // Arrange: A "language server" that echoes every message and logs what it received
+    let root_dir = std::env::temp_dir().join(format!("carai-lsp-{}", Uuid::new_v4()));
+    let mut config = config()?;
+    config
+        .lsp_mut()
+        .set_root_dir(root_dir.display().to_string())
+        .set_sandbox_command("env > {dir}/.environment; tee {dir}/.received".to_owned())
+        .set_idle_timeout_secs(1)
+        .set_max_servers_per_user(1);
+    let mut app = create_router(db_pool, config);
+    let token = login_as(&mut app, "linter").await?;
+
+    let (_, bytes) = send(
+        &mut app,
+        "POST",
+        "/workspaces",
+        Some(&token),
+        Some(&json!({ "name": "typed", "language": "python" })),
+    )
+    .await?;
+    let workspace: WorkspaceResDto = body(&bytes)?;
+    let files_uri = format!("/workspaces/{}/files", workspace.id);
+    let file_req = json!({ "path": "pkg/main.py", "content": "print(1)\n" });
+    send(&mut app, "POST", &files_uri, Some(&token), Some(&file_req)).await?;
+
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let address = listener.local_addr()?;
+    tokio::spawn(async move { axum::serve(listener, app).await });
+    let url = format!("ws://{}/workspaces/{}/lsp", address, workspace.id);
+
+    // Assert: Connections without a valid token or a language server are refused
+    assert!(
+        connect_async(format!("{}?token=invalid", url))
+            .await
+            .is_err(),
+        "Invalid tokens should be rejected"
+    );
+    assert!(
+        connect_async(format!("{}?token={}&language=ruby", url, token))
+            .await
+            .is_err(),
+        "Ruby has no language server configured"
+    );
+
+    // Act: Open the workspace file through the proxy
+    let (mut client, _) = connect_async(format!("{}?token={}", url, token)).await?;
+    let request = json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "textDocument/didOpen",
+        "params": { "textDocument": { "uri": "file:///workspace/pkg/main.py" } },
+    });
+    client.send(Message::Text(request.to_string())).await?;
+    let reply = timeout(Duration::from_secs(5), client.next())
+        .await?
+        .expect("The server should answer")?;
+
+    // Assert: The message made the round trip with the client's URIs
+    let reply: Value = serde_json::from_str(reply.to_text()?)?;
+    assert_eq!(reply, request);
+
+    // Assert: The server saw the files where they were materialized, under real paths
+    let dirs: Vec<_> = std::fs::read_dir(&root_dir)?.collect::<Result<_, _>>()?;
+    assert_eq!(dirs.len(), 1, "One directory should be materialized");
+    let dir = dirs[0].path();
+    assert_eq!(
+        std::fs::read_to_string(dir.join("pkg/main.py"))?,
+        "print(1)\n"
+    );
+    // tee echoes before it logs, so the log may lag behind the reply
+    let mut received = String::new();
+    for _ in 0..20 {
+        received = std::fs::read_to_string(dir.join(".received"))?;
+        if !received.is_empty() {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    assert!(received.starts_with("Content-Length: "));
+    assert!(received.contains(&format!("file://{}/pkg/main.py", dir.display())));
+    assert!(!received.contains("file:///workspace"));
+    let environment = std::fs::read_to_string(dir.join(".environment"))?;
+    assert!(
+        !environment.contains("APP__"),
+        "The server's secrets should not reach the language server"
+    );
+    assert!(environment.contains(&format!("HOME={}", dir.display())));
+
+    // Act & Assert: The user may not start a second server meanwhile
+    assert!(
+        connect_async(format!("{}?token={}", url, token))
+            .await
+            .is_err(),
+        "The per-user limit should be enforced"
+    );
+
+    // Act: Stay quiet past the idle timeout
+    let closing = timeout(Duration::from_secs(5), client.next())
+        .await?
+        .expect("The server should close the socket")?;
+
+    // Assert: The session is closed and cleaned up, freeing the user's slot
+    let Message::Close(Some(frame)) = closing else {
+        panic!("Expected a close frame, got {:?}", closing);
+    };
+    assert_eq!(frame.code, CloseCode::Normal);
+    assert_eq!(frame.reason, "Idle timeout");
+    drop(client);
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    assert!(!dir.exists(), "The directory should be removed");
+    let (client, _) = connect_async(format!("{}?token={}", url, token)).await?;
+    drop(client);
+
+    std::fs::remove_dir_all(&root_dir).ok();
+    Ok(())
+}
+
+#[sqlx::test]
+async fn test_viewers_cannot_start_language_servers(db_pool: PgPool) -> CaraiResult<()> {
+    let mut app = create_router(db_pool, config()?);
+    let owner = login_as(&mut app, "owner").await?;
+    let guest = login_as(&mut app, "guest").await?;
+
+    // Arrange: A viewer who joined through a link
+    let (_, bytes) = send(
+        &mut app,
+        "POST",
+        "/workspaces",
+        Some(&owner),
+        Some(&json!({ "name": "typed", "language": "python" })),
+    )
+    .await?;
+    let workspace: WorkspaceResDto = body(&bytes)?;
+    let link_uri = format!("/workspaces/{}/invite-links", workspace.id);
+    let link_req = json!({ "role": "viewer" });
+    let (_, bytes) = send(&mut app, "POST", &link_uri, Some(&owner), Some(&link_req)).await?;
+    let link: InvitationResDto = body(&bytes)?;
+    let redeem_uri = format!("/invitations/links/{}/accept", link.token.unwrap());
+    let (status, _) = send::<()>(&mut app, "POST", &redeem_uri, Some(&guest), None).await?;
+    assert_eq!(status, 200);
+
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let address = listener.local_addr()?;
+    tokio::spawn(async move { axum::serve(listener, app).await });
+    let url = format!("ws://{}/workspaces/{}/lsp", address, workspace.id);
+
+    // Act & Assert: Language servers may run workspace code, so viewers are refused
+    assert!(
+        connect_async(format!("{}?token={}", url, guest))
+            .await
+            .is_err(),
+        "Viewers should not start language servers"
+    );
+
+    Ok(())
+}
@@ -0,0 +1,173 @@
+use std::time::Duration;
+
+use carai::{
+    bootstrap::create_router,
+    dto::{NotificationPreferencesDto, NotificationsResDto, WorkspaceResDto},
+    models::NotificationCategory,
+    notifications::NotificationEvent,
+    utils::CaraiResult,
+};
+use common::{body, config, ctx, login_as, send};
+use futures_util::StreamExt;
+use serde_json::json;
+use sqlx::PgPool;
+use tokio::{
+    net::{TcpListener, TcpStream},
+    time::timeout,
+};
+use tokio_tungstenite::{connect_async, tungstenite::Message, MaybeTlsStream, WebSocketStream};
+
+mod common;
+
+type Client = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+async fn recv(client: &mut Client) -> CaraiResult<NotificationEvent> {
+    loop {
+        let message = timeout(Duration::from_secs(5), client.next())
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Connection closed"))??;
+        if let Message::Text(text) = message {
+            return Ok(serde_json::from_str(&text)?);
+        }
+    }
+}
+
+/// Creates a workspace and invites `username` to it directly.
+async fn invite(
+    app: &mut axum::Router,
+    token: &str,
+    name: &str,
+    username: &str,
+) -> CaraiResult<()> {
+    let (_, bytes) = send(
+        app,
+        "POST",
+        "/workspaces",
+        Some(token),
+        Some(&json!({ "name": name })),
+    )
+    .await?;
+    let workspace: WorkspaceResDto = body(&bytes)?;
+    let invite_req = json!({ "username": username, "role": "editor" });
+    let invite_uri = format!("/workspaces/{}/invitations", workspace.id);
+    let (status, _) = send(app, "POST", &invite_uri, Some(token), Some(&invite_req)).await?;
+    assert_eq!(status, 201, "Inviting should succeed");
+    Ok(())
+}
+
+#[sqlx::test]
+async fn test_notifications_read_and_mute(db_pool: PgPool) -> CaraiResult<()> {
+    let mut app = ctx(db_pool)?;
+    let owner = login_as(&mut app, "owner").await?;
+    let guest = login_as(&mut app, "guest").await?;
+
+    // Arrange: Two invitations addressed to the guest
+    invite(&mut app, &owner, "first", "guest").await?;
+    invite(&mut app, &owner, "second", "guest").await?;
+
+    // Act: The guest lists their unread invitation notifications
+    let (status, bytes) = send::<()>(
+        &mut app,
+        "GET",
+        "/notifications?unread=true&category=invitation",
+        Some(&guest),
+        None,
+    )
+    .await?;
+
+    // Assert: Both are listed, newest first
+    assert_eq!(status, 200);
+    let listed: NotificationsResDto = body(&bytes)?;
+    assert_eq!(listed.notifications.len(), 2);
+    assert_eq!(listed.unread_count, 2);
+    assert!(listed
+        .notifications
+        .iter()
+        .all(|n| n.category == NotificationCategory::Invitation));
+
+    // Act: One is marked as read, and nobody else may mark the other
+    let read_uri = format!("/notifications/{}/read", listed.notifications[0].id);
+    let (status, _) = send::<()>(&mut app, "POST", &read_uri, Some(&guest), None).await?;
+    assert_eq!(status, 204);
+    let other_uri = format!("/notifications/{}/read", listed.notifications[1].id);
+    let (status, _) = send::<()>(&mut app, "POST", &other_uri, Some(&owner), None).await?;
+    assert_eq!(status, 404, "Notifications of others should not be found");
+
+    // Assert: Only the other one is still unread
+    let (_, bytes) = send::<()>(
+        &mut app,
+        "GET",
+        "/notifications?unread=true",
+        Some(&guest),
+        None,
+    )
+    .await?;
+    let unread: NotificationsResDto = body(&bytes)?;
+    assert_eq!(unread.notifications.len(), 1);
+    assert_eq!(unread.notifications[0].id, listed.notifications[1].id);
+    assert_eq!(unread.unread_count, 1);
+
+    // Act: The guest marks everything read and mutes invitations
+    let (status, _) =
+        send::<()>(&mut app, "POST", "/notifications/read", Some(&guest), None).await?;
+    assert_eq!(status, 204);
+    let (status, bytes) = send(
+        &mut app,
+        "PUT",
+        "/notifications/preferences",
+        Some(&guest),
+        Some(&json!({ "muted": ["invitation", "run"] })),
+    )
+    .await?;
+    assert_eq!(status, 200);
+    let preferences: NotificationPreferencesDto = body(&bytes)?;
+    assert_eq!(
+        preferences.muted,
+        vec![NotificationCategory::Invitation, NotificationCategory::Run]
+    );
+    invite(&mut app, &owner, "third", "guest").await?;
+
+    // Assert: Muted invitations are not stored
+    let (_, bytes) = send::<()>(&mut app, "GET", "/notifications", Some(&guest), None).await?;
+    let all: NotificationsResDto = body(&bytes)?;
+    assert_eq!(all.notifications.len(), 2);
+    assert_eq!(all.unread_count, 0);
+
+    Ok(())
+}
+
+#[sqlx::test]
+async fn test_notifications_are_pushed_to_streams(db_pool: PgPool) -> CaraiResult<()> {
+    let mut app = create_router(db_pool, config()?);
+    let owner = login_as(&mut app, "owner").await?;
+    let guest = login_as(&mut app, "guest").await?;
+
+    // Arrange: A pending invitation and the guest's stream served over a real socket
+    invite(&mut app, &owner, "first", "guest").await?;
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let address = listener.local_addr()?;
+    let server = app.clone();
+    tokio::spawn(async move { axum::serve(listener, server).await });
+    let url = format!("ws://{}/notifications/stream?token={}", address, guest);
+    let (mut stream, _) = connect_async(url).await?;
+
+    // Assert: The stream opens with the unread count
+    let event = recv(&mut stream).await?;
+    assert!(
+        matches!(event, NotificationEvent::Unread { count: 1 }),
+        "Unexpected event {:?}",
+        event
+    );
+
+    // Act: Another invitation is sent while the stream is open
+    invite(&mut app, &owner, "second", "guest").await?;
+
+    // Assert: It is pushed right away
+    let NotificationEvent::Notification { notification } = recv(&mut stream).await? else {
+        panic!("The new notification should be pushed");
+    };
+    assert_eq!(notification.category, NotificationCategory::Invitation);
+    assert_eq!(notification.title, "New invitation");
+
+    Ok(())
+}
@@ -0,0 +1,370 @@
+use axum::Router;
+use carai::{
+    dto::{
+        OrganizationMemberResDto, OrganizationMembersResDto, OrganizationResDto,
+        OrganizationUsageResDto, OrganizationsResDto, TemplatesResDto, UsageResDto,
+        WorkspaceResDto, WorkspacesResDto,
+    },
+    models::{OrganizationRole, QuotaKind},
+    utils::CaraiResult,
+};
+use common::{body, ctx, login_as, send};
+use serde_json::{json, Value};
+use sqlx::PgPool;
+
+mod common;
+
+/// Creates the `acme` organization owned by `token`'s user.
+async fn create_organization(app: &mut Router, token: &str) -> CaraiResult<OrganizationResDto> {
+    let org_req = json!({ "slug": "acme", "name": "Acme Corp" });
+    let (status, bytes) = send(app, "POST", "/organizations", Some(token), Some(&org_req)).await?;
+    assert_eq!(status, 201, "Creating an organization should succeed");
+    body(&bytes)
+}
+
+async fn add_member(app: &mut Router, token: &str, username: &str, role: &str) -> CaraiResult<u16> {
+    let member_req = json!({ "username": username, "role": role });
+    let (status, _) = send(
+        app,
+        "POST",
+        "/organizations/acme/members",
+        Some(token),
+        Some(&member_req),
+    )
+    .await?;
+    Ok(status)
+}
+
+#[sqlx::test]
+async fn test_organization_membership(db_pool: PgPool) -> CaraiResult<()> {
+    let mut app = ctx(db_pool)?;
+    let lead = login_as(&mut app, "lead").await?;
+    let ops = login_as(&mut app, "ops").await?;
+    let dev = login_as(&mut app, "dev").await?;
+    let outsider = login_as(&mut app, "outsider").await?;
+
+    // Arrange: The lead founds an organization and staffs it
+    let organization = create_organization(&mut app, &lead).await?;
+    assert_eq!(add_member(&mut app, &lead, "ops", "admin").await?, 201);
+    assert_eq!(add_member(&mut app, &ops, "dev", "member").await?, 201);
+
+    // Assert: Slugs are unique, and only owners appoint owners
+    let org_req = json!({ "slug": "acme", "name": "Copycat" });
+    let (status, _) = send(
+        &mut app,
+        "POST",
+        "/organizations",
+        Some(&dev),
+        Some(&org_req),
+    )
+    .await?;
+    assert_eq!(status, 409, "Slugs should be unique");
+    assert_eq!(add_member(&mut app, &ops, "outsider", "owner").await?, 403);
+    assert_eq!(add_member(&mut app, &dev, "outsider", "member").await?, 403);
+    assert_eq!(add_member(&mut app, &ops, "nobody", "member").await?, 404);
+    assert_eq!(add_member(&mut app, &ops, "dev", "member").await?, 409);
+
+    // Assert: Members see the organization and each other; outsiders see nothing
+    let (_, bytes) = send::<()>(&mut app, "GET", "/organizations", Some(&dev), None).await?;
+    let organizations: OrganizationsResDto = body(&bytes)?;
+    assert_eq!(organizations.organizations.len(), 1);
+    assert_eq!(
+        organizations.organizations[0].role,
+        OrganizationRole::Member
+    );
+    let (_, bytes) = send::<()>(
+        &mut app,
+        "GET",
+        "/organizations/acme/members",
+        Some(&dev),
+        None,
+    )
+    .await?;
+    let members: OrganizationMembersResDto = body(&bytes)?;
+    let roles: Vec<_> = members.members.iter().map(|m| m.role).collect();
+    assert_eq!(
+        roles,
+        vec![
+            OrganizationRole::Owner,
+            OrganizationRole::Admin,
+            OrganizationRole::Member
+        ]
+    );
+    let (status, _) = send::<()>(
+        &mut app,
+        "GET",
+        "/organizations/acme",
+        Some(&outsider),
+        None,
+    )
+    .await?;
+    assert_eq!(status, 404, "Outsiders should not see the organization");
+
+    // Act: A member creates a workspace in the organization
+    let workspace_req = json!({ "name": "shared", "organization_id": organization.id });
+    let (status, bytes) = send(
+        &mut app,
+        "POST",
+        "/workspaces",
+        Some(&dev),
+        Some(&workspace_req),
+    )
+    .await?;
+    assert_eq!(status, 201);
+    let workspace: WorkspaceResDto = body(&bytes)?;
+    assert_eq!(workspace.organization_id, Some(organization.id));
+    let (status, _) = send(
+        &mut app,
+        "POST",
+        "/workspaces",
+        Some(&outsider),
+        Some(&workspace_req),
+    )
+    .await?;
+    assert_eq!(status, 404, "Outsiders cannot create workspaces in it");
+
+    // Assert: Every member can use it, admins can manage it, outsiders cannot find it
+    let workspace_uri = format!("/workspaces/{}", workspace.id);
+    let (_, bytes) = send::<()>(
+        &mut app,
+        "GET",
+        "/organizations/acme/workspaces",
+        Some(&lead),
+        None,
+    )
+    .await?;
+    let workspaces: WorkspacesResDto = body(&bytes)?;
+    assert_eq!(workspaces.workspaces.len(), 1);
+    let (_, bytes) = send::<()>(&mut app, "GET", "/workspaces", Some(&lead), None).await?;
+    let workspaces: WorkspacesResDto = body(&bytes)?;
+    assert_eq!(
+        workspaces.workspaces.len(),
+        1,
+        "Shared workspaces are listed"
+    );
+    let file_req = json!({ "path": "main.py", "content": "" });
+    let files_uri = format!("{}/files", workspace_uri);
+    let (status, _) = send(&mut app, "POST", &files_uri, Some(&ops), Some(&file_req)).await?;
+    assert_eq!(status, 201, "Organization members edit its workspaces");
+    let (status, _) = send::<()>(&mut app, "GET", &workspace_uri, Some(&outsider), None).await?;
+    assert_eq!(status, 404);
+
+    // Act: An admin publishes a template to the organization
+    let template_req = json!({
+        "slug": "acme-service",
+        "name": "Acme service",
+        "files": [{ "path": "main.py", "content": "" }],
+        "organization_id": organization.id,
+    });
+    let (status, _) = send(
+        &mut app,
+        "POST",
+        "/templates",
+        Some(&dev),
+        Some(&template_req),
+    )
+    .await?;
+    assert_eq!(status, 403, "Members cannot publish organization templates");
+    let (status, _) = send(
+        &mut app,
+        "POST",
+        "/templates",
+        Some(&ops),
+        Some(&template_req),
+    )
+    .await?;
+    assert_eq!(status, 201);
+
+    // Assert: Only members see it
+    let (_, bytes) = send::<()>(&mut app, "GET", "/templates", Some(&dev), None).await?;
+    let templates: TemplatesResDto = body(&bytes)?;
+    assert!(templates
+        .templates
+        .iter()
+        .any(|template| template.organization_id == Some(organization.id)));
+    let (status, _) = send::<()>(
+        &mut app,
+        "GET",
+        "/templates/acme-service",
+        Some(&outsider),
+        None,
+    )
+    .await?;
+    assert_eq!(status, 404, "Organization templates are private");
+
+    // Act & Assert: Roles change, but the last owner cannot step down or leave
+    let (_, bytes) = send::<()>(
+        &mut app,
+        "GET",
+        "/organizations/acme/members",
+        Some(&lead),
+        None,
+    )
+    .await?;
+    let members: OrganizationMembersResDto = body(&bytes)?;
+    let (lead_id, dev_id) = (members.members[0].user_id, members.members[2].user_id);
+    let (status, bytes) = send(
+        &mut app,
+        "PATCH",
+        &format!("/organizations/acme/members/{}", dev_id),
+        Some(&ops),
+        Some(&json!({ "role": "admin" })),
+    )
+    .await?;
+    assert_eq!(status, 200);
+    let promoted: OrganizationMemberResDto = body(&bytes)?;
+    assert_eq!(promoted.role, OrganizationRole::Admin);
+    let lead_uri = format!("/organizations/acme/members/{}", lead_id);
+    let (status, _) = send(
+        &mut app,
+        "PATCH",
+        &lead_uri,
+        Some(&lead),
+        Some(&json!({ "role": "member" })),
+    )
+    .await?;
+    assert_eq!(status, 422, "The last owner cannot step down");
+    let (status, _) = send::<()>(&mut app, "DELETE", &lead_uri, Some(&ops), None).await?;
+    assert_eq!(status, 403, "Admins cannot remove owners");
+    let (status, _) = send::<()>(&mut app, "DELETE", &lead_uri, Some(&lead), None).await?;
+    assert_eq!(status, 422, "The last owner cannot leave");
+    let dev_uri = format!("/organizations/acme/members/{}", dev_id);
+    let (status, _) = send::<()>(&mut app, "DELETE", &dev_uri, Some(&dev), None).await?;
+    assert_eq!(status, 204, "Members may leave");
+
+    // Act: The owner dissolves the organization
+    let (status, _) =
+        send::<()>(&mut app, "DELETE", "/organizations/acme", Some(&ops), None).await?;
+    assert_eq!(status, 403, "Only owners may delete the organization");
+    let (status, _) =
+        send::<()>(&mut app, "DELETE", "/organizations/acme", Some(&lead), None).await?;
+    assert_eq!(status, 204);
+
+    // Assert: Its workspaces went with it
+    let (status, _) = send::<()>(&mut app, "GET", &workspace_uri, Some(&dev), None).await?;
+    assert_eq!(status, 404, "Organization workspaces are deleted with it");
+
+    Ok(())
+}
+
+#[sqlx::test]
+async fn test_organization_quotas(db_pool: PgPool) -> CaraiResult<()> {
+    let mut app = ctx(db_pool.clone())?;
+    login_as(&mut app, "billing").await?;
+    sqlx::query("UPDATE users SET is_admin = TRUE WHERE username = 'billing'")
+        .execute(&db_pool)
+        .await?;
+    let admin = login_as(&mut app, "billing").await?;
+    let lead = login_as(&mut app, "lead").await?;
+    let dev = login_as(&mut app, "dev").await?;
+
+    // Arrange: An organization allowed a single run at a time
+    let organization = create_organization(&mut app, &lead).await?;
+    assert_eq!(add_member(&mut app, &lead, "dev", "member").await?, 201);
+    let quota_req = json!({ "concurrent_runs": 1 });
+    let (status, _) = send(
+        &mut app,
+        "PATCH",
+        "/organizations/acme",
+        Some(&lead),
+        Some(&quota_req),
+    )
+    .await?;
+    assert_eq!(status, 403, "Organizations cannot raise their own quotas");
+    let (status, bytes) = send(
+        &mut app,
+        "PATCH",
+        "/organizations/acme",
+        Some(&admin),
+        Some(&quota_req),
+    )
+    .await?;
+    assert_eq!(status, 200);
+    let updated: OrganizationResDto = body(&bytes)?;
+    assert_eq!(updated.concurrent_runs, 1);
+
+    let workspace_req = json!({ "name": "shared", "organization_id": organization.id });
+    let (_, bytes) = send(
+        &mut app,
+        "POST",
+        "/workspaces",
+        Some(&lead),
+        Some(&workspace_req),
+    )
+    .await?;
+    let workspace: WorkspaceResDto = body(&bytes)?;
+    let org_run = json!({
+        "language": "python",
+        "files": [{ "name": "main.py", "content": "print(1)" }],
+        "workspace_id": workspace.id,
+    });
+
+    // Act: Two members start runs in the shared workspace while no worker drains the queue
+    let (first, _) = send(&mut app, "POST", "/runs", Some(&lead), Some(&org_run)).await?;
+    let (second, bytes) = send(&mut app, "POST", "/runs", Some(&dev), Some(&org_run)).await?;
+
+    // Assert: They share the organization's quota
+    assert_eq!(first, 202);
+    assert_eq!(second, 429, "The organization's quota is shared");
+    let error: Value = serde_json::from_slice(&bytes)?;
+    assert!(error["message"]
+        .as_str()
+        .unwrap_or_default()
+        .starts_with("Organization execution quota exceeded"));
+    assert_eq!(error["data"]["quota"], "concurrent_runs");
+
+    // Assert: Runs of their own are charged to their personal quota instead
+    let own_run = json!({
+        "language": "python",
+        "files": [{ "name": "main.py", "content": "print(2)" }],
+    });
+    let (status, _) = send(&mut app, "POST", "/runs", Some(&lead), Some(&own_run)).await?;
+    assert_eq!(
+        status, 202,
+        "Personal runs are not billed to the organization"
+    );
+    let (_, bytes) = send::<()>(&mut app, "GET", "/users/me/usage", Some(&lead), None).await?;
+    let usage: UsageResDto = body(&bytes)?;
+    let executions = usage
+        .quotas
+        .iter()
+        .find(|q| q.quota == QuotaKind::ExecutionsPerHour)
+        .expect("hourly quota");
+    assert_eq!(executions.used, 1, "Only the personal run counts");
+
+    // Act & Assert: Admins of the organization see its usage
+    let (status, _) = send::<()>(
+        &mut app,
+        "GET",
+        "/organizations/acme/usage",
+        Some(&dev),
+        None,
+    )
+    .await?;
+    assert_eq!(status, 403, "Usage is for organization admins");
+    let (status, bytes) = send::<()>(
+        &mut app,
+        "GET",
+        "/organizations/acme/usage",
+        Some(&lead),
+        None,
+    )
+    .await?;
+    assert_eq!(status, 200);
+    let usage: OrganizationUsageResDto = body(&bytes)?;
+    let executions = usage
+        .quotas
+        .iter()
+        .find(|q| q.quota == QuotaKind::ExecutionsPerHour)
+        .expect("hourly quota");
+    assert_eq!(executions.used, 1);
+    assert_eq!(executions.limit, organization.executions_per_hour);
+    let concurrent = usage
+        .quotas
+        .iter()
+        .find(|q| q.quota == QuotaKind::ConcurrentRuns)
+        .expect("concurrency quota");
+    assert!(concurrent.is_exhausted());
+
+    Ok(())
+}
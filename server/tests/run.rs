@@ -0,0 +1,757 @@
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use axum::{
+    extract::State,
+    http::StatusCode,
+    routing::{get, post},
+    Json, Router,
+};
+use carai::{
+    bootstrap::create_router,
+    dto::{RunDetailResDto, RunResDto, RunsResDto, UsageResDto},
+    executor::{
+        find_language, grade_case, ExecutionOutput, BUILD_ARTIFACT_FILE, CASE_TIMEOUT_MARGIN_MS,
+    },
+    models::{
+        ComparisonMode, Execution, ExecutionFile, ExecutionStatus, JudgeSpec, QuotaKind, TestCase,
+        User, Verdict,
+    },
+    services::{
+        claim_next_execution, create_execution, create_user, delete_executions_finished_before,
+        finish_execution, get_execution_by_id, requeue_execution,
+    },
+    utils::CaraiResult,
+    workers::spawn_execution_workers,
+};
+use chrono::Utc;
+use common::{body, config, login_as, send};
+use serde_json::{json, Value};
+use sqlx::PgPool;
+use tokio::net::TcpListener;
+use uuid::Uuid;
+
+mod common;
+
+/// Starts a stand-in for the RCE service that echoes the first file followed by stdin.
+///
+/// Compile commands fail when any file contains `#error` and otherwise print a fake
+/// build, which run commands refuse to run without; `GET /compiles` counts the builds.
+/// Any request whose files or stdin contain `#loop` hangs for a few seconds.
+async fn spawn_rce_stub() -> CaraiResult<String> {
+    let compiles = Arc::new(AtomicUsize::new(0));
+    let app = Router::new()
+        .route(
+            "/run",
+            post(
+                |State(compiles): State<Arc<AtomicUsize>>, Json(req): Json<Value>| async move {
+                    let payload = &req["payload"];
+                    if payload.to_string().contains("#loop") {
+                        tokio::time::sleep(Duration::from_secs(3)).await;
+                    }
+                    let files = payload["files"].as_array().cloned().unwrap_or_default();
+                    let command = payload["command"].as_str().unwrap_or_default();
+                    if command.contains("tar czf") {
+                        compiles.fetch_add(1, Ordering::SeqCst);
+                        let broken = files.iter().any(|file| {
+                            file["content"]
+                                .as_str()
+                                .unwrap_or_default()
+                                .contains("#error")
+                        });
+                        return Json(match broken {
+                            true => {
+                                json!({ "stdout": "", "stderr": command, "error": "exit status 1" })
+                            }
+                            false => json!({ "stdout": "BUILD", "stderr": "", "error": "" }),
+                        });
+                    }
+                    let built = files.iter().any(|file| {
+                        file["name"] == BUILD_ARTIFACT_FILE && file["content"] == "BUILD"
+                    });
+                    if command.contains("tar xzf") && !built {
+                        return Json(json!({ "stdout": "", "stderr": "", "error": "no build" }));
+                    }
+                    let stdout = format!(
+                        "{}{}",
+                        files[0]["content"].as_str().unwrap_or_default(),
+                        payload["stdin"].as_str().unwrap_or_default()
+                    );
+                    Json(json!({ "stdout": stdout, "stderr": "", "error": "" }))
+                },
+            ),
+        )
+        .route(
+            "/compiles",
+            get(|State(compiles): State<Arc<AtomicUsize>>| async move {
+                compiles.load(Ordering::SeqCst).to_string()
+            }),
+        )
+        .with_state(compiles);
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let address = listener.local_addr()?;
+    tokio::spawn(async move { axum::serve(listener, app).await });
+
+    Ok(format!("http://{}/run", address))
+}
+
+/// Polls a run until the worker pool has finished it.
+async fn wait_for_run(app: &mut Router, token: &str, id: Uuid) -> CaraiResult<RunResDto> {
+    let uri = format!("/runs/{}", id);
+    for _ in 0..50 {
+        let (_, bytes) = send::<()>(app, "GET", &uri, Some(token), None).await?;
+        let run: RunResDto = body(&bytes)?;
+        if run.status == ExecutionStatus::Completed {
+            return Ok(run);
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    anyhow::bail!("Run {} did not complete in time", id)
+}
+
+#[sqlx::test]
+async fn test_run_is_queued_and_processed(db_pool: PgPool) -> CaraiResult<()> {
+    let mut config = config()?;
+    config.rce_mut().set_base_url(spawn_rce_stub().await?);
+    config.execution_mut().set_poll_interval_ms(50);
+    let mut app = create_router(db_pool.clone(), config.clone());
+    let token = login_as(&mut app, "runner").await?;
+
+    // Act: Submit a run before any worker is available
+    let run_req = json!({
+        "language": "python",
+        "files": [{ "name": "main.py", "content": "hello " }],
+        "stdin": "world",
+    });
+    let (status, bytes) = send(&mut app, "POST", "/runs", Some(&token), Some(&run_req)).await?;
+
+    // Assert: The run is accepted and waits in the queue
+    assert_eq!(status, 202, "Submitting a run should return 202 Accepted");
+    let run: RunResDto = body(&bytes)?;
+    assert_eq!(run.status, ExecutionStatus::Queued);
+
+    // Act: Start the worker pool and wait for the run to finish
+    spawn_execution_workers(db_pool, config)?;
+    let uri = format!("/runs/{}", run.id);
+    let run = wait_for_run(&mut app, &token, run.id).await?;
+
+    // Assert: The worker stored the sandbox output
+    assert_eq!(run.status, ExecutionStatus::Completed);
+    assert_eq!(run.stdout.as_deref(), Some("hello world"));
+    assert!(
+        run.finished_at.is_some(),
+        "Finished runs should be timestamped"
+    );
+
+    // Assert: Other users cannot see the run
+    let other = login_as(&mut app, "stranger").await?;
+    let (status, _) = send::<()>(&mut app, "GET", &uri, Some(&other), None).await?;
+    assert_eq!(status, 404, "Runs should only be visible to their owner");
+
+    Ok(())
+}
+
+#[sqlx::test]
+async fn test_claim_prefers_users_without_running_jobs(db_pool: PgPool) -> CaraiResult<()> {
+    let busy = create_user(
+        &db_pool,
+        &User::new(None, "busy@example.com", "x", "busy", None),
+    )
+    .await?;
+    let idle = create_user(
+        &db_pool,
+        &User::new(None, "idle@example.com", "x", "idle", None),
+    )
+    .await?;
+    let files = vec![ExecutionFile {
+        name: "main.py".to_string(),
+        content: "print(1)".to_string(),
+    }];
+
+    // Arrange: The busy user floods the queue before the idle user submits once
+    for _ in 0..3 {
+        create_execution(
+            &db_pool,
+            &Execution::new(busy.id, "python", files.clone(), None),
+        )
+        .await?;
+    }
+    let lease = Utc::now() + chrono::Duration::minutes(1);
+    let first = claim_next_execution(&db_pool, lease)
+        .await?
+        .expect("queued job");
+    assert_eq!(
+        first.user_id, busy.id,
+        "The oldest job should be claimed first"
+    );
+    create_execution(&db_pool, &Execution::new(idle.id, "python", files, None)).await?;
+
+    // Act: Claim while the busy user already has a job running
+    let next = claim_next_execution(&db_pool, lease)
+        .await?
+        .expect("queued job");
+
+    // Assert: The idle user's newer job jumps ahead of the backlog
+    assert_eq!(next.user_id, idle.id, "Users without running jobs go first");
+    assert_eq!(next.status, ExecutionStatus::Running);
+    assert_eq!(next.attempts, 1);
+
+    Ok(())
+}
+
+#[sqlx::test]
+async fn test_lapsed_claims_cannot_overwrite_their_successor(db_pool: PgPool) -> CaraiResult<()> {
+    let user = create_user(
+        &db_pool,
+        &User::new(None, "slow@example.com", "x", "slow", None),
+    )
+    .await?;
+    let files = vec![ExecutionFile {
+        name: "main.py".to_string(),
+        content: "print(1)".to_string(),
+    }];
+    create_execution(&db_pool, &Execution::new(user.id, "python", files, None)).await?;
+
+    // Arrange: A worker's lease lapses and another worker takes the job over
+    let lapsed = Utc::now() - chrono::Duration::seconds(1);
+    let mut stale = claim_next_execution(&db_pool, lapsed)
+        .await?
+        .expect("queued job");
+    let lease = Utc::now() + chrono::Duration::minutes(1);
+    let current = claim_next_execution(&db_pool, lease)
+        .await?
+        .expect("lapsed job");
+    assert_eq!(current.id, stale.id);
+    assert_eq!(current.attempts, 2);
+
+    // Act: The first worker finishes late, or gives up and requeues
+    stale.status = ExecutionStatus::Completed;
+    stale.stdout = Some("stale".to_string());
+    let finished = finish_execution(&db_pool, &stale).await?;
+    let requeued = requeue_execution(&db_pool, &stale).await?;
+
+    // Assert: Only the current claim may settle the job
+    assert!(!finished && !requeued, "The lapsed claim should be ignored");
+    let execution = get_execution_by_id(&db_pool, stale.id)
+        .await?
+        .expect("execution");
+    assert_eq!(execution.status, ExecutionStatus::Running);
+    assert_eq!(execution.stdout, None);
+
+    Ok(())
+}
+
+#[sqlx::test]
+async fn test_run_quota_is_enforced(db_pool: PgPool) -> CaraiResult<()> {
+    let mut config = config()?;
+    config.quota_mut().user_mut().set_concurrent_runs(1);
+    let mut app = create_router(db_pool, config);
+    let token = login_as(&mut app, "quota").await?;
+    let run_req = json!({
+        "language": "python",
+        "files": [{ "name": "main.py", "content": "print(1)" }],
+    });
+
+    // Act: Submit two runs while no worker drains the queue
+    let (first, _) = send(&mut app, "POST", "/runs", Some(&token), Some(&run_req)).await?;
+    let (second, bytes) = send(&mut app, "POST", "/runs", Some(&token), Some(&run_req)).await?;
+
+    // Assert: The second run is rejected with the exhausted quota
+    assert_eq!(first, 202);
+    assert_eq!(second, 429, "Exceeding a quota should return 429");
+    let error: Value = serde_json::from_slice(&bytes)?;
+    assert_eq!(error["data"]["quota"], "concurrent_runs");
+    assert_eq!(error["data"]["used"], 1);
+    assert_eq!(error["data"]["limit"], 1);
+
+    // Assert: The usage endpoint reports the same numbers
+    let (status, bytes) =
+        send::<()>(&mut app, "GET", "/users/me/usage", Some(&token), None).await?;
+    assert_eq!(status, 200);
+    let usage: UsageResDto = body(&bytes)?;
+    let executions = usage
+        .quotas
+        .iter()
+        .find(|q| q.quota == QuotaKind::ExecutionsPerHour)
+        .expect("hourly quota");
+    assert_eq!(executions.used, 1);
+    assert!(executions.resets_at.is_some(), "Hourly quota should reset");
+
+    Ok(())
+}
+
+#[sqlx::test]
+async fn test_parallel_runs_cannot_exceed_concurrency_quota(db_pool: PgPool) -> CaraiResult<()> {
+    let mut config = config()?;
+    config.quota_mut().user_mut().set_concurrent_runs(2);
+    let mut app = create_router(db_pool.clone(), config);
+    let token = login_as(&mut app, "racer").await?;
+    let run_req = json!({
+        "language": "python",
+        "files": [{ "name": "main.py", "content": "print(1)" }],
+    });
+
+    // Act: Submit several runs at once, so they all pass the quota check together
+    let requests = (0..6).map(|_| {
+        let mut app = app.clone();
+        let (token, run_req) = (token.clone(), run_req.clone());
+        async move { send(&mut app, "POST", "/runs", Some(&token), Some(&run_req)).await }
+    });
+    let statuses: Vec<u16> = futures_util::future::try_join_all(requests)
+        .await?
+        .into_iter()
+        .map(|(status, _)| status)
+        .collect();
+
+    // Assert: Only as many runs as the quota allows were queued
+    assert_eq!(statuses.iter().filter(|&&status| status == 202).count(), 2);
+    assert_eq!(statuses.iter().filter(|&&status| status == 429).count(), 4);
+    let queued: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM executions")
+        .fetch_one(&db_pool)
+        .await?;
+    assert_eq!(queued, 2, "Refused runs should not be stored");
+
+    Ok(())
+}
+
+#[sqlx::test]
+async fn test_failed_runs_are_charged(db_pool: PgPool) -> CaraiResult<()> {
+    let app = Router::new().route(
+        "/run",
+        post(|| async { (StatusCode::INTERNAL_SERVER_ERROR, "sandbox crashed") }),
+    );
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let address = listener.local_addr()?;
+    tokio::spawn(async move { axum::serve(listener, app).await });
+
+    let mut config = config()?;
+    config
+        .rce_mut()
+        .set_base_url(format!("http://{}/run", address));
+    config.execution_mut().set_poll_interval_ms(50);
+    config.execution_mut().set_max_attempts(2);
+    let mut app = create_router(db_pool.clone(), config.clone());
+    spawn_execution_workers(db_pool.clone(), config)?;
+    let token = login_as(&mut app, "unlucky").await?;
+
+    // Act: Submit a run that fails on every attempt
+    let run_req = json!({
+        "language": "python",
+        "files": [{ "name": "main.py", "content": "print(1)" }],
+    });
+    let (_, bytes) = send(&mut app, "POST", "/runs", Some(&token), Some(&run_req)).await?;
+    let run: RunResDto = body(&bytes)?;
+    let uri = format!("/runs/{}", run.id);
+    let mut status = run.status;
+    for _ in 0..50 {
+        let (_, bytes) = send::<()>(&mut app, "GET", &uri, Some(&token), None).await?;
+        status = body::<RunResDto>(&bytes)?.status;
+        if status == ExecutionStatus::Failed {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    assert_eq!(status, ExecutionStatus::Failed);
+
+    // Assert: Both attempts were charged to a single usage record
+    let records: i64 =
+        sqlx::query_scalar("SELECT COUNT(*) FROM execution_usage WHERE execution_id = $1")
+            .bind(run.id)
+            .fetch_one(&db_pool)
+            .await?;
+    assert_eq!(records, 1, "Failed runs should be charged once");
+
+    Ok(())
+}
+
+#[sqlx::test]
+async fn test_run_history_and_rerun(db_pool: PgPool) -> CaraiResult<()> {
+    let mut config = config()?;
+    config.rce_mut().set_base_url(spawn_rce_stub().await?);
+    config.execution_mut().set_poll_interval_ms(50);
+    config.execution_mut().set_cache_ttl_secs(0);
+    let mut app = create_router(db_pool.clone(), config.clone());
+    spawn_execution_workers(db_pool.clone(), config)?;
+    let token = login_as(&mut app, "historian").await?;
+
+    // Arrange: Finish one run
+    let run_req = json!({
+        "language": "ruby",
+        "files": [{ "name": "main.rb", "content": "puts 1" }],
+        "stdin": "in",
+    });
+    let (_, bytes) = send(&mut app, "POST", "/runs", Some(&token), Some(&run_req)).await?;
+    let run: RunResDto = body(&bytes)?;
+    let run = wait_for_run(&mut app, &token, run.id).await?;
+    assert_eq!(run.exit_code, Some(0), "Clean runs should exit with 0");
+    assert!(
+        run.duration_ms.is_some(),
+        "Finished runs should record a duration"
+    );
+
+    // Act: List and fetch the history
+    let (_, bytes) = send::<()>(
+        &mut app,
+        "GET",
+        "/users/me/runs?language=ruby",
+        Some(&token),
+        None,
+    )
+    .await?;
+    let history: RunsResDto = body(&bytes)?;
+    let (_, bytes) = send::<()>(
+        &mut app,
+        "GET",
+        "/users/me/runs?language=python",
+        Some(&token),
+        None,
+    )
+    .await?;
+    let filtered: RunsResDto = body(&bytes)?;
+    let uri = format!("/users/me/runs/{}", run.id);
+    let (_, bytes) = send::<()>(&mut app, "GET", &uri, Some(&token), None).await?;
+    let detail: RunDetailResDto = body(&bytes)?;
+
+    // Assert: The run and its inputs are recorded
+    assert_eq!(history.runs.len(), 1);
+    assert!(filtered.runs.is_empty(), "Language filter should apply");
+    assert_eq!(detail.files[0].content, "puts 1");
+    assert_eq!(detail.stdin.as_deref(), Some("in"));
+
+    // Act: Re-run the same inputs
+    let uri = format!("/runs/{}/rerun", run.id);
+    let (status, bytes) = send::<()>(&mut app, "POST", &uri, Some(&token), None).await?;
+    let replay: RunResDto = body(&bytes)?;
+    let replay = wait_for_run(&mut app, &token, replay.id).await?;
+
+    // Assert: The replay is a new run over identical files
+    assert_eq!(status, 202);
+    assert_ne!(replay.id, run.id);
+    assert_eq!(replay.files_hash, run.files_hash);
+    assert_eq!(replay.stdout, run.stdout);
+
+    // Act: Enforce retention as the cleanup job would
+    let deleted = delete_executions_finished_before(&db_pool, Utc::now()).await?;
+
+    // Assert: Finished runs past retention are gone
+    assert_eq!(deleted, 2);
+    let (_, bytes) = send::<()>(&mut app, "GET", "/users/me/runs", Some(&token), None).await?;
+    let history: RunsResDto = body(&bytes)?;
+    assert!(history.runs.is_empty(), "Expired runs should be deleted");
+
+    Ok(())
+}
+
+#[sqlx::test]
+async fn test_identical_runs_are_served_from_cache(db_pool: PgPool) -> CaraiResult<()> {
+    let mut config = config()?;
+    config.rce_mut().set_base_url(spawn_rce_stub().await?);
+    config.execution_mut().set_poll_interval_ms(50);
+    config
+        .execution_mut()
+        .set_uncached_languages("ruby".to_string());
+    let mut app = create_router(db_pool.clone(), config.clone());
+    spawn_execution_workers(db_pool, config)?;
+    let token = login_as(&mut app, "student").await?;
+
+    for (language, cacheable) in [("python", true), ("ruby", false)] {
+        let run_req = json!({
+            "language": language,
+            "files": [{ "name": "main", "content": "hello" }],
+        });
+
+        // Arrange: Run the program once so its result can be cached
+        let (_, bytes) = send(&mut app, "POST", "/runs", Some(&token), Some(&run_req)).await?;
+        let first: RunResDto = body(&bytes)?;
+        let first = wait_for_run(&mut app, &token, first.id).await?;
+        assert!(!first.cached, "The first run should execute");
+
+        // Act: Submit the exact same program again
+        let (status, bytes) = send(&mut app, "POST", "/runs", Some(&token), Some(&run_req)).await?;
+        let second: RunResDto = body(&bytes)?;
+
+        // Assert: Only cacheable languages skip the queue
+        if cacheable {
+            assert_eq!(status, 200, "Cache hits should complete immediately");
+            assert!(second.cached, "Cache hits should be flagged");
+            assert_eq!(second.status, ExecutionStatus::Completed);
+            assert_eq!(second.stdout, first.stdout);
+        } else {
+            assert_eq!(status, 202, "Opted-out languages should always be queued");
+            assert!(!second.cached);
+        }
+    }
+
+    Ok(())
+}
+
+#[sqlx::test]
+async fn test_timed_out_runs_are_not_cached(db_pool: PgPool) -> CaraiResult<()> {
+    let mut config = config()?;
+    config.rce_mut().set_base_url(spawn_rce_stub().await?);
+    config.rce_mut().set_timeout_in_secs(1);
+    config.execution_mut().set_poll_interval_ms(50);
+    let mut app = create_router(db_pool.clone(), config.clone());
+    spawn_execution_workers(db_pool, config)?;
+    let token = login_as(&mut app, "sleeper").await?;
+    let run_req = json!({
+        "language": "python",
+        "files": [{ "name": "main.py", "content": "#loop" }],
+    });
+
+    // Arrange: Run a program that outlives the sandbox timeout
+    let (_, bytes) = send(&mut app, "POST", "/runs", Some(&token), Some(&run_req)).await?;
+    let first: RunResDto = body(&bytes)?;
+    // Polling only once the timeout has passed keeps clear of the request rate limit
+    tokio::time::sleep(Duration::from_millis(1500)).await;
+    let first = wait_for_run(&mut app, &token, first.id).await?;
+    assert_eq!(first.exit_code, None, "Timed-out runs have no exit code");
+
+    // Act: Submit the same program again
+    let (status, bytes) = send(&mut app, "POST", "/runs", Some(&token), Some(&run_req)).await?;
+    let second: RunResDto = body(&bytes)?;
+
+    // Assert: The timeout is not replayed from the cache
+    assert_eq!(status, 202, "Timed-out runs should be executed again");
+    assert!(!second.cached);
+
+    Ok(())
+}
+
+#[sqlx::test]
+async fn test_judged_run_reports_verdict_per_case(db_pool: PgPool) -> CaraiResult<()> {
+    let mut config = config()?;
+    config.rce_mut().set_base_url(spawn_rce_stub().await?);
+    config.execution_mut().set_poll_interval_ms(50);
+    spawn_execution_workers(db_pool.clone(), config.clone())?;
+    let mut app = create_router(db_pool, config);
+    let token = login_as(&mut app, "judged").await?;
+
+    // Act: Judge a program (the stub echoes its source followed by stdin) against three cases
+    let run_req = json!({
+        "language": "python",
+        "files": [{ "name": "main.py", "content": "" }],
+        "comparison": "float",
+        "tolerance": 0.01,
+        "tests": [
+            { "stdin": "1.000 2", "expected_stdout": "1 2\n" },
+            { "stdin": "3.14159", "expected_stdout": "3.14" },
+            { "stdin": "41", "expected_stdout": "42" },
+        ],
+    });
+    let (status, bytes) = send(&mut app, "POST", "/runs", Some(&token), Some(&run_req)).await?;
+    assert_eq!(status, 202, "Judged runs should be queued");
+    let run: RunResDto = body(&bytes)?;
+    let run = wait_for_run(&mut app, &token, run.id).await?;
+
+    // Assert: Each case is graded and the first failure decides the overall verdict
+    let cases = run.cases.expect("Judged runs should report case results");
+    let verdicts: Vec<Verdict> = cases.iter().map(|case| case.verdict).collect();
+    assert_eq!(
+        verdicts,
+        vec![Verdict::Accepted, Verdict::Accepted, Verdict::WrongAnswer]
+    );
+    assert_eq!(run.verdict, Some(Verdict::WrongAnswer));
+    let diff = cases[2].diff.as_deref().unwrap_or_default();
+    assert!(
+        diff.contains("-42") && diff.contains("+41"),
+        "Wrong answers should include a diff, got: {}",
+        diff
+    );
+
+    // Act: Submit a test case with an empty list
+    let invalid_req = json!({
+        "language": "python",
+        "files": [{ "name": "main.py", "content": "" }],
+        "tests": [],
+    });
+    let (status, _) = send(&mut app, "POST", "/runs", Some(&token), Some(&invalid_req)).await?;
+    assert_eq!(status, 422, "An empty test list should be rejected");
+
+    Ok(())
+}
+
+#[sqlx::test]
+async fn test_runaway_cases_are_stopped_at_their_time_limit(db_pool: PgPool) -> CaraiResult<()> {
+    let mut config = config()?;
+    config.rce_mut().set_base_url(spawn_rce_stub().await?);
+    config.execution_mut().set_poll_interval_ms(50);
+    spawn_execution_workers(db_pool.clone(), config.clone())?;
+    let mut app = create_router(db_pool, config);
+    let token = login_as(&mut app, "looper").await?;
+
+    // Act: Judge a case that never finishes (the stub hangs) under a short time limit
+    let run_req = json!({
+        "language": "python",
+        "files": [{ "name": "main.py", "content": "" }],
+        "tests": [{ "stdin": "#loop", "expected_stdout": "", "time_limit_ms": 200 }],
+    });
+    let (_, bytes) = send(&mut app, "POST", "/runs", Some(&token), Some(&run_req)).await?;
+    let run: RunResDto = body(&bytes)?;
+    tokio::time::sleep(Duration::from_secs(1)).await;
+    let run = wait_for_run(&mut app, &token, run.id).await?;
+
+    // Assert: The case is cut off soon after its limit and judged as too slow
+    let cases = run.cases.expect("Judged runs should report case results");
+    assert_eq!(cases[0].verdict, Verdict::TimeLimitExceeded);
+    assert!(
+        cases[0].duration_ms < 2000,
+        "The case should not wait for the sandbox, took {}ms",
+        cases[0].duration_ms
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_entrypoints_are_quoted_in_commands() {
+    let build = |name: &str| find_language(name).and_then(|language| language.build.as_ref());
+    let rust = build("rust").expect("Rust has a build");
+    let java = build("java").expect("Java has a build");
+
+    // Act & Assert: Plain paths stay as they are
+    assert!(rust
+        .compile_command("src/main.rs")
+        .starts_with("{ rustc -O -o a.out src/main.rs; }"));
+    assert!(java
+        .run_command("app/Main.java")
+        .ends_with("java -cp out app.Main"));
+
+    // Act & Assert: Anything else reaches the shell as a single word
+    assert!(rust
+        .compile_command("a b.rs; touch pwned")
+        .starts_with("{ rustc -O -o a.out 'a b.rs; touch pwned'; }"));
+    assert!(java
+        .run_command("it's $(id).java")
+        .ends_with(r"java -cp out 'it'\''s $(id)'"));
+}
+
+#[test]
+fn test_sandbox_overhead_does_not_exceed_time_limits() {
+    let case = TestCase {
+        stdin: String::new(),
+        expected_stdout: "ok\n".to_string(),
+        time_limit_ms: Some(100),
+    };
+    let spec = JudgeSpec {
+        comparison: ComparisonMode::Exact,
+        tolerance: 0.0,
+        cases: vec![case.clone()],
+        hidden: false,
+    };
+    let output = ExecutionOutput {
+        stdout: "ok\n".to_string(),
+        ..Default::default()
+    };
+
+    // Act & Assert: Measured time within the cut-off margin is overhead, not the program
+    let within = grade_case(&spec, &case, output.clone(), 100 + CASE_TIMEOUT_MARGIN_MS);
+    assert_eq!(within.verdict, Verdict::Accepted);
+    let beyond = grade_case(&spec, &case, output, 101 + CASE_TIMEOUT_MARGIN_MS);
+    assert_eq!(beyond.verdict, Verdict::TimeLimitExceeded);
+}
+
+#[sqlx::test]
+async fn test_multi_file_project_is_built_before_running(db_pool: PgPool) -> CaraiResult<()> {
+    let mut config = config()?;
+    let rce_url = spawn_rce_stub().await?;
+    let compiles_url = rce_url.replace("/run", "/compiles");
+    config.rce_mut().set_base_url(rce_url);
+    config.execution_mut().set_poll_interval_ms(50);
+    spawn_execution_workers(db_pool.clone(), config.clone())?;
+    let mut app = create_router(db_pool, config);
+    let token = login_as(&mut app, "builder").await?;
+
+    // Act: Submit a C project whose entrypoint is not the first file
+    let run_req = json!({
+        "language": "c",
+        "files": [
+            { "name": "lib/util.c", "content": "int util(void);" },
+            { "name": "src/main.c", "content": "main " },
+        ],
+        "entrypoint": "src/main.c",
+        "stdin": "ok",
+    });
+    let (status, bytes) = send(&mut app, "POST", "/runs", Some(&token), Some(&run_req)).await?;
+    assert_eq!(status, 202, "Multi-file runs should be queued");
+    let run: RunResDto = body(&bytes)?;
+    let run = wait_for_run(&mut app, &token, run.id).await?;
+
+    // Assert: The project compiled and the entrypoint ran
+    assert_eq!(run.entrypoint, "src/main.c");
+    let compile = run
+        .compile
+        .expect("Compiled languages should report the build");
+    assert!(compile.succeeded(), "The build should succeed");
+    assert_eq!(run.stdout.as_deref(), Some("main ok"));
+
+    // Act: Judge a compiled project against several cases
+    let judged_req = json!({
+        "language": "c",
+        "files": [{ "name": "main.c", "content": "" }],
+        "tests": [
+            { "stdin": "1", "expected_stdout": "1" },
+            { "stdin": "2", "expected_stdout": "2" },
+            { "stdin": "3", "expected_stdout": "3" },
+        ],
+    });
+    let (_, bytes) = send(&mut app, "POST", "/runs", Some(&token), Some(&judged_req)).await?;
+    let run: RunResDto = body(&bytes)?;
+    let run = wait_for_run(&mut app, &token, run.id).await?;
+
+    // Assert: Every case ran the one build
+    assert_eq!(run.verdict, Some(Verdict::Accepted));
+    let compiles = reqwest::get(&compiles_url).await?.text().await?;
+    assert_eq!(compiles, "2", "Each project should be compiled once");
+
+    // Act: Submit a project that fails to build
+    let broken_req = json!({
+        "language": "c",
+        "files": [{ "name": "main.c", "content": "#error broken" }],
+    });
+    let (_, bytes) = send(&mut app, "POST", "/runs", Some(&token), Some(&broken_req)).await?;
+    let run: RunResDto = body(&bytes)?;
+    let run = wait_for_run(&mut app, &token, run.id).await?;
+
+    // Assert: Compile errors are reported separately and the program never runs
+    let compile = run.compile.expect("Failed builds should be reported");
+    assert_eq!(compile.error.as_deref(), Some("exit status 1"));
+    assert_eq!(compile.exit_code, Some(1));
+    assert!(run.stdout.is_none(), "A failed build should not run");
+    assert!(run.error.is_none(), "Compile errors are not runtime errors");
+
+    // Assert: Judging a broken build reports a compile error instead of failed cases
+    let mut broken_req = broken_req;
+    broken_req["tests"] = judged_req["tests"].clone();
+    let (_, bytes) = send(&mut app, "POST", "/runs", Some(&token), Some(&broken_req)).await?;
+    let run: RunResDto = body(&bytes)?;
+    let run = wait_for_run(&mut app, &token, run.id).await?;
+    assert_eq!(run.verdict, Some(Verdict::CompileError));
+    assert!(
+        run.cases.is_none(),
+        "A failed build should not run any case"
+    );
+
+    // Assert: Paths escaping the project and unknown entrypoints are rejected
+    for invalid_req in [
+        json!({ "language": "c", "files": [{ "name": "../main.c", "content": "" }] }),
+        json!({ "language": "c", "files": [{ "name": "main.c", "content": "" }], "entrypoint": "b.c" }),
+        json!({ "language": "c", "files": [
+            { "name": "main.c", "content": "" }, { "name": "main.c", "content": "" },
+        ] }),
+    ] {
+        let (status, _) = send(&mut app, "POST", "/runs", Some(&token), Some(&invalid_req)).await?;
+        assert_eq!(
+            status, 422,
+            "Invalid projects should be rejected: {}",
+            invalid_req
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,140 @@
+use axum::Router;
+use carai::{
+    dto::{SearchResDto, WorkspaceResDto},
+    utils::CaraiResult,
+};
+use common::{body, ctx, login_as, send};
+use serde_json::json;
+use sqlx::PgPool;
+
+mod common;
+
+const SORT: &str = "import random\n\ndef quick_sort(items):\n    return sorted(items)\n";
+
+async fn create_workspace(
+    app: &mut Router,
+    token: &str,
+    name: &str,
+    files: &[(&str, &str)],
+) -> CaraiResult<WorkspaceResDto> {
+    let (_, bytes) = send(
+        app,
+        "POST",
+        "/workspaces",
+        Some(token),
+        Some(&json!({ "name": name, "language": "python" })),
+    )
+    .await?;
+    let workspace: WorkspaceResDto = body(&bytes)?;
+    let files_uri = format!("/workspaces/{}/files", workspace.id);
+    for (path, content) in files {
+        let file_req = json!({ "path": path, "content": content });
+        send(app, "POST", &files_uri, Some(token), Some(&file_req)).await?;
+    }
+    Ok(workspace)
+}
+
+async fn search(app: &mut Router, token: Option<&str>, query: &str) -> CaraiResult<SearchResDto> {
+    let (status, bytes) =
+        send::<()>(app, "GET", &format!("/search?{}", query), token, None).await?;
+    assert_eq!(status, 200, "Searching for {} should succeed", query);
+    body(&bytes)
+}
+
+#[sqlx::test]
+async fn test_code_search(db_pool: PgPool) -> CaraiResult<()> {
+    let mut app = ctx(db_pool)?;
+    let alice = login_as(&mut app, "alice").await?;
+    let bob = login_as(&mut app, "bob").await?;
+
+    // Arrange: Both users keep a sort in a workspace, and each has a snippet using it
+    let workspace = create_workspace(
+        &mut app,
+        &alice,
+        "algos",
+        &[("sort.py", SORT), ("README.md", "Sorting algorithms")],
+    )
+    .await?;
+    create_workspace(&mut app, &bob, "secret", &[("main.py", SORT)]).await?;
+    for (token, visibility) in [(&alice, "public"), (&bob, "private")] {
+        let snippet_req = json!({
+            "title": format!("{} sort", visibility),
+            "language": "python",
+            "files": [{ "name": "snippet.py", "content": "print(quick_sort([2, 1]))" }],
+            "visibility": visibility,
+        });
+        send(
+            &mut app,
+            "POST",
+            "/snippets",
+            Some(token),
+            Some(&snippet_req),
+        )
+        .await?;
+    }
+
+    // Act: Alice searches for the function
+    let results = search(&mut app, Some(&alice), "q=quick_sort").await?;
+
+    // Assert: She finds her workspace file and the public snippet, but nothing of Bob's
+    assert_eq!(results.hits.len(), 2, "Only accessible files should match");
+    let file_hit = results
+        .hits
+        .iter()
+        .find(|hit| hit.workspace_id == Some(workspace.id))
+        .expect("Alice's workspace file should match");
+    assert_eq!(file_hit.path, "sort.py");
+    assert_eq!(file_hit.owner_username, "alice");
+    assert_eq!(file_hit.lines.len(), 1);
+    assert_eq!(file_hit.lines[0].number, 3);
+    assert_eq!(file_hit.lines[0].text, "def quick_sort(items):");
+    assert_eq!(file_hit.lines[0].highlights, vec![(4, 14)]);
+    assert!(results
+        .hits
+        .iter()
+        .any(|hit| hit.snippet_slug.is_some() && hit.title == "public sort"));
+
+    // Act & Assert: Anonymous callers only search public snippets
+    let results = search(&mut app, None, "q=quick_sort").await?;
+    assert_eq!(results.hits.len(), 1);
+    assert_eq!(results.hits[0].title, "public sort");
+
+    // Act & Assert: Filters narrow the search down
+    let results = search(
+        &mut app,
+        Some(&alice),
+        &format!("q=quick_sort&workspace={}", workspace.id),
+    )
+    .await?;
+    assert_eq!(
+        results.hits.len(),
+        1,
+        "A workspace filter leaves snippets out"
+    );
+    let results = search(&mut app, Some(&alice), "q=quick_sort&owner=bob").await?;
+    assert!(
+        results.hits.is_empty(),
+        "Bob's files stay hidden from Alice"
+    );
+    let results = search(&mut app, Some(&bob), "q=quick_sort&owner=bob").await?;
+    assert_eq!(
+        results.hits.len(),
+        2,
+        "Bob finds his own workspace and snippet"
+    );
+    let results = search(&mut app, Some(&alice), "q=quick_sort&language=go").await?;
+    assert!(results.hits.is_empty(), "Nothing is written in Go");
+
+    // Act & Assert: Words match anywhere in a file, and paths match too
+    let results = search(&mut app, Some(&alice), "q=algorithms").await?;
+    assert_eq!(results.hits.len(), 1);
+    assert_eq!(results.hits[0].path, "README.md");
+    let results = search(&mut app, Some(&alice), "q=sort.py").await?;
+    assert_eq!(results.hits[0].path, "sort.py");
+
+    // Act & Assert: Searches need at least two characters
+    let (status, _) = send::<()>(&mut app, "GET", "/search?q=q", Some(&alice), None).await?;
+    assert_eq!(status, 422, "One character is too short to search for");
+
+    Ok(())
+}
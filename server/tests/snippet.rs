@@ -0,0 +1,127 @@
+use axum::{body::Body, http::Request};
+use carai::{
+    dto::{SnippetResDto, SnippetsResDto},
+    models::SnippetVisibility,
+    utils::CaraiResult,
+};
+use common::{body, ctx, login_as, send};
+use serde_json::json;
+use sqlx::PgPool;
+use tower::ServiceExt;
+
+mod common;
+
+#[sqlx::test]
+async fn test_snippet_visibility_and_password(db_pool: PgPool) -> CaraiResult<()> {
+    let mut app = ctx(db_pool)?;
+    let token = login_as(&mut app, "sharer").await?;
+
+    // Act: Share a private snippet
+    let create_req = json!({
+        "title": "fizzbuzz",
+        "language": "python",
+        "files": [{ "name": "main.py", "content": "print('fizz')" }],
+        "visibility": "private",
+    });
+    let (status, bytes) = send(
+        &mut app,
+        "POST",
+        "/snippets",
+        Some(&token),
+        Some(&create_req),
+    )
+    .await?;
+
+    // Assert: The snippet gets a slug and is hidden from anonymous users
+    assert_eq!(status, 201, "Creating a snippet should return 201 Created");
+    let snippet: SnippetResDto = body(&bytes)?;
+    assert_eq!(snippet.slug.len(), 12);
+    let uri = format!("/snippets/{}", snippet.slug);
+    let (status, _) = send::<()>(&mut app, "GET", &uri, None, None).await?;
+    assert_eq!(status, 404, "Private snippets should be hidden");
+    let (status, _) = send::<()>(&mut app, "GET", &uri, Some(&token), None).await?;
+    assert_eq!(status, 200, "Owners should see their private snippets");
+
+    // Act: Make it public behind a password
+    let patch_req = json!({ "visibility": "public", "password": "hunter22" });
+    let (status, _) = send(&mut app, "PATCH", &uri, Some(&token), Some(&patch_req)).await?;
+    assert_eq!(status, 200, "Owners should be able to edit snippets");
+
+    // Assert: Anonymous users need the password, and protected snippets are not listed
+    let (status, _) = send::<()>(&mut app, "GET", &uri, None, None).await?;
+    assert_eq!(status, 401, "A password should be required");
+    let request = Request::builder()
+        .uri(&uri)
+        .header("X-Snippet-Password", "hunter22")
+        .body(Body::empty())?;
+    let response = app.clone().oneshot(request).await?;
+    assert_eq!(response.status(), 200, "The right password should unlock");
+    let (_, bytes) = send::<()>(&mut app, "GET", "/snippets", None, None).await?;
+    let listed: SnippetsResDto = body(&bytes)?;
+    assert!(listed.snippets.is_empty());
+
+    // Act: Remove the password
+    let patch_req = json!({ "password": null });
+    let (_, bytes) = send(&mut app, "PATCH", &uri, Some(&token), Some(&patch_req)).await?;
+    let snippet: SnippetResDto = body(&bytes)?;
+
+    // Assert: The snippet is now open and listed publicly
+    assert!(!snippet.has_password);
+    assert_eq!(snippet.visibility, SnippetVisibility::Public);
+    let (_, bytes) = send::<()>(&mut app, "GET", "/snippets", None, None).await?;
+    let listed: SnippetsResDto = body(&bytes)?;
+    assert_eq!(listed.snippets, vec![snippet]);
+
+    // Assert: Others cannot edit or delete it
+    let other = login_as(&mut app, "vandal").await?;
+    let (status, _) = send::<()>(&mut app, "DELETE", &uri, Some(&other), None).await?;
+    assert_eq!(status, 404, "Only owners should delete snippets");
+
+    // Act: The owner lists and deletes it
+    let (_, bytes) = send::<()>(&mut app, "GET", "/snippets/mine", Some(&token), None).await?;
+    let mine: SnippetsResDto = body(&bytes)?;
+    assert_eq!(mine.snippets.len(), 1);
+    let (status, _) = send::<()>(&mut app, "DELETE", &uri, Some(&token), None).await?;
+    assert_eq!(
+        status, 204,
+        "Deleting a snippet should return 204 No Content"
+    );
+
+    Ok(())
+}
+
+#[sqlx::test]
+async fn test_expired_snippets_are_hidden(db_pool: PgPool) -> CaraiResult<()> {
+    let mut app = ctx(db_pool.clone())?;
+    let token = login_as(&mut app, "ephemeral").await?;
+    let create_req = json!({
+        "title": "temp",
+        "files": [{ "name": "notes.txt", "content": "soon gone" }],
+        "expires_in_secs": 60,
+    });
+    let (_, bytes) = send(
+        &mut app,
+        "POST",
+        "/snippets",
+        Some(&token),
+        Some(&create_req),
+    )
+    .await?;
+    let snippet: SnippetResDto = body(&bytes)?;
+    let uri = format!("/snippets/{}", snippet.slug);
+    let (status, _) = send::<()>(&mut app, "GET", &uri, None, None).await?;
+    assert_eq!(status, 200, "Unlisted snippets should open by slug");
+
+    // Arrange: Let the snippet expire
+    sqlx::query("UPDATE snippets SET expires_at = NOW() - INTERVAL '1 second'")
+        .execute(&db_pool)
+        .await?;
+
+    // Assert: Expired snippets are gone for everyone but the owner
+    let (status, _) = send::<()>(&mut app, "GET", &uri, None, None).await?;
+    assert_eq!(status, 404, "Expired snippets should be hidden");
+    let (status, _) = send::<()>(&mut app, "GET", &uri, Some(&token), None).await?;
+    assert_eq!(status, 200, "Owners should still see expired snippets");
+
+    Ok(())
+}
@@ -0,0 +1,207 @@
+use carai::{
+    dto::{TemplateResDto, TemplatesResDto, WorkspaceFilesResDto, WorkspaceResDto},
+    utils::CaraiResult,
+};
+use common::{body, ctx, login_as, send};
+use serde_json::json;
+use sqlx::PgPool;
+
+mod common;
+
+#[sqlx::test]
+async fn test_template_catalogue(db_pool: PgPool) -> CaraiResult<()> {
+    let mut app = ctx(db_pool.clone())?;
+    login_as(&mut app, "curator").await?;
+    sqlx::query("UPDATE users SET is_admin = TRUE WHERE username = 'curator'")
+        .execute(&db_pool)
+        .await?;
+    let admin = login_as(&mut app, "curator").await?;
+    let lead = login_as(&mut app, "lead").await?;
+    let outsider = login_as(&mut app, "outsider").await?;
+
+    // Assert: The catalogue ships with starter templates
+    let (_, bytes) = send::<()>(&mut app, "GET", "/templates?language=Rust", None, None).await?;
+    let templates: TemplatesResDto = body(&bytes)?;
+    assert!(templates
+        .templates
+        .iter()
+        .any(|template| template.slug == "rust-cargo"));
+
+    // Act: Only admins may add to the public catalogue
+    let template_req = json!({
+        "slug": "kotlin-gradle",
+        "name": "Kotlin with Gradle",
+        "language": "kotlin",
+        "files": [{ "path": "Main.kt", "content": "fun main() {}" }],
+        "tags": ["jvm"],
+    });
+    let (status, _) = send(
+        &mut app,
+        "POST",
+        "/templates",
+        Some(&lead),
+        Some(&template_req),
+    )
+    .await?;
+    assert_eq!(status, 403, "Public templates are managed by admins");
+    let (status, _) = send(
+        &mut app,
+        "POST",
+        "/templates",
+        Some(&admin),
+        Some(&template_req),
+    )
+    .await?;
+    assert_eq!(status, 201, "Creating a template should return 201 Created");
+    let (status, _) = send(
+        &mut app,
+        "POST",
+        "/templates",
+        Some(&admin),
+        Some(&template_req),
+    )
+    .await?;
+    assert_eq!(status, 409, "Slugs must be unique");
+
+    // Assert: Templates can be filtered by tag
+    let (_, bytes) = send::<()>(&mut app, "GET", "/templates?tag=jvm", None, None).await?;
+    let templates: TemplatesResDto = body(&bytes)?;
+    assert_eq!(templates.templates.len(), 1);
+    assert_eq!(templates.templates[0].slug, "kotlin-gradle");
+
+    // Act: A team publishes a private template to its workspace
+    let (_, bytes) = send(
+        &mut app,
+        "POST",
+        "/workspaces",
+        Some(&lead),
+        Some(&json!({ "name": "team" })),
+    )
+    .await?;
+    let team: WorkspaceResDto = body(&bytes)?;
+    let private_req = json!({
+        "slug": "team-service",
+        "name": "Team service",
+        "language": "python",
+        "files": [{ "path": "app.py", "content": "app = None" }],
+        "workspace_id": team.id,
+    });
+    let (status, _) = send(
+        &mut app,
+        "POST",
+        "/templates",
+        Some(&outsider),
+        Some(&private_req),
+    )
+    .await?;
+    assert_eq!(
+        status, 404,
+        "Only editors of the workspace may publish to it"
+    );
+    let (status, bytes) = send(
+        &mut app,
+        "POST",
+        "/templates",
+        Some(&lead),
+        Some(&private_req),
+    )
+    .await?;
+    assert_eq!(status, 201);
+    let private: TemplateResDto = body(&bytes)?;
+    assert_eq!(private.workspace_id, Some(team.id));
+
+    // Assert: Private templates are only visible to the team
+    let (_, bytes) = send::<()>(&mut app, "GET", "/templates", Some(&lead), None).await?;
+    let templates: TemplatesResDto = body(&bytes)?;
+    assert!(templates
+        .templates
+        .iter()
+        .any(|template| template.slug == "team-service"));
+    let (_, bytes) = send::<()>(&mut app, "GET", "/templates", Some(&outsider), None).await?;
+    let templates: TemplatesResDto = body(&bytes)?;
+    assert!(!templates
+        .templates
+        .iter()
+        .any(|template| template.slug == "team-service"));
+    let (status, _) = send::<()>(
+        &mut app,
+        "GET",
+        "/templates/team-service",
+        Some(&outsider),
+        None,
+    )
+    .await?;
+    assert_eq!(status, 404);
+    let create_req = json!({ "name": "copy" });
+    let (status, _) = send(
+        &mut app,
+        "POST",
+        "/workspaces?template=team-service",
+        Some(&outsider),
+        Some(&create_req),
+    )
+    .await?;
+    assert_eq!(
+        status, 404,
+        "Private templates cannot be instantiated by outsiders"
+    );
+
+    // Act: Instantiate a template from the catalogue
+    let create_req = json!({ "name": "hello" });
+    let (status, bytes) = send(
+        &mut app,
+        "POST",
+        "/workspaces?template=rust-cargo",
+        Some(&outsider),
+        Some(&create_req),
+    )
+    .await?;
+
+    // Assert: The workspace starts out with the template's files and language
+    assert_eq!(
+        status, 201,
+        "Creating from a template should return 201 Created"
+    );
+    let workspace: WorkspaceResDto = body(&bytes)?;
+    assert_eq!(workspace.language.as_deref(), Some("rust"));
+    assert!(workspace.template_id.is_some());
+    let uri = format!("/workspaces/{}/files", workspace.id);
+    let (_, bytes) = send::<()>(&mut app, "GET", &uri, Some(&outsider), None).await?;
+    let files: WorkspaceFilesResDto = body(&bytes)?;
+    let mut paths: Vec<&str> = files.files.iter().map(|file| file.path.as_str()).collect();
+    paths.sort();
+    assert_eq!(paths, vec!["Cargo.toml", "src/main.rs"]);
+
+    // Act: Edit and remove templates
+    let patch = json!({ "tags": ["jvm", "gradle"] });
+    let (status, bytes) = send(
+        &mut app,
+        "PATCH",
+        "/templates/kotlin-gradle",
+        Some(&admin),
+        Some(&patch),
+    )
+    .await?;
+    assert_eq!(status, 200);
+    assert_eq!(body::<TemplateResDto>(&bytes)?.tags, vec!["jvm", "gradle"]);
+    let (status, _) = send::<()>(
+        &mut app,
+        "DELETE",
+        "/templates/kotlin-gradle",
+        Some(&lead),
+        None,
+    )
+    .await?;
+    assert_eq!(status, 403);
+    let (status, _) = send::<()>(
+        &mut app,
+        "DELETE",
+        "/templates/team-service",
+        Some(&lead),
+        None,
+    )
+    .await?;
+    assert_eq!(status, 204);
+
+    Ok(())
+}
@@ -24,7 +24,6 @@ async fn test_user(db_pool: PgPool) -> CaraiResult<()> {
         username: Some("Heregoom1940".to_string()),
         email: Some("BiTsou@dayrep.com".to_string()),
         password: "em9Nie4U".to_string(),
-        avatar_url: None,
         github_id: None,
     };
 
@@ -0,0 +1,384 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use axum::{
+    body::{Body, Bytes},
+    http::HeaderMap,
+    http::StatusCode,
+    routing::post,
+    Router,
+};
+use carai::{
+    bootstrap::create_router,
+    dto::{
+        WebhookDeliveriesResDto, WebhookDeliveryDetailResDto, WebhookDeliveryResDto, WebhookResDto,
+        WorkspaceResDto,
+    },
+    models::{WebhookDeliveryStatus, WebhookEvent},
+    utils::CaraiResult,
+    webhooks::{sign_payload, DELIVERY_HEADER, EVENT_HEADER, SIGNATURE_HEADER},
+    workers::spawn_webhook_workers,
+};
+use common::{body, config, ctx, login_as, send};
+use futures_util::{stream, StreamExt};
+use serde_json::{json, Value};
+use sqlx::PgPool;
+use tokio::net::TcpListener;
+
+mod common;
+
+type Received = Arc<Mutex<Vec<(HeaderMap, Bytes)>>>;
+
+/// Starts a stand-in receiver that records every request and fails the first one.
+async fn spawn_receiver() -> CaraiResult<(String, Received)> {
+    let received = Received::default();
+    let log = received.clone();
+    let app = Router::new().route(
+        "/hook",
+        post(move |headers: HeaderMap, bytes: Bytes| async move {
+            let mut log = log.lock().unwrap();
+            log.push((headers, bytes));
+            match log.len() {
+                1 => StatusCode::INTERNAL_SERVER_ERROR,
+                _ => StatusCode::OK,
+            }
+        }),
+    );
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let address = listener.local_addr()?;
+    tokio::spawn(async move { axum::serve(listener, app).await });
+
+    Ok((format!("http://{}/hook", address), received))
+}
+
+/// Waits until the receiver has recorded `count` requests.
+async fn wait_for_requests(received: &Received, count: usize) -> CaraiResult<()> {
+    for _ in 0..100 {
+        if received.lock().unwrap().len() >= count {
+            return Ok(());
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+    anyhow::bail!("The receiver did not get {} requests in time", count)
+}
+
+fn header<'a>(headers: &'a HeaderMap, name: &str) -> &'a str {
+    headers
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default()
+}
+
+#[sqlx::test]
+async fn test_webhook_deliveries_are_signed_retried_and_redelivered(
+    db_pool: PgPool,
+) -> CaraiResult<()> {
+    let mut config = config()?;
+    config.webhook_mut().set_poll_interval_ms(50);
+    config.webhook_mut().set_retry_base_delay_ms(50);
+    config.webhook_mut().set_allow_private_addresses(true);
+    spawn_webhook_workers(db_pool.clone(), config.clone())?;
+    let mut app = create_router(db_pool, config);
+    let token = login_as(&mut app, "builder").await?;
+    let (url, received) = spawn_receiver().await?;
+
+    // Arrange: A webhook subscribed to new workspaces only
+    let secret = "a-shared-secret-for-ci";
+    let (status, bytes) = send(
+        &mut app,
+        "POST",
+        "/webhooks",
+        Some(&token),
+        Some(&json!({ "url": url, "events": ["workspace.created"], "secret": secret })),
+    )
+    .await?;
+    assert_eq!(status, 201);
+    let webhook: WebhookResDto = body(&bytes)?;
+    assert_eq!(webhook.secret.as_deref(), Some(secret));
+
+    // Act: A workspace is created, then updated, which the webhook does not subscribe to
+    let (_, bytes) = send(
+        &mut app,
+        "POST",
+        "/workspaces",
+        Some(&token),
+        Some(&json!({ "name": "pipeline" })),
+    )
+    .await?;
+    let workspace: WorkspaceResDto = body(&bytes)?;
+    let (status, _) = send(
+        &mut app,
+        "PATCH",
+        &format!("/workspaces/{}", workspace.id),
+        Some(&token),
+        Some(&json!({ "name": "pipeline-2" })),
+    )
+    .await?;
+    assert_eq!(status, 200);
+
+    // Assert: The failed first attempt is retried with the same signed payload
+    wait_for_requests(&received, 2).await?;
+    let requests = received.lock().unwrap().clone();
+    for (headers, bytes) in &requests {
+        assert_eq!(header(headers, EVENT_HEADER), "workspace.created");
+        assert_eq!(
+            header(headers, SIGNATURE_HEADER),
+            sign_payload(secret, bytes)
+        );
+    }
+    assert_eq!(
+        header(&requests[0].0, DELIVERY_HEADER),
+        header(&requests[1].0, DELIVERY_HEADER),
+        "Retries should keep the delivery ID"
+    );
+    let payload: Value = serde_json::from_slice(&requests[1].1)?;
+    assert_eq!(payload["event"], "workspace.created");
+    assert_eq!(payload["data"]["workspace"]["name"], "pipeline");
+
+    // Assert: The delivery log records both attempts and only the subscribed event
+    let deliveries_uri = format!("/webhooks/{}/deliveries", webhook.id);
+    let mut deliveries = WebhookDeliveriesResDto { deliveries: vec![] };
+    for _ in 0..50 {
+        let (_, bytes) = send::<()>(&mut app, "GET", &deliveries_uri, Some(&token), None).await?;
+        deliveries = body(&bytes)?;
+        if deliveries.deliveries[0].status != WebhookDeliveryStatus::Pending {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+    assert_eq!(deliveries.deliveries.len(), 1);
+    let delivery = &deliveries.deliveries[0];
+    assert_eq!(delivery.event, WebhookEvent::WorkspaceCreated);
+    assert_eq!(delivery.status, WebhookDeliveryStatus::Delivered);
+    assert_eq!(delivery.attempts, 2);
+    assert_eq!(delivery.response_status, Some(200));
+
+    let (_, bytes) = send::<()>(
+        &mut app,
+        "GET",
+        &format!("{}/{}", deliveries_uri, delivery.id),
+        Some(&token),
+        None,
+    )
+    .await?;
+    let detail: WebhookDeliveryDetailResDto = body(&bytes)?;
+    assert_eq!(detail.payload, payload);
+
+    // Act: The delivery is sent again by hand
+    let (status, bytes) = send::<()>(
+        &mut app,
+        "POST",
+        &format!("{}/{}/redeliver", deliveries_uri, delivery.id),
+        Some(&token),
+        None,
+    )
+    .await?;
+    assert_eq!(status, 202);
+    let redelivery: WebhookDeliveryResDto = body(&bytes)?;
+
+    // Assert: The same payload arrives as a new delivery
+    wait_for_requests(&received, 3).await?;
+    let (headers, bytes) = received.lock().unwrap()[2].clone();
+    assert_eq!(header(&headers, DELIVERY_HEADER), redelivery.id.to_string());
+    assert_eq!(serde_json::from_slice::<Value>(&bytes)?, payload);
+    assert_eq!(redelivery.redelivery_of, Some(delivery.id));
+
+    Ok(())
+}
+
+#[sqlx::test]
+async fn test_webhooks_cannot_reach_private_addresses(db_pool: PgPool) -> CaraiResult<()> {
+    let mut config = config()?;
+    config.webhook_mut().set_poll_interval_ms(50);
+    config.webhook_mut().set_max_attempts(1);
+    spawn_webhook_workers(db_pool.clone(), config.clone())?;
+    let mut app = ctx(db_pool.clone())?;
+    let token = login_as(&mut app, "prober").await?;
+
+    // Act: Webhooks pointing to local and internal addresses are refused
+    for url in [
+        "http://127.0.0.1:8080/hook",
+        "http://localhost/hook",
+        "http://169.254.169.254/latest/meta-data",
+        "http://[::1]/hook",
+        "http://10.0.0.1/hook",
+    ] {
+        let req = json!({ "url": url, "events": ["workspace.created"] });
+        let (status, _) = send(&mut app, "POST", "/webhooks", Some(&token), Some(&req)).await?;
+        assert_eq!(status, 422, "{} should be refused", url);
+    }
+
+    // Arrange: A local webhook registered while the router allowed it
+    let mut permissive = config.clone();
+    permissive.webhook_mut().set_allow_private_addresses(true);
+    let mut app = create_router(db_pool, permissive);
+    let (url, received) = spawn_receiver().await?;
+    let (status, bytes) = send(
+        &mut app,
+        "POST",
+        "/webhooks",
+        Some(&token),
+        Some(&json!({ "url": url, "events": ["workspace.created"] })),
+    )
+    .await?;
+    assert_eq!(status, 201);
+    let webhook: WebhookResDto = body(&bytes)?;
+
+    // Act: An event is delivered by workers that do not allow it
+    send(
+        &mut app,
+        "POST",
+        "/workspaces",
+        Some(&token),
+        Some(&json!({ "name": "probe" })),
+    )
+    .await?;
+
+    // Assert: The delivery fails without reaching the receiver
+    let deliveries_uri = format!("/webhooks/{}/deliveries", webhook.id);
+    let mut deliveries = WebhookDeliveriesResDto { deliveries: vec![] };
+    for _ in 0..50 {
+        let (_, bytes) = send::<()>(&mut app, "GET", &deliveries_uri, Some(&token), None).await?;
+        deliveries = body(&bytes)?;
+        if deliveries.deliveries[0].status != WebhookDeliveryStatus::Pending {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+    let delivery = &deliveries.deliveries[0];
+    assert_eq!(delivery.status, WebhookDeliveryStatus::Failed);
+    assert!(delivery
+        .error
+        .as_deref()
+        .is_some_and(|error| error.contains("private")));
+    assert!(received.lock().unwrap().is_empty());
+
+    Ok(())
+}
+
+#[sqlx::test]
+async fn test_endless_responses_are_cut_short(db_pool: PgPool) -> CaraiResult<()> {
+    let mut config = config()?;
+    config.webhook_mut().set_poll_interval_ms(50);
+    config.webhook_mut().set_allow_private_addresses(true);
+    spawn_webhook_workers(db_pool.clone(), config.clone())?;
+    let mut app = create_router(db_pool, config);
+    let token = login_as(&mut app, "streamer").await?;
+
+    // Arrange: A receiver that accepts deliveries, then never stops answering
+    let endless = Router::new().route(
+        "/hook",
+        post(|| async {
+            let chunk = Bytes::from_static(&[b'x'; 8192]);
+            Body::from_stream(stream::repeat(chunk).map(Ok::<_, std::io::Error>))
+        }),
+    );
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let url = format!("http://{}/hook", listener.local_addr()?);
+    tokio::spawn(async move { axum::serve(listener, endless).await });
+    let (_, bytes) = send(
+        &mut app,
+        "POST",
+        "/webhooks",
+        Some(&token),
+        Some(&json!({ "url": url, "events": ["workspace.created"] })),
+    )
+    .await?;
+    let webhook: WebhookResDto = body(&bytes)?;
+
+    // Act: An event is delivered
+    send(
+        &mut app,
+        "POST",
+        "/workspaces",
+        Some(&token),
+        Some(&json!({ "name": "firehose" })),
+    )
+    .await?;
+
+    // Assert: The delivery succeeds soon, keeping only the start of the response
+    let deliveries_uri = format!("/webhooks/{}/deliveries", webhook.id);
+    let mut deliveries = WebhookDeliveriesResDto { deliveries: vec![] };
+    for _ in 0..40 {
+        let (_, bytes) = send::<()>(&mut app, "GET", &deliveries_uri, Some(&token), None).await?;
+        deliveries = body(&bytes)?;
+        if deliveries.deliveries[0].status != WebhookDeliveryStatus::Pending {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+    let delivery = &deliveries.deliveries[0];
+    assert_eq!(delivery.status, WebhookDeliveryStatus::Delivered);
+    let (_, bytes) = send::<()>(
+        &mut app,
+        "GET",
+        &format!("{}/{}", deliveries_uri, delivery.id),
+        Some(&token),
+        None,
+    )
+    .await?;
+    let detail: WebhookDeliveryDetailResDto = body(&bytes)?;
+    assert_eq!(detail.response_body, Some("x".repeat(256)));
+
+    Ok(())
+}
+
+#[sqlx::test]
+async fn test_webhooks_are_validated_and_private(db_pool: PgPool) -> CaraiResult<()> {
+    let mut app = ctx(db_pool)?;
+    let owner = login_as(&mut app, "owner").await?;
+    let stranger = login_as(&mut app, "stranger").await?;
+
+    // Act: Webhooks with a non-HTTP URL or without events are refused
+    for req in [
+        json!({ "url": "ftp://example.com/hook", "events": ["file.updated"] }),
+        json!({ "url": "https://example.com/hook", "events": [] }),
+    ] {
+        let (status, _) = send(&mut app, "POST", "/webhooks", Some(&owner), Some(&req)).await?;
+        assert_eq!(status, 422, "{} should be refused", req);
+    }
+
+    // Arrange: A valid webhook with a generated secret
+    let (status, bytes) = send(
+        &mut app,
+        "POST",
+        "/webhooks",
+        Some(&owner),
+        Some(&json!({ "url": "https://example.com/hook", "events": ["file.updated"] })),
+    )
+    .await?;
+    assert_eq!(status, 201);
+    let webhook: WebhookResDto = body(&bytes)?;
+    assert_eq!(webhook.secret.map(|secret| secret.len()), Some(32));
+    let uri = format!("/webhooks/{}", webhook.id);
+
+    // Assert: Other users cannot see it, and its secret is not shown again
+    let (status, _) = send::<()>(&mut app, "GET", &uri, Some(&stranger), None).await?;
+    assert_eq!(status, 404);
+    let (_, bytes) = send::<()>(&mut app, "GET", &uri, Some(&owner), None).await?;
+    let fetched: WebhookResDto = body(&bytes)?;
+    assert!(fetched.secret.is_none());
+
+    // Act: The owner pauses it
+    let (status, bytes) = send(
+        &mut app,
+        "PATCH",
+        &uri,
+        Some(&owner),
+        Some(&json!({ "active": false, "events": ["file.updated", "file.deleted"] })),
+    )
+    .await?;
+
+    // Assert: The changes are kept
+    assert_eq!(status, 200);
+    let updated: WebhookResDto = body(&bytes)?;
+    assert!(!updated.active);
+    assert_eq!(
+        updated.events,
+        vec![WebhookEvent::FileUpdated, WebhookEvent::FileDeleted]
+    );
+
+    Ok(())
+}
@@ -0,0 +1,348 @@
+use carai::{
+    dto::{
+        FileDiffResDto, FileRevisionContentResDto, FileRevisionsResDto, SnippetResDto,
+        WorkspaceFileResDto, WorkspaceFilesResDto, WorkspaceResDto, WorkspacesResDto,
+    },
+    utils::CaraiResult,
+};
+use common::{body, ctx, login_as, send};
+use serde_json::json;
+use sqlx::PgPool;
+
+mod common;
+
+#[sqlx::test]
+async fn test_workspace_crud(db_pool: PgPool) -> CaraiResult<()> {
+    let mut app = ctx(db_pool)?;
+    let token = login_as(&mut app, "keeper").await?;
+
+    // Act: Create a workspace
+    let create_req = json!({ "name": "scratch", "language": "Python" });
+    let (status, bytes) = send(
+        &mut app,
+        "POST",
+        "/workspaces",
+        Some(&token),
+        Some(&create_req),
+    )
+    .await?;
+
+    // Assert: The workspace is created with a normalized language
+    assert_eq!(
+        status, 201,
+        "Creating a workspace should return 201 Created"
+    );
+    let workspace: WorkspaceResDto = body(&bytes)?;
+    assert_eq!(workspace.language.as_deref(), Some("python"));
+
+    // Assert: Names are unique per owner
+    let (status, _) = send(
+        &mut app,
+        "POST",
+        "/workspaces",
+        Some(&token),
+        Some(&create_req),
+    )
+    .await?;
+    assert_eq!(status, 409, "Duplicate workspace names should conflict");
+
+    // Act: Rename the workspace
+    let uri = format!("/workspaces/{}", workspace.id);
+    let rename_req = json!({ "name": "project" });
+    let (status, bytes) = send(&mut app, "PATCH", &uri, Some(&token), Some(&rename_req)).await?;
+    assert_eq!(status, 200, "Renaming a workspace should succeed");
+    let workspace: WorkspaceResDto = body(&bytes)?;
+    assert_eq!(workspace.name, "project");
+
+    // Act: Add, update and list files
+    let files_uri = format!("{}/files", uri);
+    let file_req = json!({ "path": "src/main.py", "content": "print(1)" });
+    let (status, bytes) = send(&mut app, "POST", &files_uri, Some(&token), Some(&file_req)).await?;
+    assert_eq!(status, 201, "Creating a file should return 201 Created");
+    let file: WorkspaceFileResDto = body(&bytes)?;
+
+    let (status, _) = send(&mut app, "POST", &files_uri, Some(&token), Some(&file_req)).await?;
+    assert_eq!(status, 409, "Duplicate paths should conflict");
+
+    let escape_req = json!({ "path": "../etc/passwd", "content": "" });
+    let (status, _) = send(
+        &mut app,
+        "POST",
+        &files_uri,
+        Some(&token),
+        Some(&escape_req),
+    )
+    .await?;
+    assert_eq!(
+        status, 422,
+        "Paths outside the workspace should be rejected"
+    );
+
+    let file_uri = format!("{}/{}", files_uri, file.id);
+    let save_req = json!({ "path": "main.py", "content": "print(2)" });
+    let (status, bytes) = send(&mut app, "PATCH", &file_uri, Some(&token), Some(&save_req)).await?;
+    assert_eq!(status, 200, "Saving a file should succeed");
+    let file: WorkspaceFileResDto = body(&bytes)?;
+    assert_eq!(file.path, "main.py");
+    assert_eq!(file.content, "print(2)");
+
+    let (_, bytes) = send::<()>(&mut app, "GET", &files_uri, Some(&token), None).await?;
+    let files: WorkspaceFilesResDto = body(&bytes)?;
+    assert_eq!(files.files, vec![file]);
+
+    // Assert: Other users cannot see the workspace
+    let other = login_as(&mut app, "snooper").await?;
+    let (status, _) = send::<()>(&mut app, "GET", &files_uri, Some(&other), None).await?;
+    assert_eq!(
+        status, 404,
+        "Workspaces should only be visible to their owner"
+    );
+    let (_, bytes) = send::<()>(&mut app, "GET", "/workspaces", Some(&other), None).await?;
+    let listed: WorkspacesResDto = body(&bytes)?;
+    assert!(listed.workspaces.is_empty());
+
+    // Act: Delete the file, then the workspace
+    let (status, _) = send::<()>(&mut app, "DELETE", &file_uri, Some(&token), None).await?;
+    assert_eq!(status, 204, "Deleting a file should return 204 No Content");
+    let (status, _) = send::<()>(&mut app, "DELETE", &uri, Some(&token), None).await?;
+    assert_eq!(
+        status, 204,
+        "Deleting a workspace should return 204 No Content"
+    );
+
+    // Assert: The workspace is gone
+    let (_, bytes) = send::<()>(&mut app, "GET", "/workspaces", Some(&token), None).await?;
+    let listed: WorkspacesResDto = body(&bytes)?;
+    assert!(listed.workspaces.is_empty());
+
+    Ok(())
+}
+
+#[sqlx::test]
+async fn test_file_revisions_diff_and_restore(db_pool: PgPool) -> CaraiResult<()> {
+    let mut app = ctx(db_pool.clone())?;
+    let token = login_as(&mut app, "historian").await?;
+
+    // Arrange: A file saved three times, the last save repeating the first content
+    let create_req = json!({ "name": "history" });
+    let (_, bytes) = send(
+        &mut app,
+        "POST",
+        "/workspaces",
+        Some(&token),
+        Some(&create_req),
+    )
+    .await?;
+    let workspace: WorkspaceResDto = body(&bytes)?;
+    let files_uri = format!("/workspaces/{}/files", workspace.id);
+    let file_req = json!({ "path": "main.py", "content": "a\nb\n" });
+    let (_, bytes) = send(&mut app, "POST", &files_uri, Some(&token), Some(&file_req)).await?;
+    let file: WorkspaceFileResDto = body(&bytes)?;
+    let file_uri = format!("{}/{}", files_uri, file.id);
+    for content in ["a\nc\n", "a\nc\n", "a\nb\n"] {
+        let save_req = json!({ "content": content });
+        send(&mut app, "PATCH", &file_uri, Some(&token), Some(&save_req)).await?;
+    }
+
+    // Act: List the revisions
+    let uri = format!("{}/revisions", file_uri);
+    let (status, bytes) = send::<()>(&mut app, "GET", &uri, Some(&token), None).await?;
+
+    // Assert: Unchanged saves are skipped and identical content shares a blob
+    assert_eq!(status, 200, "Listing revisions should succeed");
+    let revisions: FileRevisionsResDto = body(&bytes)?;
+    let numbers: Vec<i32> = revisions.revisions.iter().map(|r| r.number).collect();
+    assert_eq!(numbers, vec![3, 2, 1], "Revisions should be newest first");
+    assert_eq!(revisions.revisions[0].hash, revisions.revisions[2].hash);
+    let blobs: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM blobs")
+        .fetch_one(&db_pool)
+        .await?;
+    assert_eq!(blobs, 2, "Identical content should be stored once");
+
+    // Act: Fetch a revision and diff it against the latest
+    let uri = format!("{}/revisions/2", file_uri);
+    let (_, bytes) = send::<()>(&mut app, "GET", &uri, Some(&token), None).await?;
+    let revision: FileRevisionContentResDto = body(&bytes)?;
+    assert_eq!(revision.content, "a\nc\n");
+
+    let uri = format!("{}/diff?from=2", file_uri);
+    let (status, bytes) = send::<()>(&mut app, "GET", &uri, Some(&token), None).await?;
+    assert_eq!(status, 200, "Diffing revisions should succeed");
+    let diff: FileDiffResDto = body(&bytes)?;
+    assert_eq!((diff.from, diff.to), (2, 3));
+    assert!(
+        diff.diff.contains("-c\n") && diff.diff.contains("+b\n"),
+        "Unexpected diff: {}",
+        diff.diff
+    );
+
+    // Act: Restore revision 2
+    let uri = format!("{}/revisions/2/restore", file_uri);
+    let (status, bytes) = send::<()>(&mut app, "POST", &uri, Some(&token), None).await?;
+
+    // Assert: The file has the old content and the restore is itself a revision
+    assert_eq!(status, 200, "Restoring a revision should succeed");
+    let file: WorkspaceFileResDto = body(&bytes)?;
+    assert_eq!(file.content, "a\nc\n");
+    let uri = format!("{}/revisions/4", file_uri);
+    let (status, _) = send::<()>(&mut app, "GET", &uri, Some(&token), None).await?;
+    assert_eq!(status, 200, "Restoring should record a new revision");
+
+    // Assert: Unknown revisions are not found
+    let uri = format!("{}/revisions/99", file_uri);
+    let (status, _) = send::<()>(&mut app, "GET", &uri, Some(&token), None).await?;
+    assert_eq!(status, 404);
+
+    Ok(())
+}
+
+#[sqlx::test]
+async fn test_fork_workspace_and_snippet(db_pool: PgPool) -> CaraiResult<()> {
+    let mut app = ctx(db_pool)?;
+    let author = login_as(&mut app, "author").await?;
+    let reader = login_as(&mut app, "reader").await?;
+
+    // Arrange: A workspace with two files and a public snippet
+    let (_, bytes) = send(
+        &mut app,
+        "POST",
+        "/workspaces",
+        Some(&author),
+        Some(&json!({ "name": "kata", "language": "python" })),
+    )
+    .await?;
+    let source: WorkspaceResDto = body(&bytes)?;
+    let files_uri = format!("/workspaces/{}/files", source.id);
+    for (path, content) in [("main.py", "print(1)"), ("lib/util.py", "x = 1")] {
+        let file_req = json!({ "path": path, "content": content });
+        send(&mut app, "POST", &files_uri, Some(&author), Some(&file_req)).await?;
+    }
+    let snippet_req = json!({
+        "title": "hello",
+        "language": "python",
+        "files": [{ "name": "hello.py", "content": "print('hi')" }],
+        "visibility": "public",
+    });
+    let (_, bytes) = send(
+        &mut app,
+        "POST",
+        "/snippets",
+        Some(&author),
+        Some(&snippet_req),
+    )
+    .await?;
+    let snippet: SnippetResDto = body(&bytes)?;
+
+    // Act: A stranger tries to fork the workspace
+    let fork_uri = format!("/workspaces/{}/fork", source.id);
+    let (status, _) = send(&mut app, "POST", &fork_uri, Some(&reader), Some(&json!({}))).await?;
+    assert_eq!(
+        status, 404,
+        "Only those who can view a workspace may fork it"
+    );
+
+    // Act: The author forks their own workspace, which needs a new name
+    let (status, _) = send(&mut app, "POST", &fork_uri, Some(&author), Some(&json!({}))).await?;
+    assert_eq!(
+        status, 409,
+        "The fork must not clash with an existing workspace"
+    );
+    let (status, bytes) = send(
+        &mut app,
+        "POST",
+        &fork_uri,
+        Some(&author),
+        Some(&json!({ "name": "kata-2" })),
+    )
+    .await?;
+
+    // Assert: The fork is a deep copy that remembers its origin
+    assert_eq!(status, 201, "Forking should return 201 Created");
+    let fork: WorkspaceResDto = body(&bytes)?;
+    assert_eq!(fork.forked_from_workspace_id, Some(source.id));
+    assert_eq!(fork.language.as_deref(), Some("python"));
+    let (_, bytes) = send::<()>(
+        &mut app,
+        "GET",
+        &format!("/workspaces/{}/files", fork.id),
+        Some(&author),
+        None,
+    )
+    .await?;
+    let files: WorkspaceFilesResDto = body(&bytes)?;
+    let mut paths: Vec<&str> = files.files.iter().map(|file| file.path.as_str()).collect();
+    paths.sort();
+    assert_eq!(paths, vec!["lib/util.py", "main.py"]);
+    let (_, bytes) = send::<()>(
+        &mut app,
+        "GET",
+        &format!("/workspaces/{}/files/{}", fork.id, files.files[0].id),
+        Some(&author),
+        None,
+    )
+    .await?;
+    let copied: WorkspaceFileResDto = body(&bytes)?;
+    assert!(!copied.content.is_empty());
+
+    // Act: Anyone may fork the public snippet into a workspace of their own
+    let snippet_uri = format!("/snippets/{}", snippet.slug);
+    let (status, bytes) = send(
+        &mut app,
+        "POST",
+        &format!("{}/fork", snippet_uri),
+        Some(&reader),
+        Some(&json!({})),
+    )
+    .await?;
+
+    // Assert: The snippet's files become the workspace's files
+    assert_eq!(
+        status, 201,
+        "Forking a public snippet should return 201 Created"
+    );
+    let fork: WorkspaceResDto = body(&bytes)?;
+    assert_eq!(
+        (fork.owner_id != source.owner_id, fork.name.as_str()),
+        (true, "hello")
+    );
+    assert_eq!(fork.forked_from_snippet_id, Some(snippet.id));
+    let (_, bytes) = send::<()>(
+        &mut app,
+        "GET",
+        &format!("/workspaces/{}/files", fork.id),
+        Some(&reader),
+        None,
+    )
+    .await?;
+    let files: WorkspaceFilesResDto = body(&bytes)?;
+    assert_eq!(files.files.len(), 1);
+    assert_eq!(files.files[0].path, "hello.py");
+
+    // Assert: Both sources count their forks
+    let (_, bytes) = send::<()>(
+        &mut app,
+        "GET",
+        &format!("/workspaces/{}", source.id),
+        Some(&author),
+        None,
+    )
+    .await?;
+    assert_eq!(body::<WorkspaceResDto>(&bytes)?.fork_count, 1);
+    let (_, bytes) = send::<()>(&mut app, "GET", &snippet_uri, None, None).await?;
+    assert_eq!(body::<SnippetResDto>(&bytes)?.fork_count, 1);
+
+    // Act: Private snippets stay closed to forks by others
+    let patch = json!({ "visibility": "private" });
+    send(&mut app, "PATCH", &snippet_uri, Some(&author), Some(&patch)).await?;
+    let (status, _) = send(
+        &mut app,
+        "POST",
+        &format!("{}/fork", snippet_uri),
+        Some(&reader),
+        Some(&json!({ "name": "again" })),
+    )
+    .await?;
+    assert_eq!(status, 404);
+
+    Ok(())
+}
@@ -0,0 +1,201 @@
+use carai::{
+    dto::{InvitationResDto, InvitationsResDto, MembersResDto, WorkspaceResDto},
+    models::WorkspaceRole,
+    utils::CaraiResult,
+};
+use common::{body, ctx, login_as, send};
+use serde_json::json;
+use sqlx::PgPool;
+
+mod common;
+
+#[sqlx::test]
+async fn test_invitations_and_roles(db_pool: PgPool) -> CaraiResult<()> {
+    let mut app = ctx(db_pool)?;
+    let owner = login_as(&mut app, "founder").await?;
+    let viewer = login_as(&mut app, "watcher").await?;
+    let editor = login_as(&mut app, "typist").await?;
+    let stranger = login_as(&mut app, "stranger").await?;
+
+    // Arrange: A workspace with one file
+    let create_req = json!({ "name": "shared" });
+    let (_, bytes) = send(
+        &mut app,
+        "POST",
+        "/workspaces",
+        Some(&owner),
+        Some(&create_req),
+    )
+    .await?;
+    let workspace: WorkspaceResDto = body(&bytes)?;
+    let uri = format!("/workspaces/{}", workspace.id);
+    let files_uri = format!("{}/files", uri);
+    let file_req = json!({ "path": "main.py", "content": "print(1)" });
+    let (status, _) = send(&mut app, "POST", &files_uri, Some(&owner), Some(&file_req)).await?;
+    assert_eq!(status, 201);
+
+    // Act: Invite a user by username as a viewer
+    let invite_uri = format!("{}/invitations", uri);
+    let invite_req = json!({ "username": "watcher", "role": "viewer" });
+    let (status, _) = send(
+        &mut app,
+        "POST",
+        &invite_uri,
+        Some(&viewer),
+        Some(&invite_req),
+    )
+    .await?;
+    assert_eq!(status, 404, "Non-members should not see the workspace");
+    let (status, _) = send(
+        &mut app,
+        "POST",
+        &invite_uri,
+        Some(&owner),
+        Some(&json!({ "username": "watcher", "role": "owner" })),
+    )
+    .await?;
+    assert_eq!(
+        status, 422,
+        "Ownership cannot be granted through invitations"
+    );
+    let (status, _) = send(
+        &mut app,
+        "POST",
+        &invite_uri,
+        Some(&owner),
+        Some(&invite_req),
+    )
+    .await?;
+    assert_eq!(status, 201, "Inviting a user should return 201 Created");
+    let (status, _) = send(
+        &mut app,
+        "POST",
+        &invite_uri,
+        Some(&owner),
+        Some(&invite_req),
+    )
+    .await?;
+    assert_eq!(status, 409, "A user can only have one pending invitation");
+
+    // Act: The invitee accepts
+    let (_, bytes) = send::<()>(&mut app, "GET", "/invitations", Some(&viewer), None).await?;
+    let invitations: InvitationsResDto = body(&bytes)?;
+    assert_eq!(invitations.invitations.len(), 1);
+    let accept_uri = format!("/invitations/{}/accept", invitations.invitations[0].id);
+    let (status, _) = send::<()>(&mut app, "POST", &accept_uri, Some(&stranger), None).await?;
+    assert_eq!(status, 404, "Only the invitee can accept an invitation");
+    let (status, _) = send::<()>(&mut app, "POST", &accept_uri, Some(&viewer), None).await?;
+    assert_eq!(status, 200, "Accepting an invitation should succeed");
+
+    // Assert: Viewers can read but not write
+    let (status, _) = send::<()>(&mut app, "GET", &files_uri, Some(&viewer), None).await?;
+    assert_eq!(status, 200, "Viewers should be able to list files");
+    let (status, _) = send(&mut app, "POST", &files_uri, Some(&viewer), Some(&file_req)).await?;
+    assert_eq!(status, 403, "Viewers should not be able to add files");
+    let (_, bytes) = send::<()>(&mut app, "GET", "/workspaces", Some(&viewer), None).await?;
+    assert!(String::from_utf8_lossy(&bytes).contains("shared"));
+
+    // Act: Share a single-use invite link for editors
+    let link_req = json!({ "role": "editor", "maxUses": 1, "expiresInSecs": 3600 });
+    let link_uri = format!("{}/invite-links", uri);
+    let (status, bytes) = send(&mut app, "POST", &link_uri, Some(&owner), Some(&link_req)).await?;
+    assert_eq!(
+        status, 201,
+        "Creating an invite link should return 201 Created"
+    );
+    let link: InvitationResDto = body(&bytes)?;
+    let redeem_uri = format!("/invitations/links/{}/accept", link.token.unwrap());
+    let (status, _) = send::<()>(&mut app, "POST", &redeem_uri, Some(&viewer), None).await?;
+    assert_eq!(status, 409, "Members cannot redeem links");
+    let (status, _) = send::<()>(&mut app, "POST", &redeem_uri, Some(&editor), None).await?;
+    assert_eq!(status, 200, "Redeeming an invite link should succeed");
+    let (status, _) = send::<()>(&mut app, "POST", &redeem_uri, Some(&stranger), None).await?;
+    assert_eq!(status, 410, "Used up links should be refused");
+
+    // Assert: Editors can write, members are listed owner first
+    let new_file = json!({ "path": "util.py", "content": "" });
+    let (status, _) = send(&mut app, "POST", &files_uri, Some(&editor), Some(&new_file)).await?;
+    assert_eq!(status, 201, "Editors should be able to add files");
+    let (status, _) = send::<()>(&mut app, "DELETE", &uri, Some(&editor), None).await?;
+    assert_eq!(status, 403, "Only owners can delete workspaces");
+
+    let members_uri = format!("{}/members", uri);
+    let (_, bytes) = send::<()>(&mut app, "GET", &members_uri, Some(&viewer), None).await?;
+    let members: MembersResDto = body(&bytes)?;
+    let roles: Vec<(&str, WorkspaceRole)> = members
+        .members
+        .iter()
+        .map(|member| (member.username.as_str(), member.role))
+        .collect();
+    assert_eq!(
+        roles,
+        vec![
+            ("founder", WorkspaceRole::Owner),
+            ("typist", WorkspaceRole::Editor),
+            ("watcher", WorkspaceRole::Viewer),
+        ]
+    );
+    let founder_id = members.members[0].user_id;
+    let typist_id = members.members[1].user_id;
+    let watcher_id = members.members[2].user_id;
+
+    // Act: Promote the viewer to commenter
+    let member_uri = format!("{}/{}", members_uri, watcher_id);
+    let (status, _) = send(
+        &mut app,
+        "PATCH",
+        &member_uri,
+        Some(&editor),
+        Some(&json!({ "role": "commenter" })),
+    )
+    .await?;
+    assert_eq!(status, 403, "Only owners can change roles");
+    let (status, _) = send(
+        &mut app,
+        "PATCH",
+        &member_uri,
+        Some(&owner),
+        Some(&json!({ "role": "commenter" })),
+    )
+    .await?;
+    assert_eq!(status, 200, "Owners can change roles");
+
+    // Act: Transfer the workspace to the editor
+    let transfer_uri = format!("{}/transfer", uri);
+    let (status, bytes) = send(
+        &mut app,
+        "POST",
+        &transfer_uri,
+        Some(&owner),
+        Some(&json!({ "userId": typist_id })),
+    )
+    .await?;
+    assert_eq!(status, 200, "Transferring a workspace should succeed");
+    let transferred: WorkspaceResDto = body(&bytes)?;
+    assert_eq!(transferred.owner_id, typist_id);
+
+    // Assert: The previous owner stays on as an editor and can leave
+    let (_, bytes) = send::<()>(&mut app, "GET", &members_uri, Some(&editor), None).await?;
+    let members: MembersResDto = body(&bytes)?;
+    let founder = members
+        .members
+        .iter()
+        .find(|member| member.user_id == founder_id)
+        .unwrap();
+    assert_eq!(founder.role, WorkspaceRole::Editor);
+    let leave_uri = format!("{}/leave", uri);
+    let (status, _) = send::<()>(&mut app, "POST", &leave_uri, Some(&editor), None).await?;
+    assert_eq!(status, 422, "Owners cannot leave their workspace");
+    let (status, _) = send::<()>(&mut app, "POST", &leave_uri, Some(&owner), None).await?;
+    assert_eq!(status, 204, "Members can leave a workspace");
+    let (status, _) = send::<()>(&mut app, "GET", &uri, Some(&owner), None).await?;
+    assert_eq!(status, 404, "Former members lose access");
+
+    // Act: The new owner removes the commenter
+    let (status, _) = send::<()>(&mut app, "DELETE", &member_uri, Some(&editor), None).await?;
+    assert_eq!(status, 204, "Owners can remove members");
+    let (status, _) = send::<()>(&mut app, "GET", &uri, Some(&viewer), None).await?;
+    assert_eq!(status, 404, "Removed members lose access");
+
+    Ok(())
+}